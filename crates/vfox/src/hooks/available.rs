@@ -12,6 +12,7 @@ impl Plugin {
 
     pub async fn available_async(&self) -> Result<Vec<AvailableVersion>> {
         debug!("[vfox:{}] available_async", self.name);
+        self.enter_hook("available")?;
         let ctx = self.context(None)?;
         let available = self
             .eval_async(chunk! {
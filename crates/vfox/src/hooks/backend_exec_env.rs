@@ -24,6 +24,7 @@ impl Plugin {
         ctx: BackendExecEnvContext,
     ) -> Result<BackendExecEnvResponse> {
         debug!("[vfox:{}] backend_exec_env", self.name);
+        self.enter_hook("backend_exec_env")?;
         self.eval_async(chunk! {
             require "hooks/backend_exec_env"
             return PLUGIN:BackendExecEnv($ctx)
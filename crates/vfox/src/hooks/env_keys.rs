@@ -29,6 +29,7 @@ impl Plugin {
         ctx: EnvKeysContext<T>,
     ) -> Result<Vec<EnvKey>> {
         debug!("[vfox:{}] env_keys", self.name);
+        self.enter_hook("env_keys")?;
         let env_keys = self
             .eval_async(chunk! {
                 require "hooks/env_keys"
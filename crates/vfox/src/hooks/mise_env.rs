@@ -36,6 +36,7 @@ impl Plugin {
         ctx: MiseEnvContext<T>,
     ) -> Result<MiseEnvResult> {
         debug!("[vfox:{}] mise_env", self.name);
+        self.enter_hook("mise_env")?;
         let result = self
             .eval_async(chunk! {
                 require "hooks/mise_env"
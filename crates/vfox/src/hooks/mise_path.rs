@@ -16,6 +16,7 @@ impl Plugin {
         ctx: MisePathContext<T>,
     ) -> Result<Vec<String>> {
         debug!("[vfox:{}] mise_path", self.name);
+        self.enter_hook("mise_path")?;
         let path = self
             .eval_async(chunk! {
                 require "hooks/mise_path"
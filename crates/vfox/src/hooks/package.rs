@@ -41,6 +41,7 @@ impl Plugin {
         ctx: PackageInstalledContext,
     ) -> Result<PackageInstalledResponse> {
         debug!("[vfox:{}] package_installed", self.name);
+        self.enter_hook("package_installed")?;
         self.eval_async(chunk! {
             require "hooks/package_installed"
             return PLUGIN:PackageInstalled($ctx)
@@ -53,6 +54,7 @@ impl Plugin {
         ctx: PackageActionContext,
     ) -> Result<PackageActionResponse> {
         debug!("[vfox:{}] package_install", self.name);
+        self.enter_hook("package_install")?;
         self.eval_async(chunk! {
             require "hooks/package_install"
             return PLUGIN:PackageInstall($ctx)
@@ -65,6 +67,7 @@ impl Plugin {
         ctx: PackageActionContext,
     ) -> Result<PackageActionResponse> {
         debug!("[vfox:{}] package_upgrade", self.name);
+        self.enter_hook("package_upgrade")?;
         self.eval_async(chunk! {
             require "hooks/package_upgrade"
             return PLUGIN:PackageUpgrade($ctx)
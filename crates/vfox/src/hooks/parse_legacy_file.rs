@@ -19,6 +19,7 @@ pub struct ParseLegacyFileResponse {
 impl Plugin {
     pub async fn parse_legacy_file(&self, legacy_file: &Path) -> Result<ParseLegacyFileResponse> {
         debug!("[vfox:{}] parse_legacy_file", self.name);
+        self.enter_hook("parse_legacy_file")?;
         let ctx = LegacyFileContext {
             args: vec![],
             filepath: legacy_file.to_path_buf(),
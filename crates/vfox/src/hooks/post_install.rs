@@ -8,6 +8,7 @@ use std::path::PathBuf;
 impl Plugin {
     pub async fn post_install(&self, ctx: PostInstallContext) -> Result<()> {
         debug!("[vfox:{}] post_install", self.name);
+        self.enter_hook("post_install")?;
         self.exec_async(chunk! {
             require "hooks/post_install"
             PLUGIN:PostInstall($ctx)
@@ -42,11 +43,13 @@ mod tests {
     #[test]
     async fn dummy() {
         let p = Plugin::test("dummy");
+        let root = tempfile::tempdir().unwrap();
         let ctx = PostInstallContext {
-            root_path: PathBuf::from("root_path"),
+            root_path: root.path().to_path_buf(),
             runtime_version: "runtime_version".to_string(),
             sdk_info: BTreeMap::new(),
         };
         p.post_install(ctx).await.unwrap();
+        assert!(root.path().join("bin/dummy").exists());
     }
 }
@@ -10,6 +10,7 @@ use crate::runtime::Runtime;
 impl Plugin {
     pub async fn pre_install(&self, version: &str) -> Result<PreInstall> {
         debug!("[vfox:{}] pre_install", self.name);
+        self.enter_hook("pre_install")?;
         let ctx = self.context(Some(version.to_string()))?;
         let pre_install = self
             .eval_async(chunk! {
@@ -31,6 +32,7 @@ impl Plugin {
             "[vfox:{}] pre_install_for_platform os={} arch={}",
             self.name, os, arch
         );
+        self.enter_hook("pre_install")?;
         let ctx = self.context(Some(version.to_string()))?;
         let target_os = os.to_string();
         let target_arch = arch.to_string();
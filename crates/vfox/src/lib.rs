@@ -15,6 +15,7 @@ pub use hooks::package::{
 pub use hooks::pre_install::VerifiedAttestation;
 pub use metadata::{Metadata, SystemDependency};
 pub use plugin::Plugin;
+pub use sandbox::{CommandHook, Sandbox};
 pub use vfox::InstallResult;
 pub use vfox::Vfox;
 
@@ -29,5 +30,6 @@ mod metadata;
 mod plugin;
 mod registry;
 mod runtime;
+mod sandbox;
 mod sdk_info;
 mod vfox;
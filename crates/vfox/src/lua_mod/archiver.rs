@@ -1,4 +1,5 @@
 use crate::error::Result;
+use crate::sandbox::{check_read, check_write};
 use mlua::{ExternalResult, Lua, MultiValue, Table, Value};
 use std::collections::BTreeSet;
 use std::path::{Path, PathBuf};
@@ -17,7 +18,7 @@ pub fn mod_archiver(lua: &Lua) -> Result<()> {
     )?)
 }
 
-fn decompress(_lua: &Lua, input: MultiValue) -> mlua::Result<()> {
+fn decompress(lua: &Lua, input: MultiValue) -> mlua::Result<()> {
     let paths: Vec<mlua::Value> = input.into_iter().collect();
     if paths.len() < 2 {
         return Err(mlua::Error::runtime(
@@ -26,6 +27,8 @@ fn decompress(_lua: &Lua, input: MultiValue) -> mlua::Result<()> {
     }
     let archive: PathBuf = PathBuf::from(paths[0].to_string()?);
     let destination: PathBuf = PathBuf::from(paths[1].to_string()?);
+    check_read(lua, &archive)?;
+    check_write(lua, &destination)?;
     let strip_components = match paths.get(2) {
         None | Some(Value::Nil) => 0,
         Some(Value::Table(options)) => options
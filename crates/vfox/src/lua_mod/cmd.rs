@@ -3,6 +3,8 @@ use mlua::prelude::*;
 use std::path::Path;
 use std::process::Command;
 
+use crate::sandbox;
+
 pub fn mod_cmd(lua: &Lua) -> LuaResult<()> {
     let package: Table = lua.globals().get("package")?;
     let loaded: Table = package.get("loaded")?;
@@ -85,6 +87,7 @@ fn exec(lua: &Lua, args: mlua::MultiValue) -> LuaResult<String> {
         }
     }
 
+    let _guard = sandbox::guard_command(lua, &mut cmd)?;
     let output = cmd
         .output()
         .map_err(|e| mlua::Error::RuntimeError(format!("Failed to execute command: {e}")))?;
@@ -134,6 +137,7 @@ fn os_execute(lua: &Lua, command: Option<String>) -> LuaResult<i64> {
     let mut cmd = command_from_shell(&shell, &command)?;
     let has_mise_env = apply_mise_env(lua, &mut cmd)?;
     debug!("[os.execute] command={command:?} shell={shell:?} has_mise_env={has_mise_env}");
+    let _guard = sandbox::guard_command(lua, &mut cmd)?;
     let status = cmd
         .status()
         .map_err(|e| mlua::Error::RuntimeError(format!("Failed to execute command: {e}")))?;
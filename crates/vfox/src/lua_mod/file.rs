@@ -1,4 +1,5 @@
 use crate::error::Result;
+use crate::sandbox::{check_read, check_write};
 use mlua::{ExternalResult, Lua, MultiValue, Table, Value};
 #[cfg(unix)]
 use std::os::unix::fs::{PermissionsExt, symlink as _symlink};
@@ -67,7 +68,8 @@ fn paths_to_strings(paths: Vec<PathBuf>) -> Vec<String> {
     paths
 }
 
-fn list(_lua: &Lua, path: String) -> mlua::Result<Vec<String>> {
+fn list(lua: &Lua, path: String) -> mlua::Result<Vec<String>> {
+    check_read(lua, Path::new(&path))?;
     xx::file::ls(path).map(paths_to_strings).into_lua_err()
 }
 
@@ -79,26 +81,30 @@ fn glob(_lua: &Lua, pattern: String) -> mlua::Result<Vec<String>> {
     Ok(paths_to_strings(paths))
 }
 
-fn move_path(_lua: &Lua, (source, destination): (String, String)) -> mlua::Result<()> {
+fn move_path(lua: &Lua, (source, destination): (String, String)) -> mlua::Result<()> {
+    check_write(lua, Path::new(&source))?;
+    check_write(lua, Path::new(&destination))?;
     xx::file::mv(source, destination).into_lua_err()
 }
 
-async fn read(_lua: &Lua, input: MultiValue) -> mlua::Result<String> {
+async fn read(lua: &Lua, input: MultiValue) -> mlua::Result<String> {
     let args: Vec<String> = input
         .into_iter()
         .map(|v| v.to_string())
         .collect::<mlua::Result<_>>()?;
     let path = Path::new(&args[0]);
+    check_read(lua, path)?;
     std::fs::read_to_string(path).into_lua_err()
 }
 
-async fn symlink(_lua: &Lua, input: MultiValue) -> mlua::Result<()> {
+async fn symlink(lua: &Lua, input: MultiValue) -> mlua::Result<()> {
     let input: Vec<String> = input
         .into_iter()
         .map(|v| v.to_string())
         .collect::<mlua::Result<_>>()?;
     let src = Path::new(&input[0]);
     let dst = Path::new(&input[1]);
+    check_write(lua, dst)?;
     #[cfg(windows)]
     {
         if src.is_dir() {
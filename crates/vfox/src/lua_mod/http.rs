@@ -6,6 +6,7 @@ use url::Url;
 use crate::http::{
     CLIENT, http_retry_attempts, is_transient, retry_async, retry_delay, should_retry_status,
 };
+use crate::sandbox::{check_url, check_write};
 
 async fn send_with_retry(builder: RequestBuilder) -> std::result::Result<Response, reqwest::Error> {
     let url = builder
@@ -171,6 +172,7 @@ fn add_default_headers(lua: &Lua, url: &str, mut headers: HeaderMap) -> HeaderMa
 
 async fn get(lua: &Lua, input: Table) -> Result<Table> {
     let url: String = input.get("url").into_lua_err()?;
+    check_url(lua, &url)?;
     let headers = match input.get::<Option<Table>>("headers").into_lua_err()? {
         Some(tbl) => into_headers(&tbl)?,
         None => HeaderMap::default(),
@@ -189,12 +191,14 @@ async fn get(lua: &Lua, input: Table) -> Result<Table> {
 async fn download_file(lua: &Lua, input: MultiValue) -> Result<()> {
     let t: &Table = input.iter().next().unwrap().as_table().unwrap();
     let url: String = t.get("url").into_lua_err()?;
+    check_url(lua, &url)?;
     let headers = match t.get::<Option<Table>>("headers").into_lua_err()? {
         Some(tbl) => into_headers(&tbl)?,
         None => HeaderMap::default(),
     };
     let headers = add_default_headers(lua, &url, headers);
     let path: String = input.iter().nth(1).unwrap().to_string()?;
+    check_write(lua, std::path::Path::new(&path))?;
     // Retry the whole flow (request + body) so a mid-stream drop restarts the
     // download instead of failing.
     let bytes = retry_async(&url, || async {
@@ -221,6 +225,7 @@ async fn download_file(lua: &Lua, input: MultiValue) -> Result<()> {
 
 async fn head(lua: &Lua, input: Table) -> Result<Table> {
     let url: String = input.get("url").into_lua_err()?;
+    check_url(lua, &url)?;
     let headers = match input.get::<Option<Table>>("headers").into_lua_err()? {
         Some(tbl) => into_headers(&tbl)?,
         None => HeaderMap::default(),
@@ -237,6 +242,7 @@ async fn head(lua: &Lua, input: Table) -> Result<Table> {
 
 async fn try_get(lua: &Lua, input: Table) -> Result<MultiValue> {
     let url: String = input.get("url").into_lua_err()?;
+    check_url(lua, &url)?;
     let headers = match input.get::<Option<Table>>("headers").into_lua_err()? {
         Some(tbl) => into_headers(&tbl)?,
         None => HeaderMap::default(),
@@ -268,6 +274,7 @@ async fn try_get(lua: &Lua, input: Table) -> Result<MultiValue> {
 
 async fn try_head(lua: &Lua, input: Table) -> Result<MultiValue> {
     let url: String = input.get("url").into_lua_err()?;
+    check_url(lua, &url)?;
     let headers = match input.get::<Option<Table>>("headers").into_lua_err()? {
         Some(tbl) => into_headers(&tbl)?,
         None => HeaderMap::default(),
@@ -299,6 +306,7 @@ async fn try_download_file(lua: &Lua, input: MultiValue) -> Result<MultiValue> {
         }
     };
     let url: String = t.get("url").into_lua_err()?;
+    check_url(lua, &url)?;
     let headers = match t.get::<Option<Table>>("headers").into_lua_err()? {
         Some(tbl) => into_headers(&tbl)?,
         None => HeaderMap::default(),
@@ -313,6 +321,7 @@ async fn try_download_file(lua: &Lua, input: MultiValue) -> Result<MultiValue> {
             ]));
         }
     };
+    check_write(lua, std::path::Path::new(&path))?;
     let bytes = match retry_async(&url, || async {
         let resp = CLIENT.get(&url).headers(headers.clone()).send().await?;
         let resp = resp.error_for_status()?;
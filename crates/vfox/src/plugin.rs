@@ -13,6 +13,7 @@ use crate::embedded_plugins::{self, EmbeddedPlugin};
 use crate::error::Result;
 use crate::metadata::Metadata;
 use crate::runtime::Runtime;
+use crate::sandbox::{self, Sandbox};
 use crate::sdk_info::SdkInfo;
 use crate::{VfoxError, config, error, lua_mod};
 
@@ -139,6 +140,18 @@ impl Plugin {
         Ok(())
     }
 
+    /// Restrict what the plugin's hooks can write to, connect to and run. See [`Sandbox`].
+    pub fn set_sandbox(&self, sandbox: Sandbox) -> Result<()> {
+        sandbox::install(&self.lua, sandbox)?;
+        Ok(())
+    }
+
+    /// Record the hook about to run, so sandbox violations can name it.
+    pub(crate) fn enter_hook(&self, hook: &str) -> Result<()> {
+        self.lua.set_named_registry_value("current_hook", hook)?;
+        Ok(())
+    }
+
     pub fn list() -> Result<Vec<String>> {
         let config = Config::get();
         if !config.plugin_dir.exists() {
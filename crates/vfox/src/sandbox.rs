@@ -0,0 +1,421 @@
+//! Restrictions on what a plugin's hooks can do, set with [`Plugin::set_sandbox`].
+//!
+//! Hooks run in-process, so the sandbox is enforced where they reach outside the Lua VM: the
+//! `http` module only connects to allowed hosts, the `file` and `archiver` modules and Lua's
+//! `io.open`, `io.lines`, `dofile`, `loadfile`, `os.remove` and `os.rename` only read and write
+//! allowed paths, and commands run with `cmd.exec` and `os.execute` are passed to
+//! [`Sandbox::command_hook`] so the caller can restrict them at the OS level. `io.popen` is not
+//! available in a sandbox.
+//!
+//! [`Plugin::set_sandbox`]: crate::Plugin::set_sandbox
+
+use std::any::Any;
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+
+use mlua::{Function, Lua, MultiValue, Table, Value};
+use url::Url;
+
+/// Called with each command a hook runs before it is spawned. The returned guard is kept until
+/// the command exits.
+pub type CommandHook =
+    Arc<dyn Fn(&mut Command) -> std::io::Result<Box<dyn Any + Send>> + Send + Sync>;
+
+#[derive(Clone, Default)]
+pub struct Sandbox {
+    /// Directories hooks can write to, along with the system temp dir.
+    pub allow_write: Vec<PathBuf>,
+    /// Directories hooks can't read, apart from `allow_read` and `allow_write` paths beneath
+    /// them.
+    pub deny_read: Vec<PathBuf>,
+    /// Directories beneath `deny_read` hooks can still read.
+    pub allow_read: Vec<PathBuf>,
+    /// Hosts the `http` module can connect to, as `host`, `host:port` or `*.domain`. No host
+    /// is allowed when empty.
+    pub allow_net: Vec<String>,
+    /// Restricts commands run by hooks.
+    pub command_hook: Option<CommandHook>,
+}
+
+impl fmt::Debug for Sandbox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sandbox")
+            .field("allow_write", &self.allow_write)
+            .field("deny_read", &self.deny_read)
+            .field("allow_read", &self.allow_read)
+            .field("allow_net", &self.allow_net)
+            .field(
+                "command_hook",
+                &self.command_hook.as_ref().map(|_| "<closure>"),
+            )
+            .finish()
+    }
+}
+
+impl Sandbox {
+    fn allows_write(&self, path: &Path) -> bool {
+        let path = resolve(path);
+        std::iter::once(std::env::temp_dir())
+            .chain(self.allow_write.iter().cloned())
+            .any(|dir| path.starts_with(resolve(&dir)))
+    }
+
+    fn allows_read(&self, path: &Path) -> bool {
+        let path = resolve(path);
+        let within = |dirs: &[PathBuf]| dirs.iter().any(|dir| path.starts_with(resolve(dir)));
+        !within(&self.deny_read) || within(&self.allow_read) || self.allows_write(&path)
+    }
+
+    pub(crate) fn allows_url(&self, url: &Url) -> bool {
+        let Some(host) = url.host_str() else {
+            return false;
+        };
+        let host = normalize_host(host);
+        let port = url.port_or_known_default();
+        self.allow_net.iter().any(|rule| {
+            let (rule_host, rule_port) = split_host_rule(rule);
+            if rule_port.is_some() && rule_port != port {
+                return false;
+            }
+            match rule_host.strip_prefix("*.") {
+                Some(domain) => host
+                    .strip_suffix(domain)
+                    .is_some_and(|sub| sub.len() > 1 && sub.ends_with('.')),
+                None => rule_host == "*" || rule_host == host,
+            }
+        })
+    }
+}
+
+fn split_host_rule(rule: &str) -> (String, Option<u16>) {
+    let rule = rule.trim();
+    let (host, port) = if let Some(rest) = rule.strip_prefix('[') {
+        match rest.split_once(']') {
+            Some((host, port)) => (host, port.strip_prefix(':')),
+            None => (rest, None),
+        }
+    } else if rule.matches(':').count() == 1 {
+        let (host, port) = rule.split_once(':').unwrap();
+        (host, Some(port))
+    } else {
+        (rule, None)
+    };
+    (normalize_host(host), port.and_then(|p| p.parse().ok()))
+}
+
+fn normalize_host(host: &str) -> String {
+    host.trim_start_matches('[')
+        .trim_end_matches(']')
+        .trim_end_matches('.')
+        .to_ascii_lowercase()
+}
+
+/// Makes a path absolute without `..` components, resolving symlinks in the part that exists,
+/// so a path can't escape an allowed directory through either.
+fn resolve(path: &Path) -> PathBuf {
+    let mut resolved = PathBuf::new();
+    let path = std::env::current_dir().unwrap_or_default().join(path);
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            Component::CurDir => {}
+            c => resolved.push(c),
+        }
+    }
+    let mut existing = resolved.as_path();
+    let mut rest = vec![];
+    while !existing.exists() {
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                rest.push(name.to_os_string());
+                existing = parent;
+            }
+            _ => return resolved,
+        }
+    }
+    let mut canonical = existing
+        .canonicalize()
+        .unwrap_or_else(|_| existing.to_path_buf());
+    canonical.extend(rest.into_iter().rev());
+    canonical
+}
+
+/// Stores the sandbox for a plugin's Lua state and replaces the Lua functions it can't enforce
+/// itself on.
+pub(crate) fn install(lua: &Lua, sandbox: Sandbox) -> mlua::Result<()> {
+    lua.set_app_data(sandbox);
+    let globals = lua.globals();
+    let io: Table = globals.get("io")?;
+    let os: Table = globals.get("os")?;
+
+    let open: Function = io.get("open")?;
+    io.set(
+        "open",
+        lua.create_function(move |lua, (path, mode): (String, Option<String>)| {
+            let mode = mode.unwrap_or_else(|| "r".to_string());
+            if mode.contains(['w', 'a', '+']) {
+                check_write(lua, Path::new(&path))?;
+            }
+            if mode.contains(['r', '+']) {
+                check_read(lua, Path::new(&path))?;
+            }
+            open.call::<MultiValue>((path, mode))
+        })?,
+    )?;
+    let lines: Function = io.get("lines")?;
+    io.set(
+        "lines",
+        lua.create_function(move |lua, args: MultiValue| {
+            if let Some(Value::String(path)) = args.front() {
+                check_read(lua, Path::new(&*path.to_str()?))?;
+            }
+            lines.call::<MultiValue>(args)
+        })?,
+    )?;
+    let input: Function = io.get("input")?;
+    io.set(
+        "input",
+        lua.create_function(move |lua, file: Value| {
+            if let Value::String(path) = &file {
+                check_read(lua, Path::new(&*path.to_str()?))?;
+            }
+            input.call::<Value>(file)
+        })?,
+    )?;
+    let output: Function = io.get("output")?;
+    io.set(
+        "output",
+        lua.create_function(move |lua, file: Value| {
+            if let Value::String(path) = &file {
+                check_write(lua, Path::new(&*path.to_str()?))?;
+            }
+            output.call::<Value>(file)
+        })?,
+    )?;
+    io.set(
+        "popen",
+        lua.create_function(|lua, _: MultiValue| -> mlua::Result<()> {
+            Err(violation(
+                lua,
+                "called io.popen, which is not available in the sandbox, use cmd.exec instead",
+            ))
+        })?,
+    )?;
+    for name in ["dofile", "loadfile"] {
+        let load: Function = globals.get(name)?;
+        globals.set(
+            name,
+            lua.create_function(move |lua, args: MultiValue| {
+                // without a path they read stdin
+                if let Some(Value::String(path)) = args.front() {
+                    check_read(lua, Path::new(&*path.to_str()?))?;
+                }
+                load.call::<MultiValue>(args)
+            })?,
+        )?;
+    }
+    let remove: Function = os.get("remove")?;
+    os.set(
+        "remove",
+        lua.create_function(move |lua, path: String| {
+            check_write(lua, Path::new(&path))?;
+            remove.call::<MultiValue>(path)
+        })?,
+    )?;
+    let rename: Function = os.get("rename")?;
+    os.set(
+        "rename",
+        lua.create_function(move |lua, (from, to): (String, String)| {
+            check_write(lua, Path::new(&from))?;
+            check_write(lua, Path::new(&to))?;
+            rename.call::<MultiValue>((from, to))
+        })?,
+    )?;
+    Ok(())
+}
+
+fn sandbox(lua: &Lua) -> Option<Sandbox> {
+    lua.app_data_ref::<Sandbox>().map(|s| s.clone())
+}
+
+/// Fails if the plugin is sandboxed and can't read `path`.
+pub(crate) fn check_read(lua: &Lua, path: &Path) -> mlua::Result<()> {
+    match sandbox(lua) {
+        Some(sandbox) if !sandbox.allows_read(path) => Err(violation(
+            lua,
+            &format!(
+                "tried to read {}, which is outside the directories it can read",
+                path.display()
+            ),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Fails if the plugin is sandboxed and can't write to `path`.
+pub(crate) fn check_write(lua: &Lua, path: &Path) -> mlua::Result<()> {
+    match sandbox(lua) {
+        Some(sandbox) if !sandbox.allows_write(path) => Err(violation(
+            lua,
+            &format!(
+                "tried to write to {}, which is outside the directories it can write to",
+                path.display()
+            ),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Fails if the plugin is sandboxed and can't connect to the host of `url`.
+pub(crate) fn check_url(lua: &Lua, url: &str) -> mlua::Result<()> {
+    let Some(sandbox) = sandbox(lua) else {
+        return Ok(());
+    };
+    match Url::parse(url) {
+        Ok(parsed) if sandbox.allows_url(&parsed) => Ok(()),
+        _ => Err(violation(
+            lua,
+            &format!(
+                "tried to connect to {url}, which is not one of the allowed hosts: {}",
+                sandbox.allow_net.join(", ")
+            ),
+        )),
+    }
+}
+
+/// Restricts a command a hook is about to run. The returned guard must be kept until the
+/// command exits.
+pub(crate) fn guard_command(
+    lua: &Lua,
+    cmd: &mut Command,
+) -> mlua::Result<Option<Box<dyn Any + Send>>> {
+    let Some(hook) = sandbox(lua).and_then(|s| s.command_hook) else {
+        return Ok(None);
+    };
+    hook(cmd)
+        .map(Some)
+        .map_err(|e| violation(lua, &format!("could not run a sandboxed command: {e}")))
+}
+
+fn violation(lua: &Lua, what: &str) -> mlua::Error {
+    let plugin = lua
+        .named_registry_value::<String>("plugin_name")
+        .unwrap_or_default();
+    let hook = lua
+        .named_registry_value::<String>("current_hook")
+        .unwrap_or_else(|_| "unknown".to_string());
+    mlua::Error::runtime(format!("sandbox: plugin {plugin} {hook} hook {what}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sandboxed_lua(sandbox: Sandbox) -> Lua {
+        let lua = Lua::new();
+        lua.set_named_registry_value("plugin_name", "test").unwrap();
+        lua.set_named_registry_value("current_hook", "post_install")
+            .unwrap();
+        install(&lua, sandbox).unwrap();
+        lua
+    }
+
+    #[test]
+    fn test_allows_write_only_inside_allowed_dirs() {
+        let allowed = tempfile::TempDir::new_in(".").unwrap();
+        let sandbox = Sandbox {
+            allow_write: vec![allowed.path().to_path_buf()],
+            ..Default::default()
+        };
+        assert!(sandbox.allows_write(&allowed.path().join("bin/tool")));
+        assert!(sandbox.allows_write(&std::env::temp_dir().join("scratch")));
+        assert!(!sandbox.allows_write(&allowed.path().join("../escape")));
+        assert!(!sandbox.allows_write(Path::new("/etc/passwd")));
+    }
+
+    #[test]
+    fn test_allows_url_matches_host_rules() {
+        let sandbox = Sandbox {
+            allow_net: vec!["nodejs.org".into(), "*.github.com:443".into()],
+            ..Default::default()
+        };
+        let allows = |url: &str| sandbox.allows_url(&Url::parse(url).unwrap());
+        assert!(allows("https://nodejs.org/dist/index.json"));
+        assert!(allows("https://api.github.com/repos"));
+        assert!(!allows("http://api.github.com/repos"));
+        assert!(!allows("https://github.com/"));
+        assert!(!allows("https://evil.test/nodejs.org"));
+        assert!(!Sandbox::default().allows_url(&Url::parse("https://nodejs.org").unwrap()));
+    }
+
+    #[test]
+    fn test_allows_read_outside_denied_dirs() {
+        let home = tempfile::TempDir::new_in(".").unwrap();
+        let sandbox = Sandbox {
+            deny_read: vec![home.path().to_path_buf()],
+            allow_read: vec![home.path().join("data")],
+            allow_write: vec![home.path().join("installs")],
+            ..Default::default()
+        };
+        assert!(sandbox.allows_read(Path::new("/etc/hosts")));
+        assert!(sandbox.allows_read(&home.path().join("data/plugins/main.lua")));
+        assert!(sandbox.allows_read(&home.path().join("installs/1.0.0/bin")));
+        assert!(!sandbox.allows_read(&home.path().join(".ssh/id_ed25519")));
+        assert!(!sandbox.allows_read(&home.path().join("data/../.netrc")));
+        let lua = sandboxed_lua(sandbox.clone());
+        let secret = home.path().join(".netrc");
+        std::fs::write(&secret, "secret").unwrap();
+        for code in [
+            format!("io.open({secret:?})"),
+            format!("io.lines({secret:?})"),
+            format!("dofile({secret:?})"),
+        ] {
+            let err = lua.load(&code).exec().unwrap_err().to_string();
+            assert!(err.contains("tried to read"), "{code}: {err}");
+        }
+    }
+
+    #[test]
+    fn test_violations_name_plugin_and_hook() {
+        let lua = sandboxed_lua(Sandbox::default());
+        let err = lua
+            .load(r#"io.open("/etc/mise-sandbox-test", "w")"#)
+            .exec()
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains(
+                "sandbox: plugin test post_install hook tried to write to /etc/mise-sandbox-test"
+            ),
+            "{err}"
+        );
+        let err = lua
+            .load(r#"io.popen("id")"#)
+            .exec()
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("called io.popen"), "{err}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_commands_go_through_command_hook() {
+        let lua = sandboxed_lua(Sandbox {
+            command_hook: Some(Arc::new(|cmd: &mut Command| {
+                cmd.env("FROM_HOOK", "1");
+                Ok(Box::new(()) as Box<dyn Any + Send>)
+            })),
+            ..Default::default()
+        });
+        crate::lua_mod::cmd(&lua).unwrap();
+        let output: String = lua
+            .load(r#"return require("cmd").exec("echo $FROM_HOOK")"#)
+            .eval()
+            .unwrap();
+        assert_eq!(output, "1\n");
+    }
+}
@@ -27,6 +27,7 @@ use crate::http::{CLIENT, retry_async};
 use crate::metadata::Metadata;
 use crate::plugin::Plugin;
 use crate::registry;
+use crate::sandbox::Sandbox;
 use crate::sdk_info::SdkInfo;
 
 /// Install result containing optional checksum used for verification
@@ -68,6 +69,9 @@ pub struct Vfox {
     pub github_token_resolver: Option<Arc<dyn Fn() -> Option<String> + Send + Sync>>,
     /// Optional runtime env type (`gnu` or `musl`) exposed to plugin hooks.
     pub runtime_env_type: Option<String>,
+    /// When set, hooks run in this sandbox and the download URL returned by `PreInstall` must
+    /// be on one of its allowed hosts.
+    pub sandbox: Option<Sandbox>,
     log_tx: Option<mpsc::Sender<String>>,
 }
 
@@ -87,6 +91,7 @@ impl std::fmt::Debug for Vfox {
                 &self.github_token_resolver.as_ref().map(|_| "<closure>"),
             )
             .field("runtime_env_type", &self.runtime_env_type)
+            .field("sandbox", &self.sandbox)
             .finish_non_exhaustive()
     }
 }
@@ -168,6 +173,9 @@ impl Vfox {
             plugin.set_cmd_env(env)?;
         }
         self.set_github_token(&plugin)?;
+        if let Some(sandbox) = &self.sandbox {
+            plugin.set_sandbox(sandbox.clone())?;
+        }
         Ok(plugin)
     }
 
@@ -537,6 +545,16 @@ impl Vfox {
         version: &str,
         download_dir: &Path,
     ) -> Result<PathBuf> {
+        if let Some(sandbox) = &self.sandbox
+            && !sandbox.allows_url(url)
+        {
+            return Err(format!(
+                "sandbox: plugin {} pre_install hook returned {url}, which is not one of the allowed hosts: {}",
+                sdk.name,
+                sandbox.allow_net.join(", ")
+            )
+            .into());
+        }
         self.log_emit(format!("Downloading {url}"));
         let path = Self::download_path_for(download_dir, &sdk.name, version, url)?;
         let url_str = url.to_string();
@@ -743,6 +761,7 @@ impl Default for Vfox {
             github_token: None,
             github_token_resolver: None,
             runtime_env_type: None,
+            sandbox: None,
             log_tx: None,
         }
     }
@@ -791,6 +810,7 @@ mod tests {
                 github_token: None,
                 github_token_resolver: None,
                 runtime_env_type: None,
+                sandbox: None,
                 log_tx: None,
             }
         }
@@ -306,12 +306,18 @@ export const commands: { [key: string]: Command } = {
       clear: {
         hide: false,
       },
+      dedupe: {
+        hide: false,
+      },
       path: {
         hide: false,
       },
       prune: {
         hide: false,
       },
+      serve: {
+        hide: false,
+      },
       task: {
         hide: false,
       },
@@ -477,6 +483,11 @@ export const commands: { [key: string]: Command } = {
   },
   lock: {
     hide: false,
+    subcommands: {
+      diff: {
+        hide: false,
+      },
+    },
   },
   ls: {
     hide: false,
@@ -84,10 +84,12 @@ export const sidebar: SidebarItem[] = [
           { text: "dotnet", link: "/dev-tools/backends/dotnet" },
           { text: "forgejo", link: "/dev-tools/backends/forgejo" },
           { text: "gem", link: "/dev-tools/backends/gem" },
+          { text: "git", link: "/dev-tools/backends/git" },
           { text: "github", link: "/dev-tools/backends/github" },
           { text: "gitlab", link: "/dev-tools/backends/gitlab" },
           { text: "go", link: "/dev-tools/backends/go" },
           { text: "http", link: "/dev-tools/backends/http" },
+          { text: "maven", link: "/dev-tools/backends/maven" },
           { text: "npm", link: "/dev-tools/backends/npm" },
           { text: "pipx", link: "/dev-tools/backends/pipx" },
           { text: "pkgx", link: "/dev-tools/backends/pkgx" },
@@ -185,6 +187,7 @@ export const sidebar: SidebarItem[] = [
         items: [
           { text: "sops", link: "/environments/secrets/sops" },
           { text: "age", link: "/environments/secrets/age" },
+          { text: "Providers", link: "/environments/secrets/providers" },
         ],
       },
       { text: "Hooks", link: "/hooks" },
@@ -21,6 +21,7 @@ use crate::plugins::Script::{Download, ExecEnv, Install, ParseIdiomaticFile};
 use crate::plugins::asdf_plugin::AsdfPlugin;
 use crate::plugins::mise_plugin_toml::MisePluginToml;
 use crate::plugins::{PluginType, Script, ScriptManager};
+use crate::sandbox::{self, SandboxConfig};
 use crate::toolset::{ToolRequest, ToolVersion, Toolset};
 use crate::ui::progress_report::SingleReport;
 use crate::{backend::Backend, plugins::PluginEnum, timeout};
@@ -125,12 +126,15 @@ impl AsdfBackend {
     }
 
     fn script_man_for_version_listing(&self, env_results: &EnvResults) -> Result<ScriptManager> {
-        let mut sm = self.plugin.script_man.clone();
+        let sandbox = sandbox::plugin_sandbox(&[&self.ba.cache_path])?;
+        let mut sm = self.plugin.script_man.clone().with_sandbox(sandbox);
         for key in &env_results.env_remove {
             sm = sm.without_env(key);
         }
         for (key, (value, _)) in &env_results.env {
-            sm = sm.with_env(key, value);
+            if sm.sandbox.as_ref().is_none_or(|s| s.allows_env(key)) {
+                sm = sm.with_env(key, value);
+            }
         }
         if !env_results.env_paths.is_empty() {
             let path_key = OsString::from(&*env::PATH_KEY);
@@ -169,7 +173,7 @@ impl AsdfBackend {
         let bin_paths = if matches!(tv.request, ToolRequest::System { .. }) {
             Vec::new()
         } else if list_bin_paths.exists() {
-            let sm = self.script_man_for_tv(config, tv).await?;
+            let sm = self.script_man_for_tv(config, tv, None).await?;
             // TODO: find a way to enable this without deadlocking
             // for (t, tv) in ts.list_current_installed_versions(config) {
             //     if t.name == self.name {
@@ -202,7 +206,7 @@ impl AsdfBackend {
         ts: &Toolset,
         tv: &ToolVersion,
     ) -> Result<EnvMap> {
-        let mut sm = self.script_man_for_tv(config, tv).await?;
+        let mut sm = self.script_man_for_tv(config, tv, None).await?;
         for p in ts.list_paths(config).await {
             sm.prepend_path(p);
         }
@@ -225,8 +229,9 @@ impl AsdfBackend {
         &self,
         config: &Arc<Config>,
         tv: &ToolVersion,
+        sandbox: Option<SandboxConfig>,
     ) -> Result<ScriptManager> {
-        let mut sm = self.plugin.script_man.clone();
+        let mut sm = self.plugin.script_man.clone().with_sandbox(sandbox);
         for (key, value) in tv.request.options().opts_as_strings() {
             let k = format!("RTX_TOOL_OPTS__{}", key.to_uppercase());
             sm = sm.with_env(k, value.clone());
@@ -259,7 +264,9 @@ impl AsdfBackend {
         };
         // add env vars from mise.toml files
         for (key, value) in config.env().await? {
-            sm = sm.with_env(key, value.clone());
+            if sm.sandbox.as_ref().is_none_or(|s| s.allows_env(&key)) {
+                sm = sm.with_env(key, value.clone());
+            }
         }
         let install = tv.install_path().to_string_lossy().to_string();
         let download = tv.download_path().to_string_lossy().to_string();
@@ -327,7 +334,7 @@ impl Backend for AsdfBackend {
     async fn _list_remote_versions(&self, config: &Arc<Config>) -> Result<Vec<VersionInfo>> {
         let env_results = config.env_results().await?;
         let sm = self.script_man_for_version_listing(env_results)?;
-        let versions = self.plugin.fetch_remote_versions(&sm)?;
+        let versions = self.plugin.fetch_remote_versions(&sm).await?;
         Ok(versions
             .into_iter()
             .map(|v| VersionInfo {
@@ -348,7 +355,8 @@ impl Backend for AsdfBackend {
                     return Ok(None);
                 }
                 cache
-                    .get_or_try_init(|| self.plugin.fetch_latest_stable(&sm))
+                    .get_or_try_init_async(|| self.plugin.fetch_latest_stable(&sm))
+                    .await
                     .wrap_err_with(|| {
                         eyre!(
                             "Failed fetching latest stable version for plugin {}",
@@ -433,7 +441,12 @@ impl Backend for AsdfBackend {
     }
 
     async fn install_version_(&self, ctx: &InstallContext, tv: ToolVersion) -> Result<ToolVersion> {
-        let mut sm = self.script_man_for_tv(&ctx.config, &tv).await?;
+        let sandbox = sandbox::plugin_sandbox(&[
+            &tv.install_path(),
+            &tv.download_path(),
+            &self.ba.cache_path,
+        ])?;
+        let mut sm = self.script_man_for_tv(&ctx.config, &tv, sandbox).await?;
 
         // `ctx.ts` is the unresolved install toolset during a combined install, so it
         // does not expose tools that just finished installing. Resolve this tool's
@@ -456,14 +469,12 @@ impl Backend for AsdfBackend {
             sm.prepend_path(p);
         }
 
-        let run_script = |script| sm.run_by_line(script, ctx.pr.as_ref());
-
         if sm.script_exists(&Download) {
             ctx.pr.set_message("bin/download".into());
-            run_script(&Download)?;
+            sm.run_by_line(&Download, ctx.pr.as_ref()).await?;
         }
         ctx.pr.set_message("bin/install".into());
-        run_script(&Install)?;
+        sm.run_by_line(&Install, ctx.pr.as_ref()).await?;
         verify_install_script_output(&self.ba.short, &tv.install_path())?;
         file::remove_dir(&self.ba.downloads_path)?;
 
@@ -477,9 +488,10 @@ impl Backend for AsdfBackend {
         tv: &ToolVersion,
     ) -> Result<()> {
         if self.plugin_path.join("bin/uninstall").exists() {
-            self.script_man_for_tv(config, tv)
+            self.script_man_for_tv(config, tv, None)
                 .await?
-                .run_by_line(&Script::Uninstall, pr)?;
+                .run_by_line(&Script::Uninstall, pr)
+                .await?;
         }
         Ok(())
     }
@@ -22,9 +22,11 @@ pub enum BackendType {
     Dotnet,
     Forgejo,
     Gem,
+    Git,
     Github,
     Gitlab,
     Go,
+    Maven,
     Npm,
     Pipx,
     Pkgx,
@@ -67,9 +69,11 @@ impl BackendType {
             "dotnet" => BackendType::Dotnet,
             "forgejo" => BackendType::Forgejo,
             "gem" => BackendType::Gem,
+            "git" => BackendType::Git,
             "github" => BackendType::Github,
             "gitlab" => BackendType::Gitlab,
             "go" => BackendType::Go,
+            "maven" => BackendType::Maven,
             "npm" => BackendType::Npm,
             "pipx" => BackendType::Pipx,
             "pkgx" => BackendType::Pkgx,
@@ -84,9 +88,11 @@ impl BackendType {
 
     /// Returns true if this backend is still gated behind experimental mode.
     pub fn is_experimental(&self) -> bool {
-        use super::{dotnet, pkgx, s3, spm};
+        use super::{dotnet, git, maven, pkgx, s3, spm};
         match self {
             BackendType::Dotnet => dotnet::EXPERIMENTAL,
+            BackendType::Git => git::EXPERIMENTAL,
+            BackendType::Maven => maven::EXPERIMENTAL,
             BackendType::Pkgx => pkgx::EXPERIMENTAL,
             BackendType::S3 => s3::EXPERIMENTAL,
             BackendType::Spm => spm::EXPERIMENTAL,
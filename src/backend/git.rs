@@ -0,0 +1,291 @@
+//! Git backend for mise - builds tools from a git repository at a tag or commit
+//!
+//! The repository is cloned into the install directory and the configured
+//! `build` command runs there with the project's tools on PATH. Versions are
+//! the repository's tags; a full commit SHA may be used as a version too.
+//!
+//! ## Configuration
+//!
+//! ```toml
+//! [tools]
+//! "git:github.com/org/tool" = { version = "v1.2.3", build = "make install PREFIX=$MISE_TOOL_INSTALL_PATH" }
+//!
+//! # With an explicit clone URL and bin directory
+//! [tools."git:mytool"]
+//! version = "0123456789abcdef0123456789abcdef01234567"
+//! url = "git@example.com:org/mytool.git"
+//! build = "cargo build --release"
+//! bin_path = "target/release"
+//! ```
+
+pub const EXPERIMENTAL: bool = true;
+
+use crate::backend::backend_type::BackendType;
+use crate::backend::options::BackendOptions;
+use crate::backend::platform_target::PlatformTarget;
+use crate::backend::static_helpers::template_string;
+use crate::backend::{Backend, VersionInfo, runtime_path_for_install_path};
+use crate::cli::args::BackendArg;
+use crate::cmd::CmdLineRunner;
+use crate::config::{Config, Settings};
+use crate::file;
+use crate::git::{self, CloneOptions, Git};
+use crate::hash::hash_to_str;
+use crate::install_context::InstallContext;
+use crate::lockfile::PlatformInfo;
+use crate::toolset::{ToolVersion, ToolVersionOptions};
+use async_trait::async_trait;
+use eyre::{Result, bail, eyre};
+use std::path::PathBuf;
+use std::sync::Arc;
+use versions::Versioning;
+
+/// Git backend that builds tools from source at a tag or commit
+#[derive(Debug)]
+pub struct GitBackend {
+    ba: Arc<BackendArg>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct GitOptions<'a> {
+    values: BackendOptions<'a>,
+}
+
+impl<'a> GitOptions<'a> {
+    fn new(raw: &'a ToolVersionOptions) -> Self {
+        Self {
+            values: BackendOptions::new(raw),
+        }
+    }
+
+    fn url(&self) -> Option<String> {
+        self.values.platform_string("url")
+    }
+
+    fn build(&self) -> Option<String> {
+        self.values.platform_string("build")
+    }
+
+    fn bin_path(&self) -> Option<String> {
+        self.values.platform_string("bin_path")
+    }
+}
+
+pub fn install_time_option_keys() -> Vec<String> {
+    vec!["url".into(), "build".into(), "bin_path".into()]
+}
+
+impl GitBackend {
+    pub fn from_arg(ba: BackendArg) -> Self {
+        Self { ba: Arc::new(ba) }
+    }
+
+    /// Clone URL from the `url` option, falling back to the tool name
+    /// (`git:github.com/org/tool` clones `https://github.com/org/tool`).
+    fn repo_url(&self, opts: &GitOptions) -> String {
+        if let Some(url) = opts.url() {
+            return url;
+        }
+        repo_url_from_name(&self.tool_name())
+    }
+
+    /// Resolves the commit to build: the version itself when it is a SHA,
+    /// then the commit recorded in the lockfile, then the tag's commit upstream.
+    fn resolve_commit(&self, tv: &ToolVersion, url: &str) -> Result<String> {
+        if git::looks_like_sha(&tv.version) {
+            return Ok(tv.version.clone());
+        }
+        if let Some(commit) = tv
+            .lock_platforms
+            .get(&self.get_platform_key())
+            .and_then(|p| p.commit.clone())
+        {
+            return Ok(commit);
+        }
+        self.tag_commit(url, &tv.version)
+    }
+
+    fn tag_commit(&self, url: &str, tag: &str) -> Result<String> {
+        git::ls_remote_tags(url)?
+            .into_iter()
+            .find(|(t, _)| t == tag)
+            .map(|(_, sha)| sha)
+            .ok_or_else(|| eyre!("tag {tag} not found in {url}"))
+    }
+
+    /// Build outputs are reused across installs of the same commit and build
+    /// command, so reinstalling or switching back to a version skips the build.
+    fn build_cache_path(&self, commit: &str, build: Option<&str>) -> PathBuf {
+        self.ba
+            .cache_path
+            .join("builds")
+            .join(format!("{commit}-{}", hash_to_str(&build)))
+    }
+
+    async fn run_build(&self, ctx: &InstallContext, tv: &ToolVersion, build: &str) -> Result<()> {
+        let shell = Settings::get().default_inline_shell()?;
+        let (program, shell_args) = shell.split_first().ok_or_else(|| {
+            eyre!(
+                "default inline shell is empty; check unix_default_inline_shell_args / windows_default_inline_shell_args"
+            )
+        })?;
+        ctx.pr.set_message(format!("build {build}"));
+        CmdLineRunner::new(program)
+            .current_dir(tv.install_path())
+            .with_pr(ctx.pr.as_ref())
+            .cmd_body_args(shell_args, build)
+            .env_values(tv.install_env())
+            .env("MISE_TOOL_INSTALL_PATH", tv.install_path())
+            .env("MISE_TOOL_VERSION", &tv.version)
+            .prepend_path(ctx.ts.list_paths(&ctx.config).await)?
+            .execute()
+    }
+}
+
+fn repo_url_from_name(name: &str) -> String {
+    if name.contains("://") || name.starts_with("git@") {
+        name.to_string()
+    } else {
+        format!("https://{name}")
+    }
+}
+
+#[async_trait]
+impl Backend for GitBackend {
+    fn get_type(&self) -> BackendType {
+        BackendType::Git
+    }
+
+    fn ba(&self) -> &Arc<BackendArg> {
+        &self.ba
+    }
+
+    fn supports_lockfile_url(&self) -> bool {
+        false
+    }
+
+    fn remote_version_listing_tool_option_keys(&self) -> &'static [&'static str] {
+        &["url"]
+    }
+
+    async fn _list_remote_versions(&self, config: &Arc<Config>) -> Result<Vec<VersionInfo>> {
+        let opts = config.get_tool_opts_with_overrides(&self.ba).await?;
+        let url = self.repo_url(&GitOptions::new(&opts));
+        let mut tags = git::ls_remote_tags(&url)?;
+        tags.sort_by_cached_key(|(tag, _)| Versioning::new(tag.trim_start_matches('v')));
+        Ok(tags
+            .into_iter()
+            .map(|(tag, _)| VersionInfo {
+                version: tag,
+                ..Default::default()
+            })
+            .collect())
+    }
+
+    async fn resolve_exact_version(
+        &self,
+        _config: &Arc<Config>,
+        version: &str,
+    ) -> Result<Option<String>> {
+        // commits are not listed as versions but can be checked out directly
+        Ok(git::looks_like_sha(version).then(|| version.to_string()))
+    }
+
+    async fn resolve_lock_info(
+        &self,
+        tv: &ToolVersion,
+        _target: &PlatformTarget,
+    ) -> Result<PlatformInfo> {
+        let raw_opts = tv.request.options();
+        let url = self.repo_url(&GitOptions::new(&raw_opts));
+        Ok(PlatformInfo {
+            commit: Some(self.resolve_commit(tv, &url)?),
+            ..Default::default()
+        })
+    }
+
+    async fn install_version_(
+        &self,
+        ctx: &InstallContext,
+        mut tv: ToolVersion,
+    ) -> Result<ToolVersion> {
+        let raw_opts = tv.request.options();
+        let opts = GitOptions::new(&raw_opts);
+        let url = self.repo_url(&opts);
+        let commit = self.resolve_commit(&tv, &url)?;
+        let build = opts.build();
+        let install_path = tv.install_path();
+
+        let cache_path = self.build_cache_path(&commit, build.as_deref());
+        if cache_path.is_dir() {
+            ctx.pr.set_message(format!("restore build of {commit}"));
+            file::remove_all(&install_path)?;
+            file::copy_dir_all_preserve_symlinks(&cache_path, &install_path)?;
+        } else {
+            ctx.pr.set_message(format!("clone {url}"));
+            file::remove_all(&install_path)?;
+            Git::new(&install_path).clone(&url, CloneOptions::default().branch(&commit))?;
+            let head = Git::new(&install_path).current_sha()?;
+            if head != commit {
+                bail!("{url} checked out {head} but {commit} was expected");
+            }
+            if let Some(build) = &build {
+                self.run_build(ctx, &tv, build).await?;
+            }
+            file::remove_all(install_path.join(".git"))?;
+
+            let tmp = cache_path.with_extension("partial");
+            file::remove_all(&tmp)?;
+            file::copy_dir_all_preserve_symlinks(&install_path, &tmp)?;
+            file::rename(&tmp, &cache_path)?;
+        }
+
+        tv.lock_platforms
+            .entry(self.get_platform_key())
+            .or_default()
+            .commit = Some(commit);
+        Ok(tv)
+    }
+
+    async fn list_bin_paths(
+        &self,
+        _config: &Arc<Config>,
+        tv: &ToolVersion,
+    ) -> Result<Vec<PathBuf>> {
+        let raw_opts = tv.request.options();
+        let opts = GitOptions::new(&raw_opts);
+        if let Some(bin_path) = opts.bin_path() {
+            let bin_path = template_string(&bin_path, tv);
+            return Ok(vec![runtime_path_for_install_path(
+                tv,
+                tv.install_path().join(bin_path),
+            )]);
+        }
+        let bin_dir = tv.install_path().join("bin");
+        if bin_dir.is_dir() {
+            return Ok(vec![runtime_path_for_install_path(tv, bin_dir)]);
+        }
+        Ok(vec![tv.runtime_path()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repo_url_from_name() {
+        assert_eq!(
+            repo_url_from_name("github.com/jdx/mise"),
+            "https://github.com/jdx/mise"
+        );
+        assert_eq!(
+            repo_url_from_name("git@github.com:jdx/mise.git"),
+            "git@github.com:jdx/mise.git"
+        );
+        assert_eq!(
+            repo_url_from_name("ssh://git@example.com/org/tool"),
+            "ssh://git@example.com/org/tool"
+        );
+    }
+}
@@ -0,0 +1,969 @@
+//! Maven backend for mise - installs JVM command-line tools from Maven repositories
+//!
+//! Versions come from the artifact's `maven-metadata.xml`. Installing resolves
+//! the artifact's runtime dependencies from its POM (parents, properties,
+//! dependencyManagement and imported BOMs), downloads every jar into `lib/`
+//! after checking it against the checksum published next to it, and writes a
+//! launcher into `bin/` that runs the main class with `java` from PATH.
+//!
+//! ## Configuration
+//!
+//! ```toml
+//! [tools]
+//! "maven:com.pinterest.ktlint:ktlint-cli" = "1.5.0"
+//!
+//! # Internal repository and explicit main class
+//! [tools."maven:com.example:tool"]
+//! version = "2.1.0"
+//! repositories = ["https://maven.example.com/releases", "https://repo1.maven.org/maven2"]
+//! main_class = "com.example.tool.Main"
+//! bin = "tool"
+//! ```
+
+pub const EXPERIMENTAL: bool = true;
+
+use crate::backend::backend_type::BackendType;
+use crate::backend::options::BackendOptions;
+use crate::backend::platform_target::PlatformTarget;
+use crate::backend::{Backend, VersionInfo};
+use crate::cli::args::BackendArg;
+use crate::config::{Config, Settings};
+use crate::file;
+use crate::hash;
+use crate::http::{self, HTTP, HTTP_FETCH};
+use crate::install_context::InstallContext;
+use crate::lockfile::{ArtifactInfo, PlatformInfo};
+use crate::toolset::{ToolVersion, ToolVersionOptions};
+use crate::ui::progress_report::SingleReport;
+use crate::xml::XmlElement;
+use async_trait::async_trait;
+use eyre::{Result, bail, eyre};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt::{Display, Formatter};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use url::Url;
+use versions::Versioning;
+
+const MAVEN_CENTRAL: &str = "https://repo1.maven.org/maven2";
+
+/// Maven backend for JVM command-line tools
+#[derive(Debug)]
+pub struct MavenBackend {
+    ba: Arc<BackendArg>,
+    /// Resolved artifact sets by version and repositories. Locking resolves
+    /// the same version once per platform, so this avoids refetching POMs.
+    resolved: Mutex<HashMap<String, Vec<ArtifactInfo>>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct MavenOptions<'a> {
+    values: BackendOptions<'a>,
+}
+
+impl<'a> MavenOptions<'a> {
+    fn new(raw: &'a ToolVersionOptions) -> Self {
+        Self {
+            values: BackendOptions::new(raw),
+        }
+    }
+
+    /// Repositories to resolve from, in order. Maven Central by default.
+    fn repositories(&self) -> Vec<String> {
+        match self.values.comma_joined("repositories") {
+            Some(repos) => repos
+                .split(',')
+                .map(|r| r.trim().trim_end_matches('/').to_string())
+                .filter(|r| !r.is_empty())
+                .collect(),
+            None => vec![MAVEN_CENTRAL.to_string()],
+        }
+    }
+
+    fn main_class(&self) -> Option<String> {
+        self.values.platform_string("main_class")
+    }
+
+    fn bin(&self) -> Option<String> {
+        self.values.platform_string("bin")
+    }
+}
+
+pub fn install_time_option_keys() -> Vec<String> {
+    vec!["repositories".into(), "main_class".into(), "bin".into()]
+}
+
+impl MavenBackend {
+    pub fn from_arg(ba: BackendArg) -> Self {
+        Self {
+            ba: Arc::new(ba),
+            resolved: Default::default(),
+        }
+    }
+
+    fn coordinate(&self) -> Result<Coordinate> {
+        Coordinate::parse(&self.tool_name())
+    }
+
+    /// Resolves the artifact and its runtime dependencies to download URLs
+    /// and the checksums their repositories publish.
+    async fn resolve_artifacts(
+        &self,
+        tv: &ToolVersion,
+        repos: &[String],
+    ) -> Result<Vec<ArtifactInfo>> {
+        let key = format!("{}@{}", repos.join(","), tv.version);
+        if let Some(infos) = self.resolved.lock().unwrap().get(&key) {
+            return Ok(infos.clone());
+        }
+        let mut resolver = Resolver::new(repos);
+        let artifacts = resolver.resolve(&self.coordinate()?, &tv.version).await?;
+        let mut infos = vec![];
+        for artifact in artifacts {
+            let url = resolver.locate(&artifact).await?;
+            let checksum = published_checksum(&url)
+                .await?
+                .ok_or_else(|| eyre!("no checksum published for {url}"))?;
+            infos.push(ArtifactInfo {
+                url,
+                checksum: Some(checksum),
+                ..Default::default()
+            });
+        }
+        self.resolved.lock().unwrap().insert(key, infos.clone());
+        Ok(infos)
+    }
+
+    fn write_launcher(&self, tv: &ToolVersion, opts: &MavenOptions, main_jar: &Path) -> Result<()> {
+        let coordinate = self.coordinate()?;
+        let main_class = match opts.main_class() {
+            Some(main_class) => main_class,
+            None => jar_main_class(main_jar)?.ok_or_else(|| {
+                eyre!(
+                    "{} has no Main-Class in its manifest, set the main_class tool option",
+                    file::display_path(main_jar)
+                )
+            })?,
+        };
+        let bin = opts.bin().unwrap_or_else(|| coordinate.artifact.clone());
+        let bin_dir = tv.install_path().join("bin");
+        file::create_dir_all(&bin_dir)?;
+        if cfg!(windows) {
+            file::write(
+                bin_dir.join(format!("{bin}.cmd")),
+                format!(
+                    "@echo off\r\njava %JAVA_OPTS% -cp \"%~dp0..\\lib\\*\" {main_class} %*\r\n"
+                ),
+            )?;
+        } else {
+            let launcher = bin_dir.join(&bin);
+            file::write(
+                &launcher,
+                format!(
+                    "#!/bin/sh\n\
+                     # {coordinate}@{version} launcher generated by mise\n\
+                     MISE_MAVEN_HOME=\"$(cd \"$(dirname \"$0\")/..\" && pwd)\"\n\
+                     exec java $JAVA_OPTS -cp \"$MISE_MAVEN_HOME/lib/*\" {main_class} \"$@\"\n",
+                    version = tv.version,
+                ),
+            )?;
+            file::make_executable(&launcher)?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Backend for MavenBackend {
+    fn get_type(&self) -> BackendType {
+        BackendType::Maven
+    }
+
+    fn ba(&self) -> &Arc<BackendArg> {
+        &self.ba
+    }
+
+    fn get_dependencies(&self) -> Result<Vec<&str>> {
+        Ok(vec!["java"])
+    }
+
+    fn remote_version_listing_tool_option_keys(&self) -> &'static [&'static str] {
+        &["repositories"]
+    }
+
+    async fn _list_remote_versions(&self, config: &Arc<Config>) -> Result<Vec<VersionInfo>> {
+        let raw_opts = config.get_tool_opts_with_overrides(&self.ba).await?;
+        let repos = MavenOptions::new(&raw_opts).repositories();
+        let coordinate = self.coordinate()?;
+        let mut versions = BTreeSet::new();
+        for repo in &repos {
+            let url = format!("{}/maven-metadata.xml", coordinate.repo_dir(repo));
+            let Some(metadata) = fetch_text(&url).await? else {
+                continue;
+            };
+            versions.extend(parse_metadata_versions(&metadata)?);
+        }
+        let mut versions: Vec<_> = versions
+            .into_iter()
+            .filter(|v| !v.ends_with("-SNAPSHOT"))
+            .collect();
+        versions.sort_by_cached_key(|v| Versioning::new(v));
+        Ok(versions
+            .into_iter()
+            .map(|version| VersionInfo {
+                version,
+                ..Default::default()
+            })
+            .collect())
+    }
+
+    /// Jars are platform independent, so every platform locks the same
+    /// artifact set: the tool's jar plus its resolved dependencies.
+    async fn resolve_lock_info(
+        &self,
+        tv: &ToolVersion,
+        _target: &PlatformTarget,
+    ) -> Result<PlatformInfo> {
+        let raw_opts = tv.request.options();
+        let repos = MavenOptions::new(&raw_opts).repositories();
+        let mut artifacts = self.resolve_artifacts(tv, &repos).await?.into_iter();
+        let main = artifacts
+            .next()
+            .ok_or_else(|| eyre!("no artifacts resolved for {}", self.ba.full()))?;
+        Ok(PlatformInfo {
+            url: Some(main.url),
+            checksum: main.checksum,
+            additional_artifacts: artifacts.collect(),
+            ..Default::default()
+        })
+    }
+
+    async fn install_version_(
+        &self,
+        ctx: &InstallContext,
+        mut tv: ToolVersion,
+    ) -> Result<ToolVersion> {
+        let raw_opts = tv.request.options();
+        let opts = MavenOptions::new(&raw_opts);
+        let platform_key = self.get_platform_key();
+
+        // A locked artifact set is installed as-is so installs do not depend on
+        // POM resolution producing the same result later.
+        let locked = tv.lock_platforms.get(&platform_key).cloned();
+        let artifacts = match locked {
+            Some(PlatformInfo {
+                url: Some(url),
+                checksum,
+                additional_artifacts,
+                ..
+            }) => std::iter::once(ArtifactInfo {
+                url,
+                checksum,
+                ..Default::default()
+            })
+            .chain(additional_artifacts)
+            .collect(),
+            _ => {
+                ctx.pr.set_message("resolve dependencies".into());
+                self.resolve_artifacts(&tv, &opts.repositories()).await?
+            }
+        };
+
+        let lib_dir = tv.install_path().join("lib");
+        file::create_dir_all(&lib_dir)?;
+        let mut jars = vec![];
+        let repos = opts.repositories();
+        for artifact in &artifacts {
+            let filename = artifact_filename(&artifact.url, &repos)?;
+            let dest = lib_dir.join(&filename);
+            ctx.pr.set_message(format!("download {filename}"));
+            download(&artifact.url, &dest, Some(ctx.pr.as_ref())).await?;
+            let checksum = match &artifact.checksum {
+                Some(checksum) => checksum.clone(),
+                None => published_checksum(&artifact.url)
+                    .await?
+                    .ok_or_else(|| eyre!("no checksum published for {}", artifact.url))?,
+            };
+            let (algo, check) = checksum
+                .split_once(':')
+                .ok_or_else(|| eyre!("Invalid checksum: {checksum}"))?;
+            ctx.pr.set_message(format!("checksum {filename}"));
+            hash::ensure_checksum(&dest, check, Some(ctx.pr.as_ref()), algo)?;
+            jars.push((dest, checksum));
+        }
+
+        let main_jar = &jars
+            .first()
+            .ok_or_else(|| eyre!("no artifacts resolved for {}", self.ba.full()))?
+            .0;
+        self.write_launcher(&tv, &opts, main_jar)?;
+
+        if Settings::get().lockfile_enabled() {
+            let mut artifacts =
+                artifacts
+                    .into_iter()
+                    .zip(jars)
+                    .map(|(a, (_, checksum))| ArtifactInfo {
+                        url: a.url,
+                        checksum: Some(checksum),
+                        ..Default::default()
+                    });
+            if let Some(main) = artifacts.next() {
+                let info = tv.lock_platforms.entry(platform_key).or_default();
+                info.url = Some(main.url);
+                info.checksum = main.checksum;
+                info.additional_artifacts = artifacts.collect();
+            }
+        }
+        Ok(tv)
+    }
+}
+
+/// A `groupId:artifactId` pair
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct Coordinate {
+    group: String,
+    artifact: String,
+}
+
+impl Coordinate {
+    fn parse(name: &str) -> Result<Self> {
+        match name.split(':').collect::<Vec<_>>().as_slice() {
+            [group, artifact] if !group.is_empty() && !artifact.is_empty() => Ok(Self {
+                group: group.to_string(),
+                artifact: artifact.to_string(),
+            }),
+            _ => bail!("maven tools are named groupId:artifactId, got {name}"),
+        }
+    }
+
+    fn repo_dir(&self, repo: &str) -> String {
+        format!("{repo}/{}/{}", self.group.replace('.', "/"), self.artifact)
+    }
+
+    /// Matches exclusion patterns, which may use `*` for either part
+    fn matches(&self, other: &Coordinate) -> bool {
+        (self.group == "*" || self.group == other.group)
+            && (self.artifact == "*" || self.artifact == other.artifact)
+    }
+}
+
+impl Display for Coordinate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.group, self.artifact)
+    }
+}
+
+/// A jar to download
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Artifact {
+    coordinate: Coordinate,
+    version: String,
+    classifier: Option<String>,
+}
+
+impl Artifact {
+    fn path(&self, repo: &str) -> String {
+        let classifier = self
+            .classifier
+            .as_ref()
+            .map(|c| format!("-{c}"))
+            .unwrap_or_default();
+        format!(
+            "{}/{version}/{artifact}-{version}{classifier}.jar",
+            self.coordinate.repo_dir(repo),
+            version = self.version,
+            artifact = self.coordinate.artifact,
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Dependency {
+    coordinate: Coordinate,
+    version: Option<String>,
+    scope: Option<String>,
+    classifier: Option<String>,
+    kind: Option<String>,
+    optional: bool,
+    exclusions: Vec<Coordinate>,
+}
+
+impl Dependency {
+    fn from_xml(el: &XmlElement) -> Self {
+        Self {
+            coordinate: Coordinate {
+                group: el.child_text("groupId").unwrap_or_default().to_string(),
+                artifact: el.child_text("artifactId").unwrap_or_default().to_string(),
+            },
+            version: el.child_text("version").map(String::from),
+            scope: el.child_text("scope").map(String::from),
+            classifier: el.child_text("classifier").map(String::from),
+            kind: el.child_text("type").map(String::from),
+            optional: el.child_text("optional") == Some("true"),
+            exclusions: el
+                .path(&["exclusions"])
+                .map(|ex| {
+                    ex.children("exclusion")
+                        .map(|e| Coordinate {
+                            group: e.child_text("groupId").unwrap_or("*").to_string(),
+                            artifact: e.child_text("artifactId").unwrap_or("*").to_string(),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+
+    fn interpolate(&mut self, props: &BTreeMap<String, String>) {
+        self.coordinate.group = interpolate(&self.coordinate.group, props);
+        self.coordinate.artifact = interpolate(&self.coordinate.artifact, props);
+        for value in [
+            &mut self.version,
+            &mut self.scope,
+            &mut self.classifier,
+            &mut self.kind,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            *value = interpolate(value, props);
+        }
+    }
+
+    /// Whether the dependency is needed on the runtime classpath
+    fn is_runtime(&self) -> bool {
+        !self.optional && matches!(self.scope.as_deref(), None | Some("compile" | "runtime"))
+    }
+}
+
+/// The parts of a POM needed to resolve runtime dependencies
+#[derive(Debug, Clone, Default)]
+struct Pom {
+    group: Option<String>,
+    version: Option<String>,
+    packaging: Option<String>,
+    parent: Option<(Coordinate, String)>,
+    properties: BTreeMap<String, String>,
+    managed: Vec<Dependency>,
+    dependencies: Vec<Dependency>,
+}
+
+impl Pom {
+    fn parse(xml: &str) -> Result<Self> {
+        let project = XmlElement::parse(xml)?;
+        if project.name != "project" {
+            bail!("expected a <project> POM, found <{}>", project.name);
+        }
+        let parent = project.path(&["parent"]).and_then(|p| {
+            Some((
+                Coordinate {
+                    group: p.child_text("groupId")?.to_string(),
+                    artifact: p.child_text("artifactId")?.to_string(),
+                },
+                p.child_text("version")?.to_string(),
+            ))
+        });
+        let dependencies = |path: &[&str]| {
+            project
+                .path(path)
+                .map(|deps| {
+                    deps.children("dependency")
+                        .map(Dependency::from_xml)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        Ok(Self {
+            group: project.child_text("groupId").map(String::from),
+            version: project.child_text("version").map(String::from),
+            packaging: project.child_text("packaging").map(String::from),
+            parent,
+            properties: project
+                .path(&["properties"])
+                .map(|props| {
+                    props
+                        .children
+                        .iter()
+                        .map(|p| (p.name.clone(), p.text.trim().to_string()))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            managed: dependencies(&["dependencyManagement", "dependencies"]),
+            dependencies: dependencies(&["dependencies"]),
+        })
+    }
+
+    fn managed_version(&self, coordinate: &Coordinate) -> Option<&str> {
+        self.managed
+            .iter()
+            .find(|d| &d.coordinate == coordinate)
+            .and_then(|d| d.version.as_deref())
+    }
+}
+
+/// Replaces `${name}` references with property values. References are
+/// expanded repeatedly so properties may refer to other properties.
+pub(crate) fn interpolate(value: &str, props: &BTreeMap<String, String>) -> String {
+    let mut value = value.to_string();
+    for _ in 0..10 {
+        let mut changed = false;
+        let mut out = String::with_capacity(value.len());
+        let mut rest = value.as_str();
+        while let Some(start) = rest.find("${") {
+            out.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+            match after.find('}') {
+                Some(end) => {
+                    let name = &after[..end];
+                    match props.get(name) {
+                        Some(v) => {
+                            out.push_str(v);
+                            changed = true;
+                        }
+                        None => out.push_str(&rest[start..start + 2 + end + 1]),
+                    }
+                    rest = &after[end + 1..];
+                }
+                None => {
+                    out.push_str(&rest[start..]);
+                    rest = "";
+                }
+            }
+        }
+        out.push_str(rest);
+        value = out;
+        if !changed {
+            break;
+        }
+    }
+    value
+}
+
+/// Picks a concrete version out of a version range such as `[1.2,2.0)`.
+/// Exact pins (`[1.2]`) and lower bounds are what real-world POMs use, so the
+/// lower bound is taken rather than querying every version in between.
+fn range_version(version: &str) -> String {
+    if !version.starts_with(['[', '(']) {
+        return version.to_string();
+    }
+    version
+        .trim_matches(['[', ']', '(', ')'])
+        .split(',')
+        .map(str::trim)
+        .find(|v| !v.is_empty())
+        .unwrap_or(version)
+        .to_string()
+}
+
+struct Resolver<'a> {
+    repos: &'a [String],
+    poms: HashMap<(Coordinate, String), Arc<Pom>>,
+}
+
+impl<'a> Resolver<'a> {
+    fn new(repos: &'a [String]) -> Self {
+        Self {
+            repos,
+            poms: HashMap::new(),
+        }
+    }
+
+    async fn fetch_pom(&mut self, coordinate: &Coordinate, version: &str) -> Result<Pom> {
+        let artifact = &coordinate.artifact;
+        for repo in self.repos {
+            let url = format!(
+                "{}/{version}/{artifact}-{version}.pom",
+                coordinate.repo_dir(repo)
+            );
+            if let Some(xml) = fetch_text(&url).await? {
+                return Pom::parse(&xml).map_err(|e| eyre!("failed to parse {url}: {e}"));
+            }
+        }
+        bail!(
+            "{coordinate}:{version} not found in {}",
+            self.repos.join(", ")
+        )
+    }
+
+    /// Loads a POM with its parents' properties and dependencyManagement
+    /// merged in, imported BOMs expanded and `${...}` references resolved.
+    async fn effective_pom(&mut self, coordinate: &Coordinate, version: &str) -> Result<Arc<Pom>> {
+        let key = (coordinate.clone(), version.to_string());
+        if let Some(pom) = self.poms.get(&key) {
+            return Ok(pom.clone());
+        }
+        let mut chain = vec![];
+        let mut next = Some((coordinate.clone(), version.to_string()));
+        while let Some((c, v)) = next {
+            if chain.len() > 20 {
+                bail!("POM parent chain of {coordinate}:{version} is too deep");
+            }
+            let pom = self.fetch_pom(&c, &v).await?;
+            next = pom.parent.clone();
+            chain.push(pom);
+        }
+
+        let mut pom = chain[0].clone();
+        let parent = pom.parent.clone();
+        pom.group = pom
+            .group
+            .or_else(|| parent.as_ref().map(|(c, _)| c.group.clone()));
+        pom.version = pom
+            .version
+            .or_else(|| parent.as_ref().map(|(_, v)| v.clone()));
+        let mut props = BTreeMap::new();
+        for ancestor in chain.iter().rev() {
+            props.extend(ancestor.properties.clone());
+        }
+        for (prefix, value) in [
+            ("groupId", pom.group.clone()),
+            ("version", pom.version.clone()),
+            ("artifactId", Some(coordinate.artifact.clone())),
+        ] {
+            if let Some(value) = value {
+                props.insert(format!("project.{prefix}"), value.clone());
+                props.insert(format!("pom.{prefix}"), value);
+            }
+        }
+        if let Some((c, v)) = &parent {
+            props.insert("project.parent.groupId".into(), c.group.clone());
+            props.insert("project.parent.version".into(), v.clone());
+            props.insert("parent.version".into(), v.clone());
+        }
+        pom.managed = chain.iter().flat_map(|p| p.managed.clone()).collect();
+        for dep in pom.managed.iter_mut().chain(pom.dependencies.iter_mut()) {
+            dep.interpolate(&props);
+        }
+
+        let mut managed = vec![];
+        for dep in std::mem::take(&mut pom.managed) {
+            if dep.scope.as_deref() == Some("import") && dep.kind.as_deref() == Some("pom") {
+                if let Some(version) = &dep.version {
+                    let bom = Box::pin(self.effective_pom(&dep.coordinate, version)).await?;
+                    managed.extend(bom.managed.clone());
+                }
+            } else {
+                managed.push(dep);
+            }
+        }
+        pom.managed = managed;
+        pom.properties = props;
+
+        let pom = Arc::new(pom);
+        self.poms.insert(key, pom.clone());
+        Ok(pom)
+    }
+
+    /// Resolves the runtime classpath of an artifact. The artifact comes first;
+    /// dependencies follow breadth-first and the nearest declaration of an
+    /// artifact wins, as in Maven.
+    async fn resolve(&mut self, coordinate: &Coordinate, version: &str) -> Result<Vec<Artifact>> {
+        let root = self.effective_pom(coordinate, version).await?;
+        let mut artifacts = vec![];
+        if root.packaging.as_deref() != Some("pom") {
+            artifacts.push(Artifact {
+                coordinate: coordinate.clone(),
+                version: version.to_string(),
+                classifier: None,
+            });
+        }
+        let mut seen = HashSet::from([coordinate.clone()]);
+        let mut queue = VecDeque::from([(root.clone(), true, Vec::<Coordinate>::new())]);
+        while let Some((pom, is_root, exclusions)) = queue.pop_front() {
+            for dep in pom.dependencies.iter().filter(|d| d.is_runtime()) {
+                if exclusions.iter().any(|e| e.matches(&dep.coordinate))
+                    || !seen.insert(dep.coordinate.clone())
+                {
+                    continue;
+                }
+                // the root's dependencyManagement overrides versions of
+                // transitive dependencies, but not ones it declares itself
+                let version = if is_root {
+                    dep.version
+                        .as_deref()
+                        .or_else(|| root.managed_version(&dep.coordinate))
+                } else {
+                    root.managed_version(&dep.coordinate)
+                        .or(dep.version.as_deref())
+                        .or_else(|| pom.managed_version(&dep.coordinate))
+                };
+                let Some(version) = version.map(range_version) else {
+                    bail!("no version found for dependency {}", dep.coordinate);
+                };
+                let kind = dep.kind.as_deref().unwrap_or("jar");
+                if matches!(kind, "jar" | "bundle") {
+                    artifacts.push(Artifact {
+                        coordinate: dep.coordinate.clone(),
+                        version: version.clone(),
+                        classifier: dep.classifier.clone(),
+                    });
+                }
+                let dep_pom = self.effective_pom(&dep.coordinate, &version).await?;
+                let mut exclusions = exclusions.clone();
+                exclusions.extend(dep.exclusions.iter().cloned());
+                queue.push_back((dep_pom, false, exclusions));
+            }
+        }
+        Ok(artifacts)
+    }
+
+    /// Finds the first repository that has the artifact's jar
+    async fn locate(&self, artifact: &Artifact) -> Result<String> {
+        for repo in self.repos {
+            let url = artifact.path(repo);
+            if exists(&url).await? {
+                return Ok(url);
+            }
+        }
+        bail!(
+            "{}:{} jar not found in {}",
+            artifact.coordinate,
+            artifact.version,
+            self.repos.join(", ")
+        )
+    }
+}
+
+fn parse_metadata_versions(xml: &str) -> Result<Vec<String>> {
+    let metadata = XmlElement::parse(xml)?;
+    Ok(metadata
+        .path(&["versioning", "versions"])
+        .map(|versions| {
+            versions
+                .children("version")
+                .map(|v| v.text.trim().to_string())
+                .filter(|v| !v.is_empty())
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Reads `Main-Class` from a jar's manifest
+fn jar_main_class(jar: &Path) -> Result<Option<String>> {
+    let mut archive = zip::ZipArchive::new(file::open(jar)?)?;
+    let Ok(mut entry) = archive.by_name("META-INF/MANIFEST.MF") else {
+        return Ok(None);
+    };
+    let mut manifest = String::new();
+    entry.read_to_string(&mut manifest)?;
+    Ok(manifest_main_class(&manifest))
+}
+
+fn manifest_main_class(manifest: &str) -> Option<String> {
+    // manifest lines wrap at 72 bytes with continuations starting with a space
+    let unwrapped = manifest.replace("\r\n", "\n").replace("\n ", "");
+    unwrapped
+        .lines()
+        .find_map(|line| line.strip_prefix("Main-Class:"))
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+}
+
+/// Name of an artifact in `lib/`, prefixed with its groupId because artifacts from
+/// different groups may share the same `artifactId-version.jar`
+fn artifact_filename(url: &str, repos: &[String]) -> Result<String> {
+    let path = match repos
+        .iter()
+        .find_map(|repo| url.strip_prefix(repo.as_str())?.strip_prefix('/'))
+    {
+        Some(path) => path.to_string(),
+        // a locked url may come from a repository that is no longer configured
+        None => Url::parse(url)
+            .map(|url| url.path().to_string())
+            .unwrap_or_default(),
+    };
+    let segments = path
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>();
+    match segments.as_slice() {
+        [group @ .., _artifact, _version, jar] if !group.is_empty() && jar.ends_with(".jar") => {
+            Ok(format!("{}.{jar}", group.join(".")))
+        }
+        _ => bail!("not a jar url: {url}"),
+    }
+}
+
+/// Local path of a `file://` repository URL
+fn file_url_path(url: &str) -> Option<PathBuf> {
+    url.starts_with("file:")
+        .then(|| Url::parse(url).ok()?.to_file_path().ok())
+        .flatten()
+}
+
+/// Reads a repository file, returning None when the repository does not have it
+async fn fetch_text(url: &str) -> Result<Option<String>> {
+    if let Some(path) = file_url_path(url) {
+        return path
+            .is_file()
+            .then(|| file::read_to_string(&path))
+            .transpose();
+    }
+    match HTTP_FETCH.get_text(url).await {
+        Ok(text) => Ok(Some(text)),
+        Err(err) if http::error_code(&err) == Some(404) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+async fn exists(url: &str) -> Result<bool> {
+    if let Some(path) = file_url_path(url) {
+        return Ok(path.is_file());
+    }
+    match HTTP_FETCH.head(url).await {
+        Ok(_) => Ok(true),
+        Err(err) if http::error_code(&err) == Some(404) => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+async fn download(url: &str, dest: &Path, pr: Option<&dyn SingleReport>) -> Result<()> {
+    match file_url_path(url) {
+        Some(path) => file::copy(path, dest),
+        None => HTTP.download_file(url, dest, pr).await,
+    }
+}
+
+/// Checksum a repository publishes next to an artifact, preferring sha256 over
+/// the sha1 every Maven repository provides
+async fn published_checksum(url: &str) -> Result<Option<String>> {
+    for algo in ["sha256", "sha1"] {
+        if let Some(body) = fetch_text(&format!("{url}.{algo}")).await?
+            && let Some(hex) = body.split_whitespace().next()
+        {
+            return Ok(Some(format!("{algo}:{}", hex.to_lowercase())));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POM: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<project xmlns="http://maven.apache.org/POM/4.0.0">
+  <parent>
+    <groupId>com.example</groupId>
+    <artifactId>parent</artifactId>
+    <version>3</version>
+  </parent>
+  <artifactId>tool</artifactId>
+  <version>1.0.0</version>
+  <properties>
+    <lib.version>2.${minor}</lib.version>
+    <minor>1</minor>
+  </properties>
+  <dependencies>
+    <dependency>
+      <groupId>${project.groupId}</groupId>
+      <artifactId>lib</artifactId>
+      <version>${lib.version}</version>
+      <exclusions>
+        <exclusion><groupId>org.unwanted</groupId><artifactId>*</artifactId></exclusion>
+      </exclusions>
+    </dependency>
+    <dependency>
+      <groupId>junit</groupId>
+      <artifactId>junit</artifactId>
+      <version>4.13</version>
+      <scope>test</scope>
+    </dependency>
+    <!-- <dependency><artifactId>commented</artifactId></dependency> -->
+  </dependencies>
+</project>
+"#;
+
+    #[test]
+    fn test_parse_pom() {
+        let pom = Pom::parse(POM).unwrap();
+        assert_eq!(pom.group, None);
+        assert_eq!(
+            pom.parent,
+            Some((
+                Coordinate::parse("com.example:parent").unwrap(),
+                "3".to_string()
+            ))
+        );
+        assert_eq!(pom.dependencies.len(), 2);
+        let lib = &pom.dependencies[0];
+        assert!(lib.is_runtime());
+        assert!(!pom.dependencies[1].is_runtime());
+        assert!(lib.exclusions[0].matches(&Coordinate::parse("org.unwanted:anything").unwrap()));
+
+        let mut props = pom.properties.clone();
+        props.insert("project.groupId".into(), "com.example".into());
+        let mut lib = lib.clone();
+        lib.interpolate(&props);
+        assert_eq!(lib.coordinate.group, "com.example");
+        assert_eq!(lib.version.as_deref(), Some("2.1"));
+    }
+
+    #[test]
+    fn test_parse_metadata_versions() {
+        let xml = r#"<metadata>
+  <groupId>com.example</groupId>
+  <artifactId>tool</artifactId>
+  <versioning>
+    <release>1.1.0</release>
+    <versions><version>1.0.0</version><version>1.1.0</version></versions>
+  </versioning>
+</metadata>"#;
+        assert_eq!(
+            parse_metadata_versions(xml).unwrap(),
+            vec!["1.0.0", "1.1.0"]
+        );
+    }
+
+    #[test]
+    fn test_range_version() {
+        assert_eq!(range_version("1.2.3"), "1.2.3");
+        assert_eq!(range_version("[1.2.3]"), "1.2.3");
+        assert_eq!(range_version("[1.0,2.0)"), "1.0");
+        assert_eq!(range_version("(,2.0]"), "2.0");
+    }
+
+    #[test]
+    fn test_manifest_main_class() {
+        let manifest =
+            "Manifest-Version: 1.0\r\nMain-Class: com.example.very.long.packag\r\n e.Main\r\n\r\n";
+        assert_eq!(
+            manifest_main_class(manifest).as_deref(),
+            Some("com.example.very.long.package.Main")
+        );
+        assert_eq!(manifest_main_class("Manifest-Version: 1.0\n"), None);
+    }
+
+    #[test]
+    fn test_coordinate_parse() {
+        let c = Coordinate::parse("com.pinterest.ktlint:ktlint-cli").unwrap();
+        assert_eq!(
+            c.repo_dir(MAVEN_CENTRAL),
+            "https://repo1.maven.org/maven2/com/pinterest/ktlint/ktlint-cli"
+        );
+        assert!(Coordinate::parse("ktlint").is_err());
+    }
+
+    #[test]
+    fn test_artifact_filename() {
+        let repos = vec![MAVEN_CENTRAL.to_string()];
+        let jackson = "https://repo1.maven.org/maven2/com/fasterxml/jackson/core/jackson-core/2.17.0/jackson-core-2.17.0.jar";
+        let shaded = "https://repo1.maven.org/maven2/org/example/shaded/jackson-core/2.17.0/jackson-core-2.17.0.jar";
+        assert_eq!(
+            artifact_filename(jackson, &repos).unwrap(),
+            "com.fasterxml.jackson.core.jackson-core-2.17.0.jar"
+        );
+        assert_eq!(
+            artifact_filename(shaded, &repos).unwrap(),
+            "org.example.shaded.jackson-core-2.17.0.jar"
+        );
+        assert_eq!(
+            artifact_filename("https://mirror.example/com/acme/tool/1.0/tool-1.0.jar", &[])
+                .unwrap(),
+            "com.acme.tool-1.0.jar"
+        );
+        assert!(artifact_filename("https://repo1.maven.org/maven2/x.pom", &repos).is_err());
+    }
+}
@@ -23,6 +23,7 @@ use crate::file::{
 };
 use crate::install_before::resolve_before_date_for_tool;
 use crate::install_context::InstallContext;
+use crate::install_store::InstallStore;
 use crate::lockfile::{PlatformInfo, ProvenanceType};
 use crate::path_env::PathEnv;
 use crate::platform::Platform;
@@ -34,6 +35,7 @@ use crate::registry::{
 use crate::runtime_symlinks::is_runtime_symlink;
 use crate::semver::semver_triplet;
 use crate::tera::{contains_template_syntax, get_tera, render_str};
+use crate::timeline;
 use crate::toolset::outdated_info::OutdatedInfo;
 use crate::toolset::{
     ResolveOptions, ToolOptionSource, ToolRequest, ToolVersion, ToolVersionOptions, Toolset,
@@ -44,7 +46,7 @@ use crate::{
     cache::{CacheManager, CacheManagerBuilder},
     plugins::PluginEnum,
 };
-use crate::{dirs, env, file, hash, versions_host};
+use crate::{dirs, download_cache, env, file, hash, versions_host};
 use async_trait::async_trait;
 use backend_type::BackendType;
 use eyre::{Result, WrapErr, bail, eyre};
@@ -65,10 +67,12 @@ pub mod conda;
 pub mod dotnet;
 mod external_plugin_cache;
 pub mod gem;
+pub mod git;
 pub mod github;
 pub mod go;
 pub mod http;
 pub mod jq;
+pub mod maven;
 pub mod npm;
 pub mod npm_registry;
 pub(crate) mod options;
@@ -472,9 +476,11 @@ pub fn arg_to_backend(ba: BackendArg) -> Option<ABackend> {
         BackendType::Dotnet => Some(Arc::new(dotnet::DotnetBackend::from_arg(ba))),
         BackendType::Forgejo => Some(Arc::new(github::UnifiedGitBackend::from_arg(ba))),
         BackendType::Gem => Some(Arc::new(gem::GemBackend::from_arg(ba))),
+        BackendType::Git => Some(Arc::new(git::GitBackend::from_arg(ba))),
         BackendType::Github => Some(Arc::new(github::UnifiedGitBackend::from_arg(ba))),
         BackendType::Gitlab => Some(Arc::new(github::UnifiedGitBackend::from_arg(ba))),
         BackendType::Go => Some(Arc::new(go::GoBackend::from_arg(ba))),
+        BackendType::Maven => Some(Arc::new(maven::MavenBackend::from_arg(ba))),
         BackendType::Npm => Some(Arc::new(npm::NPMBackend::from_arg(ba))),
         BackendType::Pipx => Some(Arc::new(pipx::PIPXBackend::from_arg(ba))),
         BackendType::Pkgx => Some(Arc::new(pkgx::PkgxBackend::from_arg(ba))),
@@ -511,6 +517,8 @@ pub fn install_time_option_keys_for_type(backend_type: &BackendType) -> Vec<Stri
         BackendType::Pkgx => pkgx::install_time_option_keys(),
         BackendType::Aqua => aqua::install_time_option_keys(),
         BackendType::Spm => spm::install_time_option_keys(),
+        BackendType::Git => git::install_time_option_keys(),
+        BackendType::Maven => maven::install_time_option_keys(),
         _ => vec![],
     }
 }
@@ -2719,8 +2727,24 @@ pub trait Backend: Debug + Send + Sync {
         ctx.pr.set_message("install".into());
         self.create_install_dirs(&tv)?;
 
+        if let Some(PlatformInfo {
+            url: Some(url),
+            checksum: Some(checksum),
+            ..
+        }) = tv.lock_platforms.get(&self.get_platform_key())
+        {
+            download_cache::register_locked_checksum(url, checksum);
+        }
+
         let old_tv = tv.clone();
-        let tv = match self.install_version_(&ctx, tv).await {
+        let tv = match timeline::scope(
+            format!("install {tv}"),
+            "install",
+            None,
+            self.install_version_(&ctx, tv),
+        )
+        .await
+        {
             Ok(tv) => tv,
             Err(e) => {
                 self.cleanup_install_dirs_on_error(&old_tv);
@@ -2757,6 +2781,12 @@ pub trait Backend: Debug + Send + Sync {
                 .finish_with_message("running custom postinstall hook".to_string());
             self.run_postinstall_hook(&ctx, &tv, script).await?;
         }
+        if InstallStore::is_enabled() && install_path.starts_with(*dirs::INSTALLS) {
+            ctx.pr.set_message("dedupe".into());
+            if let Err(err) = InstallStore::get().ingest(&install_path, false) {
+                warn!("failed to add {} to the install store: {err:#}", tv.style());
+            }
+        }
         ctx.pr.finish_with_message("installed".to_string());
         Ok(tv)
     }
@@ -2952,6 +2982,9 @@ pub trait Backend: Debug + Send + Sync {
             remove_all_with_progress(dir, pr)
         };
         rmdir(&tv.install_path())?;
+        if !dryrun {
+            InstallStore::get().release(&tv.install_path())?;
+        }
         if !Settings::get().always_keep_download {
             rmdir(&tv.download_path())?;
         }
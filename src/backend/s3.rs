@@ -63,14 +63,14 @@ use url::Url;
 
 /// Parsed S3 URL components
 #[derive(Debug, Clone)]
-struct S3Url {
-    bucket: String,
-    key: String,
+pub(crate) struct S3Url {
+    pub(crate) bucket: String,
+    pub(crate) key: String,
 }
 
 impl S3Url {
     /// Parse an S3 URL like "s3://bucket/path/to/object?region=us-west-2"
-    fn parse(url_str: &str) -> Result<Self> {
+    pub(crate) fn parse(url_str: &str) -> Result<Self> {
         let url = Url::parse(url_str).map_err(|e| eyre!("Invalid S3 URL: {e}"))?;
 
         if url.scheme() != "s3" {
@@ -442,7 +442,10 @@ pub fn install_time_option_keys() -> Vec<String> {
 }
 
 /// Create an S3 client with the given configuration
-async fn create_s3_client(region: Option<&str>, endpoint: Option<&str>) -> Result<S3Client> {
+pub(crate) async fn create_s3_client(
+    region: Option<&str>,
+    endpoint: Option<&str>,
+) -> Result<S3Client> {
     let mut config_loader = aws_config::defaults(BehaviorVersion::latest());
 
     if let Some(region) = region {
@@ -461,7 +464,7 @@ async fn create_s3_client(region: Option<&str>, endpoint: Option<&str>) -> Resul
 }
 
 /// Convert S3 SDK errors to user-friendly error messages
-fn handle_s3_error<E: std::fmt::Debug>(err: E, bucket: &str, key: &str) -> eyre::Report {
+pub(crate) fn handle_s3_error<E: std::fmt::Debug>(err: E, bucket: &str, key: &str) -> eyre::Report {
     let err_str = format!("{err:?}");
 
     if err_str.contains("NoSuchKey") {
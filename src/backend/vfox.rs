@@ -120,6 +120,7 @@ impl Backend for VfoxBackend {
                 if let Ok(dep_env) = this.dependency_env(config).await {
                     vfox.cmd_env = Some(dep_env.into_iter().collect());
                 }
+                Self::apply_install_sandbox(&mut vfox, &[&this.ba.cache_path]).await?;
 
                 // Use backend methods if the plugin supports them
                 if this.is_backend_plugin() {
@@ -236,6 +237,11 @@ impl Backend for VfoxBackend {
         if !cmd_env.is_empty() {
             vfox.cmd_env = Some(cmd_env);
         }
+        Self::apply_install_sandbox(
+            &mut vfox,
+            &[&tv.install_path(), &tv.download_path(), &self.ba.cache_path],
+        )
+        .await?;
 
         // Use backend methods if the plugin supports them
         if self.is_backend_plugin() {
@@ -463,6 +469,26 @@ impl VfoxBackend {
         });
     }
 
+    /// Restricts the plugin's hooks to writing to `dirs` when `install_sandbox.enabled` is set,
+    /// and drops the env vars the sandbox doesn't allow from the commands they run.
+    async fn apply_install_sandbox(vfox: &mut vfox::Vfox, dirs: &[&Path]) -> eyre::Result<()> {
+        let Some(sandbox) = crate::sandbox::plugin_sandbox(dirs)? else {
+            return Ok(());
+        };
+        let cmd_env = vfox
+            .cmd_env
+            .take()
+            .unwrap_or_else(|| env::PRISTINE_ENV.clone().into_iter().collect());
+        vfox.cmd_env = Some(
+            cmd_env
+                .into_iter()
+                .filter(|(key, _)| sandbox.allows_env(key))
+                .collect(),
+        );
+        vfox.sandbox = Some(crate::sandbox::vfox_sandbox(&sandbox).await);
+        Ok(())
+    }
+
     fn is_backend_plugin(&self) -> bool {
         matches!(&self.plugin_enum, PluginEnum::VfoxBackend(_))
     }
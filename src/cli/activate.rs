@@ -235,5 +235,6 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
     $ <bold>mise activate fish | source</bold>
     $ <bold>execx($(mise activate xonsh))</bold>
     $ <bold>(&mise activate pwsh) | Out-String | Invoke-Expression</bold>
+    $ <bold>eval "`mise activate tcsh`"</bold>
 "#
 );
@@ -0,0 +1,37 @@
+use crate::sandbox::ResourceLimits;
+
+/// Resource limit flags shared by `mise exec` and `mise run`.
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct LimitsArg {
+    /// Limit CPU time to this many CPUs, e.g. 1.5 (Linux only)
+    #[clap(long, value_name = "CPUS", verbatim_doc_comment)]
+    pub limit_cpus: Option<f64>,
+
+    /// Kill the command when it uses more memory than this, e.g. 2GiB
+    #[clap(long, value_name = "SIZE", verbatim_doc_comment)]
+    pub limit_memory: Option<String>,
+
+    /// Limit the number of files each process can open
+    #[clap(long, value_name = "N", verbatim_doc_comment)]
+    pub limit_open_files: Option<u64>,
+
+    /// Kill the command when it writes more than this to stdout and stderr, e.g. 10MiB
+    #[clap(long, value_name = "SIZE", verbatim_doc_comment)]
+    pub limit_output_bytes: Option<String>,
+
+    /// Limit the number of processes and threads
+    #[clap(long, value_name = "N", verbatim_doc_comment)]
+    pub limit_pids: Option<u64>,
+}
+
+impl LimitsArg {
+    pub fn parse(&self) -> eyre::Result<ResourceLimits> {
+        ResourceLimits::parse(
+            self.limit_memory.as_deref(),
+            self.limit_cpus,
+            self.limit_pids,
+            self.limit_open_files,
+            self.limit_output_bytes.as_deref(),
+        )
+    }
+}
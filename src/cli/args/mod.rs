@@ -1,7 +1,9 @@
 pub use backend_arg::{BackendArg, BackendResolution, split_bracketed_opts};
 pub use env_var_arg::EnvVarArg;
+pub use limits_arg::LimitsArg;
 pub use tool_arg::{ToolArg, ToolVersionType};
 
 mod backend_arg;
 mod env_var_arg;
+mod limits_arg;
 mod tool_arg;
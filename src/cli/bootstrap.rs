@@ -1637,6 +1637,7 @@ impl Bootstrap {
             quiet: false,
             silent: false,
             raw: false,
+            remote: None,
             timings: false,
             tmpdir: Default::default(),
             tool: Default::default(),
@@ -1648,6 +1649,10 @@ impl Bootstrap {
             task_cache_explain: false,
             task_cache_explain_json: false,
             task_cache_stats: false,
+            retry: None,
+            report: vec![],
+            trace_file: None,
+            trace_format: Default::default(),
             timeout: None,
             skip_deps: false,
             // a dry run must not auto-install tools before the (not actually
@@ -1656,6 +1661,8 @@ impl Bootstrap {
             skip_tools: self.dry_run || skip_tools,
             no_deps: false,
             fresh_env: false,
+            limits: Default::default(),
+            sandbox_profile: None,
             deny_all: false,
             deny_read: false,
             deny_write: false,
@@ -0,0 +1,106 @@
+use crate::dirs::INSTALLS;
+use crate::file::{self, display_path};
+use crate::install_store::{DedupeStats, InstallStore};
+use bytesize::ByteSize;
+use eyre::Result;
+use heck::ToKebabCase;
+
+/// Deduplicate installed tools through the content-addressed install store
+///
+/// Hashes every file in the installed tool versions and replaces duplicates with
+/// links to a single copy under `$MISE_DATA_DIR/store`. New installs are added to
+/// the store automatically when the `install_store` setting is enabled; use this
+/// command to convert installs that already exist.
+///
+/// Files are hardlinked by default. Set `install_store_link_mode = "reflink"` to
+/// use copy-on-write clones on filesystems that support them.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct CacheDedupe {
+    /// Tool(s) to deduplicate
+    /// e.g.: node, python
+    tool: Option<Vec<String>>,
+
+    /// Just show how much space would be saved
+    #[clap(long)]
+    dry_run: bool,
+
+    /// Show each install as it is processed
+    #[clap(long, short, action = clap::ArgAction::Count)]
+    verbose: u8,
+}
+
+impl CacheDedupe {
+    pub fn run(self) -> Result<()> {
+        let store = InstallStore::get();
+        let tool_dirs = match &self.tool {
+            Some(tools) => tools
+                .iter()
+                .filter_map(|tool| {
+                    let kebab = tool.to_kebab_case();
+                    if kebab.is_empty() {
+                        warn!("invalid tool name: {tool}");
+                        None
+                    } else {
+                        Some(INSTALLS.join(kebab))
+                    }
+                })
+                .collect(),
+            None => file::dir_subdirs(&INSTALLS)?
+                .into_iter()
+                .map(|d| INSTALLS.join(d))
+                .collect::<Vec<_>>(),
+        };
+
+        let mut stats = DedupeStats::default();
+        let mut installs = 0;
+        for tool_dir in tool_dirs {
+            if !tool_dir.is_dir() {
+                continue;
+            }
+            for version in file::dir_subdirs(&tool_dir)? {
+                let install_path = tool_dir.join(version);
+                // runtime symlinks such as `latest` point at installs we already visit
+                if install_path.is_symlink() {
+                    continue;
+                }
+                let s = store.ingest(&install_path, self.dry_run)?;
+                if self.verbose > 0 {
+                    info!(
+                        "{}: {} files, {} linked, {}",
+                        display_path(&install_path),
+                        s.files,
+                        s.linked,
+                        bytes_str(s.bytes_saved)
+                    );
+                }
+                stats += s;
+                installs += 1;
+            }
+        }
+
+        // drop refs for installs that were removed by hand
+        let gc = store.gc(self.dry_run)?;
+        let prefix = if self.dry_run { "would save" } else { "saved" };
+        info!(
+            "deduplicated {installs} installs: {} of {} files linked, {prefix} {}",
+            stats.linked,
+            stats.files,
+            bytes_str(stats.bytes_saved + gc.bytes)
+        );
+        Ok(())
+    }
+}
+
+fn bytes_str(bytes: u64) -> String {
+    ByteSize::b(bytes).display().iec().to_string()
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise cache dedupe</bold>
+    $ <bold>mise cache dedupe node python</bold>
+    $ <bold>mise cache dedupe --dry-run</bold>
+"#
+);
@@ -4,8 +4,10 @@ use eyre::Result;
 use crate::env;
 
 mod clear;
+mod dedupe;
 mod path;
 mod prune;
+mod serve;
 mod task;
 
 /// Manage the mise cache
@@ -21,8 +23,10 @@ pub struct Cache {
 #[derive(Debug, Subcommand)]
 enum Commands {
     Clear(clear::CacheClear),
+    Dedupe(dedupe::CacheDedupe),
     Path(path::CachePath),
     Prune(prune::CachePrune),
+    Serve(serve::CacheServe),
     Task(task::CacheTask),
 }
 
@@ -30,8 +34,10 @@ impl Commands {
     pub async fn run(self) -> Result<()> {
         match self {
             Self::Clear(cmd) => cmd.run().await,
+            Self::Dedupe(cmd) => cmd.run(),
             Self::Path(cmd) => cmd.run(),
             Self::Prune(cmd) => cmd.run(),
+            Self::Serve(cmd) => cmd.run().await,
             Self::Task(cmd) => cmd.run().await,
         }
     }
@@ -0,0 +1,59 @@
+use std::path::PathBuf;
+
+use eyre::Result;
+use tokio::net::TcpListener;
+
+use crate::dirs::CACHE;
+use crate::download_cache;
+use crate::file::display_path;
+
+/// Serve tool downloads to other machines on the network
+///
+/// Starts an HTTP server that stores tool artifacts keyed by the checksum
+/// recorded in `mise.lock`. Point clients at it with the `download_cache_url`
+/// setting and they will fetch pinned artifacts from this server before
+/// falling back to upstream, then upload what they downloaded so the next
+/// machine gets a cache hit.
+///
+/// Artifacts are verified against their checksum both when they are uploaded
+/// and when clients download them.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct CacheServe {
+    /// Address to listen on
+    #[clap(long, short, default_value = "127.0.0.1:8787")]
+    bind: String,
+
+    /// Directory to store artifacts in
+    /// [default: $MISE_CACHE_DIR/download-cache]
+    #[clap(long, value_hint = clap::ValueHint::DirPath)]
+    dir: Option<PathBuf>,
+
+    /// Only serve existing artifacts, reject uploads from clients
+    #[clap(long)]
+    read_only: bool,
+}
+
+impl CacheServe {
+    pub async fn run(self) -> Result<()> {
+        let dir = self.dir.unwrap_or_else(|| CACHE.join("download-cache"));
+        let listener = TcpListener::bind(&self.bind).await?;
+        info!(
+            "serving download cache from {} on http://{}",
+            display_path(&dir),
+            listener.local_addr()?
+        );
+        download_cache::serve(listener, dir, self.read_only).await
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    # on the cache host
+    $ <bold>mise cache serve --bind 0.0.0.0:8787</bold>
+
+    # on developer machines and CI runners
+    $ <bold>mise settings set download_cache_url http://cache.lan:8787</bold>
+"#
+);
@@ -125,8 +125,10 @@ pub const EFFECTS: &[(&str, SpecCommandEffect)] = &[
     // The cache is regenerated automatically, so clearing it costs the user
     // nothing but time — `write` rather than `destructive`.
     ("cache clear", Write),
+    ("cache dedupe", Write),
     ("cache path", Read),
     ("cache prune", Write),
+    ("cache serve", Write),
     ("cache task", Read),
     ("completion", Read),
     ("config", Read),
@@ -184,6 +186,7 @@ pub const EFFECTS: &[(&str, SpecCommandEffect)] = &[
     ("latest", Read),
     ("link", Write),
     ("lock", Write),
+    ("lock diff", Read),
     ("local", Write),
     ("ls", Read),
     ("ls-remote", Read),
@@ -202,6 +205,8 @@ pub const EFFECTS: &[(&str, SpecCommandEffect)] = &[
     ("prune", Destructive),
     ("registry", Read),
     ("reshim", Write),
+    ("sandbox", Read),
+    ("sandbox show", Read),
     ("search", Read),
     ("self-update", Write),
     ("set", Write),
@@ -233,12 +238,15 @@ pub const EFFECTS: &[(&str, SpecCommandEffect)] = &[
     ("sync node", Write),
     ("sync python", Write),
     ("sync ruby", Write),
+    ("task-cache", Read),
+    ("task-cache serve", Write),
     ("tasks", Read),
     ("tasks add", Write),
     ("tasks deps", Read),
     ("tasks edit", Write),
     ("tasks graph", Read),
     ("tasks info", Read),
+    ("tasks lock", Write),
     ("tasks ls", Read),
     ("tasks validate", Read),
     ("token", Read),
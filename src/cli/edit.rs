@@ -77,9 +77,11 @@ impl BackendProvider for MiseBackendProvider {
                 BackendType::Dotnet => ("dotnet", Some("Install .NET tools")),
                 BackendType::Forgejo => ("forgejo", Some("Install from Forgejo releases")),
                 BackendType::Gem => ("gem", Some("Install Ruby gems")),
+                BackendType::Git => ("git", Some("Build from git repositories")),
                 BackendType::Github => ("github", Some("Install from GitHub releases")),
                 BackendType::Gitlab => ("gitlab", Some("Install from GitLab releases")),
                 BackendType::Go => ("go", Some("Install Go modules")),
+                BackendType::Maven => ("maven", Some("Install JVM tools from Maven repositories")),
                 BackendType::Npm => ("npm", Some("Install npm packages globally")),
                 BackendType::Pipx => ("pipx", Some("Install Python CLI tools")),
                 BackendType::Pkgx => ("pkgx", Some("Install pkgx pantry packages")),
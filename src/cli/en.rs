@@ -36,6 +36,8 @@ impl En {
             command: Some(command),
             no_deps: false,
             fresh_env: false,
+            limits: Default::default(),
+            sandbox_profile: None,
             deny_all: false,
             deny_read: false,
             deny_write: false,
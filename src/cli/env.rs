@@ -3,12 +3,16 @@ use std::{collections::BTreeMap, sync::Arc};
 
 use crate::cli::args::ToolArg;
 use crate::config::Config;
+use crate::config::env_directive::EnvResults;
 use crate::env_diff::EnvMap;
 use crate::shell::{ShellType, get_shell};
 use crate::toolset::{InstallOptions, Toolset, ToolsetBuilder};
 use crate::wildcard::wildcard_match;
+use export::EnvFormat;
 use indexmap::IndexSet;
 
+mod export;
+
 /// Exports env vars to activate mise a single time
 ///
 /// Use this if you don't want to permanently install mise. It's not necessary to
@@ -43,6 +47,25 @@ pub struct Env {
     /// Only show values of environment variables
     #[clap(long)]
     values: bool,
+
+    /// Output in a format for a CI system or container runtime
+    ///
+    /// - `github-env`: lines to append to `$GITHUB_ENV`, using heredoc syntax for multiline values
+    /// - `github-path`: the directories mise adds to PATH, to append to `$GITHUB_PATH`
+    /// - `gitlab`: a GitLab `artifacts:reports:dotenv` file
+    /// - `docker`: a file for `docker run --env-file`
+    /// - `kubernetes`: a ConfigMap named `mise-env`, with redacted variables in a Secret of the
+    ///   same name
+    /// - `systemd`: a systemd `EnvironmentFile`
+    ///
+    /// Like `--dotenv`, these leave out PATH.
+    #[clap(
+        long,
+        value_name = "FORMAT",
+        verbatim_doc_comment,
+        conflicts_with_all = ["dotenv", "json", "json_extended", "shell", "values"]
+    )]
+    format: Option<EnvFormat>,
 }
 
 impl Env {
@@ -57,29 +80,27 @@ impl Env {
             .await?;
         ts.notify_missing_versions(missing);
 
-        // Pre-compute final_env when needed by --redacted or --dotenv to
+        // Pre-compute final_env when needed by --redacted, --dotenv or --format to
         // avoid calling it twice. final_env includes the tools-only pass
         // whose redactions are not in config.env_results().
-        let final_env = if self.redacted || self.dotenv {
+        let final_env = if self.redacted || self.dotenv || self.format.is_some() {
             Some(ts.final_env(&config).await?)
         } else {
             None
         };
 
         let redacted_keys = if self.redacted {
-            let env_results = config.env_results().await?;
-            let mut keys = IndexSet::new();
-            keys.extend(env_results.redactions.clone());
-            if let Some((_, ref tools_env_results)) = final_env {
-                keys.extend(tools_env_results.redactions.clone());
-            }
-            keys.extend(config.redaction_keys());
-            Some(keys)
+            let tools_env_results = final_env.as_ref().map(|(_, results)| results);
+            Some(redaction_keys(&config, tools_env_results).await?)
         } else {
             None
         };
 
-        if self.json {
+        if let Some(format) = self.format {
+            let (env, env_results) = final_env.unwrap();
+            self.output_format(&config, ts, format, env, env_results, &redacted_keys)
+                .await
+        } else if self.json {
             self.output_json(&config, ts, &redacted_keys).await
         } else if self.json_extended {
             self.output_extended_json(&config, ts, &redacted_keys).await
@@ -213,6 +234,30 @@ impl Env {
         Ok(())
     }
 
+    async fn output_format(
+        &self,
+        config: &Arc<Config>,
+        ts: Toolset,
+        format: EnvFormat,
+        mut env: EnvMap,
+        env_results: EnvResults,
+        redacted_keys: &Option<IndexSet<String>>,
+    ) -> Result<()> {
+        let secret_keys = match redacted_keys {
+            Some(keys) => keys.clone(),
+            None => redaction_keys(config, Some(&env_results)).await?,
+        };
+        if let Some(keys) = redacted_keys {
+            env.retain(|k, _| self.should_include_key(k, keys));
+        }
+        let paths = match format {
+            EnvFormat::GithubPath => ts.list_final_paths(config, env_results).await?,
+            _ => vec![],
+        };
+        miseprint!("{}", export::render(format, &env, &paths, &secret_keys)?)?;
+        Ok(())
+    }
+
     async fn output_values(
         &self,
         config: &Arc<Config>,
@@ -238,6 +283,22 @@ impl Env {
     }
 }
 
+/// Patterns for the env vars that are redacted, from `redact` env directives, including those
+/// only evaluated with tools, and `redactions` in config files.
+async fn redaction_keys(
+    config: &Arc<Config>,
+    tools_env_results: Option<&EnvResults>,
+) -> Result<IndexSet<String>> {
+    let env_results = config.env_results().await?;
+    let mut keys = IndexSet::new();
+    keys.extend(env_results.redactions.clone());
+    if let Some(tools_env_results) = tools_env_results {
+        keys.extend(tools_env_results.redactions.clone());
+    }
+    keys.extend(config.redaction_keys());
+    Ok(keys)
+}
+
 static AFTER_LONG_HELP: &str = color_print::cstr!(
     r#"<bold><underline>Examples:</underline></bold>
 
@@ -245,5 +306,7 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
     $ <bold>eval "$(mise env -s zsh)"</bold>
     $ <bold>mise env -s fish | source</bold>
     $ <bold>execx($(mise env -s xonsh))</bold>
+    $ <bold>mise env --format github-env >> "$GITHUB_ENV"</bold>
+    $ <bold>mise env --format kubernetes | kubectl apply -f -</bold>
 "#
 );
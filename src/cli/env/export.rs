@@ -0,0 +1,256 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use eyre::{Result, bail};
+use indexmap::IndexSet;
+use serde::Serialize;
+
+use crate::env_diff::EnvMap;
+use crate::rand::random_string;
+use crate::wildcard::wildcard_match;
+
+/// Formats `mise env --format` can write the environment in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum EnvFormat {
+    /// Lines to append to `$GITHUB_ENV`, using heredoc syntax for multiline values
+    GithubEnv,
+    /// The directories mise adds to PATH, to append to `$GITHUB_PATH`
+    GithubPath,
+    /// A GitLab `artifacts:reports:dotenv` file
+    Gitlab,
+    /// A file for `docker run --env-file`
+    Docker,
+    /// A Kubernetes ConfigMap, with redacted variables in a Secret
+    Kubernetes,
+    /// A systemd `EnvironmentFile`
+    Systemd,
+}
+
+/// Name of the Kubernetes ConfigMap and Secret
+const KUBERNETES_NAME: &str = "mise-env";
+
+/// Renders `env` in `format`. `paths` are only used by [`EnvFormat::GithubPath`], and
+/// `secret_keys` are the redaction patterns that send a variable to the Kubernetes Secret.
+pub fn render(
+    format: EnvFormat,
+    env: &EnvMap,
+    paths: &[PathBuf],
+    secret_keys: &IndexSet<String>,
+) -> Result<String> {
+    match format {
+        EnvFormat::GithubEnv => Ok(env.iter().map(|(k, v)| github_env(k, v)).collect()),
+        EnvFormat::GithubPath => Ok(paths
+            .iter()
+            // GitHub prepends each line to PATH in turn, so the first path has to come last
+            .rev()
+            .map(|p| format!("{}\n", p.display()))
+            .collect()),
+        EnvFormat::Gitlab => env
+            .iter()
+            .map(|(k, v)| {
+                if !is_identifier(k) {
+                    bail!("{k} is not a valid GitLab dotenv variable name");
+                }
+                single_line("GitLab dotenv reports", k, v)?;
+                Ok(format!("{k}={v}\n"))
+            })
+            .collect(),
+        EnvFormat::Docker => env
+            .iter()
+            .map(|(k, v)| {
+                if k.contains('=') {
+                    bail!("{k} is not a valid docker --env-file variable name");
+                }
+                single_line("docker --env-file files", k, v)?;
+                Ok(format!("{k}={v}\n"))
+            })
+            .collect(),
+        EnvFormat::Kubernetes => kubernetes(env, secret_keys),
+        EnvFormat::Systemd => env
+            .iter()
+            .map(|(k, v)| {
+                if !is_identifier(k) {
+                    bail!("{k} is not a valid systemd environment variable name");
+                }
+                Ok(format!("{k}=\"{}\"\n", systemd_escape(v)))
+            })
+            .collect(),
+    }
+}
+
+fn github_env(k: &str, v: &str) -> String {
+    if !v.contains(['\n', '\r']) {
+        return format!("{k}={v}\n");
+    }
+    let delimiter = loop {
+        let delimiter = format!("ghadelimiter_{}", random_string(16));
+        if !v.contains(&delimiter) {
+            break delimiter;
+        }
+    };
+    format!("{k}<<{delimiter}\n{v}\n{delimiter}\n")
+}
+
+fn kubernetes(env: &EnvMap, secret_keys: &IndexSet<String>) -> Result<String> {
+    let mut data = BTreeMap::new();
+    let mut string_data = BTreeMap::new();
+    for (k, v) in env {
+        if k.is_empty()
+            || !k
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        {
+            bail!("{k} is not a valid Kubernetes ConfigMap or Secret key");
+        }
+        if secret_keys.iter().any(|pattern| wildcard_match(k, pattern)) {
+            string_data.insert(k.as_str(), v.as_str());
+        } else {
+            data.insert(k.as_str(), v.as_str());
+        }
+    }
+    let mut docs = vec![];
+    if !data.is_empty() || string_data.is_empty() {
+        docs.push(serde_yaml::to_string(&KubernetesObject {
+            kind: "ConfigMap",
+            data: Some(data),
+            ..KubernetesObject::default()
+        })?);
+    }
+    if !string_data.is_empty() {
+        docs.push(serde_yaml::to_string(&KubernetesObject {
+            kind: "Secret",
+            type_: Some("Opaque"),
+            string_data: Some(string_data),
+            ..KubernetesObject::default()
+        })?);
+    }
+    Ok(docs.join("---\n"))
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct KubernetesObject<'a> {
+    api_version: &'static str,
+    kind: &'static str,
+    metadata: BTreeMap<&'static str, &'static str>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    type_: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<BTreeMap<&'a str, &'a str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    string_data: Option<BTreeMap<&'a str, &'a str>>,
+}
+
+impl Default for KubernetesObject<'_> {
+    fn default() -> Self {
+        Self {
+            api_version: "v1",
+            kind: "",
+            metadata: BTreeMap::from([("name", KUBERNETES_NAME)]),
+            type_: None,
+            data: None,
+            string_data: None,
+        }
+    }
+}
+
+fn is_identifier(k: &str) -> bool {
+    !k.is_empty()
+        && !k.starts_with(|c: char| c.is_ascii_digit())
+        && k.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn single_line(format: &str, k: &str, v: &str) -> Result<()> {
+    if v.contains(['\n', '\r']) {
+        bail!("{k} has a multiline value, which {format} do not support");
+    }
+    Ok(())
+}
+
+/// Quotes a value for a double-quoted systemd `EnvironmentFile` value, which keeps newlines
+/// and unescapes `\"`, `\\`, `` \` `` and `\$`.
+fn systemd_escape(v: &str) -> String {
+    let mut out = String::with_capacity(v.len());
+    for c in v.chars() {
+        if matches!(c, '"' | '\\' | '`' | '$') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(entries: &[(&str, &str)]) -> EnvMap {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_github_env_uses_heredoc_for_multiline_values() {
+        let out = render(
+            EnvFormat::GithubEnv,
+            &env(&[("CERT", "line 1\nline 2"), ("FOO", "bar")]),
+            &[],
+            &IndexSet::new(),
+        )
+        .unwrap();
+        let mut lines = out.lines();
+        let delimiter = lines.next().unwrap().strip_prefix("CERT<<").unwrap();
+        assert!(delimiter.starts_with("ghadelimiter_"));
+        assert_eq!(
+            lines.collect::<Vec<_>>(),
+            ["line 1", "line 2", delimiter, "FOO=bar"]
+        );
+    }
+
+    #[test]
+    fn test_github_path_keeps_path_order() {
+        let paths = [PathBuf::from("/first"), PathBuf::from("/second")];
+        let out = render(EnvFormat::GithubPath, &env(&[]), &paths, &IndexSet::new()).unwrap();
+        assert_eq!(out, "/second\n/first\n");
+    }
+
+    #[test]
+    fn test_single_line_formats_reject_multiline_values() {
+        for format in [EnvFormat::Gitlab, EnvFormat::Docker] {
+            let err = render(format, &env(&[("KEY", "a\nb")]), &[], &IndexSet::new())
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains("KEY has a multiline value"), "{err}");
+        }
+    }
+
+    #[test]
+    fn test_kubernetes_routes_redacted_keys_to_secret() {
+        let secret_keys = IndexSet::from(["*_TOKEN".to_string()]);
+        let out = render(
+            EnvFormat::Kubernetes,
+            &env(&[("API_TOKEN", "s3cret"), ("NODE_ENV", "production")]),
+            &[],
+            &secret_keys,
+        )
+        .unwrap();
+        assert_eq!(
+            out,
+            "apiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: mise-env\ndata:\n  NODE_ENV: production\n---\napiVersion: v1\nkind: Secret\nmetadata:\n  name: mise-env\ntype: Opaque\nstringData:\n  API_TOKEN: s3cret\n"
+        );
+    }
+
+    #[test]
+    fn test_systemd_escapes_values() {
+        let out = render(
+            EnvFormat::Systemd,
+            &env(&[("GREETING", "say \"hi\" to $USER")]),
+            &[],
+            &IndexSet::new(),
+        )
+        .unwrap();
+        assert_eq!(out, "GREETING=\"say \\\"hi\\\" to \\$USER\"\n");
+    }
+}
@@ -9,7 +9,7 @@ use eyre::{Result, bail};
 #[cfg(any(test, windows))]
 use eyre::{Result, eyre};
 
-use crate::cli::args::ToolArg;
+use crate::cli::args::{LimitsArg, ToolArg};
 #[cfg(any(test, windows))]
 use crate::cmd;
 use crate::config::{Config, Settings};
@@ -56,7 +56,7 @@ pub struct Exec {
     pub allow_env: Vec<String>,
 
     /// Allow network to specific host (implies --deny-net for everything else)
-    /// macOS only in v1; on Linux falls back to allowing all network
+    /// On Linux, traffic goes through a filtering proxy (needs unprivileged user namespaces)
     #[clap(long, value_name = "HOST", verbatim_doc_comment)]
     pub allow_net: Vec<String>,
 
@@ -88,10 +88,17 @@ pub struct Exec {
     #[clap(long, verbatim_doc_comment)]
     pub deny_write: bool,
 
+    /// Apply the restrictions of a `[sandbox.profiles]` profile from mise.toml
+    #[clap(long, value_name = "NAME", verbatim_doc_comment)]
+    pub sandbox_profile: Option<String>,
+
     /// Bypass the environment cache and recompute the environment
     #[clap(long)]
     pub fresh_env: bool,
 
+    #[clap(flatten)]
+    pub limits: LimitsArg,
+
     /// Skip automatic dependency preparation
     #[clap(long)]
     pub no_deps: bool,
@@ -272,8 +279,14 @@ impl Exec {
                 allow_env: self.allow_env,
                 pass_through_env: vec![],
                 cache_env: vec![],
+                allow_list_dir: vec![],
+                deny_read_dirs: vec![],
+                limits: self.limits.parse()?,
             },
         );
+        if let Some(profile) = &self.sandbox_profile {
+            sandbox.merge(config.sandbox_profiles().resolve(profile)?);
+        }
         sandbox.resolve_paths();
 
         if sandbox.is_active() {
@@ -384,6 +397,31 @@ where
         ));
     }
     env::remove_var(env::MISE_SHIM_PATH_ENV);
+    // Resource limits are checked once the program exits, and per-host network filtering on
+    // Linux goes through a proxy that lives in this process, so either way the program runs as
+    // a child instead of replacing mise.
+    #[cfg(target_os = "linux")]
+    let supervised = sandbox.limits.is_set() || !sandbox.allow_net.is_empty();
+    #[cfg(not(target_os = "linux"))]
+    let supervised = sandbox.limits.is_set();
+    if supervised {
+        crate::ui::ctrlc::exit_on_ctrl_c(false);
+        let mut runner = crate::cmd::CmdLineRunner::new(&program)
+            .args(&args)
+            .envs(&env)
+            .stdin(std::process::Stdio::inherit())
+            .raw(true)
+            .with_sandbox(sandbox.clone());
+        runner.apply_sandbox().await?;
+        return match runner.execute() {
+            Ok(()) => Ok(()),
+            Err(err) if crate::errors::Error::get_limit_exceeded(&err).is_some() => Err(err),
+            Err(err) => match crate::errors::Error::get_exit_status(&err) {
+                Some(code) => Err(crate::request_exit(code)),
+                None => Err(err),
+            },
+        };
+    }
     // Apply sandbox (Landlock/seccomp on Linux, sandbox-exec on macOS)
     let args_str: Vec<String> = args
         .iter()
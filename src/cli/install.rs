@@ -8,6 +8,7 @@ use crate::config::Settings;
 use crate::errors::split_install_result;
 use crate::hooks::Hooks;
 use crate::install_before::resolve_cli_minimum_release_age;
+use crate::timeline::{self, TimelineFormat};
 use crate::toolset::{
     InstallOptions, ResolveOptions, ToolRequest, ToolRequestSet, ToolSource, Toolset, tool_env_vars,
 };
@@ -92,6 +93,31 @@ pub struct Install {
     #[clap(long, verbatim_doc_comment, conflicts_with = "shared")]
     system: bool,
 
+    /// Write a timeline of the install to a file, or send it to an OTLP collector
+    ///
+    /// Covers each tool's download, extract and verify phases. An http(s) URL
+    /// such as `http://localhost:4318/v1/traces` is sent as OTLP JSON spans.
+    #[clap(
+        long,
+        value_name = "PATH|URL",
+        env = "MISE_TRACE_FILE",
+        verbatim_doc_comment
+    )]
+    trace_file: Option<String>,
+
+    /// Format of the `--trace-file` timeline
+    ///
+    /// - `chrome` - Chrome Trace Event format, for chrome://tracing or https://ui.perfetto.dev
+    /// - `otlp` - OpenTelemetry OTLP/JSON spans
+    #[clap(
+        long,
+        value_name = "FORMAT",
+        default_value_t,
+        requires = "trace_file",
+        verbatim_doc_comment
+    )]
+    trace_format: TimelineFormat,
+
     /// Skip confirmation when installing missing plugin system dependencies.
     /// Set internally by `mise bootstrap --yes`; not a user-facing flag.
     #[clap(skip)]
@@ -119,15 +145,22 @@ impl Install {
         if !self.is_dry_run() {
             crate::lockfile::migrate_monorepo_lockfiles(&config)?;
         }
-        match &self.tool {
+        if let Some(trace_file) = &self.trace_file {
+            timeline::init(trace_file, self.trace_format, "mise install");
+        }
+        let result = match &self.tool {
             Some(runtime) => {
                 let original_tool_args = env::TOOL_ARGS.read().unwrap().clone();
                 env::TOOL_ARGS.write().unwrap().clone_from(runtime);
                 self.install_runtimes(config, runtime, original_tool_args)
-                    .await?
+                    .await
             }
-            None => self.install_missing_runtimes(config).await?,
+            None => self.install_missing_runtimes(config).await,
         };
+        if let Err(err) = timeline::finish().await {
+            warn!("failed to write trace: {err:#}");
+        }
+        result?;
         self.hint_missing_system_packages().await;
         Ok(())
     }
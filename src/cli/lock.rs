@@ -16,6 +16,8 @@ use jiff::Timestamp;
 use tokio::sync::Semaphore;
 use tokio::task::JoinSet;
 
+mod diff;
+
 /// A tool to lock for a specific lockfile target.
 type LockTool = (crate::cli::args::BackendArg, crate::toolset::ToolVersion);
 type ToolSelectors = (BTreeSet<String>, BTreeSet<String>);
@@ -31,8 +33,15 @@ fn request_matches(a: &ToolRequest, b: &ToolRequest) -> bool {
 /// This allows you to refresh lockfile data for platforms other than the one you're currently on.
 /// Operates on the lockfile in the current config root. Use TOOL arguments to target specific tools.
 #[derive(Debug, clap::Args)]
-#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+#[clap(
+    verbatim_doc_comment,
+    args_conflicts_with_subcommands = true,
+    after_long_help = AFTER_LONG_HELP
+)]
 pub struct Lock {
+    #[clap(subcommand)]
+    command: Option<Commands>,
+
     /// Tool(s) to update in lockfile
     /// e.g.: node python
     /// If not specified, all tools in lockfile will be updated
@@ -102,6 +111,19 @@ pub struct Lock {
     pub minimum_release_age: Option<String>,
 }
 
+#[derive(Debug, clap::Subcommand)]
+enum Commands {
+    Diff(diff::LockDiff),
+}
+
+impl Commands {
+    pub async fn run(self) -> Result<()> {
+        match self {
+            Self::Diff(cmd) => cmd.run().await,
+        }
+    }
+}
+
 /// A lockfile version change reported by `--json`
 #[derive(serde::Serialize)]
 struct LockChange {
@@ -127,6 +149,9 @@ enum LockTaskStatus {
 
 impl Lock {
     pub async fn run(self) -> Result<()> {
+        if let Some(cmd) = self.command {
+            return cmd.run().await;
+        }
         let settings = Settings::get();
         let config = Config::get().await?;
         if !self.dry_run {
@@ -1189,6 +1214,7 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
     $ <bold>mise lock --minimum-release-age 2024-01-01</bold>   # lock latest/fuzzy versions released before 2024-01-01
     $ <bold>mise lock --local</bold>               # update mise.local.lock for local configs
     $ <bold>mise lock --global</bold>              # update only global config lockfiles
+    $ <bold>mise lock diff origin/main</bold>      # summarize lockfile changes since origin/main
 "#
 );
 
@@ -1204,6 +1230,7 @@ mod tests {
 
     fn lock_cmd(tool_filters: &[&str]) -> Lock {
         Lock {
+            command: None,
             tool: tool_filters
                 .iter()
                 .map(|tool| ToolArg::from_str(tool).unwrap())
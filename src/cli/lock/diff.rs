@@ -0,0 +1,480 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use console::style;
+use eyre::Result;
+use itertools::Itertools;
+
+use super::Lock;
+use crate::config::Config;
+use crate::file::display_path;
+use crate::git::Git;
+use crate::lockfile::{self, Lockfile, LockfileTool, PlatformInfo};
+use crate::ui::table::MiseTable;
+
+/// Compare lockfiles against a Git revision
+///
+/// Shows a per-tool summary of what changed between the lockfile at REVISION
+/// and the working tree: version bumps, platforms added or removed, provenance
+/// changes, and checksum changes. A checksum that changes while the version
+/// stays the same is flagged, since upstream artifacts are not expected to be
+/// replaced in place.
+///
+/// Operates on the lockfiles in the current config root, like `mise lock`.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct LockDiff {
+    /// Git revision to compare the working tree against
+    #[clap(value_name = "REVISION", default_value = "HEAD")]
+    pub revision: String,
+
+    /// Output the changes as JSON
+    #[clap(long, short = 'J', conflicts_with = "markdown")]
+    pub json: bool,
+
+    /// Compare mise.local.lock instead of mise.lock
+    #[clap(long, verbatim_doc_comment)]
+    pub local: bool,
+
+    /// Output a markdown summary, e.g. for a pull request comment
+    #[clap(long, short)]
+    pub markdown: bool,
+
+    /// Link version bumps to their GitHub release when the locked URLs point at one
+    #[clap(long, verbatim_doc_comment)]
+    pub release_links: bool,
+}
+
+/// Changes to a single tool between two lockfiles
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize)]
+struct ToolDiff {
+    name: String,
+    backend: Option<String>,
+    lockfile: String,
+    old_versions: Vec<String>,
+    new_versions: Vec<String>,
+    platforms_added: Vec<String>,
+    platforms_removed: Vec<String>,
+    provenance_changes: Vec<ProvenanceChange>,
+    /// Platforms whose checksum changed while the version did not
+    checksum_changes: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    release_urls: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+struct ProvenanceChange {
+    platform: String,
+    old: Option<String>,
+    new: Option<String>,
+}
+
+impl ToolDiff {
+    fn is_empty(&self) -> bool {
+        self.old_versions == self.new_versions
+            && self.platforms_added.is_empty()
+            && self.platforms_removed.is_empty()
+            && self.provenance_changes.is_empty()
+            && self.checksum_changes.is_empty()
+    }
+
+    fn versions(&self) -> String {
+        let old = self.old_versions.join(", ");
+        let new = self.new_versions.join(", ");
+        match (old.is_empty(), new.is_empty()) {
+            _ if old == new => new,
+            (true, _) => format!("(added) {new}"),
+            (_, true) => format!("{old} (removed)"),
+            _ => format!("{old} → {new}"),
+        }
+    }
+
+    fn platforms(&self) -> String {
+        self.platforms_added
+            .iter()
+            .map(|p| format!("+{p}"))
+            .chain(self.platforms_removed.iter().map(|p| format!("-{p}")))
+            .join(" ")
+    }
+
+    fn provenance(&self) -> String {
+        self.provenance_changes
+            .iter()
+            .map(|c| {
+                format!(
+                    "{}: {} → {}",
+                    c.platform,
+                    c.old.as_deref().unwrap_or("none"),
+                    c.new.as_deref().unwrap_or("none")
+                )
+            })
+            .join(", ")
+    }
+}
+
+impl LockDiff {
+    pub async fn run(self) -> Result<()> {
+        let config = Config::get().await?;
+        let mut diffs = vec![];
+        for path in self.lockfile_paths(&config) {
+            let dir = path.parent().unwrap_or(Path::new("."));
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+            let git = Git::new(dir);
+            git.verify_revision(&self.revision)?;
+            let old =
+                match git.file_at_revision(&self.revision, Path::new(&format!("./{file_name}")))? {
+                    Some(content) => Lockfile::parse(&content)?,
+                    None => Lockfile::default(),
+                };
+            let new = Lockfile::read(&path)?;
+            diffs.extend(diff_lockfiles(&display_path(&path), &old, &new));
+        }
+        if !self.release_links {
+            for diff in &mut diffs {
+                diff.release_urls.clear();
+            }
+        }
+
+        if self.json {
+            miseprintln!("{}", serde_json::to_string_pretty(&diffs)?);
+        } else if self.markdown {
+            miseprintln!("{}", render_markdown(&diffs));
+        } else {
+            self.print_table(&diffs)?;
+        }
+        Ok(())
+    }
+
+    /// Lockfiles for the configs `mise lock` would update in the current config root
+    fn lockfile_paths(&self, config: &Config) -> BTreeSet<PathBuf> {
+        let monorepo_root = config.monorepo_lockfile_root();
+        let target_root = Lock::target_lock_scope_root(config);
+        config
+            .config_files
+            .iter()
+            .filter(|(path, cf)| {
+                cf.source().is_mise_toml()
+                    && !crate::config::is_global_config(path)
+                    && match &monorepo_root {
+                        Some(root) => path.starts_with(root),
+                        None => {
+                            target_root.as_deref()
+                                == Some(
+                                    cf.project_root()
+                                        .unwrap_or_else(|| cf.config_root())
+                                        .as_path(),
+                                )
+                        }
+                    }
+            })
+            .map(|(path, _)| lockfile::lockfile_path_for_config(path, monorepo_root.as_deref()))
+            .filter(|(_, is_local)| *is_local == self.local)
+            .map(|(path, _)| path)
+            .collect()
+    }
+
+    fn print_table(&self, diffs: &[ToolDiff]) -> Result<()> {
+        if diffs.is_empty() {
+            info!("no lockfile changes since {}", self.revision);
+            return Ok(());
+        }
+        let mut table = MiseTable::new(
+            false,
+            &["Tool", "Version", "Platforms", "Provenance", "Checksums"],
+        );
+        for diff in diffs {
+            let checksums = if diff.checksum_changes.is_empty() {
+                String::new()
+            } else {
+                style(format!("changed: {}", diff.checksum_changes.join(", ")))
+                    .red()
+                    .to_string()
+            };
+            table.add_row(vec![
+                diff.name.clone(),
+                diff.versions(),
+                diff.platforms(),
+                diff.provenance(),
+                checksums,
+            ]);
+        }
+        table.print()?;
+        for diff in diffs.iter().filter(|d| !d.checksum_changes.is_empty()) {
+            warn!(
+                "{} checksum changed without a version change in {}: {}",
+                diff.name,
+                diff.lockfile,
+                diff.checksum_changes.join(", ")
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Compares every tool in two lockfiles, returning only tools with changes
+fn diff_lockfiles(lockfile: &str, old: &Lockfile, new: &Lockfile) -> Vec<ToolDiff> {
+    let names: BTreeSet<&String> = old.tools().keys().chain(new.tools().keys()).collect();
+    names
+        .into_iter()
+        .map(|name| {
+            let old = old.tools().get(name).map(Vec::as_slice).unwrap_or_default();
+            let new = new.tools().get(name).map(Vec::as_slice).unwrap_or_default();
+            diff_tool(name, lockfile, old, new)
+        })
+        .filter(|diff| !diff.is_empty())
+        .collect()
+}
+
+fn diff_tool(name: &str, lockfile: &str, old: &[LockfileTool], new: &[LockfileTool]) -> ToolDiff {
+    let mut diff = ToolDiff {
+        name: name.to_string(),
+        backend: new.iter().chain(old).find_map(|t| t.backend.clone()),
+        lockfile: lockfile.to_string(),
+        old_versions: old.iter().map(|t| t.version.clone()).collect(),
+        new_versions: new.iter().map(|t| t.version.clone()).collect(),
+        ..Default::default()
+    };
+    for (old, new) in pair_entries(old, new) {
+        // An entry that only exists on one side is reported through its version,
+        // so listing every one of its platforms would only add noise.
+        let (Some(old), Some(new)) = (old, new) else {
+            continue;
+        };
+        let same_version = old.version == new.version;
+        let (old_platforms, new_platforms) = (&old.platforms, &new.platforms);
+        for platform in new_platforms.keys() {
+            if !old_platforms.contains_key(platform) {
+                diff.platforms_added.push(platform.clone());
+            }
+        }
+        for (platform, old_info) in old_platforms {
+            let Some(new_info) = new_platforms.get(platform) else {
+                diff.platforms_removed.push(platform.clone());
+                continue;
+            };
+            let (old_provenance, new_provenance) = (provenance(old_info), provenance(new_info));
+            if old_provenance != new_provenance {
+                diff.provenance_changes.push(ProvenanceChange {
+                    platform: platform.clone(),
+                    old: old_provenance,
+                    new: new_provenance,
+                });
+            }
+            if same_version && old_info.checksum.is_some() && old_info.checksum != new_info.checksum
+            {
+                diff.checksum_changes.push(platform.clone());
+            }
+        }
+    }
+    // Most GitHub-hosted tools lock release assets, so their URLs lead to the
+    // release page of each newly locked version.
+    diff.release_urls = new
+        .iter()
+        .filter(|t| !diff.old_versions.contains(&t.version))
+        .filter_map(|t| {
+            t.platforms
+                .values()
+                .find_map(|p| p.url.as_deref().and_then(github_release_url))
+        })
+        .unique()
+        .collect();
+    for list in [&mut diff.platforms_added, &mut diff.platforms_removed] {
+        list.sort();
+        list.dedup();
+    }
+    diff
+}
+
+/// Pairs old and new entries of a tool: identical versions first, then the
+/// remaining entries with the same options in lockfile order, which is how a
+/// version bump shows up. Anything left over was added or removed.
+fn pair_entries<'a>(
+    old: &'a [LockfileTool],
+    new: &'a [LockfileTool],
+) -> Vec<(Option<&'a LockfileTool>, Option<&'a LockfileTool>)> {
+    let mut pairs = vec![];
+    let mut old_left: Vec<&LockfileTool> = old.iter().collect();
+    let mut new_left: Vec<&LockfileTool> = vec![];
+    for n in new {
+        match old_left
+            .iter()
+            .position(|o| o.version == n.version && o.options == n.options)
+        {
+            Some(idx) => pairs.push((Some(old_left.remove(idx)), Some(n))),
+            None => new_left.push(n),
+        }
+    }
+    for n in new_left {
+        match old_left.iter().position(|o| o.options == n.options) {
+            Some(idx) => pairs.push((Some(old_left.remove(idx)), Some(n))),
+            None => pairs.push((None, Some(n))),
+        }
+    }
+    pairs.extend(old_left.into_iter().map(|o| (Some(o), None)));
+    pairs
+}
+
+fn provenance(info: &PlatformInfo) -> Option<String> {
+    info.provenance.as_ref().map(|p| {
+        if info.provenance_verified {
+            format!("{p} (verified)")
+        } else {
+            p.to_string()
+        }
+    })
+}
+
+/// Converts `https://github.com/<owner>/<repo>/releases/download/<tag>/<asset>`
+/// into the release page `https://github.com/<owner>/<repo>/releases/tag/<tag>`
+fn github_release_url(url: &str) -> Option<String> {
+    let rest = url.strip_prefix("https://github.com/")?;
+    let mut parts = rest.splitn(5, '/');
+    let (owner, repo) = (parts.next()?, parts.next()?);
+    if parts.next()? != "releases" || parts.next()? != "download" {
+        return None;
+    }
+    let tag = parts.next()?.split('/').next()?;
+    Some(format!(
+        "https://github.com/{owner}/{repo}/releases/tag/{tag}"
+    ))
+}
+
+fn render_markdown(diffs: &[ToolDiff]) -> String {
+    if diffs.is_empty() {
+        return "No lockfile changes.".to_string();
+    }
+    let cell = |s: &str| s.replace('|', "\\|");
+    let mut out = vec![
+        "| Tool | Version | Platforms | Provenance | Checksums |".to_string(),
+        "| --- | --- | --- | --- | --- |".to_string(),
+    ];
+    for diff in diffs {
+        let mut versions = cell(&diff.versions());
+        for url in &diff.release_urls {
+            versions.push_str(&format!(" ([release]({url}))"));
+        }
+        let checksums = if diff.checksum_changes.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "⚠️ **changed without a version bump**: {}",
+                diff.checksum_changes.join(", ")
+            )
+        };
+        out.push(format!(
+            "| `{}` | {} | {} | {} | {} |",
+            diff.name,
+            versions,
+            cell(&diff.platforms()),
+            cell(&diff.provenance()),
+            checksums
+        ));
+    }
+    out.join("\n")
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise lock diff</bold>                        # compare mise.lock with HEAD
+    $ <bold>mise lock diff origin/main</bold>            # compare mise.lock with another revision
+    $ <bold>mise lock diff main --markdown --release-links</bold>   # summary for a pull request comment
+    $ <bold>mise lock diff --json</bold>                 # machine-readable changes
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use super::{diff_lockfiles, github_release_url, render_markdown};
+    use crate::lockfile::{Lockfile, PlatformInfo};
+    use std::collections::BTreeMap;
+
+    fn lockfile(entries: &[(&str, &str, &str, &str)]) -> Lockfile {
+        let mut lockfile = Lockfile::default();
+        for (version, platform, checksum, url) in entries {
+            lockfile.set_platform_info(
+                "rg",
+                version,
+                Some("aqua:BurntSushi/ripgrep"),
+                &BTreeMap::new(),
+                platform,
+                PlatformInfo {
+                    checksum: Some(checksum.to_string()),
+                    url: Some(url.to_string()),
+                    ..Default::default()
+                },
+            );
+        }
+        lockfile
+    }
+
+    const OLD_URL: &str =
+        "https://github.com/BurntSushi/ripgrep/releases/download/14.0.0/ripgrep.tar.gz";
+    const NEW_URL: &str =
+        "https://github.com/BurntSushi/ripgrep/releases/download/14.1.0/ripgrep.tar.gz";
+
+    #[test]
+    fn test_unchanged_lockfile_has_no_diff() {
+        let old = lockfile(&[("14.0.0", "linux-x64", "sha256:a", OLD_URL)]);
+        assert!(diff_lockfiles("mise.lock", &old, &old.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_version_bump_and_platform_changes() {
+        let old = lockfile(&[
+            ("14.0.0", "linux-x64", "sha256:a", OLD_URL),
+            ("14.0.0", "windows-x64", "sha256:b", OLD_URL),
+        ]);
+        let new = lockfile(&[
+            ("14.1.0", "linux-x64", "sha256:c", NEW_URL),
+            ("14.1.0", "macos-arm64", "sha256:d", NEW_URL),
+        ]);
+        let diffs = diff_lockfiles("mise.lock", &old, &new);
+        assert_eq!(diffs.len(), 1);
+        let diff = &diffs[0];
+        assert_eq!(diff.versions(), "14.0.0 → 14.1.0");
+        assert_eq!(diff.platforms(), "+macos-arm64 -windows-x64");
+        // a new checksum is expected when the version changes
+        assert!(diff.checksum_changes.is_empty());
+        assert_eq!(
+            diff.release_urls,
+            vec!["https://github.com/BurntSushi/ripgrep/releases/tag/14.1.0"]
+        );
+    }
+
+    #[test]
+    fn test_checksum_change_without_version_change() {
+        let old = lockfile(&[("14.0.0", "linux-x64", "sha256:a", OLD_URL)]);
+        let new = lockfile(&[("14.0.0", "linux-x64", "sha256:evil", OLD_URL)]);
+        let diffs = diff_lockfiles("mise.lock", &old, &new);
+        assert_eq!(diffs[0].checksum_changes, vec!["linux-x64"]);
+        assert!(diffs[0].release_urls.is_empty());
+        assert!(render_markdown(&diffs).contains("changed without a version bump"));
+    }
+
+    #[test]
+    fn test_added_and_removed_tools() {
+        let old = lockfile(&[("14.0.0", "linux-x64", "sha256:a", OLD_URL)]);
+        let diffs = diff_lockfiles("mise.lock", &Lockfile::default(), &old);
+        assert_eq!(diffs[0].versions(), "(added) 14.0.0");
+        assert!(diffs[0].platforms().is_empty());
+        let diffs = diff_lockfiles("mise.lock", &old, &Lockfile::default());
+        assert_eq!(diffs[0].versions(), "14.0.0 (removed)");
+    }
+
+    #[test]
+    fn test_github_release_url() {
+        assert_eq!(
+            github_release_url(NEW_URL).as_deref(),
+            Some("https://github.com/BurntSushi/ripgrep/releases/tag/14.1.0")
+        );
+        assert_eq!(
+            github_release_url("https://nodejs.org/dist/v22.0.0/node.tar.gz"),
+            None
+        );
+        assert_eq!(
+            github_release_url("https://github.com/jdx/mise/archive/refs/tags/v1.tar.gz"),
+            None
+        );
+    }
+}
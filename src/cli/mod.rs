@@ -58,6 +58,7 @@ mod registry;
 mod render_help;
 mod reshim;
 pub mod run;
+mod sandbox;
 mod search;
 #[cfg_attr(not(feature = "self_update"), path = "self_update_stub.rs")]
 pub mod self_update;
@@ -68,6 +69,7 @@ mod shell_alias;
 mod sponsors;
 mod sync;
 pub(crate) mod system;
+mod task_cache;
 mod tasks;
 mod test_tool;
 mod token;
@@ -251,6 +253,7 @@ pub enum Commands {
     RenderHelp(render_help::RenderHelp),
     Reshim(reshim::Reshim),
     Run(Box<run::Run>),
+    Sandbox(sandbox::Sandbox),
     Search(search::Search),
     SelfUpdate(self_update::SelfUpdate),
     Set(set::Set),
@@ -259,6 +262,7 @@ pub enum Commands {
     ShellAlias(shell_alias::ShellAlias),
     Sponsors(sponsors::Sponsors),
     Sync(sync::Sync),
+    TaskCache(task_cache::TaskCache),
     Tasks(tasks::Tasks),
     TestTool(test_tool::TestTool),
     Token(token::Token),
@@ -335,6 +339,7 @@ impl Commands {
             Self::RenderHelp(cmd) => cmd.run(),
             Self::Reshim(cmd) => cmd.run().await,
             Self::Run(cmd) => (*cmd).run().await,
+            Self::Sandbox(cmd) => cmd.run().await,
             Self::Search(cmd) => cmd.run().await,
             Self::SelfUpdate(cmd) => cmd.run().await,
             Self::Set(cmd) => cmd.run().await,
@@ -343,6 +348,7 @@ impl Commands {
             Self::ShellAlias(cmd) => cmd.run().await,
             Self::Sponsors(cmd) => cmd.run(),
             Self::Sync(cmd) => cmd.run().await,
+            Self::TaskCache(cmd) => cmd.run().await,
             Self::Tasks(cmd) => cmd.run().await,
             Self::TestTool(cmd) => cmd.run().await,
             Self::Token(cmd) => cmd.run().await,
@@ -812,11 +818,17 @@ impl Cli {
                         task_cache_explain: false,
                         task_cache_explain_json: false,
                         task_cache_stats: false,
+                        retry: None,
+                        report: vec![],
+                        trace_file: None,
+                        trace_format: Default::default(),
                         timeout: None,
                         skip_deps: false,
                         skip_tools: false,
                         no_deps: false,
                         fresh_env: false,
+                        limits: Default::default(),
+                        sandbox_profile: None,
                         deny_all: false,
                         deny_read: false,
                         deny_write: false,
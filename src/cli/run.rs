@@ -5,7 +5,7 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
-use super::args::ToolArg;
+use super::args::{LimitsArg, ToolArg};
 use crate::cli::{Cli, unescape_task_args};
 use crate::config::{Config, Settings};
 use crate::deps::{DepsEngine, DepsOptions, DepsStepResult};
@@ -18,7 +18,9 @@ use crate::task::task_helpers::task_needs_permit;
 use crate::task::task_list::{get_task_lists, resolve_depends};
 use crate::task::task_output::TaskOutput;
 use crate::task::task_output_handler::OutputHandler;
+use crate::task::task_report::TaskReport;
 use crate::task::{Deps, Task, TaskCacheMode};
+use crate::timeline::{self, TimelineFormat};
 use crate::toolset::{InstallOptions, ResolveOptions, ToolVersion, ToolsetBuilder};
 use crate::ui::{ctrlc, info, style};
 use bytesize::ByteSize;
@@ -152,6 +154,12 @@ pub struct Run {
     #[clap(long, short, verbatim_doc_comment)]
     pub raw: bool,
 
+    /// Run the selected tasks on a host or tag from `[bootstrap.remote.hosts]`
+    /// Sources are synced to the host first and outputs are copied back afterwards.
+    /// Dependencies still run locally unless they set `remote` themselves.
+    #[clap(long, value_name = "HOST|TAG", verbatim_doc_comment)]
+    pub remote: Option<String>,
+
     /// Shell to use to run toml tasks
     ///
     /// Defaults to `sh -c -o errexit -o pipefail` on unix, and `cmd /c` on Windows
@@ -209,10 +217,17 @@ pub struct Run {
     #[clap(long, verbatim_doc_comment)]
     pub deny_write: bool,
 
+    /// Apply the restrictions of a `[sandbox.profiles]` profile from mise.toml
+    #[clap(long, value_name = "NAME", verbatim_doc_comment)]
+    pub sandbox_profile: Option<String>,
+
     /// Bypass the environment cache and recompute the environment
     #[clap(long)]
     pub fresh_env: bool,
 
+    #[clap(flatten)]
+    pub limits: LimitsArg,
+
     /// Do not use cache on remote tasks
     #[clap(long, verbatim_doc_comment, env = "MISE_TASK_REMOTE_NO_CACHE")]
     pub no_cache: bool,
@@ -271,6 +286,49 @@ pub struct Run {
     #[clap(long, conflicts_with = "dry_run", verbatim_doc_comment)]
     pub task_cache_stats: bool,
 
+    /// Number of times to run a failing task before giving up
+    ///
+    /// Overrides the `attempts` of each task's `retry` policy.
+    /// Tasks without a policy are retried immediately on any failure.
+    #[clap(long, value_name = "ATTEMPTS", verbatim_doc_comment)]
+    pub retry: Option<u32>,
+
+    /// Write a report of the task results, e.g. `--report junit=report.xml`
+    ///
+    /// - `junit` - JUnit XML with one testcase per task
+    /// - `ctrf` - Common Test Report Format JSON (https://ctrf.io)
+    /// - `json` - the task results as JSON
+    ///
+    /// Can be passed multiple times to write several reports.
+    #[clap(long, value_name = "FORMAT=PATH", verbatim_doc_comment)]
+    pub report: Vec<TaskReport>,
+
+    /// Write a timeline of the run to a file, or send it to an OTLP collector
+    ///
+    /// Covers each task's queue wait, tool installation, cache lookup and
+    /// restore, and execution. An http(s) URL such as
+    /// `http://localhost:4318/v1/traces` is sent as OTLP JSON spans.
+    #[clap(
+        long,
+        value_name = "PATH|URL",
+        env = "MISE_TRACE_FILE",
+        verbatim_doc_comment
+    )]
+    pub trace_file: Option<String>,
+
+    /// Format of the `--trace-file` timeline
+    ///
+    /// - `chrome` - Chrome Trace Event format, for chrome://tracing or https://ui.perfetto.dev
+    /// - `otlp` - OpenTelemetry OTLP/JSON spans
+    #[clap(
+        long,
+        value_name = "FORMAT",
+        default_value_t,
+        requires = "trace_file",
+        verbatim_doc_comment
+    )]
+    pub trace_format: TimelineFormat,
+
     /// Timeout for the task to complete
     /// e.g.: 30s, 5m
     #[clap(long, verbatim_doc_comment)]
@@ -338,10 +396,17 @@ async fn get_affected_task_list(
     let global_inputs = config.monorepo_global_task_inputs().await?;
     let git = crate::git::Git::new(&workspace_root);
     let cargo = crate::task::workspace::cargo::CargoWorkspaceProvider;
+    let dart = crate::task::workspace::dart::DartWorkspaceProvider;
+    let deno = crate::task::workspace::deno::DenoWorkspaceProvider;
+    let dotnet = crate::task::workspace::dotnet::DotnetWorkspaceProvider;
     let go = crate::task::workspace::go::GoWorkspaceProvider;
+    let gradle = crate::task::workspace::gradle::GradleWorkspaceProvider;
+    let maven = crate::task::workspace::maven::MavenWorkspaceProvider;
     let node = crate::task::workspace::node::NodeWorkspaceProvider;
     let uv = crate::task::workspace::uv::UvWorkspaceProvider;
-    let providers: [&dyn crate::task::workspace::WorkspaceProvider; 4] = [&cargo, &go, &node, &uv];
+    let providers: [&dyn crate::task::workspace::WorkspaceProvider; 9] = [
+        &cargo, &dart, &deno, &dotnet, &go, &gradle, &maven, &node, &uv,
+    ];
     let mut regular_paths = BTreeSet::new();
     let mut lockfile_projects = BTreeMap::<PathBuf, BTreeSet<_>>::new();
     let mut comparison_base: Option<String> = None;
@@ -665,6 +730,11 @@ impl Run {
         // file-based remote tasks have their files resolved to local cache.
         let fetcher = crate::task::task_fetcher::TaskFetcher::new(self.no_cache);
         fetcher.fetch_tasks(&config, &mut task_list).await?;
+        if let Some(remote) = &self.remote {
+            for task in &mut task_list {
+                task.remote = Some(remote.clone());
+            }
+        }
 
         // Re-render dependency templates with parent task's usage arg/flag values.
         // This enables patterns like: depends = ["child {{usage.app}}"]
@@ -816,17 +886,26 @@ impl Run {
             Settings::get().task_timeout_duration()
         };
 
-        if let Some(timeout) = timeout {
+        if let Some(trace_file) = &self.trace_file {
+            timeline::init(trace_file, self.trace_format, "mise run");
+        }
+        let result = if let Some(timeout) = timeout {
             tokio::time::timeout(
                 timeout,
                 self.parallelize_tasks(config, execution_tasks, previewed_tools),
             )
             .await
-            .map_err(|_| eyre!("mise run timed out after {:?}", timeout))??
+            .map_err(|_| eyre!("mise run timed out after {:?}", timeout))
+            .and_then(|r| r)
         } else {
             self.parallelize_tasks(config, execution_tasks, previewed_tools)
-                .await?
+                .await
+        };
+        // Failed runs are the ones most worth a trace, so write it either way.
+        if let Err(err) = timeline::finish().await {
+            warn!("failed to write trace: {err:#}");
         }
+        result?;
 
         time!("run done");
         Ok(())
@@ -849,6 +928,7 @@ impl Run {
         time!("parallelize_tasks start");
 
         // Step 1: Prepare tasks (resolve dependencies, fetch, validate)
+        let requested = tasks.clone();
         let tasks = self.prepare_tasks(&config, tasks).await?;
         let num_tasks = tasks.all().count();
 
@@ -863,7 +943,12 @@ impl Run {
         }
 
         // Step 4: Create TaskExecutor after tool installation
-        self.setup_executor()?;
+        self.setup_executor(&config)?;
+        self.executor
+            .as_ref()
+            .unwrap()
+            .services
+            .set_requested(&requested);
 
         // Disable exit-on-ctrl-c so tasks can handle SIGINT gracefully
         ctrlc::exit_on_ctrl_c(false);
@@ -879,7 +964,7 @@ impl Run {
         // Pump deps leaves into scheduler
         let mut main_done_rx = scheduler.pump_deps(main_deps.clone()).await;
         let spawn_context = scheduler.spawn_context(config.clone());
-        scheduler
+        let result = scheduler
             .run_loop(
                 &mut main_done_rx,
                 main_deps.clone(),
@@ -901,18 +986,36 @@ impl Run {
                     }
                 },
             )
-            .await?;
+            .await;
 
+        // Only services can still be running once the graph is done. Services the
+        // user asked for keep running until they exit, unless the run failed.
+        let services = &this.executor.as_ref().unwrap().services;
+        if result.is_ok() && !this.is_stopping() {
+            services.stop_dependencies().await;
+        } else {
+            services.stop_all().await;
+        }
+        this.executor
+            .as_ref()
+            .unwrap()
+            .remote_hosts
+            .disconnect()
+            .await;
+        result?;
         scheduler.join_all(this.continue_on_error).await?;
 
         // Step 5: Display results and handle failures
         let results_display = crate::task::task_results_display::TaskResultsDisplay::new(
             this.output_handler.clone().unwrap(),
             this.executor.as_ref().unwrap().failed_tasks.clone(),
+            this.executor.as_ref().unwrap().retried_tasks.clone(),
+            this.executor.as_ref().unwrap().results.clone(),
             this.continue_on_error,
             this.timings(),
             this.is_interrupted(),
-        );
+        )
+        .with_reports(this.report.clone());
         let result = results_display.display_results(num_tasks, timer);
         if this.task_cache_stats {
             this.display_task_cache_stats();
@@ -930,6 +1033,7 @@ impl Run {
         inherited_allow_during_interruption: bool,
         ctx: crate::task::task_scheduler::SpawnContext,
     ) -> Result<()> {
+        let queued = std::time::Instant::now();
         if Self::should_abort_while_stopping(
             &this,
             &task,
@@ -989,32 +1093,94 @@ impl Run {
         // always sees the parent in `executed` — avoiding a race where a
         // concurrent task fails between spawn and first poll.
         deps_for_remove.lock().await.mark_executed(&task);
+        let service = match this.executor.as_ref().unwrap().services.start(&task) {
+            Ok(service) => service,
+            Err(err) => {
+                in_flight_c.fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
+                deps_for_remove.lock().await.remove(&task);
+                return Err(err);
+            }
+        };
+        if let Some(service) = service.clone() {
+            // Dependents of a service start once it is ready rather than once it exits.
+            let this = this.clone();
+            let task = task.clone();
+            let deps_for_remove = deps_for_remove.clone();
+            let in_flight_c = in_flight_c.clone();
+            tokio::spawn(async move {
+                if service.wait_ready().await && service.release() {
+                    if !this.quiet(Some(&task)) {
+                        this.eprint(&task, &task.estyled_prefix(), "service ready");
+                    }
+                    deps_for_remove.lock().await.remove(&task);
+                    in_flight_c.fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
+                }
+            });
+        }
         let semaphore = ctx.semaphore.clone();
-        ctx.jset.lock().await.spawn(async move {
+        let jset = ctx.jset.clone();
+        jset.lock().await.spawn(async move {
             let mut permit = permit_opt;
+            let started = std::time::Instant::now();
+            this.executor.as_ref().unwrap().results.start(&task);
             let (completion_state, dependency_state) = {
                 let deps = deps_for_remove.lock().await;
                 (deps.completion_state(), deps.dependency_state(&task))
             };
-            let (result, panicked) = match AssertUnwindSafe(this.run_task_sched(TaskRunContext {
-                task: &task,
-                config: &ctx.config,
-                sched_tx: ctx.sched_tx.clone(),
-                completion_state,
-                dependency_state,
-                semaphore,
-                permit: &mut permit,
-                allow_during_interruption,
-            }))
-            .catch_unwind()
-            .await
-            {
+            let run = async {
+                timeline::record("queue", "queue", queued, started);
+                this.run_task_sched(TaskRunContext {
+                    task: &task,
+                    config: &ctx.config,
+                    sched_tx: ctx.sched_tx.clone(),
+                    completion_state,
+                    dependency_state,
+                    semaphore,
+                    permit: &mut permit,
+                    allow_during_interruption,
+                })
+                .await
+            };
+            let run = timeline::scope(task.display_name.clone(), "task", Some(queued), run);
+            let (result, panicked) = match AssertUnwindSafe(run).catch_unwind().await {
                 Ok(result) => (result, false),
                 Err(payload) => (
                     Err(eyre!("task panicked: {}", panic_payload_message(&payload))),
                     true,
                 ),
             };
+            // A service stopped by mise at the end of the run has not failed,
+            // and one that already released its dependents is no longer in the graph.
+            let (result, released) = match &service {
+                Some(service) => {
+                    service.finish();
+                    let result = if service.is_stopping() {
+                        Ok(Default::default())
+                    } else {
+                        result
+                    };
+                    (result, !service.release())
+                }
+                None => (result, false),
+            };
+            if released {
+                this.record_result(&task, &result, started.elapsed());
+                if let Some(oh) = &this.output_handler
+                    && oh.output(Some(&task)) == TaskOutput::KeepOrder
+                {
+                    oh.keep_order_state.lock().unwrap().on_task_finished(&task);
+                }
+                return match result {
+                    Err(err) if !panicked && !this.is_stopping() => {
+                        let prefix = task.estyled_prefix();
+                        this.eprint(&task, &prefix, &format!("{} {err}", style::ered("ERROR")));
+                        this.add_failed_task(task.clone(), Error::get_exit_status(&err));
+                        Err(err)
+                    }
+                    Err(err) if panicked => Err(err),
+                    _ => Ok(()),
+                };
+            }
             // If the task executed or restored outputs and has sources defined,
             // mark it so dependents' source freshness checks are invalidated.
             // Tasks without sources always run and should not trigger invalidation.
@@ -1030,6 +1196,25 @@ impl Run {
             let interrupted = result.as_ref().is_err_and(|err| {
                 !panicked && ctrlc::is_cancelled() && Error::is_task_interrupted(err)
             });
+            if let Err(err) = &result
+                && !panicked
+                && !interrupted
+                && let Some(delay) = this.retry_delay(&task, Error::get_exit_status(err))
+            {
+                // Leave the task in the graph and hand it back to the scheduler;
+                // dependents keep waiting and dependencies are not rerun. The
+                // jobs slot is freed while waiting out the backoff.
+                drop(permit);
+                if delay > Duration::ZERO {
+                    tokio::time::sleep(delay).await;
+                }
+                ctx.requeue(task, deps_for_remove, allow_during_interruption);
+                in_flight_c.fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
+                return Ok(());
+            }
+            if !interrupted {
+                this.record_result(&task, &result, started.elapsed());
+            }
             if let Err(err) = &result {
                 if interrupted {
                     this.mark_interrupted();
@@ -1183,7 +1368,29 @@ impl Run {
     }
 
     /// Create TaskExecutor after tool installation to ensure caches are populated
-    fn setup_executor(&mut self) -> Result<()> {
+    fn setup_executor(&mut self, config: &Config) -> Result<()> {
+        let mut sandbox = crate::sandbox::SandboxConfig::from_settings_and_cli(
+            &Settings::get().sandbox,
+            self.deny_all,
+            crate::sandbox::SandboxConfig {
+                deny_read: self.deny_read,
+                deny_write: self.deny_write,
+                deny_net: self.deny_net,
+                deny_env: self.deny_env,
+                allow_read: self.allow_read.clone(),
+                allow_write: self.allow_write.clone(),
+                allow_net: self.allow_net.clone(),
+                allow_env: self.allow_env.clone(),
+                pass_through_env: vec![],
+                cache_env: vec![],
+                allow_list_dir: vec![],
+                deny_read_dirs: vec![],
+                limits: self.limits.parse()?,
+            },
+        );
+        if let Some(profile) = &self.sandbox_profile {
+            sandbox.merge(config.sandbox_profiles().resolve(profile)?);
+        }
         let executor_config = crate::task::task_executor::TaskExecutorConfig {
             force: self.force,
             cd: self.cd.clone(),
@@ -1196,22 +1403,9 @@ impl Run {
             task_cache: self.task_cache,
             task_cache_explain: self.task_cache_explain,
             task_cache_explain_json: self.task_cache_explain_json,
-            sandbox: crate::sandbox::SandboxConfig::from_settings_and_cli(
-                &Settings::get().sandbox,
-                self.deny_all,
-                crate::sandbox::SandboxConfig {
-                    deny_read: self.deny_read,
-                    deny_write: self.deny_write,
-                    deny_net: self.deny_net,
-                    deny_env: self.deny_env,
-                    allow_read: self.allow_read.clone(),
-                    allow_write: self.allow_write.clone(),
-                    allow_net: self.allow_net.clone(),
-                    allow_env: self.allow_env.clone(),
-                    pass_through_env: vec![],
-                    cache_env: vec![],
-                },
-            ),
+            retry: self.retry,
+            capture_stderr: !self.report.is_empty(),
+            sandbox,
         };
         self.executor = Some(crate::task::task_executor::TaskExecutor::new(
             self.context_builder.clone(),
@@ -1233,9 +1427,13 @@ impl Run {
             &self.context_builder,
             &self.tool,
         );
-        installer
-            .install_tools(config, tasks, self.dry_run, previewed_tools)
-            .await
+        timeline::scope(
+            "install tools",
+            "tools",
+            None,
+            installer.install_tools(config, tasks, self.dry_run, previewed_tools),
+        )
+        .await
     }
 
     // ============================================================================
@@ -1292,6 +1490,58 @@ impl Run {
             .await
     }
 
+    /// If the failed task should run again, records the retry, reports it and
+    /// returns how long to wait before requeueing it.
+    fn retry_delay(&self, task: &Task, status: Option<i32>) -> Option<Duration> {
+        if self.is_stopping() && !self.continue_on_error {
+            return None;
+        }
+        let executor = self.executor.as_ref()?;
+        let retry = executor.retry_policy(task)?;
+        let attempt = executor.attempt(task);
+        if !retry.should_retry(attempt, status) {
+            return None;
+        }
+        let delay = match retry.delay(attempt) {
+            Ok(delay) => delay,
+            Err(err) => {
+                warn!("invalid retry backoff for task {}: {err}", task.name);
+                Duration::ZERO
+            }
+        };
+        let next = executor.record_retry(task);
+        let status = status
+            .map(|s| format!("exited with status {s}"))
+            .unwrap_or_else(|| "failed".to_string());
+        let wait = if delay.is_zero() {
+            String::new()
+        } else {
+            format!(" in {}", crate::ui::time::format_duration(delay))
+        };
+        let prefix = task.estyled_prefix();
+        self.eprint(
+            task,
+            &prefix,
+            &format!(
+                "{} {status}, retrying{wait} (attempt {next}/{})",
+                style::eyellow("WARN"),
+                retry.attempts
+            ),
+        );
+        Some(delay)
+    }
+
+    fn record_result(
+        &self,
+        task: &Task,
+        result: &Result<crate::task::task_executor::TaskRunOutcome>,
+        duration: Duration,
+    ) {
+        if let Some(executor) = &self.executor {
+            executor.results.record(task, result.as_ref(), duration);
+        }
+    }
+
     fn add_failed_task(&self, task: Task, status: Option<i32>) {
         if let Some(executor) = &self.executor {
             executor.add_failed_task(task, status);
@@ -0,0 +1,25 @@
+use clap::Subcommand;
+use eyre::Result;
+
+mod show;
+
+/// Inspect sandbox profiles and the sandboxes tasks run in
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+pub struct Sandbox {
+    #[clap(subcommand)]
+    command: Commands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Commands {
+    Show(show::SandboxShow),
+}
+
+impl Sandbox {
+    pub async fn run(self) -> Result<()> {
+        match self.command {
+            Commands::Show(cmd) => cmd.run().await,
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use eyre::{Result, bail};
+
+use crate::config::{Config, Settings};
+use crate::sandbox::{SandboxConfig, SandboxProfile, describe_platform_rules};
+use crate::task::GetMatchingExt;
+use crate::task::task_executor::build_task_sandbox;
+
+/// Show the effective sandbox of a profile or task
+///
+/// Prints the resolved policy as a `[sandbox.profiles]` table, with `extends`
+/// applied, templates like `{{config_root}}` rendered and paths made absolute.
+/// It is followed by the rules that enforce it on this platform: Landlock rules
+/// and network filtering on Linux, or the Seatbelt profile on macOS.
+///
+/// A task's sandbox combines its own sandbox fields with its `sandbox` profile.
+/// Both include the `sandbox.*` settings.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct SandboxShow {
+    /// Name of a sandbox profile or task
+    name: String,
+}
+
+impl SandboxShow {
+    pub async fn run(self) -> Result<()> {
+        let config = Config::get().await?;
+        let profiles = config.sandbox_profiles();
+        let settings = SandboxConfig::from_settings_and_cli(
+            &Settings::get().sandbox,
+            false,
+            SandboxConfig::default(),
+        );
+        let (header, sandbox) = if profiles.contains(&self.name) {
+            let mut sandbox = settings;
+            sandbox.merge(profiles.resolve(&self.name)?);
+            sandbox.resolve_paths();
+            (format!("# sandbox profile {}", self.name), sandbox)
+        } else {
+            let tasks = config.tasks().await?;
+            let tasks = crate::task::build_task_ref_map(tasks.iter());
+            let Some(task) = tasks
+                .get_matching(&self.name)
+                .ok()
+                .and_then(|m| m.first().cloned().cloned())
+            else {
+                bail!("no sandbox profile or task named {}", self.name);
+            };
+            let sandbox = build_task_sandbox(&task, &config, &settings, false).await?;
+            (format!("# task {}", task.name), sandbox)
+        };
+
+        miseprintln!("{header}");
+        if !sandbox.is_active() {
+            miseprintln!("# not sandboxed");
+            return Ok(());
+        }
+        let policy = toml::to_string(&SandboxProfile::from_config(&sandbox))?;
+        miseprintln!("{}", policy.trim_end());
+        if let Some(rules) = describe_platform_rules(&sandbox).await {
+            miseprintln!("\n{rules}");
+        }
+        Ok(())
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise sandbox show ci</bold>
+    # sandbox profile ci
+    deny_write = true
+    deny_net = true
+    allow_write = ["/home/me/src/myproj/dist"]
+    limit_memory = "2GiB"
+
+    # landlock: restricts read-write
+    read       /
+    read-write /tmp
+    read-write /dev
+    read-write /home/me/src/myproj/dist
+
+    # seccomp: deny AF_INET and AF_INET6 sockets
+"#
+);
@@ -0,0 +1,25 @@
+use clap::Subcommand;
+use eyre::Result;
+
+mod serve;
+
+/// Run a shared remote task cache
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+pub struct TaskCache {
+    #[clap(subcommand)]
+    command: Commands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Commands {
+    Serve(serve::TaskCacheServe),
+}
+
+impl TaskCache {
+    pub async fn run(self) -> Result<()> {
+        match self.command {
+            Commands::Serve(cmd) => cmd.run().await,
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use std::path::PathBuf;
+
+use bytesize::ByteSize;
+use eyre::{Result, WrapErr, eyre};
+use tokio::net::TcpListener;
+
+use crate::dirs::CACHE;
+use crate::duration;
+use crate::file::{self, display_path};
+use crate::task::task_cache_server::{self, TaskCacheServerOptions, TokenGrant};
+
+/// Serve a remote task cache from a local directory
+///
+/// Starts an HTTP server implementing the remote task cache protocol. Point
+/// clients at it with `task.cache_remote_url` and `task.cache_remote_namespace`
+/// to share task outputs between machines and CI runners.
+///
+/// Every namespace has its own store, uploaded blobs are verified against their
+/// digest, and an action result is only served while everything it references
+/// is present. Least-recently-used entries are evicted when the store grows
+/// past --max-size or has not been used for --max-age.
+///
+/// Without --token or --token-file anyone who can reach the server may read and
+/// write every namespace. The server speaks plain HTTP, so put it behind a TLS
+/// reverse proxy when it is reachable from other machines; clients only send
+/// tokens over plain HTTP to loopback addresses.
+///
+/// `GET /stats` reports the entries and disk usage of each namespace along with
+/// hit, miss, upload, and eviction counts.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct TaskCacheServe {
+    /// Address to listen on
+    #[clap(long, short, default_value = "127.0.0.1:8788")]
+    bind: String,
+
+    /// Directory to store cache entries in
+    /// [default: $MISE_CACHE_DIR/task-cache-server]
+    #[clap(long, value_hint = clap::ValueHint::DirPath)]
+    dir: Option<PathBuf>,
+
+    /// Require this bearer token to read and write every namespace
+    #[clap(long, env = "MISE_TASK_CACHE_SERVE_TOKEN", hide_env_values = true)]
+    token: Option<String>,
+
+    /// File with one `<namespace> <read|write> <token>` grant per line
+    ///
+    /// Use `*` as the namespace to grant access to every namespace.
+    #[clap(long, value_hint = clap::ValueHint::FilePath, verbatim_doc_comment)]
+    token_file: Option<PathBuf>,
+
+    /// Only serve existing entries, reject uploads from clients
+    #[clap(long)]
+    read_only: bool,
+
+    /// Evict least-recently-used entries once the store is larger than this, e.g. `20GiB`
+    #[clap(long)]
+    max_size: Option<String>,
+
+    /// Evict entries that have not been used for this long, e.g. `14d`
+    #[clap(long)]
+    max_age: Option<String>,
+}
+
+impl TaskCacheServe {
+    pub async fn run(self) -> Result<()> {
+        let dir = self.dir.unwrap_or_else(|| CACHE.join("task-cache-server"));
+        let mut grants = Vec::new();
+        if let Some(token) = self.token.as_deref().filter(|t| !t.trim().is_empty()) {
+            grants.push(TokenGrant::new(token, None, true));
+        }
+        if let Some(path) = &self.token_file {
+            grants.extend(
+                TokenGrant::parse_file(&file::read_to_string(path)?)
+                    .wrap_err_with(|| format!("invalid token file {}", display_path(path)))?,
+            );
+        }
+        let max_size = self
+            .max_size
+            .as_deref()
+            .map(|size| size.parse::<ByteSize>().map(|size| size.as_u64()))
+            .transpose()
+            .map_err(|err| eyre!("invalid --max-size: {err}"))?
+            .filter(|size| *size > 0);
+        let max_age = self
+            .max_age
+            .as_deref()
+            .map(duration::parse_duration)
+            .transpose()
+            .wrap_err("invalid --max-age")?
+            .filter(|age| !age.is_zero());
+        let listener = TcpListener::bind(&self.bind).await?;
+        let addr = listener.local_addr()?;
+        if grants.is_empty() && !addr.ip().is_loopback() {
+            warn!(
+                "serving the task cache without --token or --token-file; anyone who can reach {addr} may read and write it"
+            );
+        }
+        info!(
+            "serving task cache from {} on http://{addr}",
+            display_path(&dir)
+        );
+        let options = TaskCacheServerOptions {
+            dir,
+            grants,
+            read_only: self.read_only,
+            max_size,
+            max_age,
+        };
+        task_cache_server::serve(listener, options).await
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    # on the cache host
+    $ <bold>mise task-cache serve --bind 0.0.0.0:8788 --token-file tokens.txt --max-size 50GiB</bold>
+
+    # tokens.txt: CI writes the shared namespace, developers only read it
+    myorg/app write ci-secret
+    myorg/app read dev-secret
+
+    # on developer machines and CI runners
+    $ <bold>mise settings set task.cache_remote_url https://cache.example.com</bold>
+    $ <bold>mise settings set task.cache_remote_namespace myorg/app</bold>
+    $ <bold>export MISE_TASK_CACHE_REMOTE_TOKEN=dev-secret</bold>
+"#
+);
@@ -0,0 +1,131 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+use eyre::{Result, bail};
+
+use crate::config::{Config, Settings};
+use crate::file::display_path;
+use crate::lockfile::{self, Lockfile};
+use crate::task::Task;
+use crate::task::task_fetcher::TaskFetcher;
+use crate::task::task_file_providers::TaskFileProvidersBuilder;
+use crate::task::task_remote_integrity::{self, TaskIntegrity};
+
+/// Record checksums of remote task files in mise.lock
+///
+/// Fetches each remote `file =` task again and records the sha256 of its
+/// content in the lockfile next to the config that defines it. `mise run`
+/// refuses to run a remote task file whose content no longer matches, and
+/// `task.remote_require_pinned` accepts a recorded checksum in place of an
+/// `integrity`. When locking all tasks, checksums of remote task files that
+/// are no longer configured are removed.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct TasksLock {
+    /// Tasks to lock
+    /// If not specified, locks all remote tasks
+    #[clap(verbatim_doc_comment)]
+    tasks: Vec<String>,
+
+    /// Show the checksums that would be recorded without writing lockfiles
+    #[clap(long, short = 'n', verbatim_doc_comment)]
+    dry_run: bool,
+}
+
+impl TasksLock {
+    pub async fn run(self) -> Result<()> {
+        if !Settings::get().lockfile_enabled() {
+            bail!(
+                "mise tasks lock requires lockfile to be enabled\n\
+                hint: Remove `lockfile = false` or set `lockfile = true`"
+            );
+        }
+        let config = Config::get().await?;
+        let tasks = config.tasks().await?;
+        for name in &self.tasks {
+            match tasks.get(name) {
+                Some(task) if remote_source(task).is_none() => {
+                    bail!("task {name} does not have a remote file")
+                }
+                Some(_) => {}
+                None => bail!("task {name} not found"),
+            }
+        }
+
+        let monorepo_root = config.monorepo_lockfile_root();
+        let mut by_lockfile: BTreeMap<PathBuf, Vec<(String, &Task)>> = BTreeMap::new();
+        for task in tasks.values() {
+            if !self.tasks.is_empty() && !self.tasks.contains(&task.name) {
+                continue;
+            }
+            let Some(source) = remote_source(task) else {
+                continue;
+            };
+            let (path, _) =
+                lockfile::lockfile_path_for_config(&task.config_source, monorepo_root.as_deref());
+            by_lockfile.entry(path).or_default().push((source, task));
+        }
+
+        // Fetch the current content rather than trusting what is cached.
+        let providers = TaskFileProvidersBuilder::new()
+            .with_cache(true)
+            .with_refresh(true)
+            .build();
+        for (path, entries) in by_lockfile {
+            let mut lockfile = Lockfile::read(&path)?;
+            let mut sources = BTreeSet::new();
+            for (source, task) in entries {
+                task_remote_integrity::check_remote_source(&source, true)?;
+                let Some(provider) = providers.get_provider(&source) else {
+                    bail!("No provider found for file: {source}");
+                };
+                let local_path = provider.get_local_path(&source).await?;
+                if let Some(integrity) = &task.integrity {
+                    integrity
+                        .parse::<TaskIntegrity>()?
+                        .verify(&source, &local_path)?;
+                }
+                let checksum = TaskIntegrity::of_file(&local_path)?.to_string();
+                let previous = lockfile
+                    .get_remote_task(&source)
+                    .map(|info| info.checksum.clone());
+                if previous.as_deref() != Some(checksum.as_str()) {
+                    if self.dry_run {
+                        miseprintln!("{source} {checksum}");
+                    } else {
+                        info!("locked {source} {checksum}");
+                    }
+                }
+                lockfile.set_remote_task(&source, &checksum);
+                sources.insert(source);
+            }
+            if self.tasks.is_empty() {
+                lockfile.retain_remote_tasks(&sources);
+            }
+            if !self.dry_run {
+                lockfile.write(&path)?;
+                debug!("wrote {}", display_path(&path));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn remote_source(task: &Task) -> Option<String> {
+    let source = task.file.as_ref()?.to_string_lossy().to_string();
+    TaskFetcher::is_remote_source(&source).then_some(source)
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    # Record checksums of all remote task files
+    $ <bold>mise tasks lock</bold>
+
+    # Refresh the checksum of one task after its remote file changed
+    $ <bold>mise tasks lock build</bold>
+
+    # Show the checksums without writing mise.lock
+    $ <bold>mise tasks lock --dry-run</bold>
+"#
+);
@@ -8,6 +8,7 @@ mod deps;
 mod edit;
 mod graph;
 mod info;
+mod lock;
 mod ls;
 mod validate;
 
@@ -32,6 +33,7 @@ enum Commands {
     Edit(edit::TasksEdit),
     Graph(graph::TasksGraph),
     Info(info::TasksInfo),
+    Lock(lock::TasksLock),
     Ls(ls::TasksLs),
     Run(Box<run::Run>),
     Validate(validate::TasksValidate),
@@ -45,6 +47,7 @@ impl Commands {
             Self::Edit(cmd) => cmd.run().await,
             Self::Graph(cmd) => cmd.run().await,
             Self::Info(cmd) => cmd.run().await,
+            Self::Lock(cmd) => cmd.run().await,
             Self::Ls(cmd) => cmd.run().await,
             Self::Run(cmd) => (*cmd).run().await,
             Self::Validate(cmd) => cmd.run().await,
@@ -376,6 +376,46 @@ impl TasksValidate {
             }
         }
 
+        if let Some(backoff) = task.retry.as_ref().and_then(|r| r.backoff.as_ref())
+            && let Err(e) = duration::parse_duration(backoff)
+        {
+            issues.push(ValidationIssue {
+                task: task.name.clone(),
+                severity: Severity::Error,
+                category: "invalid-retry-backoff".to_string(),
+                message: format!("Invalid retry backoff format: '{}'", backoff),
+                details: Some(format!("Parse error: {}", e)),
+            });
+        }
+
+        if let Some(service) = &task.service {
+            for (field, value) in [
+                ("ready_timeout", &service.ready_timeout),
+                ("grace_period", &service.grace_period),
+            ] {
+                if let Some(value) = value
+                    && let Err(e) = duration::parse_duration(value)
+                {
+                    issues.push(ValidationIssue {
+                        task: task.name.clone(),
+                        severity: Severity::Error,
+                        category: "invalid-service-duration".to_string(),
+                        message: format!("Invalid service {field} format: '{value}'"),
+                        details: Some(format!("Parse error: {}", e)),
+                    });
+                }
+            }
+            if let Err(e) = service.log_regex() {
+                issues.push(ValidationIssue {
+                    task: task.name.clone(),
+                    severity: Severity::Error,
+                    category: "invalid-service-probe".to_string(),
+                    message: "Invalid service log probe".to_string(),
+                    details: Some(e.to_string()),
+                });
+            }
+        }
+
         issues
     }
 
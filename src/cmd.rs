@@ -121,6 +121,10 @@ pub struct CmdLineRunner<'a> {
     observe_stderr: Option<OutputObserver<'a>>,
     timeout: Option<Duration>,
     sandbox: Option<crate::sandbox::SandboxConfig>,
+    #[cfg(target_os = "linux")]
+    net_proxy: Option<crate::sandbox::NetProxy>,
+    limits: Option<crate::sandbox::AppliedLimits>,
+    on_spawn: Option<Box<dyn Fn(u32) + Send + 'a>>,
 }
 
 const GUARD_RUNNING: u8 = 0;
@@ -128,7 +132,7 @@ const GUARD_CANCELLED: u8 = 1;
 const GUARD_TIMED_OUT: u8 = 2;
 
 #[cfg(unix)]
-fn signal_process_tree(pid: u32, signal: nix::sys::signal::Signal) {
+pub(crate) fn signal_process_tree(pid: u32, signal: nix::sys::signal::Signal) {
     let pid = nix::unistd::Pid::from_raw(pid as i32);
     if !should_use_pgroup() || nix::sys::signal::killpg(pid, signal).is_err() {
         let _ = nix::sys::signal::kill(pid, signal);
@@ -136,7 +140,7 @@ fn signal_process_tree(pid: u32, signal: nix::sys::signal::Signal) {
 }
 
 #[cfg(windows)]
-fn kill_process_tree(pid: u32) {
+pub(crate) fn kill_process_tree(pid: u32) {
     let _ = std::process::Command::new("taskkill")
         .args(["/F", "/T", "/PID", &pid.to_string()])
         .stdout(Stdio::null())
@@ -417,6 +421,10 @@ impl<'a> CmdLineRunner<'a> {
             observe_stderr: None,
             timeout: None,
             sandbox: None,
+            #[cfg(target_os = "linux")]
+            net_proxy: None,
+            limits: None,
+            on_spawn: None,
         }
     }
 
@@ -511,6 +519,12 @@ impl<'a> CmdLineRunner<'a> {
         self
     }
 
+    /// Called with the pid of the child process once it has been spawned.
+    pub(crate) fn with_on_spawn<F: Fn(u32) + Send + 'a>(mut self, on_spawn: F) -> Self {
+        self.on_spawn = Some(Box::new(on_spawn));
+        self
+    }
+
     pub fn current_dir<P: AsRef<Path>>(mut self, dir: P) -> Self {
         self.cmd.current_dir(dir);
         self
@@ -675,7 +689,7 @@ impl<'a> CmdLineRunner<'a> {
     pub fn execute(mut self) -> Result<()> {
         let read_lock = raw_read_lock_blocking();
         debug!("$ {self}");
-        if Settings::get().raw || self.raw {
+        if (Settings::get().raw || self.raw) && !self.counts_output() {
             drop(read_lock);
             let _write_lock = raw_write_lock_blocking();
             return self.execute_raw();
@@ -804,11 +818,17 @@ impl<'a> CmdLineRunner<'a> {
             };
             match msg {
                 ChildProcessOutput::Stdout(line) => {
+                    if !self.track_output(id, &line) {
+                        continue;
+                    }
                     let line = self.redactor.redact(&line);
                     self.on_stdout(line.clone());
                     combined_output.push((line, OutputSource::Stdout));
                 }
                 ChildProcessOutput::Stderr(line) => {
+                    if !self.track_output(id, &line) {
+                        continue;
+                    }
                     let line = self.redactor.redact(&line);
                     self.on_stderr(line.clone());
                     combined_output.push((line, OutputSource::Stderr));
@@ -850,6 +870,9 @@ impl<'a> CmdLineRunner<'a> {
         let status = status.unwrap();
 
         if !status.success() {
+            if let Some(err) = self.limit_error(status) {
+                return Err(err.into());
+            }
             if let Some(duration) = timeout_guard.as_ref().and_then(|g| g.timed_out()) {
                 bail!("timed out after {duration:?}");
             }
@@ -874,7 +897,7 @@ impl<'a> CmdLineRunner<'a> {
         }
         let read_lock = RAW_LOCK.read().await;
         debug!("$ {self}");
-        if Settings::get().raw || self.raw {
+        if (Settings::get().raw || self.raw) && !self.counts_output() {
             drop(read_lock);
             let _write_lock = RAW_LOCK.write().await;
             return self.execute_raw_async_with_cancel_check(is_cancelled).await;
@@ -901,6 +924,9 @@ impl<'a> CmdLineRunner<'a> {
             .wrap_err_with(|| format!("failed to execute command: {self}"))?;
         let id = cp.id().unwrap_or_default();
         RUNNING_PIDS.lock().unwrap().insert(id);
+        if let Some(on_spawn) = &self.on_spawn {
+            on_spawn(id);
+        }
         if is_cancelled() {
             #[cfg(unix)]
             signal_process_tree(id, nix::sys::signal::SIGINT);
@@ -997,11 +1023,17 @@ impl<'a> CmdLineRunner<'a> {
                     };
                     match msg {
                         ChildProcessOutput::Stdout(line) => {
+                            if !self.track_output(id, &line) {
+                                continue;
+                            }
                             let line = self.redactor.redact(&line);
                             self.on_stdout(line.clone());
                             combined_output.push((line, OutputSource::Stdout));
                         }
                         ChildProcessOutput::Stderr(line) => {
+                            if !self.track_output(id, &line) {
+                                continue;
+                            }
                             let line = self.redactor.redact(&line);
                             self.on_stderr(line.clone());
                             combined_output.push((line, OutputSource::Stderr));
@@ -1042,11 +1074,17 @@ impl<'a> CmdLineRunner<'a> {
             };
             match msg {
                 ChildProcessOutput::Stdout(line) => {
+                    if !self.track_output(id, &line) {
+                        continue;
+                    }
                     let line = self.redactor.redact(&line);
                     self.on_stdout(line.clone());
                     combined_output.push((line, OutputSource::Stdout));
                 }
                 ChildProcessOutput::Stderr(line) => {
+                    if !self.track_output(id, &line) {
+                        continue;
+                    }
                     let line = self.redactor.redact(&line);
                     self.on_stderr(line.clone());
                     combined_output.push((line, OutputSource::Stderr));
@@ -1063,6 +1101,9 @@ impl<'a> CmdLineRunner<'a> {
 
         let status = status.unwrap();
         if !status.success() {
+            if let Some(err) = self.limit_error(status) {
+                return Err(err.into());
+            }
             if let Some(duration) = timeout_guard.as_ref().and_then(|g| g.timed_out()) {
                 bail!("timed out after {duration:?}");
             }
@@ -1255,6 +1296,9 @@ impl<'a> CmdLineRunner<'a> {
         }
         let status = status.expect("command wait must complete");
         if !status.success() {
+            if let Some(err) = self.limit_error(status) {
+                return Err(err.into());
+            }
             if let Some(timeout) = timeout_guard.as_ref().and_then(|guard| guard.timed_out()) {
                 bail!("timed out after {timeout:?}");
             }
@@ -1342,6 +1386,9 @@ impl<'a> CmdLineRunner<'a> {
             g.cancel();
         }
         if !status.success() {
+            if let Some(err) = self.limit_error(status) {
+                return Err(err.into());
+            }
             if let Some(duration) = timeout_guard.as_ref().and_then(|g| g.timed_out()) {
                 bail!("timed out after {duration:?}");
             }
@@ -1361,6 +1408,9 @@ impl<'a> CmdLineRunner<'a> {
         self.cmd.stderr(Stdio::inherit());
         let mut cp = self.spawn_async_with_etxtbsy_retry().await?;
         let id = cp.id().unwrap_or_default();
+        if let Some(on_spawn) = &self.on_spawn {
+            on_spawn(id);
+        }
         if is_cancelled() {
             #[cfg(unix)]
             signal_process_tree(id, nix::sys::signal::SIGINT);
@@ -1373,6 +1423,9 @@ impl<'a> CmdLineRunner<'a> {
             g.cancel();
         }
         if !status.success() {
+            if let Some(err) = self.limit_error(status) {
+                return Err(err.into());
+            }
             if let Some(duration) = timeout_guard.as_ref().and_then(|g| g.timed_out()) {
                 bail!("timed out after {duration:?}");
             }
@@ -1426,15 +1479,6 @@ impl<'a> CmdLineRunner<'a> {
             return Ok(());
         }
 
-        // Fail early on Linux if per-host network filtering is requested
-        #[cfg(target_os = "linux")]
-        if !sandbox.allow_net.is_empty() {
-            eyre::bail!(
-                "per-host network filtering (--allow-net=<host>) is not supported on Linux. \
-                 Use --deny-net to block all network, or remove --allow-net."
-            );
-        }
-
         #[cfg(target_os = "linux")]
         {
             // On Linux, clear inherited env before pre_exec so child only sees filtered vars.
@@ -1451,16 +1495,32 @@ impl<'a> CmdLineRunner<'a> {
                     self.cmd.env(k, v);
                 }
             }
+            // The child joins its cgroup before it enters any namespace and before Landlock
+            // would deny it writing to /sys/fs/cgroup.
+            if sandbox.limits.is_set() {
+                self.limits = Some(crate::sandbox::limits_apply(
+                    self.cmd.as_std_mut(),
+                    &sandbox,
+                )?);
+            }
+            // Per-host filtering moves the child into its own network namespace behind a
+            // proxy run by mise. Its pre_exec hook must run before Landlock is applied.
+            if !sandbox.allow_net.is_empty() {
+                self.net_proxy = Some(crate::sandbox::net_proxy_apply(
+                    self.cmd.as_std_mut(),
+                    &sandbox,
+                )?);
+            }
             // Use pre_exec to apply Landlock/seccomp in the child process
             // before it execs the target program. This avoids restricting the mise process.
             let sandbox = sandbox.clone();
             unsafe {
                 self.cmd.as_std_mut().pre_exec(move || {
-                    if sandbox.effective_deny_read() || sandbox.effective_deny_write() {
+                    if sandbox.restricts_read() || sandbox.effective_deny_write() {
                         crate::sandbox::landlock_apply(&sandbox)
                             .map_err(|e| std::io::Error::other(e.to_string()))?;
                     }
-                    if sandbox.effective_deny_net() {
+                    if sandbox.deny_net && sandbox.allow_net.is_empty() {
                         crate::sandbox::seccomp_apply()
                             .map_err(|e| std::io::Error::other(e.to_string()))?;
                     }
@@ -1470,7 +1530,7 @@ impl<'a> CmdLineRunner<'a> {
         }
 
         #[cfg(target_os = "macos")]
-        {
+        if sandbox.restricts_access() {
             // On macOS, rewrite the command to go through sandbox-exec.
             // Build a new Command that wraps the original through sandbox-exec,
             // preserving stdio, cwd, and env from the original.
@@ -1507,6 +1567,14 @@ impl<'a> CmdLineRunner<'a> {
             }
             self.cmd = new_cmd;
         }
+        // rlimits are inherited through sandbox-exec
+        #[cfg(target_os = "macos")]
+        if sandbox.limits.is_set() {
+            self.limits = Some(crate::sandbox::limits_apply(
+                self.cmd.as_std_mut(),
+                &sandbox,
+            )?);
+        }
 
         #[cfg(not(any(target_os = "linux", target_os = "macos")))]
         {
@@ -1581,6 +1649,35 @@ impl<'a> CmdLineRunner<'a> {
         }
     }
 
+    /// Whether output is read by mise to enforce an output limit, which raw mode would bypass.
+    fn counts_output(&self) -> bool {
+        self.limits
+            .as_ref()
+            .is_some_and(|limits| limits.counts_output())
+    }
+
+    /// Counts a line of output against the output limit, killing the command once it is
+    /// exceeded. Returns false for lines past the limit, which are discarded.
+    fn track_output(&mut self, id: u32, line: &str) -> bool {
+        let Some(limits) = &mut self.limits else {
+            return true;
+        };
+        if limits.record_output(line.len() + 1) {
+            debug!("output limit exceeded, killing {id}");
+            #[cfg(unix)]
+            signal_process_tree(id, nix::sys::signal::SIGKILL);
+            #[cfg(windows)]
+            kill_process_tree(id);
+        }
+        !limits.output_exceeded()
+    }
+
+    /// The error for a command that failed because it hit a resource limit.
+    fn limit_error(&self, status: ExitStatus) -> Option<crate::errors::Error> {
+        let message = self.limits.as_ref()?.exceeded()?;
+        Some(crate::errors::Error::LimitExceeded(message, status))
+    }
+
     fn on_error(&self, output: Vec<(String, OutputSource)>, status: ExitStatus) -> Result<()> {
         match self
             .pr
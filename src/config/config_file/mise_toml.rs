@@ -35,6 +35,7 @@ use crate::hooks::{Hook, HookDef, Hooks};
 use crate::oci::OciConfig;
 use crate::redactions::Redactions;
 use crate::registry::REGISTRY;
+use crate::sandbox::SandboxToml;
 use crate::system::{BootstrapTomlConfig, DotfilesTomlConfig};
 use crate::task::workspace::WorkspaceProjectOverride;
 use crate::task::{Task, TaskTemplate, TaskTomlBoolPresence};
@@ -249,6 +250,8 @@ pub struct MiseToml {
     bootstrap: Option<BootstrapTomlConfig>,
     #[serde(default)]
     dotfiles: Option<DotfilesTomlConfig>,
+    #[serde(default)]
+    sandbox: Option<SandboxToml>,
     #[serde(default, deserialize_with = "deserialize_vars")]
     vars: EnvList,
     #[serde(default)]
@@ -1414,6 +1417,10 @@ impl ConfigFile for MiseToml {
     fn dotfiles_config(&self) -> Option<DotfilesTomlConfig> {
         self.dotfiles.clone()
     }
+
+    fn sandbox_config(&self) -> Option<SandboxToml> {
+        self.sandbox.clone()
+    }
 }
 
 fn resolve_plugin_source_path(config_path: &Path, source: String) -> eyre::Result<String> {
@@ -1584,6 +1591,7 @@ impl Clone for MiseToml {
             oci: self.oci.clone(),
             bootstrap: self.bootstrap.clone(),
             dotfiles: self.dotfiles.clone(),
+            sandbox: self.sandbox.clone(),
             vars: self.vars.clone(),
             monorepo_root: self.monorepo_root,
             experimental_monorepo_root: self.experimental_monorepo_root,
@@ -1822,6 +1830,48 @@ impl<'de> de::Deserialize<'de> for EnvList {
                                 Vec<MiseTomlEnvDirective>,
                             );
 
+                            #[derive(Deserialize)]
+                            #[serde(deny_unknown_fields)]
+                            struct EnvDirectiveSecret {
+                                key: String,
+                                provider: String,
+                                #[serde(rename = "ref")]
+                                reference: String,
+                                #[serde(default)]
+                                ttl: Option<String>,
+                                #[serde(default)]
+                                tools: bool,
+                            }
+
+                            impl EnvDirectiveSecret {
+                                fn into_directive(self) -> EnvDirective {
+                                    EnvDirective::Secret {
+                                        key: self.key,
+                                        provider: self.provider,
+                                        reference: self.reference,
+                                        ttl: self.ttl,
+                                        options: EnvDirectiveOptions {
+                                            tools: self.tools,
+                                            redact: Some(true),
+                                            ..Default::default()
+                                        },
+                                    }
+                                }
+                            }
+
+                            impl FromStr for EnvDirectiveSecret {
+                                type Err = String;
+                                fn from_str(_: &str) -> Result<Self, Self::Err> {
+                                    Err("`_.secret` must be a table with `key`, `provider` and `ref`".into())
+                                }
+                            }
+
+                            #[derive(Deserialize)]
+                            struct SecretArr(
+                                #[serde(deserialize_with = "deserialize_arr")]
+                                Vec<EnvDirectiveSecret>,
+                            );
+
                             impl<'de> de::Deserialize<'de> for EnvDirectivePythonVenv {
                                 fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
                                 where
@@ -1985,6 +2035,14 @@ impl<'de> de::Deserialize<'de> for EnvList {
                                                             EnvDirective::Source,
                                                         ));
                                                     }
+                                                    "secret" => {
+                                                        directives.extend(
+                                                            map.next_value::<SecretArr>()?
+                                                                .0
+                                                                .into_iter()
+                                                                .map(EnvDirectiveSecret::into_directive),
+                                                        );
+                                                    }
                                                     "python" => {
                                                         venv = map
                                                             .next_value::<EnvDirectivePython>()?
@@ -2839,6 +2897,37 @@ mod tests {
         assert_eq!(kinds, ["source", "path", "file"]);
     }
 
+    #[tokio::test]
+    async fn test_env_secret_directive() {
+        let _config = Config::get().await.unwrap();
+        let p = CWD.as_ref().unwrap().join(".test.mise.toml");
+        file::write(
+            &p,
+            formatdoc! {r#"
+            [env]
+            _.secret = [
+              {{ key = "GITHUB_TOKEN", provider = "pass", ref = "github/token" }},
+              {{ key = "DB_PASSWORD", provider = "op", ref = "op://app/db/password", ttl = "5m" }},
+            ]
+            "#},
+        )
+        .unwrap();
+        let cf = MiseToml::from_file(&p).unwrap();
+        let secrets: Vec<String> = cf.env.0.iter().map(|d| d.to_string()).collect();
+        assert_eq!(
+            secrets,
+            [
+                "GITHUB_TOKEN (secret from pass)",
+                "DB_PASSWORD (secret from op)"
+            ]
+        );
+        let EnvDirective::Secret { ttl, options, .. } = &cf.env.0[1] else {
+            panic!("expected a secret directive");
+        };
+        assert_eq!(ttl.as_deref(), Some("5m"));
+        assert_eq!(options.redact, Some(true));
+    }
+
     #[tokio::test]
     async fn test_env_var_in_tool() {
         let _config = Config::get().await.unwrap();
@@ -156,6 +156,10 @@ pub trait ConfigFile: Debug + Send + Sync {
     fn dotfiles_config(&self) -> Option<crate::system::DotfilesTomlConfig> {
         None
     }
+
+    fn sandbox_config(&self) -> Option<crate::sandbox::SandboxToml> {
+        None
+    }
 }
 
 impl dyn ConfigFile {
@@ -21,6 +21,7 @@ use super::{Config, Settings};
 mod file;
 mod module;
 mod path;
+mod secret;
 mod source;
 pub(crate) mod venv;
 
@@ -214,6 +215,14 @@ pub enum EnvDirective {
         format: Option<AgeFormat>,
         options: EnvDirectiveOptions,
     },
+    /// [experimental] value read from a secret manager CLI
+    Secret {
+        key: String,
+        provider: String,
+        reference: String,
+        ttl: Option<String>,
+        options: EnvDirectiveOptions,
+    },
     PythonVenv {
         path: String,
         create: bool,
@@ -236,6 +245,7 @@ impl EnvDirective {
             | EnvDirective::Path(_, opts)
             | EnvDirective::Source(_, opts)
             | EnvDirective::Age { options: opts, .. }
+            | EnvDirective::Secret { options: opts, .. }
             | EnvDirective::PythonVenv { options: opts, .. }
             | EnvDirective::Module(_, _, opts) => opts,
         }
@@ -275,6 +285,9 @@ impl Display for EnvDirective {
                 }
                 write!(f, ")")
             }
+            EnvDirective::Secret { key, provider, .. } => {
+                write!(f, "{key} (secret from {provider})")
+            }
             EnvDirective::Module(name, _, _) => write!(f, "module {name}"),
             EnvDirective::PythonVenv {
                 path,
@@ -650,6 +663,37 @@ impl EnvResults {
                         env.insert(k.clone(), (decrypted_v, Some(source.clone())));
                     }
                 }
+                EnvDirective::Secret {
+                    key,
+                    provider,
+                    reference,
+                    ttl,
+                    options: _opts,
+                } => {
+                    let mut directive_ctx = EnvDirectiveContext {
+                        config,
+                        tera_ctx: &mut ctx,
+                        tera: &mut tera,
+                        results: &mut r,
+                        normalize_path,
+                        source: &source,
+                        exec_env: &env_vars,
+                        config_root: &config_root,
+                        toolset,
+                    };
+                    let v = Self::secret(&mut directive_ctx, &provider, &reference, ttl.as_deref())
+                        .await
+                        .wrap_err_with(|| eyre!("failed to resolve secret {key}"))?;
+                    // secrets are always redacted, whatever `redact` says
+                    r.track_redaction_override(&key, None);
+                    r.redactions.push(key.clone());
+                    if resolve_opts.vars {
+                        r.vars.insert(key, (v, source.clone()));
+                    } else {
+                        r.env_remove.remove(&key);
+                        env.insert(key, (v, Some(source.clone())));
+                    }
+                }
                 EnvDirective::Path(input_str, _opts) => {
                     let mut directive_ctx = EnvDirectiveContext {
                         config,
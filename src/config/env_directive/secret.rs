@@ -0,0 +1,220 @@
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use eyre::{bail, eyre};
+
+use crate::backend::configured_toolset_or_path_which;
+use crate::config::config_file::trust_check;
+use crate::config::env_directive::{EnvDirectiveContext, EnvResults};
+use crate::config::{Config, Settings};
+use crate::duration::parse_duration;
+use crate::env_diff::EnvMap;
+use crate::toolset::env_cache::CachedSecret;
+use crate::{Result, cmd};
+
+/// A secret manager CLI that `_.secret` can read values from
+struct SecretProvider {
+    name: &'static str,
+    /// the CLI to run
+    bin: &'static str,
+    /// registry tools that provide `bin`, so a version pinned in `[tools]` is preferred
+    tools: &'static [&'static str],
+    /// arguments that print the secret for a `ref`
+    args: fn(&str) -> Result<Vec<String>>,
+    /// only the first line of the output is the secret, like `pass` entries
+    first_line: bool,
+    /// env vars that select the store, account or server a ref is read from
+    env: &'static [&'static str],
+    install_url: &'static str,
+}
+
+const PROVIDERS: &[SecretProvider] = &[
+    SecretProvider {
+        name: "pass",
+        bin: "pass",
+        tools: &[],
+        args: |r| Ok(vec!["show".into(), r.into()]),
+        first_line: true,
+        env: &["PASSWORD_STORE_DIR"],
+        install_url: "https://www.passwordstore.org",
+    },
+    SecretProvider {
+        name: "gopass",
+        bin: "gopass",
+        tools: &["gopass"],
+        args: |r| Ok(vec!["show".into(), "--password".into(), r.into()]),
+        first_line: false,
+        env: &["GOPASS_HOMEDIR", "PASSWORD_STORE_DIR"],
+        install_url: "https://github.com/gopasspw/gopass",
+    },
+    SecretProvider {
+        name: "op",
+        bin: "op",
+        tools: &["1password"],
+        args: |r| Ok(vec!["read".into(), "--no-newline".into(), r.into()]),
+        first_line: false,
+        env: &["OP_ACCOUNT"],
+        install_url: "https://developer.1password.com/docs/cli",
+    },
+    SecretProvider {
+        name: "vault",
+        bin: "vault",
+        tools: &["vault"],
+        args: |r| {
+            let Some((path, field)) = r.rsplit_once('#') else {
+                bail!("vault secret refs must be `<path>#<field>`, got `{r}`");
+            };
+            Ok(vec![
+                "kv".into(),
+                "get".into(),
+                format!("-field={field}"),
+                path.into(),
+            ])
+        },
+        first_line: false,
+        env: &["VAULT_ADDR", "VAULT_NAMESPACE"],
+        install_url: "https://developer.hashicorp.com/vault/install",
+    },
+    SecretProvider {
+        name: "bw",
+        bin: "bw",
+        tools: &["bitwarden"],
+        args: |r| Ok(vec!["get".into(), "password".into(), r.into()]),
+        first_line: false,
+        env: &["BITWARDENCLI_APPDATA_DIR"],
+        install_url: "https://bitwarden.com/help/cli",
+    },
+];
+
+fn provider(name: &str) -> Result<&'static SecretProvider> {
+    PROVIDERS.iter().find(|p| p.name == name).ok_or_else(|| {
+        let names = PROVIDERS.iter().map(|p| p.name).collect::<Vec<_>>();
+        eyre!(
+            "unknown secret provider `{name}`, expected one of: {}",
+            names.join(", ")
+        )
+    })
+}
+
+impl EnvResults {
+    /// Resolves an `_.secret` directive. Values are cached in the encrypted env cache for
+    /// `ttl` (default: `env_cache_ttl`) so the provider CLI isn't run on every prompt.
+    pub(super) async fn secret(
+        ctx: &mut EnvDirectiveContext<'_>,
+        provider_name: &str,
+        reference: &str,
+        ttl: Option<&str>,
+    ) -> Result<String> {
+        Settings::get().ensure_experimental("_.secret")?;
+        let provider = provider(provider_name)?;
+        let reference = ctx.parse_template(reference)?;
+        let env_cache_ttl = Settings::get().env_cache_ttl();
+        let ttl = match ttl {
+            Some(ttl) => parse_duration(ttl)?,
+            None => env_cache_ttl,
+        };
+        // the whole env is cached for `env_cache_ttl`, which would outlive a shorter secret ttl
+        if ttl < env_cache_ttl {
+            ctx.results.has_uncacheable = true;
+        }
+        // an untrusted config must not get a secret out of the cache either
+        trust_check(ctx.source)?;
+        let provider_env = provider
+            .env
+            .iter()
+            .map(|key| (*key, ctx.exec_env.get(*key).map(String::as_str)))
+            .collect::<Vec<_>>();
+        let cache_key =
+            CachedSecret::compute_cache_key(provider.name, &reference, ctx.source, &provider_env);
+        if CachedSecret::is_enabled()
+            && let Some(cached) = CachedSecret::load(&cache_key, ttl)?
+        {
+            return Ok(cached.value);
+        }
+
+        let value = read_secret(ctx.config, ctx.exec_env, provider, &reference).await?;
+        if CachedSecret::is_enabled() && !ttl.is_zero() {
+            let cached = CachedSecret {
+                value: value.clone(),
+                created_at: SystemTime::now()
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .unwrap_or(Duration::ZERO)
+                    .as_secs(),
+                mise_version: env!("CARGO_PKG_VERSION").to_string(),
+            };
+            if let Err(e) = cached.save(&cache_key) {
+                debug!("env_cache: failed to save secret: {e}");
+            }
+        }
+        Ok(value)
+    }
+}
+
+async fn read_secret(
+    config: &Arc<Config>,
+    exec_env: &EnvMap,
+    provider: &SecretProvider,
+    reference: &str,
+) -> Result<String> {
+    let tools = provider.tools.iter().map(|t| t.to_string());
+    let Some(bin) = configured_toolset_or_path_which(config, tools, provider.bin).await? else {
+        let install = match provider.tools.first() {
+            Some(tool) => format!("`mise use -g {tool}` or see {}", provider.install_url),
+            None => provider.install_url.to_string(),
+        };
+        bail!(
+            "secret provider `{}` needs the `{}` CLI, which was not found on PATH. Install it with {install}",
+            provider.name,
+            provider.bin,
+        );
+    };
+    let args = (provider.args)(reference)?;
+    debug!("_.secret: {} {}", bin.display(), args.join(" "));
+    let output = cmd::cmd(&bin, &args)
+        .full_env(exec_env)
+        .stdout_capture()
+        .stderr_capture()
+        .unchecked()
+        .run()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!(
+            "`{} {}` failed to read secret `{reference}`: {}",
+            provider.bin,
+            args.first().map(String::as_str).unwrap_or_default(),
+            stderr.trim()
+        );
+    }
+    let stdout = String::from_utf8(output.stdout)
+        .map_err(|_| eyre!("secret `{reference}` from {} is not UTF-8", provider.name))?;
+    let value = if provider.first_line {
+        stdout.lines().next().unwrap_or_default()
+    } else {
+        stdout.trim_end_matches(['\n', '\r'])
+    };
+    Ok(value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vault_ref_needs_a_field() {
+        let vault = provider("vault").unwrap();
+        assert_eq!(
+            (vault.args)("secret/app#token").unwrap(),
+            ["kv", "get", "-field=token", "secret/app"]
+        );
+        assert!((vault.args)("secret/app").is_err());
+    }
+
+    #[test]
+    fn test_unknown_provider() {
+        let err = provider("keychain").err().unwrap().to_string();
+        assert_eq!(
+            err,
+            "unknown secret provider `keychain`, expected one of: pass, gopass, op, vault, bw"
+        );
+    }
+}
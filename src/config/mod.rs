@@ -28,6 +28,7 @@ use crate::config::env_directive::{EnvResolveOptions, EnvResults, ToolsFilter};
 use crate::config::tracking::Tracker;
 use crate::env::{MISE_DEFAULT_CONFIG_FILENAME, MISE_DEFAULT_TOOL_VERSIONS_FILENAME};
 use crate::file::display_path;
+use crate::sandbox::SandboxProfiles;
 use crate::shorthands::{Shorthands, get_shorthands};
 use crate::task::task_file_providers::TaskFileProvidersBuilder;
 use crate::task::task_sources::TaskOutputs;
@@ -548,12 +549,19 @@ impl Config {
             .cloned()
             .unwrap_or_default();
         let cargo = crate::task::workspace::cargo::CargoWorkspaceProvider;
+        let dart = crate::task::workspace::dart::DartWorkspaceProvider;
+        let deno = crate::task::workspace::deno::DenoWorkspaceProvider;
+        let dotnet = crate::task::workspace::dotnet::DotnetWorkspaceProvider;
         let go = crate::task::workspace::go::GoWorkspaceProvider;
+        let gradle = crate::task::workspace::gradle::GradleWorkspaceProvider;
+        let maven = crate::task::workspace::maven::MavenWorkspaceProvider;
         let node = crate::task::workspace::node::NodeWorkspaceProvider;
         let uv = crate::task::workspace::uv::UvWorkspaceProvider;
 
         crate::task::workspace::WorkspaceProjectGraph::discover_all_with_overrides_lenient(
-            &[&cargo, &go, &node, &uv],
+            &[
+                &cargo, &dart, &deno, &dotnet, &go, &gradle, &maven, &node, &uv,
+            ],
             &monorepo_root,
             &overrides,
         )
@@ -935,6 +943,7 @@ impl Config {
                 _ => {}
             }
         }
+        crate::task::expand_task_matrices(&mut tasks)?;
         let all_tasks = tasks.clone();
         for task in tasks.values_mut() {
             task.display_name = task.display_name(&all_tasks);
@@ -1308,6 +1317,18 @@ impl Config {
     pub fn redact(&self, input: &str) -> String {
         _REDACTOR.lock().unwrap().redact(input)
     }
+
+    /// The `[sandbox.profiles]` of every config file, with local profiles replacing global
+    /// profiles of the same name.
+    pub fn sandbox_profiles(&self) -> SandboxProfiles {
+        let mut profiles = SandboxProfiles::default();
+        for cf in self.config_files.values().rev() {
+            if let Some(sandbox) = cf.sandbox_config() {
+                profiles.add(sandbox, &cf.config_root());
+            }
+        }
+        profiles
+    }
 }
 
 fn configs_at_root<'a>(dir: &Path, config_files: &'a ConfigMap) -> Vec<&'a Arc<dyn ConfigFile>> {
@@ -4019,6 +4040,7 @@ fn is_mise_config_file_in_task_include(root: &Path, path: &Path) -> bool {
 }
 
 async fn resolve_git_url_to_path(git_url: &str) -> Result<PathBuf> {
+    crate::task::task_remote_integrity::check_remote_source(git_url, false)?;
     let no_cache = Settings::get().task.remote_no_cache.unwrap_or(false);
     let task_file_providers = TaskFileProvidersBuilder::new()
         .with_cache(!no_cache)
@@ -0,0 +1,291 @@
+//! Shared download cache for tool artifacts.
+//!
+//! `mise cache serve` runs a small HTTP server that stores artifacts keyed by the checksum
+//! recorded in `mise.lock`. Clients with `download_cache_url` set ask the server for
+//! `/artifacts/<algo>/<hex>` before downloading from upstream, verify what they get against the
+//! lockfile checksum, and upload the artifact after a verified upstream download.
+//!
+//! Because every artifact is addressed by its checksum and verified on both ends, a client can
+//! never be served (or upload) content that does not match what the lockfile pins.
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock as Lazy;
+use std::sync::Mutex;
+
+use eyre::{Result, bail};
+use reqwest::StatusCode;
+use tokio::io::{AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use url::Url;
+
+use crate::config::Settings;
+use crate::file::display_path;
+use crate::http_server::read_request;
+use crate::ui::progress_report::SingleReport;
+use crate::{file, hash};
+
+const ALGORITHMS: &[&str] = &["sha256", "sha512", "sha1", "md5", "blake3"];
+
+/// Lockfile checksums for the urls that are about to be downloaded, keyed by url.
+static LOCKED_CHECKSUMS: Lazy<Mutex<HashMap<String, String>>> = Lazy::new(Default::default);
+
+/// Records the lockfile checksum for `url` so downloads of it can be served from the cache.
+pub fn register_locked_checksum(url: &str, checksum: &str) {
+    if Settings::get().download_cache_url.is_none() || parse_checksum(checksum).is_none() {
+        return;
+    }
+    LOCKED_CHECKSUMS
+        .lock()
+        .unwrap()
+        .insert(url.to_string(), checksum.to_string());
+}
+
+/// Returns the checksum to look up in the download cache for `url`, if the cache is
+/// configured and the lockfile pins this url.
+pub fn locked_checksum(url: &Url) -> Option<String> {
+    Settings::get().download_cache_url.as_ref()?;
+    LOCKED_CHECKSUMS.lock().unwrap().get(url.as_str()).cloned()
+}
+
+/// Splits `sha256:abcd…` into its algorithm and lowercase hex digest.
+fn parse_checksum(checksum: &str) -> Option<(&str, String)> {
+    let (algo, hex) = checksum.split_once(':')?;
+    if !ALGORITHMS.contains(&algo) || hex.is_empty() || !hex.chars().all(|c| c.is_ascii_hexdigit())
+    {
+        return None;
+    }
+    Some((algo, hex.to_ascii_lowercase()))
+}
+
+fn artifact_url(checksum: &str) -> Result<Option<Url>> {
+    let Some(base) = &Settings::get().download_cache_url else {
+        return Ok(None);
+    };
+    let Some((algo, hex)) = parse_checksum(checksum) else {
+        return Ok(None);
+    };
+    let base = Url::parse(&format!("{}/", base.trim_end_matches('/')))?;
+    Ok(Some(base.join(&format!("artifacts/{algo}/{hex}"))?))
+}
+
+/// Tries to download the artifact for `checksum` from the download cache into `path`.
+/// Returns `false` on a cache miss or if the cached artifact does not match the checksum.
+pub async fn fetch(
+    client: &reqwest::Client,
+    checksum: &str,
+    path: &Path,
+    pr: Option<&dyn SingleReport>,
+) -> Result<bool> {
+    let (Some(url), Some((algo, hex))) = (artifact_url(checksum)?, parse_checksum(checksum)) else {
+        return Ok(false);
+    };
+    let mut resp = client.get(url.clone()).send().await?;
+    if resp.status() == StatusCode::NOT_FOUND {
+        debug!("download cache miss: {url}");
+        return Ok(false);
+    }
+    if let Err(err) = resp.error_for_status_ref() {
+        bail!("download cache request failed: {err}");
+    }
+    if let Some(pr) = pr {
+        if let Some(length) = resp.content_length() {
+            pr.set_length(length);
+        }
+        pr.set_position(0);
+    }
+    let parent = path.parent().unwrap();
+    file::create_dir_all(parent)?;
+    let temp_file = tempfile::NamedTempFile::with_prefix_in(path, parent)?;
+    let mut out = tokio::fs::File::from_std(temp_file.reopen()?);
+    while let Some(chunk) = resp.chunk().await? {
+        out.write_all(&chunk).await?;
+        if let Some(pr) = pr {
+            pr.inc(chunk.len() as u64);
+        }
+    }
+    out.shutdown().await?;
+    drop(out);
+    let algo = algo.to_string();
+    let temp_file = tokio::task::spawn_blocking(move || {
+        hash::ensure_checksum(temp_file.path(), &hex, None, &algo).map(|_| temp_file)
+    })
+    .await?;
+    match temp_file {
+        Ok(temp_file) => {
+            temp_file.persist(path)?;
+            debug!("download cache hit: {url}");
+            Ok(true)
+        }
+        Err(err) => {
+            warn!("ignoring download cache entry {url}: {err:#}");
+            Ok(false)
+        }
+    }
+}
+
+/// Uploads a downloaded artifact to the download cache. Failures are only logged since the
+/// download itself already succeeded.
+pub async fn upload(client: &reqwest::Client, checksum: &str, path: &Path) {
+    if !Settings::get().download_cache_upload {
+        return;
+    }
+    let url = match artifact_url(checksum) {
+        Ok(Some(url)) => url,
+        _ => return,
+    };
+    let result = async {
+        let file = tokio::fs::File::open(path).await?;
+        let len = file.metadata().await?.len();
+        let body = reqwest::Body::wrap_stream(tokio_util::io::ReaderStream::new(file));
+        client
+            .put(url.clone())
+            .header(reqwest::header::CONTENT_LENGTH, len)
+            .body(body)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok::<_, eyre::Report>(())
+    }
+    .await;
+    match result {
+        Ok(()) => debug!("uploaded {} to download cache", display_path(path)),
+        Err(err) => debug!("failed to upload {} to download cache: {err:#}", url),
+    }
+}
+
+/// Serves the artifacts in `dir` until the process is stopped.
+pub async fn serve(listener: TcpListener, dir: PathBuf, read_only: bool) -> Result<()> {
+    file::create_dir_all(dir.join("tmp"))?;
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let dir = dir.clone();
+        tokio::spawn(async move {
+            if let Err(err) = handle_connection(stream, peer, &dir, read_only).await {
+                debug!("download cache: {peer}: {err:#}");
+            }
+        });
+    }
+}
+
+/// Maps `/artifacts/<algo>/<hex>` to its location in `dir`.
+fn artifact_path(dir: &Path, path: &str) -> Option<(PathBuf, String, String)> {
+    let rest = path.strip_prefix("/artifacts/")?;
+    let (algo, hex) = rest.split_once('/')?;
+    let (algo, hex) =
+        parse_checksum(&format!("{algo}:{hex}")).map(|(algo, hex)| (algo.to_string(), hex))?;
+    if hex.len() < 8 {
+        return None;
+    }
+    let file = dir.join(&algo).join(&hex[..2]).join(&hex);
+    Some((file, algo, hex))
+}
+
+async fn respond(stream: &mut TcpStream, status: StatusCode, body: &str) -> Result<()> {
+    let head = format!(
+        "HTTP/1.1 {status}\r\ncontent-type: text/plain\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+        body.len()
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(body.as_bytes()).await?;
+    Ok(())
+}
+
+async fn handle_connection(
+    stream: TcpStream,
+    peer: SocketAddr,
+    dir: &Path,
+    read_only: bool,
+) -> Result<()> {
+    let mut reader = BufReader::new(stream);
+    let request = read_request(&mut reader).await?;
+    debug!("download cache: {peer} {} {}", request.method, request.path);
+    if request.path == "/" {
+        let stream = reader.get_mut();
+        return respond(stream, StatusCode::OK, "mise download cache\n").await;
+    }
+    let Some((artifact, algo, hex)) = artifact_path(dir, &request.path) else {
+        return respond(reader.get_mut(), StatusCode::NOT_FOUND, "not found\n").await;
+    };
+    match request.method.as_str() {
+        "GET" | "HEAD" => {
+            let stream = reader.get_mut();
+            let Ok(mut file) = tokio::fs::File::open(&artifact).await else {
+                return respond(stream, StatusCode::NOT_FOUND, "not found\n").await;
+            };
+            let len = file.metadata().await?.len();
+            let head = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/octet-stream\r\ncontent-length: {len}\r\nconnection: close\r\n\r\n"
+            );
+            stream.write_all(head.as_bytes()).await?;
+            if request.method == "GET" {
+                tokio::io::copy(&mut file, stream).await?;
+            }
+            stream.flush().await?;
+            Ok(())
+        }
+        "PUT" if read_only => respond(reader.get_mut(), StatusCode::FORBIDDEN, "read-only\n").await,
+        "PUT" => {
+            let Some(len) = request.content_length() else {
+                return respond(reader.get_mut(), StatusCode::LENGTH_REQUIRED, "").await;
+            };
+            if artifact.exists() {
+                return respond(reader.get_mut(), StatusCode::OK, "exists\n").await;
+            }
+            let temp_file = tempfile::NamedTempFile::new_in(dir.join("tmp"))?;
+            let mut out = tokio::fs::File::from_std(temp_file.reopen()?);
+            let copied = tokio::io::copy(&mut (&mut reader).take(len), &mut out).await?;
+            out.shutdown().await?;
+            drop(out);
+            if copied != len {
+                bail!("upload ended after {copied} of {len} bytes");
+            }
+            let verified = tokio::task::spawn_blocking(move || {
+                hash::ensure_checksum(temp_file.path(), &hex, None, &algo).map(|_| temp_file)
+            })
+            .await?;
+            let stream = reader.get_mut();
+            match verified {
+                Ok(temp_file) => {
+                    file::create_dir_all(artifact.parent().unwrap())?;
+                    temp_file.persist(&artifact)?;
+                    info!("stored {}", display_path(&artifact));
+                    respond(stream, StatusCode::CREATED, "stored\n").await
+                }
+                Err(err) => {
+                    warn!("rejected upload from {peer}: {err:#}");
+                    respond(stream, StatusCode::BAD_REQUEST, "checksum mismatch\n").await
+                }
+            }
+        }
+        _ => respond(reader.get_mut(), StatusCode::METHOD_NOT_ALLOWED, "").await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_checksum() {
+        assert_eq!(
+            parse_checksum("sha256:ABCDEF01"),
+            Some(("sha256", "abcdef01".to_string()))
+        );
+        assert_eq!(parse_checksum("sha256:not-hex"), None);
+        assert_eq!(parse_checksum("crc32:abcd"), None);
+        assert_eq!(parse_checksum("abcd"), None);
+    }
+
+    #[test]
+    fn test_artifact_path_rejects_traversal() {
+        let dir = Path::new("/cache");
+        let (path, algo, hex) = artifact_path(dir, "/artifacts/sha256/abcdef0123").unwrap();
+        assert_eq!(path, dir.join("sha256/ab/abcdef0123"));
+        assert_eq!(algo, "sha256");
+        assert_eq!(hex, "abcdef0123");
+        assert!(artifact_path(dir, "/artifacts/sha256/../../etc/passwd").is_none());
+        assert!(artifact_path(dir, "/artifacts/sha256/ab").is_none());
+        assert!(artifact_path(dir, "/other").is_none());
+    }
+}
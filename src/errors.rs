@@ -23,6 +23,8 @@ pub enum Error {
     ScriptFailed(String, Option<ExitStatus>),
     #[error("task interrupted before process start")]
     TaskInterrupted,
+    #[error("{0}")]
+    LimitExceeded(String, ExitStatus),
     #[error(
         "Config files in {} are not trusted.\nTrust them with `mise trust`. See https://mise.jdx.dev/cli/trust.html for more information.",
         display_path(.0)
@@ -110,13 +112,23 @@ pub fn split_install_result(
 
 impl Error {
     pub fn get_exit_status(err: &Report) -> Option<i32> {
-        if let Some(Error::ScriptFailed(_, Some(status))) = err.downcast_ref::<Error>() {
-            status.code()
-        } else {
-            None
+        match err.downcast_ref::<Error>() {
+            Some(Error::ScriptFailed(_, Some(status))) => status.code(),
+            Some(Error::LimitExceeded(_, status)) => status.code(),
+            _ => None,
         }
     }
 
+    /// Why a command was stopped, e.g. "killed: memory limit 2GiB", when it failed because of
+    /// a resource limit.
+    pub fn get_limit_exceeded(err: &Report) -> Option<String> {
+        err.chain()
+            .find_map(|err| match err.downcast_ref::<Error>() {
+                Some(Error::LimitExceeded(message, _)) => Some(message.clone()),
+                _ => None,
+            })
+    }
+
     #[cfg(unix)]
     pub fn is_sigint(err: &Report) -> bool {
         use std::os::unix::process::ExitStatusExt;
@@ -179,6 +191,22 @@ mod tests {
         assert!(!Error::is_sigint(&err));
     }
 
+    #[test]
+    fn detects_limit_exceeded_through_context() {
+        let status = ExitStatus::from_raw(nix::sys::signal::SIGKILL as i32);
+        let err = Report::new(Error::LimitExceeded(
+            "killed: memory limit 2GiB".into(),
+            status,
+        ))
+        .wrap_err("task build failed");
+
+        assert_eq!(
+            Error::get_limit_exceeded(&err).as_deref(),
+            Some("killed: memory limit 2GiB")
+        );
+        assert_eq!(Error::get_exit_status(&err), None);
+    }
+
     #[test]
     fn detects_interruption_before_process_start() {
         let err = Report::new(Error::TaskInterrupted);
@@ -28,7 +28,7 @@ use zip::ZipArchive;
 #[cfg(windows)]
 use crate::config::Settings;
 use crate::ui::progress_report::SingleReport;
-use crate::{dirs, env};
+use crate::{dirs, env, timeline};
 
 pub fn open<P: AsRef<Path>>(path: P) -> Result<File> {
     let path = path.as_ref();
@@ -1386,6 +1386,10 @@ pub fn extract_archive(
     }
 }
 
+fn extract_span(archive: &Path) -> timeline::Span {
+    timeline::span("extract", "extract").arg("archive", display_path(archive))
+}
+
 pub fn untar(
     archive: &Path,
     dest: &Path,
@@ -1395,6 +1399,7 @@ pub fn untar(
     if !format.is_tar_archive() && format != ExtractionFormat::Raw {
         bail!("untar only supports tar formats, got {}", format);
     }
+    let _span = extract_span(archive);
 
     debug!("tar -xf {} -C {}", archive.display(), dest.display());
     if let Some(pr) = &opts.pr {
@@ -1506,6 +1511,7 @@ fn strip_archive_path_components(dir: &Path, strip_depth: usize) -> Result<()> {
 }
 
 pub fn unzip(archive: &Path, dest: &Path, opts: &ExtractOptions<'_>) -> Result<()> {
+    let _span = extract_span(archive);
     // TODO: show progress
     debug!("unzip {} -d {}", archive.display(), dest.display());
     if let Some(pr) = &opts.pr {
@@ -1576,6 +1582,7 @@ pub fn un_pkg(archive: &Path, dest: &Path) -> Result<()> {
 }
 
 pub fn un7z(archive: &Path, dest: &Path, opts: &ExtractOptions<'_>) -> Result<()> {
+    let _span = extract_span(archive);
     if let Some(pr) = &opts.pr {
         pr.set_message(format!(
             "extract {}",
@@ -1760,6 +1767,27 @@ pub fn clone_dir(from: &PathBuf, to: &PathBuf) -> Result<()> {
     Ok(())
 }
 
+/// Copies a single file as a copy-on-write clone where the filesystem supports it. Without
+/// `fallback`, failing to share the blocks is an error instead of a regular copy.
+pub fn clone_file(from: &Path, to: &Path, fallback: bool) -> Result<()> {
+    if cfg!(macos) {
+        cmd!("/bin/cp", "-c", from, to).run()?;
+    } else if cfg!(windows) {
+        if !fallback {
+            bail!("copy-on-write clones are not supported on Windows");
+        }
+        fs::copy(from, to)?;
+    } else {
+        let reflink = if fallback {
+            "--reflink=auto"
+        } else {
+            "--reflink=always"
+        };
+        cmd!("cp", reflink, from, to).run()?;
+    }
+    Ok(())
+}
+
 /// Inspects the top-level contents of a tar archive without extracting it
 /// Skips leading CurDir (".") components from a path's components iterator.
 /// Archives often have paths like "./foo/bar" where the leading "." should be ignored.
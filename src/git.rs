@@ -434,6 +434,31 @@ impl Git {
             .to_string())
     }
 
+    /// Fails unless `revision` names a commit, so callers can tell an unknown revision apart
+    /// from a file that does not exist at it.
+    pub fn verify_revision(&self, revision: &str) -> Result<()> {
+        validate_revision("revision", revision)?;
+        let output = git_cmd!(
+            &self.dir,
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            format!("{revision}^{{commit}}")
+        )
+        .stdout_capture()
+        .stderr_capture()
+        .unchecked()
+        .run()
+        .wrap_err("git rev-parse failed")?;
+        if !output.status.success() {
+            return Err(eyre!(
+                "unknown Git revision {revision:?} in {}",
+                self.dir.display()
+            ));
+        }
+        Ok(())
+    }
+
     /// Reads a UTF-8 file at a Git revision, returning `None` when it does not exist there.
     pub fn file_at_revision(&self, revision: &str, path: &Path) -> Result<Option<String>> {
         validate_revision("revision", revision)?;
@@ -467,6 +492,36 @@ impl Git {
     }
 }
 
+/// Lists the tags of a remote repository as `(tag, commit)` pairs without
+/// cloning it. Annotated tags resolve to the commit they point at.
+pub fn ls_remote_tags(url: &str) -> Result<Vec<(String, String)>> {
+    let output = sanitize_git_env(cmd!("git", "ls-remote", "--tags", url))
+        .read()
+        .wrap_err_with(|| format!("git ls-remote --tags {url} failed"))?;
+    Ok(parse_remote_tags(&output))
+}
+
+fn parse_remote_tags(output: &str) -> Vec<(String, String)> {
+    let mut tags: Vec<(String, String)> = vec![];
+    for (sha, name) in output
+        .lines()
+        .filter_map(|line| line.split_once(char::is_whitespace))
+    {
+        let Some(name) = name.trim().strip_prefix("refs/tags/") else {
+            continue;
+        };
+        match name.strip_suffix("^{}") {
+            // the peeled entry follows its tag object, prefer the commit it names
+            Some(tag) => match tags.iter_mut().find(|(t, _)| t == tag) {
+                Some(entry) => entry.1 = sha.to_string(),
+                None => tags.push((tag.to_string(), sha.to_string())),
+            },
+            None => tags.push((name.to_string(), sha.to_string())),
+        }
+    }
+    tags
+}
+
 fn validate_revisions(base: &str, head: &str) -> Result<()> {
     validate_revision("base", base)?;
     validate_revision("head", head)
@@ -562,7 +617,7 @@ fn remote_ref_kind(output: &str, branch_ref: &str, tag_ref: &str) -> Option<Remo
 /// so the heuristic is safe in practice. Abbreviated SHAs are intentionally not
 /// matched — they are ambiguous with short branch names and need server-side
 /// resolution before they can be checked out.
-fn looks_like_sha(s: &str) -> bool {
+pub fn looks_like_sha(s: &str) -> bool {
     matches!(s.len(), 40 | 64) && s.bytes().all(|b| b.is_ascii_hexdigit())
 }
 
@@ -685,6 +740,23 @@ mod tests {
         assert!(!looks_like_sha("g123456789abcdef0123456789abcdef01234567")); // non-hex
     }
 
+    #[test]
+    fn remote_tags_parser_peels_annotated_tags() {
+        let output = "\
+aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\trefs/tags/v1.0.0
+bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb\trefs/tags/v1.1.0
+cccccccccccccccccccccccccccccccccccccccc\trefs/tags/v1.1.0^{}
+dddddddddddddddddddddddddddddddddddddddd\trefs/heads/main
+";
+        assert_eq!(
+            super::parse_remote_tags(output),
+            vec![
+                ("v1.0.0".to_string(), "a".repeat(40)),
+                ("v1.1.0".to_string(), "c".repeat(40)),
+            ]
+        );
+    }
+
     #[test]
     fn remote_ref_parser_prefers_branches_over_tags() {
         let output = "\
@@ -752,6 +824,9 @@ bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb\trefs/tags/release
                 .unwrap(),
             None
         );
+        assert!(repo.verify_revision(&base).is_ok());
+        assert!(repo.verify_revision("HEAD~1").is_ok());
+        assert!(repo.verify_revision("no-such-branch").is_err());
     }
 
     #[test]
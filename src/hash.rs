@@ -5,6 +5,7 @@ use std::path::Path;
 
 use crate::file;
 use crate::file::display_path;
+use crate::timeline;
 use crate::ui::progress_report::SingleReport;
 use blake3::Hasher as Blake3Hasher;
 use digest::Digest;
@@ -97,6 +98,7 @@ pub fn ensure_checksum(
     pr: Option<&dyn SingleReport>,
     algo: &str,
 ) -> Result<()> {
+    let _span = timeline::span("verify", "verify").arg("algorithm", algo);
     let use_external_hasher = file::size(path).unwrap_or(u64::MAX) > 10 * 1024 * 1024;
     let actual = match algo {
         "blake3" => file_hash_blake3(path, pr)?,
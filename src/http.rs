@@ -20,9 +20,10 @@ use crate::cli::version;
 use crate::config::Settings;
 use crate::file::display_path;
 use crate::netrc;
+use crate::timeline;
 use crate::ui::progress_report::SingleReport;
 use crate::ui::time::format_duration;
-use crate::{env, file};
+use crate::{download_cache, env, file};
 
 pub static HTTP: Lazy<Client> =
     Lazy::new(|| Client::new_shared(Settings::get().http_timeout(), ClientKind::Http));
@@ -432,6 +433,15 @@ impl Client {
     ) -> Result<()> {
         ensure!(!Settings::get().offline(), "offline mode is enabled");
         let url = url.into_url()?;
+        let _span = timeline::span("download", "download").arg("url", url.to_string());
+        let cache_checksum = download_cache::locked_checksum(&url);
+        if let Some(checksum) = &cache_checksum {
+            match download_cache::fetch(self.reqwest()?, checksum, path, pr).await {
+                Ok(true) => return Ok(()),
+                Ok(false) => {}
+                Err(err) => debug!("download cache unavailable, falling back to {url}: {err:#}"),
+            }
+        }
         debug!("GET Downloading {} to {}", &url, display_path(path));
         let parent = path.parent().unwrap();
         file::create_dir_all(parent)?;
@@ -499,8 +509,11 @@ impl Client {
         // blocking task cannot be cancelled once it starts, so keeping it out
         // of `timeout` prevents us from returning an error while it can still
         // install the destination in the background.
-        let path = path.to_path_buf();
-        tokio::task::spawn_blocking(move || temp_file.persist(path)).await??;
+        let dest = path.to_path_buf();
+        tokio::task::spawn_blocking(move || temp_file.persist(dest)).await??;
+        if let Some(checksum) = &cache_checksum {
+            download_cache::upload(self.reqwest()?, checksum, path).await;
+        }
         Ok(())
     }
 
@@ -0,0 +1,98 @@
+//! Minimal HTTP/1.1 request reading for the servers mise runs itself, such as
+//! `mise cache serve` and `mise task-cache serve`.
+//!
+//! Each connection handles a single request and is closed after the response, so only the
+//! request line and headers are parsed here. Handlers read the body themselves using
+//! [`Request::content_length`].
+use std::collections::HashMap;
+
+use eyre::{Result, bail};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+
+/// Upper bound on the request line and headers, so a client cannot make the server buffer
+/// an unbounded amount of data before the body.
+const MAX_HEADER_BYTES: usize = 64 * 1024;
+
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    headers: HashMap<String, String>,
+}
+
+impl Request {
+    /// Returns a header value. Names are case-insensitive.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .get(&name.to_ascii_lowercase())
+            .map(String::as_str)
+    }
+
+    pub fn content_length(&self) -> Option<u64> {
+        self.header("content-length")?.parse().ok()
+    }
+}
+
+/// Reads the request line and headers, leaving the body in `reader`.
+pub async fn read_request<R: AsyncRead + Unpin>(reader: &mut BufReader<R>) -> Result<Request> {
+    let mut line = String::new();
+    reader.read_line(&mut line).await?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(path)) = (parts.next(), parts.next()) else {
+        bail!("malformed request line: {line:?}");
+    };
+    let mut request = Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        headers: HashMap::new(),
+    };
+    let mut header_bytes = line.len();
+    loop {
+        line.clear();
+        let n = reader.read_line(&mut line).await?;
+        header_bytes += n;
+        if n == 0 || line == "\r\n" || line == "\n" {
+            break;
+        }
+        if header_bytes > MAX_HEADER_BYTES {
+            bail!("request headers too large");
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            request
+                .headers
+                .insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+    }
+    Ok(request)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_read_request() {
+        let raw =
+            b"PUT /artifacts/sha256/abc HTTP/1.1\r\nContent-Length: 3\r\nX-Mise: 1\r\n\r\nabc";
+        let mut reader = BufReader::new(&raw[..]);
+        let request = read_request(&mut reader).await.unwrap();
+        assert_eq!(request.method, "PUT");
+        assert_eq!(request.path, "/artifacts/sha256/abc");
+        assert_eq!(request.content_length(), Some(3));
+        assert_eq!(request.header("x-mise"), Some("1"));
+        let mut body = String::new();
+        tokio::io::AsyncReadExt::read_to_string(&mut reader, &mut body)
+            .await
+            .unwrap();
+        assert_eq!(body, "abc");
+    }
+
+    #[tokio::test]
+    async fn test_read_request_headers_too_large() {
+        let raw = format!(
+            "GET / HTTP/1.1\r\nX-Big: {}\r\n\r\n",
+            "a".repeat(MAX_HEADER_BYTES)
+        );
+        let mut reader = BufReader::new(raw.as_bytes());
+        assert!(read_request(&mut reader).await.is_err());
+    }
+}
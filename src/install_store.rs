@@ -0,0 +1,441 @@
+//! Content-addressed store for installed tool files.
+//!
+//! When `install_store` is enabled, regular files in an install directory are hashed and
+//! replaced with hardlinks (or reflinks) to a single copy under `$MISE_DATA_DIR/store/objects`.
+//! Patch releases of the same tool usually share most of their files, so this can save a lot
+//! of disk space on machines that keep many versions around.
+//!
+//! Each install that has been added to the store gets a ref file under `store/refs` listing
+//! the objects it uses. Objects are only deleted once no ref file mentions them, which is what
+//! `uninstall`/`prune` rely on through [`InstallStore::release`].
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use eyre::{Result, WrapErr};
+use walkdir::WalkDir;
+
+use crate::config::Settings;
+use crate::dirs;
+use crate::file;
+use crate::file::display_path;
+use crate::hash;
+use crate::lock_file::LockFile;
+
+const TMP_SUFFIX: &str = ".mise-store-tmp";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkMode {
+    Hardlink,
+    Reflink,
+}
+
+impl LinkMode {
+    fn from_settings(settings: &Settings) -> Self {
+        match settings.install_store_link_mode.as_str() {
+            "reflink" => Self::Reflink,
+            _ => Self::Hardlink,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DedupeStats {
+    /// regular files that were examined
+    pub files: u64,
+    /// files that now share storage with an existing store object
+    pub linked: u64,
+    /// bytes freed by replacing duplicate files with links
+    pub bytes_saved: u64,
+}
+
+impl std::ops::AddAssign for DedupeStats {
+    fn add_assign(&mut self, other: Self) {
+        self.files += other.files;
+        self.linked += other.linked;
+        self.bytes_saved += other.bytes_saved;
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct GcStats {
+    /// ref files dropped because their install directory no longer exists
+    pub stale_refs: u64,
+    /// objects deleted because nothing references them anymore
+    pub objects: u64,
+    /// bytes freed by deleting those objects
+    pub bytes: u64,
+}
+
+pub struct InstallStore {
+    root: PathBuf,
+    mode: LinkMode,
+    /// objects a dry run would have added, so later installs in the same run see them
+    planned: Mutex<HashSet<String>>,
+}
+
+impl InstallStore {
+    pub fn get() -> Self {
+        Self::new(
+            dirs::STORE.to_path_buf(),
+            LinkMode::from_settings(&Settings::get()),
+        )
+    }
+
+    pub fn is_enabled() -> bool {
+        Settings::get().install_store
+    }
+
+    fn new(root: PathBuf, mode: LinkMode) -> Self {
+        Self {
+            root,
+            mode,
+            planned: Default::default(),
+        }
+    }
+
+    fn objects_dir(&self) -> PathBuf {
+        self.root.join("objects")
+    }
+
+    fn refs_dir(&self) -> PathBuf {
+        self.root.join("refs")
+    }
+
+    fn ref_path(&self, install_path: &Path) -> PathBuf {
+        self.refs_dir().join(hash::hash_to_str(&install_path))
+    }
+
+    fn object_path(&self, key: &str) -> PathBuf {
+        self.objects_dir().join(&key[..2]).join(&key[2..])
+    }
+
+    fn lock(&self) -> Result<fslock::LockFile> {
+        LockFile::new(&self.root)
+            .with_callback(|l| debug!("waiting for lock on {}", display_path(l)))
+            .lock()
+    }
+
+    /// Moves the files of `install_path` into the store, replacing duplicates with links to
+    /// the stored copy. Running it again on the same directory is cheap and only picks up new
+    /// or changed files.
+    pub fn ingest(&self, install_path: &Path, dry_run: bool) -> Result<DedupeStats> {
+        let _lock = self.lock()?;
+        let mut stats = DedupeStats::default();
+        let mut keys = Vec::new();
+        for entry in WalkDir::new(install_path).follow_links(false) {
+            let entry = entry?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let path = entry.path();
+            let meta = entry.metadata()?;
+            if meta.len() == 0 || path.to_string_lossy().ends_with(TMP_SUFFIX) {
+                continue;
+            }
+            stats.files += 1;
+            let key = object_key(path, &meta)?;
+            let object = self.object_path(&key);
+            let exists =
+                object.exists() || (dry_run && self.planned.lock().unwrap().contains(&key));
+            if !exists {
+                if dry_run {
+                    self.planned.lock().unwrap().insert(key.clone());
+                    keys.push(key);
+                    continue;
+                }
+                file::create_dir_all(object.parent().unwrap())?;
+                let stored = match self.mode {
+                    LinkMode::Hardlink => fs::hard_link(path, &object).map_err(Into::into),
+                    LinkMode::Reflink => file::clone_file(path, &object, true),
+                };
+                match stored {
+                    Ok(()) => keys.push(key),
+                    Err(err) => trace!("install store: skipping {}: {err:#}", display_path(path)),
+                }
+                continue;
+            }
+            if self.mode == LinkMode::Hardlink && same_file(&meta, &object) {
+                keys.push(key);
+                continue;
+            }
+            let saved = if self.mode == LinkMode::Reflink || link_count(&meta) == 1 {
+                meta.len()
+            } else {
+                // other hardlinks outside the store still hold on to this data
+                0
+            };
+            if dry_run {
+                stats.linked += 1;
+                stats.bytes_saved += saved;
+                keys.push(key);
+                continue;
+            }
+            match self.link_object(&object, path) {
+                Ok(()) => {
+                    stats.linked += 1;
+                    stats.bytes_saved += saved;
+                    keys.push(key);
+                }
+                Err(err) => trace!("install store: skipping {}: {err:#}", display_path(path)),
+            }
+        }
+        if !dry_run {
+            self.write_ref(install_path, &keys)?;
+        }
+        Ok(stats)
+    }
+
+    /// Atomically replaces `path` with a link to `object`.
+    fn link_object(&self, object: &Path, path: &Path) -> Result<()> {
+        let tmp = PathBuf::from(format!("{}{TMP_SUFFIX}", path.display()));
+        let _ = fs::remove_file(&tmp);
+        match self.mode {
+            LinkMode::Hardlink => fs::hard_link(object, &tmp)?,
+            LinkMode::Reflink => {
+                file::clone_file(object, &tmp, false)?;
+                fs::set_permissions(&tmp, fs::metadata(path)?.permissions())?;
+            }
+        }
+        fs::rename(&tmp, path).inspect_err(|_| {
+            let _ = fs::remove_file(&tmp);
+        })?;
+        Ok(())
+    }
+
+    fn write_ref(&self, install_path: &Path, keys: &[String]) -> Result<()> {
+        let mut content = format!("{}\n", install_path.display());
+        for key in keys {
+            content.push_str(key);
+            content.push('\n');
+        }
+        file::create_dir_all(self.refs_dir())?;
+        file::write(self.ref_path(install_path), content)
+    }
+
+    fn read_ref(path: &Path) -> Result<(PathBuf, Vec<String>)> {
+        let content = file::read_to_string(path)?;
+        let mut lines = content.lines();
+        let install_path = PathBuf::from(lines.next().unwrap_or_default());
+        Ok((install_path, lines.map(str::to_string).collect()))
+    }
+
+    fn ref_files(&self) -> Result<Vec<PathBuf>> {
+        let refs_dir = self.refs_dir();
+        if !refs_dir.exists() {
+            return Ok(vec![]);
+        }
+        Ok(file::ls(&refs_dir)?.into_iter().collect())
+    }
+
+    /// Drops the ref for `install_path` and deletes any objects that no other install
+    /// references. Does nothing if the install was never added to the store.
+    pub fn release(&self, install_path: &Path) -> Result<GcStats> {
+        let ref_path = self.ref_path(install_path);
+        if !ref_path.exists() {
+            return Ok(GcStats::default());
+        }
+        let _lock = self.lock()?;
+        let (_, released) = Self::read_ref(&ref_path)?;
+        file::remove_file(&ref_path)?;
+        let referenced = self.referenced_keys()?;
+        let mut stats = GcStats::default();
+        for key in released.iter().collect::<HashSet<_>>() {
+            if !referenced.contains(key) {
+                self.remove_object(key, false, &mut stats)?;
+            }
+        }
+        debug!(
+            "install store: released {} ({} objects removed)",
+            display_path(install_path),
+            stats.objects
+        );
+        Ok(stats)
+    }
+
+    /// Drops refs for installs that were deleted without going through mise and deletes every
+    /// object that is no longer referenced.
+    pub fn gc(&self, dry_run: bool) -> Result<GcStats> {
+        let _lock = self.lock()?;
+        let mut stats = GcStats::default();
+        let mut referenced = HashSet::new();
+        for ref_path in self.ref_files()? {
+            let (install_path, keys) = Self::read_ref(&ref_path)?;
+            if install_path.exists() {
+                referenced.extend(keys);
+                continue;
+            }
+            stats.stale_refs += 1;
+            if !dry_run {
+                file::remove_file(&ref_path)?;
+            }
+        }
+        let objects_dir = self.objects_dir();
+        if !objects_dir.exists() {
+            return Ok(stats);
+        }
+        for entry in WalkDir::new(&objects_dir).min_depth(2).max_depth(2) {
+            let entry = entry?;
+            let rel = entry.path().strip_prefix(&objects_dir)?;
+            let key = rel.to_string_lossy().replace(['/', '\\'], "");
+            if !referenced.contains(&key) {
+                self.remove_object(&key, dry_run, &mut stats)?;
+            }
+        }
+        Ok(stats)
+    }
+
+    fn referenced_keys(&self) -> Result<HashSet<String>> {
+        let mut keys = HashSet::new();
+        for ref_path in self.ref_files()? {
+            keys.extend(Self::read_ref(&ref_path)?.1);
+        }
+        Ok(keys)
+    }
+
+    fn remove_object(&self, key: &str, dry_run: bool, stats: &mut GcStats) -> Result<()> {
+        let object = self.object_path(key);
+        let Ok(meta) = object.symlink_metadata() else {
+            return Ok(());
+        };
+        stats.objects += 1;
+        // a hardlinked object that is still in use by an install frees nothing
+        if self.mode == LinkMode::Reflink || link_count(&meta) == 1 {
+            stats.bytes += meta.len();
+        }
+        if !dry_run {
+            file::remove_file(&object)
+                .wrap_err_with(|| format!("failed to remove {}", display_path(&object)))?;
+        }
+        Ok(())
+    }
+}
+
+/// Objects are keyed by content and permissions since hardlinks share their mode.
+fn object_key(path: &Path, meta: &fs::Metadata) -> Result<String> {
+    let hash = hash::file_hash_blake3(path, None)?;
+    Ok(format!("{hash}-{:o}", file_mode(meta)))
+}
+
+#[cfg(unix)]
+fn file_mode(meta: &fs::Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    meta.permissions().mode() & 0o7777
+}
+
+#[cfg(windows)]
+fn file_mode(meta: &fs::Metadata) -> u32 {
+    if meta.permissions().readonly() {
+        0o444
+    } else {
+        0o644
+    }
+}
+
+#[cfg(unix)]
+fn link_count(meta: &fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    meta.nlink()
+}
+
+#[cfg(windows)]
+fn link_count(_meta: &fs::Metadata) -> u64 {
+    1
+}
+
+#[cfg(unix)]
+fn same_file(meta: &fs::Metadata, other: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    other
+        .metadata()
+        .is_ok_and(|o| o.dev() == meta.dev() && o.ino() == meta.ino())
+}
+
+#[cfg(windows)]
+fn same_file(_meta: &fs::Metadata, _other: &Path) -> bool {
+    false
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::os::unix::fs::MetadataExt;
+
+    use super::*;
+
+    fn write_install(dir: &Path, files: &[(&str, &str)]) {
+        for (name, content) in files {
+            let path = dir.join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_ingest_links_identical_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = InstallStore::new(tmp.path().join("store"), LinkMode::Hardlink);
+        let a = tmp.path().join("installs/node/20.0.0");
+        let b = tmp.path().join("installs/node/20.0.1");
+        write_install(&a, &[("bin/node", "node-binary"), ("VERSION", "20.0.0")]);
+        write_install(&b, &[("bin/node", "node-binary"), ("VERSION", "20.0.1")]);
+
+        let first = store.ingest(&a, false).unwrap();
+        assert_eq!(first.files, 2);
+        assert_eq!(first.linked, 0);
+        let second = store.ingest(&b, false).unwrap();
+        assert_eq!(second.linked, 1);
+        assert_eq!(second.bytes_saved, "node-binary".len() as u64);
+
+        let a_meta = fs::metadata(a.join("bin/node")).unwrap();
+        let b_meta = fs::metadata(b.join("bin/node")).unwrap();
+        assert_eq!(a_meta.ino(), b_meta.ino());
+        assert_eq!(fs::read_to_string(b.join("VERSION")).unwrap(), "20.0.1");
+
+        // running it again is a no-op
+        assert_eq!(store.ingest(&b, false).unwrap().linked, 0);
+    }
+
+    #[test]
+    fn test_release_keeps_shared_objects() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = InstallStore::new(tmp.path().join("store"), LinkMode::Hardlink);
+        let a = tmp.path().join("installs/node/20.0.0");
+        let b = tmp.path().join("installs/node/20.0.1");
+        write_install(&a, &[("bin/node", "node-binary"), ("VERSION", "20.0.0")]);
+        write_install(&b, &[("bin/node", "node-binary"), ("VERSION", "20.0.1")]);
+        store.ingest(&a, false).unwrap();
+        store.ingest(&b, false).unwrap();
+
+        fs::remove_dir_all(&a).unwrap();
+        let stats = store.release(&a).unwrap();
+        // only a's VERSION file is unique to it
+        assert_eq!(stats.objects, 1);
+        assert_eq!(store.referenced_keys().unwrap().len(), 2);
+
+        // removing b behind mise's back leaves a stale ref that gc cleans up
+        fs::remove_dir_all(&b).unwrap();
+        let stats = store.gc(false).unwrap();
+        assert_eq!(stats.stale_refs, 1);
+        assert_eq!(stats.objects, 2);
+        assert!(store.ref_files().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_ingest_dry_run_changes_nothing() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = InstallStore::new(tmp.path().join("store"), LinkMode::Hardlink);
+        let a = tmp.path().join("a");
+        let b = tmp.path().join("b");
+        write_install(&a, &[("lib/x.so", "shared")]);
+        write_install(&b, &[("lib/x.so", "shared")]);
+        store.ingest(&a, false).unwrap();
+
+        let stats = store.ingest(&b, true).unwrap();
+        assert_eq!(stats.linked, 1);
+        let a_meta = fs::metadata(a.join("lib/x.so")).unwrap();
+        let b_meta = fs::metadata(b.join("lib/x.so")).unwrap();
+        assert_ne!(a_meta.ino(), b_meta.ino());
+        assert!(!store.ref_path(&b).exists());
+    }
+}
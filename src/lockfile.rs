@@ -66,6 +66,15 @@ pub struct Lockfile {
     /// Shared pkgx packages: platform -> package@version -> PkgxPackageInfo
     #[serde(skip)]
     pkgx_packages: BTreeMap<String, BTreeMap<String, PkgxPackageInfo>>,
+    /// Content checksums of remote task files: source -> RemoteTaskInfo
+    #[serde(skip)]
+    remote_tasks: BTreeMap<String, RemoteTaskInfo>,
+}
+
+/// A remote task file recorded by `mise tasks lock`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RemoteTaskInfo {
+    pub checksum: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -348,6 +357,9 @@ pub struct PlatformInfo {
     pub url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub url_api: Option<String>,
+    /// Commit a source-built tool was built from (git backend)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
     /// References to conda packages in the shared conda-packages section (by basename)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub conda_deps: Option<Vec<String>>,
@@ -395,6 +407,7 @@ impl PlatformInfo {
             && self.checksum.is_none()
             && self.url.is_none()
             && self.url_api.is_none()
+            && self.commit.is_none()
             && self.conda_deps.is_none()
             && self.pkgx_deps.is_none()
             && self.pkgx_provides.is_none()
@@ -411,6 +424,7 @@ impl PlatformInfo {
     pub fn without_artifact_data(&self) -> Self {
         PlatformInfo {
             install: self.install.clone(),
+            commit: self.commit.clone(),
             conda_deps: self.conda_deps.clone(),
             pkgx_deps: self.pkgx_deps.clone(),
             pkgx_provides: self.pkgx_provides.clone(),
@@ -504,6 +518,7 @@ impl PlatformInfo {
                 self.url.clone().or_else(|| other.url.clone())
             },
             url_api,
+            commit: self.commit.clone().or_else(|| other.commit.clone()),
             conda_deps: self.conda_deps.clone().or_else(|| other.conda_deps.clone()),
             pkgx_deps: self.pkgx_deps.clone().or_else(|| other.pkgx_deps.clone()),
             pkgx_provides: self
@@ -559,6 +574,10 @@ impl TryFrom<toml::Value> for PlatformInfo {
                     Some(toml::Value::String(s)) => Some(s),
                     _ => None,
                 };
+                let commit = match t.remove("commit") {
+                    Some(toml::Value::String(s)) => Some(s),
+                    _ => None,
+                };
                 let conda_deps = match t.remove("conda_deps") {
                     Some(toml::Value::Array(arr)) => Some(
                         arr.into_iter()
@@ -659,6 +678,7 @@ impl TryFrom<toml::Value> for PlatformInfo {
                     size,
                     url,
                     url_api,
+                    commit,
                     conda_deps,
                     pkgx_deps,
                     pkgx_provides,
@@ -689,6 +709,9 @@ impl From<PlatformInfo> for toml::Value {
         if let Some(url_api) = platform_info.url_api {
             table.insert("url_api".to_string(), url_api.into());
         }
+        if let Some(commit) = platform_info.commit {
+            table.insert("commit".to_string(), commit.into());
+        }
         if let Some(conda_deps) = platform_info.conda_deps {
             let deps: toml::Value = conda_deps
                 .into_iter()
@@ -861,8 +884,13 @@ impl Lockfile {
         }
         trace!("reading lockfile {}", path.display_user());
         let content = file::read_to_string(path)?;
-        let generated_header_url = existing_lockfile_doc_url(&content);
-        let mut table: toml::Table = toml::from_str(&content)?;
+        Self::parse(&content)
+    }
+
+    /// Parses lockfile contents that were not read from disk, e.g. a lockfile at a Git revision.
+    pub fn parse(content: &str) -> Result<Self> {
+        let generated_header_url = existing_lockfile_doc_url(content);
+        let mut table: toml::Table = toml::from_str(content)?;
 
         let tools: toml::Table = table
             .remove("tools")
@@ -919,6 +947,14 @@ impl Lockfile {
             }
         }
 
+        // Parse remote-tasks section: source -> RemoteTaskInfo
+        if let Some(remote_tasks) = table.remove("remote-tasks") {
+            let sources: toml::Table = remote_tasks.try_into()?;
+            for (source, info) in sources {
+                lockfile.remote_tasks.insert(source, info.try_into()?);
+            }
+        }
+
         Ok(lockfile)
     }
 
@@ -978,6 +1014,16 @@ impl Lockfile {
             lockfile.insert("pkgx-packages".to_string(), pkgx_packages.into());
         }
 
+        if !self.remote_tasks.is_empty() {
+            let mut remote_tasks = toml::Table::new();
+            for (source, info) in &self.remote_tasks {
+                let mut task_table = toml::Table::new();
+                task_table.insert("checksum".to_string(), info.checksum.clone().into());
+                remote_tasks.insert(source.clone(), task_table.into());
+            }
+            lockfile.insert("remote-tasks".to_string(), remote_tasks.into());
+        }
+
         // Write tools section
         let mut tools = toml::Table::new();
         for (short, versions) in &self.tools {
@@ -1078,6 +1124,26 @@ impl Lockfile {
         self.pkgx_packages.get(platform)?.get(id)
     }
 
+    /// Record the content checksum of a remote task file, e.g. "sha256:abc123"
+    pub fn set_remote_task(&mut self, source: &str, checksum: &str) {
+        self.remote_tasks.insert(
+            source.to_string(),
+            RemoteTaskInfo {
+                checksum: checksum.to_string(),
+            },
+        );
+    }
+
+    pub fn get_remote_task(&self, source: &str) -> Option<&RemoteTaskInfo> {
+        self.remote_tasks.get(source)
+    }
+
+    /// Remove remote task entries whose source is not in `sources`.
+    pub fn retain_remote_tasks(&mut self, sources: &BTreeSet<String>) {
+        self.remote_tasks
+            .retain(|source, _| sources.contains(source));
+    }
+
     /// Remove unreferenced conda packages from the shared section.
     /// A package is unreferenced if no tool's conda_deps references it.
     fn cleanup_unreferenced_conda_packages(&mut self) {
@@ -1312,6 +1378,7 @@ impl Lockfile {
                             None
                         }
                     }),
+                    commit: platform_info.commit.or_else(|| existing.commit.clone()),
                     // For dependency lists, always use the new value - None means "no dependencies"
                     // rather than "not computed", so we shouldn't preserve stale deps
                     conda_deps: platform_info.conda_deps,
@@ -4749,6 +4816,30 @@ backend = "conda:jq"
         let _ = std::fs::remove_file(&test_lockfile);
     }
 
+    #[test]
+    fn test_remote_tasks_roundtrip() {
+        let mut lockfile = Lockfile::default();
+        let source = "git::https://github.com/myorg/tasks.git//lint.sh?ref=v1";
+        lockfile.set_remote_task(source, "sha256:abc123");
+        lockfile.set_remote_task("https://example.com/stale.sh", "sha256:def456");
+        lockfile.retain_remote_tasks(&BTreeSet::from([source.to_string()]));
+
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("mise.lock");
+        lockfile.save(&path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("[remote-tasks."), "{content}");
+        assert!(!content.contains("stale.sh"), "{content}");
+
+        let reloaded = Lockfile::read(&path).unwrap();
+        assert_eq!(
+            reloaded
+                .get_remote_task(source)
+                .map(|t| t.checksum.as_str()),
+            Some("sha256:abc123")
+        );
+    }
+
     #[test]
     fn test_cleanup_unreferenced_conda_packages() {
         let mut lockfile = Lockfile::default();
@@ -42,6 +42,7 @@ mod deps;
 pub(crate) mod deps_graph;
 mod direnv;
 mod dirs;
+mod download_cache;
 pub(crate) mod duration;
 mod env;
 mod env_diff;
@@ -60,8 +61,10 @@ mod hash;
 mod hook_env;
 mod hooks;
 mod http;
+mod http_server;
 mod install_before;
 mod install_context;
+mod install_store;
 mod lock_file;
 mod lockfile;
 pub(crate) mod logger;
@@ -91,6 +94,7 @@ mod sysconfig;
 mod system;
 pub(crate) mod task;
 pub(crate) mod tera;
+mod timeline;
 pub(crate) mod timeout;
 mod tokens;
 mod toml;
@@ -100,6 +104,7 @@ mod uv;
 mod versions_host;
 mod watch_files;
 mod wildcard;
+mod xml;
 
 pub(crate) use crate::exit::request as request_exit;
 pub(crate) use crate::result::Result;
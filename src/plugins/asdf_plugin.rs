@@ -63,7 +63,7 @@ impl AsdfPlugin {
             .ok_or_else(|| eyre!("No repository found for plugin {}", self.name))
     }
 
-    fn exec_hook_post_plugin_update(
+    async fn exec_hook_post_plugin_update(
         &self,
         pr: &dyn SingleReport,
         pre: String,
@@ -79,15 +79,15 @@ impl AsdfPlugin {
             .into_iter()
             .map(|(k, v)| (k.into(), v.into()))
             .collect();
-            self.exec_hook_env(pr, "post-plugin-update", env)?;
+            self.exec_hook_env(pr, "post-plugin-update", env).await?;
         }
         Ok(())
     }
 
-    fn exec_hook(&self, pr: &dyn SingleReport, hook: &str) -> eyre::Result<()> {
-        self.exec_hook_env(pr, hook, Default::default())
+    async fn exec_hook(&self, pr: &dyn SingleReport, hook: &str) -> eyre::Result<()> {
+        self.exec_hook_env(pr, hook, Default::default()).await
     }
-    fn exec_hook_env(
+    async fn exec_hook_env(
         &self,
         pr: &dyn SingleReport,
         hook: &str,
@@ -98,11 +98,24 @@ impl AsdfPlugin {
         sm.env.extend(env);
         if sm.script_exists(&script) {
             pr.set_message(format!("bin/{hook}"));
-            sm.run_by_line(&script, pr)?;
+            sm.run_by_line(&script, pr).await?;
         }
         Ok(())
     }
-    pub fn fetch_remote_versions(&self, script_man: &ScriptManager) -> eyre::Result<Vec<String>> {
+    pub async fn fetch_remote_versions(
+        &self,
+        script_man: &ScriptManager,
+    ) -> eyre::Result<Vec<String>> {
+        let stdout = match script_man.sandbox {
+            Some(_) => script_man.read_sandboxed(&Script::ListAll).await?,
+            None => self.run_list_all(script_man)?,
+        };
+        Ok(stdout
+            .split_whitespace()
+            .map(|v| regex!(r"^v(\d+)").replace(v, "$1").to_string())
+            .collect())
+    }
+    fn run_list_all(&self, script_man: &ScriptManager) -> eyre::Result<String> {
         Settings::ensure_not_safe("executing asdf plugin scripts")?;
         let cmd = script_man.cmd(&Script::ListAll);
         let result = run_with_timeout(
@@ -134,13 +147,17 @@ impl AsdfPlugin {
             display_stderr();
         }
 
-        Ok(stdout
-            .split_whitespace()
-            .map(|v| regex!(r"^v(\d+)").replace(v, "$1").to_string())
-            .collect())
+        Ok(stdout)
     }
-    pub fn fetch_latest_stable(&self, script_man: &ScriptManager) -> eyre::Result<Option<String>> {
-        let latest_stable = script_man.read(&Script::LatestStable)?.trim().to_string();
+    pub async fn fetch_latest_stable(
+        &self,
+        script_man: &ScriptManager,
+    ) -> eyre::Result<Option<String>> {
+        let latest_stable = match script_man.sandbox {
+            Some(_) => script_man.read_sandboxed(&Script::LatestStable).await?,
+            None => script_man.read(&Script::LatestStable)?,
+        };
+        let latest_stable = latest_stable.trim().to_string();
         Ok(if latest_stable.is_empty() {
             None
         } else {
@@ -334,7 +351,7 @@ impl Plugin for AsdfPlugin {
         let (pre, post) = git.update(gitref)?;
         let sha = git.current_sha_short()?;
         let repo_url = self.get_remote_url()?.unwrap_or_default();
-        self.exec_hook_post_plugin_update(pr, pre, post)?;
+        self.exec_hook_post_plugin_update(pr, pre, post).await?;
         pr.finish_with_message(format!(
             "{repo_url}#{}",
             style(&sha).bright().yellow().for_stderr(),
@@ -346,7 +363,7 @@ impl Plugin for AsdfPlugin {
         if !self.is_installed() {
             return Ok(());
         }
-        self.exec_hook(pr, "pre-plugin-remove")?;
+        self.exec_hook(pr, "pre-plugin-remove").await?;
         pr.set_message("uninstall".into());
 
         remove_git_plugin_source(&self.name, &self.plugin_path, pr)?;
@@ -370,7 +387,7 @@ impl Plugin for AsdfPlugin {
 
         if let Some(source) = local_source {
             install_local_plugin_source(&self.plugin_path, &source, pr)?;
-            if let Err(err) = self.exec_hook(pr, "post-plugin-add") {
+            if let Err(err) = self.exec_hook(pr, "post-plugin-add").await {
                 if let Err(cleanup_err) =
                     remove_git_plugin_source(&self.name, &self.plugin_path, pr)
                 {
@@ -387,7 +404,7 @@ impl Plugin for AsdfPlugin {
         match source {
             PluginSource::Zip { url } => {
                 self.install_from_zip(&url, pr).await?;
-                self.exec_hook(pr, "post-plugin-add")?;
+                self.exec_hook(pr, "post-plugin-add").await?;
                 pr.finish_with_message(url.to_string());
                 Ok(())
             }
@@ -404,7 +421,7 @@ impl Plugin for AsdfPlugin {
                     subdir.as_deref(),
                     pr,
                 )?;
-                self.exec_hook(pr, "post-plugin-add")?;
+                self.exec_hook(pr, "post-plugin-add").await?;
 
                 let sha = git.current_sha_short()?;
                 pr.finish_with_message(format!(
@@ -331,10 +331,10 @@ fn install_script_path() -> PathBuf {
 }
 
 #[cfg(unix)]
-const DOTNET_BIN: &str = "dotnet";
+pub(super) const DOTNET_BIN: &str = "dotnet";
 
 #[cfg(windows)]
-const DOTNET_BIN: &str = "dotnet.exe";
+pub(super) const DOTNET_BIN: &str = "dotnet.exe";
 
 #[cfg(unix)]
 const INSTALL_SCRIPT_NAME: &str = "dotnet-install.sh";
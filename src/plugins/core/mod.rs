@@ -51,6 +51,11 @@ pub static CORE_PLUGINS: Lazy<BackendMap> = Lazy::new(|| {
         .collect()
 });
 
+/// The name of the `dotnet` executable on this platform
+pub fn dotnet_bin() -> &'static str {
+    dotnet::DOTNET_BIN
+}
+
 pub fn path_env_with_tv_path(tv: &ToolVersion) -> Result<OsString> {
     let mut path_env = PathEnv::from_iter(env::PATH.clone());
     path_env.add(tv.install_path().join("bin"));
@@ -2,11 +2,12 @@ use std::collections::HashMap;
 use std::ffi::OsString;
 use std::fmt;
 use std::fmt::{Display, Formatter};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use color_eyre::eyre::{Context, Result};
 use duct::Expression;
 use indexmap::indexmap;
+use itertools::Itertools;
 use std::sync::LazyLock as Lazy;
 
 use crate::cmd::{CmdLineRunner, cmd};
@@ -16,6 +17,7 @@ use crate::errors::Error;
 use crate::errors::Error::ScriptFailed;
 use crate::fake_asdf::get_path_with_fake_asdf;
 use crate::file::display_path;
+use crate::sandbox::SandboxConfig;
 use crate::ui::progress_report::SingleReport;
 use crate::{dirs, env};
 
@@ -23,6 +25,8 @@ use crate::{dirs, env};
 pub struct ScriptManager {
     pub plugin_path: PathBuf,
     pub env: HashMap<OsString, OsString>,
+    /// The install sandbox scripts run in, see [`crate::sandbox::plugin_sandbox`]
+    pub sandbox: Option<SandboxConfig>,
 }
 
 #[derive(Debug, Clone)]
@@ -104,7 +108,22 @@ impl ScriptManager {
             // used for testing failure cases
             env.insert("MISE_FAILURE".into(), failure);
         }
-        Self { env, plugin_path }
+        Self {
+            env,
+            plugin_path,
+            sandbox: None,
+        }
+    }
+
+    /// Runs scripts in `sandbox`, dropping the env vars it doesn't allow. Vars set afterwards
+    /// with [`Self::with_env`] still reach the scripts.
+    pub fn with_sandbox(mut self, sandbox: Option<SandboxConfig>) -> Self {
+        if let Some(sandbox) = &sandbox {
+            self.env
+                .retain(|key, _| sandbox.allows_env(&key.to_string_lossy()));
+        }
+        self.sandbox = sandbox;
+        self
     }
 
     pub fn with_env<K, V>(mut self, k: K, v: V) -> Self
@@ -188,28 +207,72 @@ impl ScriptManager {
             .wrap_err_with(|| ScriptFailed(display_path(self.get_script_path(script)), None))
     }
 
-    pub fn run_by_line(&self, script: &Script, pr: &dyn SingleReport) -> Result<()> {
+    /// Runs a script like [`Self::read`], in the install sandbox when there is one.
+    pub async fn read_sandboxed(&self, script: &Script) -> Result<String> {
+        Settings::ensure_not_safe("executing asdf plugin scripts")?;
+        let path = self.get_script_path(script);
+        let cmd = self
+            .runner(&path)
+            .env_clear()
+            .envs(&self.env)
+            .with_timeout(Settings::get().fetch_remote_versions_timeout());
+        let cmd = self.sandboxed(cmd).await?;
+        cmd.read().await.map_err(|e| self.script_failed(script, e))
+    }
+
+    pub async fn run_by_line(&self, script: &Script, pr: &dyn SingleReport) -> Result<()> {
         Settings::ensure_not_safe("executing asdf plugin scripts")?;
         let path = self.get_script_path(script);
         pr.set_message(display_path(&path));
-        let mut cmd = CmdLineRunner::new(path.clone());
+        let cmd = self.runner(&path).with_pr(pr).env_clear().envs(&self.env);
+        let cmd = self.sandboxed(cmd).await?;
+        cmd.execute().map_err(|e| self.script_failed(script, e))
+    }
+
+    fn runner<'a>(&self, path: &Path) -> CmdLineRunner<'a> {
         if let Some(arch) = &Settings::get().arch
             && arch == "x86_64"
             && cfg!(macos)
         {
-            cmd = CmdLineRunner::new("/usr/bin/arch")
-                .arg("-x86_64")
-                .arg(path.clone());
+            return CmdLineRunner::new("/usr/bin/arch").arg("-x86_64").arg(path);
         }
-        let cmd = cmd.with_pr(pr).env_clear().envs(&self.env);
-        if let Err(e) = cmd.execute() {
-            let status = match e.downcast_ref::<Error>() {
-                Some(ScriptFailed(_, status)) => *status,
-                _ => None,
-            };
-            return Err(ScriptFailed(display_path(&path), status).into());
-        }
-        Ok(())
+        CmdLineRunner::new(path)
+    }
+
+    async fn sandboxed<'a>(&self, cmd: CmdLineRunner<'a>) -> Result<CmdLineRunner<'a>> {
+        let Some(sandbox) = &self.sandbox else {
+            return Ok(cmd);
+        };
+        let mut cmd = cmd.with_sandbox(sandbox.clone());
+        cmd.apply_sandbox().await?;
+        Ok(cmd)
+    }
+
+    /// Reports a failed script, naming the plugin and its sandbox when the sandbox may be what
+    /// stopped it.
+    fn script_failed(&self, script: &Script, err: eyre::Report) -> eyre::Report {
+        let path = self.get_script_path(script);
+        let status = match err.downcast_ref::<Error>() {
+            Some(ScriptFailed(_, status)) => *status,
+            _ => None,
+        };
+        let report = eyre::Report::from(ScriptFailed(display_path(&path), status));
+        let Some(sandbox) = &self.sandbox else {
+            return report;
+        };
+        let plugin = self
+            .plugin_path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy();
+        let dirs = sandbox.allow_write.iter().map(display_path).join(", ");
+        let hosts = match sandbox.allow_net.is_empty() {
+            true => " and connect to no hosts".to_string(),
+            false => format!(" and connect to {}", sandbox.allow_net.join(", ")),
+        };
+        report.wrap_err(format!(
+            "plugin {plugin} hook bin/{script} failed in the install sandbox, which only lets it write to {dirs}{hosts} (see the install_sandbox settings)"
+        ))
     }
 }
 
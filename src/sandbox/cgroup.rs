@@ -0,0 +1,226 @@
+//! Resource limits through a delegated cgroup v2 subtree.
+//!
+//! mise creates one child cgroup per limited command under the cgroup it runs in. That needs the
+//! subtree to be writable by the user, e.g. the root cgroup of a container or a systemd unit with
+//! `Delegate=yes`. When mise shares its cgroup with nothing else, it first moves itself into a
+//! `mise` leaf so controllers can be enabled for its children.
+
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use eyre::{Result, bail};
+
+use super::limits::ResourceLimits;
+use crate::file::display_path;
+
+const CGROUP_MOUNT: &str = "/sys/fs/cgroup";
+const CPU_PERIOD_US: u64 = 100_000;
+const CONTROLLERS: &[&str] = &["cpu", "memory", "pids"];
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A cgroup holding one limited command and its descendants, removed on drop.
+pub struct Cgroup {
+    path: PathBuf,
+}
+
+struct Delegation {
+    path: PathBuf,
+    controllers: Vec<&'static str>,
+}
+
+impl Cgroup {
+    /// Creates a cgroup enforcing the memory, CPU and process limits, or `None` when the limits
+    /// need none or cgroup v2 isn't delegated to mise.
+    pub fn create(limits: &ResourceLimits) -> Option<Self> {
+        let mut settings = vec![];
+        if let Some(memory) = limits.memory {
+            settings.push(("memory", "memory.max", memory.to_string()));
+            // without this, memory past the limit would be swapped out rather than killed
+            settings.push(("memory", "memory.swap.max", "0".to_string()));
+        }
+        if let Some(cpus) = limits.cpus {
+            let quota = (cpus * CPU_PERIOD_US as f64).ceil() as u64;
+            settings.push(("cpu", "cpu.max", format!("{quota} {CPU_PERIOD_US}")));
+        }
+        if let Some(pids) = limits.pids {
+            settings.push(("pids", "pids.max", pids.to_string()));
+        }
+        if settings.is_empty() {
+            return None;
+        }
+        let delegation = delegation()?;
+        if let Some((controller, _, _)) = settings
+            .iter()
+            .find(|(controller, _, _)| !delegation.controllers.contains(controller))
+        {
+            debug!("cgroup {controller} controller is unavailable, using rlimits");
+            return None;
+        }
+
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let path = delegation
+            .path
+            .join(format!("mise-{}-{id}", std::process::id()));
+        if let Err(err) = fs::create_dir(&path) {
+            debug!("failed to create cgroup {}: {err}", display_path(&path));
+            return None;
+        }
+        let cgroup = Self { path };
+        for (_, file, value) in settings {
+            let file = cgroup.path.join(file);
+            // swap accounting may be disabled
+            if file.ends_with("memory.swap.max") && !file.exists() {
+                continue;
+            }
+            if let Err(err) = fs::write(&file, value) {
+                debug!("failed to write {}: {err}", display_path(&file));
+                return None;
+            }
+        }
+        Some(cgroup)
+    }
+
+    /// `cgroup.procs`, opened for the child to move itself into the cgroup before exec.
+    pub fn procs_file(&self) -> io::Result<File> {
+        OpenOptions::new()
+            .write(true)
+            .open(self.path.join("cgroup.procs"))
+    }
+
+    /// Whether the kernel killed a process in the cgroup for exceeding `memory.max`.
+    pub fn memory_killed(&self) -> bool {
+        self.event("memory.events", "oom_kill") > 0
+    }
+
+    /// Whether a fork in the cgroup failed because of `pids.max`.
+    pub fn pids_exhausted(&self) -> bool {
+        self.event("pids.events", "max") > 0
+    }
+
+    fn event(&self, file: &str, key: &str) -> u64 {
+        fs::read_to_string(self.path.join(file))
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| line.split_once(' '))
+            .find(|(name, _)| *name == key)
+            .and_then(|(_, count)| count.trim().parse().ok())
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for Cgroup {
+    fn drop(&mut self) {
+        // kills anything the command left running, since a cgroup with processes can't be removed
+        let _ = fs::write(self.path.join("cgroup.kill"), "1");
+        for _ in 0..50 {
+            match fs::remove_dir(&self.path) {
+                Ok(()) => return,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => return,
+                Err(_) => std::thread::sleep(Duration::from_millis(10)),
+            }
+        }
+        debug!("failed to remove cgroup {}", display_path(&self.path));
+    }
+}
+
+/// The cgroup new cgroups are created in, set up once per process.
+fn delegation() -> Option<&'static Delegation> {
+    static DELEGATION: OnceLock<Option<Delegation>> = OnceLock::new();
+    DELEGATION
+        .get_or_init(|| match delegate() {
+            Ok(delegation) => Some(delegation),
+            Err(err) => {
+                debug!("cgroup v2 limits are unavailable, using rlimits: {err}");
+                None
+            }
+        })
+        .as_ref()
+}
+
+fn delegate() -> Result<Delegation> {
+    let own = fs::read_to_string("/proc/self/cgroup")?;
+    let Some(own) = own.lines().find_map(|line| line.strip_prefix("0::")) else {
+        bail!("not running in a cgroup v2 hierarchy");
+    };
+    let path = Path::new(CGROUP_MOUNT).join(own.trim_start_matches('/'));
+    let available = fs::read_to_string(path.join("cgroup.controllers"))?;
+    let controllers: Vec<&'static str> = CONTROLLERS
+        .iter()
+        .copied()
+        .filter(|controller| available.split_whitespace().any(|c| c == *controller))
+        .collect();
+    if controllers.is_empty() {
+        bail!(
+            "no cpu, memory or pids controller in {}",
+            display_path(&path)
+        );
+    }
+    if enable_controllers(&path, &controllers).is_err() {
+        // a cgroup other than the root can't both hold processes and enable controllers for
+        // its children, so mise moves into a leaf of its own first. That only helps when mise is
+        // alone: other processes would keep the cgroup from enabling controllers anyway, and
+        // moving away from them would change the limits mise itself runs under.
+        let pid = std::process::id();
+        let procs = fs::read_to_string(path.join("cgroup.procs"))?;
+        if !holds_only(&procs, pid) {
+            bail!(
+                "{} holds processes other than mise, not moving into a leaf",
+                display_path(&path)
+            );
+        }
+        let leaf = path.join("mise");
+        let created = !leaf.exists();
+        if created {
+            fs::create_dir(&leaf)?;
+        }
+        let moved = fs::write(leaf.join("cgroup.procs"), pid.to_string())
+            .and_then(|()| enable_controllers(&path, &controllers));
+        if let Err(err) = moved {
+            if let Err(err) = fs::write(path.join("cgroup.procs"), pid.to_string()) {
+                debug!("failed to move back to {}: {err}", display_path(&path));
+            }
+            if created && let Err(err) = fs::remove_dir(&leaf) {
+                debug!("failed to remove cgroup {}: {err}", display_path(&leaf));
+            }
+            return Err(err.into());
+        }
+    }
+    Ok(Delegation { path, controllers })
+}
+
+/// Whether the `cgroup.procs` listing `procs` contains no process but `pid`.
+fn holds_only(procs: &str, pid: u32) -> bool {
+    procs
+        .split_whitespace()
+        .all(|entry| entry.parse::<u32>().is_ok_and(|entry| entry == pid))
+}
+
+fn enable_controllers(path: &Path, controllers: &[&str]) -> io::Result<()> {
+    let enabled = fs::read_to_string(path.join("cgroup.subtree_control"))?;
+    let missing: Vec<String> = controllers
+        .iter()
+        .filter(|controller| !enabled.split_whitespace().any(|c| c == **controller))
+        .map(|controller| format!("+{controller}"))
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+    fs::write(path.join("cgroup.subtree_control"), missing.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::holds_only;
+
+    #[test]
+    fn test_holds_only() {
+        assert!(holds_only("42\n", 42));
+        assert!(!holds_only("42\n43\n", 42));
+        assert!(!holds_only("1\n", 42));
+    }
+}
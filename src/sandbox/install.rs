@@ -0,0 +1,177 @@
+//! The sandbox plugin code runs in while tools are installed and their versions listed, when
+//! `install_sandbox.enabled` is set.
+//!
+//! asdf plugin scripts are separate processes and run in a [`SandboxConfig`] like `mise exec`.
+//! vfox hooks run inside mise, so the vfox crate enforces the same rules on what they do through
+//! its Lua modules, and restricts the commands they start with [`CommandSandbox`].
+
+use std::any::Any;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+
+use eyre::Result;
+
+use super::SandboxConfig;
+use crate::config::Settings;
+use crate::{dirs, env, file};
+
+/// Env vars mise sets for plugin code, which it can always read in the sandbox.
+const PLUGIN_ENV: &[&str] = &[
+    "ASDF_*",
+    "RTX_*",
+    "MISE_CACHE_DIR",
+    "MISE_CONCURRENCY",
+    "MISE_DATA_DIR",
+    "MISE_DEBUG",
+    "MISE_DOWNLOAD_PATH",
+    "MISE_FAILURE",
+    "MISE_INSTALL_PATH",
+    "MISE_INSTALL_TYPE",
+    "MISE_INSTALL_VERSION",
+    "MISE_LOG_LEVEL",
+    "MISE_PROJECT_ROOT",
+    "MISE_TOOL_OPTS__*",
+    "MISE_TRACE",
+    "MISE_VERBOSE",
+    "__MISE_BIN",
+    "__MISE_SCRIPT",
+    "TMPDIR",
+    "HTTP_PROXY",
+    "HTTPS_PROXY",
+    "ALL_PROXY",
+    "NO_PROXY",
+    "http_proxy",
+    "https_proxy",
+    "all_proxy",
+    "no_proxy",
+];
+
+/// Returns the sandbox for plugin code working on a tool, which can write to `tool_dirs`, or
+/// None when `install_sandbox.enabled` is not set. The directories are created first, since
+/// Landlock can only allow paths that exist.
+///
+/// Network access is limited to `install_sandbox.allow_net`, so none by default, and nothing in
+/// `$HOME` is readable apart from the mise data and cache dirs, the tool's directories and
+/// `install_sandbox.allow_read`.
+pub fn plugin_sandbox(tool_dirs: &[&Path]) -> Result<Option<SandboxConfig>> {
+    let settings = &Settings::get().install_sandbox;
+    if !settings.enabled {
+        return Ok(None);
+    }
+    for dir in tool_dirs {
+        file::create_dir_all(dir)?;
+    }
+    let mut sandbox = SandboxConfig {
+        deny_write: true,
+        deny_net: true,
+        deny_env: true,
+        deny_read_dirs: vec![dirs::HOME.to_path_buf()],
+        allow_read: [
+            env::MISE_DATA_DIR.to_path_buf(),
+            env::MISE_CACHE_DIR.to_path_buf(),
+        ]
+        .into_iter()
+        .chain(settings.allow_read.iter().map(PathBuf::from))
+        .collect(),
+        allow_write: tool_dirs
+            .iter()
+            .map(|dir| dir.to_path_buf())
+            .chain(settings.allow_write.iter().map(PathBuf::from))
+            .collect(),
+        allow_net: settings.allow_net.clone(),
+        allow_env: PLUGIN_ENV
+            .iter()
+            .map(|key| key.to_string())
+            .chain(settings.allow_env.iter().cloned())
+            .collect(),
+        ..Default::default()
+    };
+    sandbox.resolve_paths();
+    Ok(Some(sandbox))
+}
+
+/// Converts a plugin sandbox into the rules the vfox crate enforces on Lua hooks.
+pub async fn vfox_sandbox(sandbox: &SandboxConfig) -> vfox::Sandbox {
+    let commands = CommandSandbox {
+        config: sandbox.clone(),
+        #[cfg(target_os = "macos")]
+        profile: super::macos::generate_seatbelt_profile(sandbox).await,
+    };
+    vfox::Sandbox {
+        allow_write: sandbox.allow_write.clone(),
+        deny_read: sandbox.deny_read_dirs.clone(),
+        allow_read: sandbox.allow_read.clone(),
+        allow_net: sandbox.allow_net.clone(),
+        command_hook: Some(Arc::new(move |cmd: &mut Command| commands.restrict(cmd))),
+    }
+}
+
+/// Restricts the commands vfox hooks start with `cmd.exec` and `os.execute`.
+struct CommandSandbox {
+    #[cfg_attr(not(any(target_os = "linux", target_os = "macos")), allow(dead_code))]
+    config: SandboxConfig,
+    #[cfg(target_os = "macos")]
+    profile: String,
+}
+
+impl CommandSandbox {
+    /// Applies Landlock, and the network proxy when hosts are allowed or seccomp when none are.
+    /// The returned proxy must be kept until the command exits.
+    #[cfg(target_os = "linux")]
+    fn restrict(&self, cmd: &mut Command) -> io::Result<Box<dyn Any + Send>> {
+        use std::os::unix::process::CommandExt;
+
+        let proxy = match self.config.allow_net.is_empty() {
+            true => None,
+            false => Some(
+                super::net_proxy_apply(cmd, &self.config)
+                    .map_err(|e| io::Error::other(e.to_string()))?,
+            ),
+        };
+        let config = self.config.clone();
+        unsafe {
+            cmd.pre_exec(move || {
+                super::landlock_apply(&config).map_err(|e| io::Error::other(e.to_string()))?;
+                if config.deny_net && config.allow_net.is_empty() {
+                    super::seccomp_apply().map_err(|e| io::Error::other(e.to_string()))?;
+                }
+                Ok(())
+            });
+        }
+        Ok(Box::new(proxy))
+    }
+
+    /// Rewrites the command to run through sandbox-exec.
+    #[cfg(target_os = "macos")]
+    fn restrict(&self, cmd: &mut Command) -> io::Result<Box<dyn Any + Send>> {
+        let mut sandboxed = Command::new("sandbox-exec");
+        sandboxed
+            .arg("-p")
+            .arg(&self.profile)
+            .arg("--")
+            .arg(cmd.get_program())
+            .args(cmd.get_args());
+        if let Some(dir) = cmd.get_current_dir() {
+            sandboxed.current_dir(dir);
+        }
+        if self.config.effective_deny_env() {
+            sandboxed.env_clear();
+        }
+        for (key, value) in cmd.get_envs() {
+            match value {
+                Some(value) => sandboxed.env(key, value),
+                None => sandboxed.env_remove(key),
+            };
+        }
+        *cmd = sandboxed;
+        Ok(Box::new(()))
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    fn restrict(&self, _cmd: &mut Command) -> io::Result<Box<dyn Any + Send>> {
+        warn!("sandbox is not supported on this platform, running plugin command unsandboxed");
+        Ok(Box::new(()))
+    }
+}
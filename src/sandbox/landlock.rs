@@ -1,3 +1,5 @@
+use std::path::{Path, PathBuf};
+
 use eyre::{Result, eyre};
 use landlock::{
     ABI, AccessFs, BitFlags, Compatible, PathBeneath, PathFd, Ruleset, RulesetAttr,
@@ -22,28 +24,207 @@ const SYSTEM_READ_PATHS: &[&str] = &[
     "/home/linuxbrew",
 ];
 
-fn add_read_rule(
-    ruleset: landlock::RulesetCreated,
-    path: &str,
-    access: BitFlags<AccessFs>,
-) -> Result<landlock::RulesetCreated> {
-    match PathFd::new(path) {
-        Ok(fd) => ruleset
-            .add_rule(PathBeneath::new(fd, access))
-            .map_err(|e| eyre!("landlock add_rule failed for {path}: {e}")),
-        Err(_) => Ok(ruleset), // Path doesn't exist, skip
+/// Access granted beneath a path by a Landlock rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleAccess {
+    Read,
+    Write,
+    ReadWrite,
+    ListDir,
+}
+
+impl RuleAccess {
+    fn access_fs(self, abi: ABI) -> BitFlags<AccessFs> {
+        match self {
+            Self::Read => AccessFs::from_read(abi),
+            Self::Write => AccessFs::from_write(abi),
+            Self::ReadWrite => AccessFs::from_all(abi),
+            Self::ListDir => AccessFs::ReadDir.into(),
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Write => "write",
+            Self::ReadWrite => "read-write",
+            Self::ListDir => "list",
+        }
+    }
+}
+
+/// A path the ruleset grants access beneath.
+#[derive(Debug)]
+pub struct Rule {
+    pub path: PathBuf,
+    pub access: RuleAccess,
+    /// System paths are skipped quietly when missing, configured paths with a warning.
+    pub system: bool,
+}
+
+/// The Landlock rules for a sandbox config: the access types the ruleset handles (everything
+/// else stays unrestricted) and the rules granting access back, or None if the filesystem is
+/// not restricted.
+pub fn rules(config: &SandboxConfig) -> Option<(RuleAccess, Vec<Rule>)> {
+    let deny_read = config.effective_deny_read();
+    let deny_write = config.effective_deny_write();
+
+    // Only handle the access types we're actually restricting.
+    // If we handle_access(full_access) but only add read rules,
+    // writes to un-ruled paths get blocked too (Landlock denies by default).
+    let handled = match (config.restricts_read(), deny_write) {
+        (true, true) => RuleAccess::ReadWrite,
+        (true, false) => RuleAccess::Read,
+        // need full to add read+write rules for allowed paths
+        (false, true) => RuleAccess::ReadWrite,
+        // nothing to restrict
+        (false, false) => return None,
+    };
+
+    let mut rules = vec![];
+    let mut system = |path: &str, access| {
+        rules.push(Rule {
+            path: PathBuf::from(path),
+            access,
+            system: true,
+        })
+    };
+    if deny_read {
+        for path in SYSTEM_READ_PATHS {
+            system(path, RuleAccess::Read);
+        }
+        // /tmp and /dev are writable unless only reads are restricted
+        let tmp_access = if deny_write {
+            RuleAccess::ReadWrite
+        } else {
+            RuleAccess::Read
+        };
+        system("/tmp", tmp_access);
+        system("/dev", tmp_access);
+        let installs_dir: &Path = &crate::dirs::INSTALLS;
+        let mut configured = |path: &Path, access| {
+            rules.push(Rule {
+                path: path.to_path_buf(),
+                access,
+                system: false,
+            })
+        };
+        if installs_dir.exists() {
+            configured(installs_dir, RuleAccess::Read);
+        }
+        configured(&crate::env::MISE_DATA_DIR, RuleAccess::Read);
+        for path in &config.allow_read {
+            configured(path, RuleAccess::Read);
+        }
+        // when only reads are restricted, allow_write paths are implicitly readable
+        for path in &config.allow_write {
+            configured(path, tmp_access);
+        }
+        for path in &config.allow_list_dir {
+            configured(path, RuleAccess::ListDir);
+        }
+    } else {
+        // Reads allowed everywhere but deny_read_dirs, writes denied except to allowed paths
+        let write_access = if deny_write {
+            RuleAccess::ReadWrite
+        } else {
+            RuleAccess::Read
+        };
+        if config.deny_read_dirs.is_empty() {
+            system("/", RuleAccess::Read);
+        } else {
+            for path in readable_around(Path::new("/"), &config.deny_read_dirs) {
+                rules.push(Rule {
+                    path,
+                    access: RuleAccess::Read,
+                    system: true,
+                });
+            }
+            for path in &config.allow_read {
+                rules.push(Rule {
+                    path: path.clone(),
+                    access: RuleAccess::Read,
+                    system: false,
+                });
+            }
+        }
+        for path in ["/tmp", "/dev"] {
+            // a denied dir beneath them must not become readable again through their rule
+            let access = match (
+                config.deny_read_dirs.iter().any(|d| d.starts_with(path)),
+                deny_write,
+            ) {
+                (false, _) => write_access,
+                (true, true) => RuleAccess::Write,
+                (true, false) => continue,
+            };
+            rules.push(Rule {
+                path: PathBuf::from(path),
+                access,
+                system: true,
+            });
+        }
+        for path in &config.allow_write {
+            rules.push(Rule {
+                path: path.clone(),
+                access: write_access,
+                system: false,
+            });
+        }
+    }
+    Some((handled, rules))
+}
+
+/// The paths beneath `dir` that cover everything except `hidden` and the directories on
+/// the way to them, since Landlock can only grant access, not take it away beneath a rule.
+fn readable_around(dir: &Path, hidden: &[PathBuf]) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut readable = vec![];
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if hidden.contains(&path) {
+            continue;
+        }
+        if hidden.iter().any(|hidden| hidden.starts_with(&path)) {
+            readable.extend(readable_around(&path, hidden));
+        } else {
+            readable.push(path);
+        }
     }
+    readable.sort();
+    readable
 }
 
-fn add_path_rule(
+/// Describe the Landlock rules for a sandbox config, one `<access> <path>` line per rule.
+pub fn describe(config: &SandboxConfig) -> Option<String> {
+    let (handled, rules) = rules(config)?;
+    let mut lines = vec![format!("# landlock: restricts {}", handled.as_str())];
+    for rule in rules {
+        if rule.system && !rule.path.exists() {
+            continue;
+        }
+        lines.push(format!(
+            "{:<10} {}",
+            rule.access.as_str(),
+            rule.path.display()
+        ));
+    }
+    Some(lines.join("\n"))
+}
+
+fn add_rule(
     ruleset: landlock::RulesetCreated,
-    path: &std::path::Path,
-    access: BitFlags<AccessFs>,
+    rule: &Rule,
+    abi: ABI,
 ) -> Result<landlock::RulesetCreated> {
+    let path = &rule.path;
     match PathFd::new(path) {
         Ok(fd) => ruleset
-            .add_rule(PathBeneath::new(fd, access))
+            .add_rule(PathBeneath::new(fd, rule.access.access_fs(abi)))
             .map_err(|e| eyre!("landlock add_rule failed for {}: {e}", path.display())),
+        Err(_) if rule.system => Ok(ruleset), // Path doesn't exist, skip
         Err(_) => {
             // Path doesn't exist — on Linux, Landlock requires existing paths.
             // This affects cases like --allow-write=./dist where the dir doesn't exist yet.
@@ -61,76 +242,19 @@ fn add_path_rule(
 pub fn apply_landlock(config: &SandboxConfig) -> Result<()> {
     let abi = ABI::V5;
 
-    let read_access = AccessFs::from_read(abi);
-    let write_access = AccessFs::from_write(abi);
-    let full_access = read_access | write_access;
-
-    let deny_read = config.effective_deny_read();
-    let deny_write = config.effective_deny_write();
-
-    // Only handle the access types we're actually restricting.
-    // If we handle_access(full_access) but only add read rules,
-    // writes to un-ruled paths get blocked too (Landlock denies by default).
-    let handled_access = match (deny_read, deny_write) {
-        (true, true) => full_access,
-        (true, false) => read_access,
-        (false, true) => full_access, // need full to add read+write rules for allowed paths
-        (false, false) => return Ok(()), // nothing to restrict
+    let Some((handled, rules)) = rules(config) else {
+        return Ok(());
     };
 
     let mut ruleset = Ruleset::default()
-        .handle_access(handled_access)
+        .handle_access(handled.access_fs(abi))
         .map_err(|e| eyre!("failed to create landlock ruleset: {e}"))?
         .set_compatibility(landlock::CompatLevel::BestEffort)
         .create()
         .map_err(|e| eyre!("failed to create landlock ruleset: {e}"))?;
 
-    if deny_read && deny_write {
-        // Both restricted: add read rules for system paths, full for /tmp and /dev
-        for path in SYSTEM_READ_PATHS {
-            ruleset = add_read_rule(ruleset, path, read_access)?;
-        }
-        ruleset = add_read_rule(ruleset, "/tmp", full_access)?;
-        ruleset = add_read_rule(ruleset, "/dev", full_access)?;
-        let installs_dir: &std::path::Path = &crate::dirs::INSTALLS;
-        if installs_dir.exists() {
-            ruleset = add_path_rule(ruleset, installs_dir, read_access)?;
-        }
-        ruleset = add_path_rule(ruleset, &crate::env::MISE_DATA_DIR, read_access)?;
-        for path in &config.allow_read {
-            ruleset = add_path_rule(ruleset, path, read_access)?;
-        }
-        for path in &config.allow_write {
-            ruleset = add_path_rule(ruleset, path, full_access)?;
-        }
-    } else if deny_read {
-        // Only reads restricted — only handle read access so writes are unaffected
-        for path in SYSTEM_READ_PATHS {
-            ruleset = add_read_rule(ruleset, path, read_access)?;
-        }
-        // /tmp and /dev need read access (not in SYSTEM_READ_PATHS, handled separately)
-        ruleset = add_read_rule(ruleset, "/tmp", read_access)?;
-        ruleset = add_read_rule(ruleset, "/dev", read_access)?;
-        let installs_dir: &std::path::Path = &crate::dirs::INSTALLS;
-        if installs_dir.exists() {
-            ruleset = add_path_rule(ruleset, installs_dir, read_access)?;
-        }
-        ruleset = add_path_rule(ruleset, &crate::env::MISE_DATA_DIR, read_access)?;
-        for path in &config.allow_read {
-            ruleset = add_path_rule(ruleset, path, read_access)?;
-        }
-        // allow_write paths are implicitly readable
-        for path in &config.allow_write {
-            ruleset = add_path_rule(ruleset, path, read_access)?;
-        }
-    } else if deny_write {
-        // Only writes restricted — allow read everywhere, deny write except allowed paths
-        ruleset = add_read_rule(ruleset, "/", read_access)?;
-        ruleset = add_read_rule(ruleset, "/tmp", full_access)?;
-        ruleset = add_read_rule(ruleset, "/dev", full_access)?;
-        for path in &config.allow_write {
-            ruleset = add_path_rule(ruleset, path, full_access)?;
-        }
+    for rule in &rules {
+        ruleset = add_rule(ruleset, rule, abi)?;
     }
 
     let status = ruleset
@@ -142,3 +266,22 @@ pub fn apply_landlock(config: &SandboxConfig) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_readable_around() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path().canonicalize().unwrap();
+        for dir in ["home/me/.cache", "home/me/.ssh", "home/other", "usr"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        std::fs::write(root.join("file"), "").unwrap();
+        assert_eq!(
+            readable_around(&root, &[root.join("home/me")]),
+            vec![root.join("file"), root.join("home/other"), root.join("usr")]
+        );
+    }
+}
@@ -0,0 +1,339 @@
+//! Resource limits for sandboxed commands.
+//!
+//! On Linux, memory, CPU and process limits use a cgroup v2 subtree when one is delegated to mise
+//! (see [`super::cgroup`]) and fall back to rlimits otherwise. Open files always use
+//! `RLIMIT_NOFILE`. Output is counted by mise as it reads the command's stdout and stderr.
+
+use bytesize::ByteSize;
+use eyre::{Result, bail, eyre};
+#[cfg(unix)]
+use nix::libc;
+
+/// Resource limits for a sandboxed command and everything it spawns.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResourceLimits {
+    /// Maximum memory in bytes.
+    pub memory: Option<u64>,
+    /// Maximum CPU time as a number of CPUs, e.g. `1.5`.
+    pub cpus: Option<f64>,
+    /// Maximum number of processes and threads.
+    pub pids: Option<u64>,
+    /// Maximum number of open files per process.
+    pub open_files: Option<u64>,
+    /// Maximum bytes of stdout and stderr.
+    pub output_bytes: Option<u64>,
+}
+
+impl ResourceLimits {
+    /// Parses limits as written in task config and CLI flags, with sizes like `2GiB`.
+    pub fn parse(
+        memory: Option<&str>,
+        cpus: Option<f64>,
+        pids: Option<u64>,
+        open_files: Option<u64>,
+        output_bytes: Option<&str>,
+    ) -> Result<Self> {
+        if let Some(cpus) = cpus
+            && !(cpus.is_finite() && cpus > 0.0)
+        {
+            bail!("invalid CPU limit {cpus}: must be greater than 0");
+        }
+        if pids == Some(0) {
+            bail!("invalid pids limit 0: must be at least 1");
+        }
+        if open_files == Some(0) {
+            bail!("invalid open files limit 0: must be at least 1");
+        }
+        Ok(Self {
+            memory: memory.map(|size| parse_size("memory", size)).transpose()?,
+            cpus,
+            pids,
+            open_files,
+            output_bytes: output_bytes
+                .map(|size| parse_size("output", size))
+                .transpose()?,
+        })
+    }
+
+    pub fn is_set(&self) -> bool {
+        self.memory.is_some()
+            || self.cpus.is_some()
+            || self.pids.is_some()
+            || self.open_files.is_some()
+            || self.output_bytes.is_some()
+    }
+
+    /// Combines two sets of limits, keeping the stricter value of each.
+    pub fn stricter(&self, other: &Self) -> Self {
+        fn min<T: PartialOrd + Copy>(a: Option<T>, b: Option<T>) -> Option<T> {
+            match (a, b) {
+                (Some(a), Some(b)) => Some(if b < a { b } else { a }),
+                (a, b) => a.or(b),
+            }
+        }
+        Self {
+            memory: min(self.memory, other.memory),
+            cpus: min(self.cpus, other.cpus),
+            pids: min(self.pids, other.pids),
+            open_files: min(self.open_files, other.open_files),
+            output_bytes: min(self.output_bytes, other.output_bytes),
+        }
+    }
+}
+
+fn parse_size(kind: &str, size: &str) -> Result<u64> {
+    match size.trim().parse::<ByteSize>() {
+        Ok(size) if size.as_u64() > 0 => Ok(size.as_u64()),
+        Ok(_) => bail!("invalid {kind} limit {size:?}: must be greater than 0"),
+        Err(err) => Err(eyre!("invalid {kind} limit {size:?}: {err}")),
+    }
+}
+
+/// Formats a size the way limits are usually written, e.g. `2GiB`.
+pub fn display_size(bytes: u64) -> String {
+    for (unit, suffix) in [
+        (1 << 40, "TiB"),
+        (1 << 30, "GiB"),
+        (1 << 20, "MiB"),
+        (1 << 10, "KiB"),
+    ] {
+        if bytes >= unit && bytes.is_multiple_of(unit) {
+            return format!("{}{suffix}", bytes / unit);
+        }
+    }
+    ByteSize::b(bytes).display().iec().to_string()
+}
+
+/// The limits applied to one spawned command, checked after it exits.
+#[cfg_attr(windows, allow(dead_code))]
+pub struct AppliedLimits {
+    limits: ResourceLimits,
+    output_written: u64,
+    output_exceeded: bool,
+    #[cfg(target_os = "linux")]
+    cgroup: Option<super::cgroup::Cgroup>,
+}
+
+impl AppliedLimits {
+    /// Whether there is an output limit to count output against.
+    pub fn counts_output(&self) -> bool {
+        self.limits.output_bytes.is_some()
+    }
+
+    /// Counts `bytes` of output, returning true once when the output limit is first exceeded.
+    pub fn record_output(&mut self, bytes: usize) -> bool {
+        let Some(limit) = self.limits.output_bytes else {
+            return false;
+        };
+        self.output_written += bytes as u64;
+        if self.output_exceeded || self.output_written <= limit {
+            return false;
+        }
+        self.output_exceeded = true;
+        true
+    }
+
+    /// Whether output past the limit is being discarded.
+    pub fn output_exceeded(&self) -> bool {
+        self.output_exceeded
+    }
+
+    /// Why the command failed, when it was because of a limit.
+    pub fn exceeded(&self) -> Option<String> {
+        if self.output_exceeded {
+            let limit = self.limits.output_bytes.unwrap_or_default();
+            return Some(format!("killed: output limit {}", display_size(limit)));
+        }
+        #[cfg(target_os = "linux")]
+        if let Some(cgroup) = &self.cgroup {
+            if let Some(memory) = self.limits.memory
+                && cgroup.memory_killed()
+            {
+                return Some(format!("killed: memory limit {}", display_size(memory)));
+            }
+            if let Some(pids) = self.limits.pids
+                && cgroup.pids_exhausted()
+            {
+                return Some(format!("failed: pids limit {pids} reached"));
+            }
+        }
+        None
+    }
+}
+
+/// Lowers both the soft and hard limit of `resource`, never raising them.
+#[cfg(unix)]
+macro_rules! lower_rlimit {
+    ($resource:expr, $value:expr) => {{
+        let mut limit = libc::rlimit {
+            rlim_cur: 0,
+            rlim_max: 0,
+        };
+        if libc::getrlimit($resource, &mut limit) != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        let value = ($value as libc::rlim_t).min(limit.rlim_max);
+        limit.rlim_cur = value;
+        limit.rlim_max = value;
+        if libc::setrlimit($resource, &limit) != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+    }};
+}
+
+/// Prepares `cmd` to run under `limits`.
+///
+/// On Linux the child joins a fresh cgroup before exec when cgroup v2 is delegated to mise. The
+/// remaining limits are set as rlimits in the child: open files always, and memory (as
+/// `RLIMIT_DATA`), processes (`RLIMIT_NPROC`, which counts all of the user's processes) and CPUs
+/// (as CPU affinity) when there is no cgroup.
+#[cfg(unix)]
+pub fn apply(cmd: &mut std::process::Command, limits: &ResourceLimits) -> Result<AppliedLimits> {
+    use std::os::unix::process::CommandExt;
+
+    #[cfg(target_os = "linux")]
+    let cgroup = super::cgroup::Cgroup::create(limits);
+    #[cfg(target_os = "linux")]
+    let mut procs = cgroup
+        .as_ref()
+        .map(|cgroup| cgroup.procs_file())
+        .transpose()?;
+    #[cfg(target_os = "linux")]
+    let (memory, pids, cpus) = if cgroup.is_some() {
+        (None, None, None)
+    } else {
+        (
+            limits.memory,
+            limits.pids,
+            limits.cpus.map(|cpus| cpus.ceil() as usize),
+        )
+    };
+    #[cfg(not(target_os = "linux"))]
+    let (memory, pids) = (limits.memory, limits.pids);
+    #[cfg(not(target_os = "linux"))]
+    if limits.cpus.is_some() {
+        warn_once!("limit_cpus is only supported on Linux, running without a CPU limit");
+    }
+    let open_files = limits.open_files;
+
+    unsafe {
+        cmd.pre_exec(move || {
+            #[cfg(target_os = "linux")]
+            if let Some(procs) = &mut procs {
+                // "0" moves the writing process
+                std::io::Write::write_all(procs, b"0")?;
+            }
+            if let Some(open_files) = open_files {
+                lower_rlimit!(libc::RLIMIT_NOFILE, open_files);
+            }
+            if let Some(memory) = memory {
+                lower_rlimit!(libc::RLIMIT_DATA, memory);
+            }
+            if let Some(pids) = pids {
+                lower_rlimit!(libc::RLIMIT_NPROC, pids);
+            }
+            #[cfg(target_os = "linux")]
+            if let Some(cpus) = cpus {
+                restrict_cpus(cpus)?;
+            }
+            Ok(())
+        });
+    }
+    Ok(AppliedLimits {
+        limits: limits.clone(),
+        output_written: 0,
+        output_exceeded: false,
+        #[cfg(target_os = "linux")]
+        cgroup,
+    })
+}
+
+/// Pins the process to the first `cpus` CPUs it is allowed to run on.
+#[cfg(target_os = "linux")]
+fn restrict_cpus(cpus: usize) -> std::io::Result<()> {
+    unsafe {
+        let mut allowed: libc::cpu_set_t = std::mem::zeroed();
+        let size = size_of::<libc::cpu_set_t>();
+        if libc::sched_getaffinity(0, size, &mut allowed) != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        let mut pinned: libc::cpu_set_t = std::mem::zeroed();
+        let mut count = 0;
+        for cpu in 0..libc::CPU_SETSIZE as usize {
+            if count == cpus {
+                break;
+            }
+            if libc::CPU_ISSET(cpu, &allowed) {
+                libc::CPU_SET(cpu, &mut pinned);
+                count += 1;
+            }
+        }
+        if libc::sched_setaffinity(0, size, &pinned) != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_limits() {
+        let limits =
+            ResourceLimits::parse(Some("2GiB"), Some(1.5), Some(64), None, Some("10MiB")).unwrap();
+        assert_eq!(limits.memory, Some(2 << 30));
+        assert_eq!(limits.cpus, Some(1.5));
+        assert_eq!(limits.output_bytes, Some(10 << 20));
+        assert!(limits.is_set());
+        assert!(!ResourceLimits::default().is_set());
+        assert!(ResourceLimits::parse(Some("lots"), None, None, None, None).is_err());
+        assert!(ResourceLimits::parse(None, Some(0.0), None, None, None).is_err());
+        assert!(ResourceLimits::parse(None, None, Some(0), None, None).is_err());
+    }
+
+    #[test]
+    fn test_stricter_limits() {
+        let task = ResourceLimits {
+            memory: Some(4 << 30),
+            cpus: Some(2.0),
+            ..Default::default()
+        };
+        let cli = ResourceLimits {
+            memory: Some(1 << 30),
+            pids: Some(100),
+            ..Default::default()
+        };
+        let limits = task.stricter(&cli);
+        assert_eq!(limits.memory, Some(1 << 30));
+        assert_eq!(limits.cpus, Some(2.0));
+        assert_eq!(limits.pids, Some(100));
+        assert_eq!(limits.open_files, None);
+    }
+
+    #[test]
+    fn test_display_size() {
+        assert_eq!(display_size(2 << 30), "2GiB");
+        assert_eq!(display_size(512 << 20), "512MiB");
+        assert_eq!(display_size(1536), "1.5 KiB");
+    }
+
+    #[test]
+    fn test_output_limit() {
+        let mut applied = AppliedLimits {
+            limits: ResourceLimits {
+                output_bytes: Some(10),
+                ..Default::default()
+            },
+            output_written: 0,
+            output_exceeded: false,
+            #[cfg(target_os = "linux")]
+            cgroup: None,
+        };
+        assert!(!applied.record_output(6));
+        assert!(applied.record_output(6));
+        assert!(!applied.record_output(6));
+        assert_eq!(applied.exceeded().unwrap(), "killed: output limit 10 B");
+    }
+}
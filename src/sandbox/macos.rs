@@ -60,6 +60,16 @@ pub async fn generate_seatbelt_profile(config: &SandboxConfig) -> String {
             let path_str = sbpl_escape(&path.to_string_lossy());
             rules.push(format!("(allow file-read* (subpath \"{path_str}\"))"));
         }
+    } else if !config.deny_read_dirs.is_empty() {
+        for path in &config.deny_read_dirs {
+            let path_str = sbpl_escape(&path.to_string_lossy());
+            rules.push(format!("(deny file-read* (subpath \"{path_str}\"))"));
+        }
+        // later rules take precedence, so these reopen paths beneath the denied dirs
+        for path in config.allow_read.iter().chain(&config.allow_write) {
+            let path_str = sbpl_escape(&path.to_string_lossy());
+            rules.push(format!("(allow file-read* (subpath \"{path_str}\"))"));
+        }
     }
 
     // Network restrictions
@@ -179,6 +189,24 @@ mod tests {
         assert!(profile.contains("(allow file-read* (subpath \"/System\"))"));
     }
 
+    #[tokio::test]
+    async fn test_deny_read_dirs_reopens_allowed_paths() {
+        let config = SandboxConfig {
+            deny_read_dirs: vec![PathBuf::from("/Users/me")],
+            allow_read: vec![PathBuf::from("/Users/me/.cache/mise")],
+            ..Default::default()
+        };
+        let profile = generate_seatbelt_profile(&config).await;
+        assert!(!profile.contains("(deny file-read*)\n"));
+        let deny = profile
+            .find("(deny file-read* (subpath \"/Users/me\"))")
+            .unwrap();
+        let allow = profile
+            .find("(allow file-read* (subpath \"/Users/me/.cache/mise\"))")
+            .unwrap();
+        assert!(deny < allow);
+    }
+
     #[tokio::test]
     async fn test_deny_all() {
         let config = SandboxConfig {
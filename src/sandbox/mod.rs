@@ -2,13 +2,26 @@ use std::path::PathBuf;
 
 use crate::file::replace_path;
 
+#[cfg(target_os = "linux")]
+mod cgroup;
+mod install;
 #[cfg(target_os = "linux")]
 mod landlock;
+mod limits;
 #[cfg(target_os = "macos")]
 mod macos;
 #[cfg(target_os = "linux")]
+mod net_proxy;
+mod profile;
+#[cfg(target_os = "linux")]
 mod seccomp;
 
+pub use install::{plugin_sandbox, vfox_sandbox};
+pub use limits::{AppliedLimits, ResourceLimits};
+#[cfg(target_os = "linux")]
+pub use net_proxy::NetProxy;
+pub use profile::{SandboxProfile, SandboxProfiles, SandboxToml};
+
 /// Configuration for process sandboxing.
 ///
 /// Any `deny_*` or `allow_*` field being set implicitly enables sandboxing.
@@ -28,6 +41,14 @@ pub struct SandboxConfig {
     pub pass_through_env: Vec<String>,
     /// Exact hashed environment names that survive an active env sandbox.
     pub cache_env: Vec<String>,
+    /// Directories whose entries can be listed, without their files becoming readable.
+    /// Only applies on Linux, when reads are denied.
+    pub allow_list_dir: Vec<PathBuf>,
+    /// Directories that can't be read while the rest of the filesystem can. `allow_read` and
+    /// `allow_write` paths beneath them stay readable. Ignored when reads are denied.
+    pub deny_read_dirs: Vec<PathBuf>,
+    /// Resource limits, which enable sandboxing without restricting access.
+    pub limits: ResourceLimits,
 }
 
 /// Minimal env vars inherited when deny_env is active.
@@ -68,8 +89,31 @@ impl SandboxConfig {
         cli
     }
 
-    /// Returns true if any sandbox restriction is configured.
+    /// Add the restrictions of `other` to this config: denies combine, allow lists extend, and
+    /// the stricter of each resource limit is kept.
+    pub fn merge(&mut self, other: Self) {
+        self.deny_read |= other.deny_read;
+        self.deny_write |= other.deny_write;
+        self.deny_net |= other.deny_net;
+        self.deny_env |= other.deny_env;
+        self.allow_read.extend(other.allow_read);
+        self.allow_write.extend(other.allow_write);
+        self.allow_net.extend(other.allow_net);
+        self.allow_env.extend(other.allow_env);
+        self.pass_through_env.extend(other.pass_through_env);
+        self.cache_env.extend(other.cache_env);
+        self.allow_list_dir.extend(other.allow_list_dir);
+        self.deny_read_dirs.extend(other.deny_read_dirs);
+        self.limits = self.limits.stricter(&other.limits);
+    }
+
+    /// Returns true if any sandbox restriction or resource limit is configured.
     pub fn is_active(&self) -> bool {
+        self.restricts_access() || self.limits.is_set()
+    }
+
+    /// Returns true if any filesystem, network or env restriction is configured.
+    pub fn restricts_access(&self) -> bool {
         self.deny_read
             || self.deny_write
             || self.deny_net
@@ -78,6 +122,7 @@ impl SandboxConfig {
             || !self.allow_write.is_empty()
             || !self.allow_net.is_empty()
             || !self.allow_env.is_empty()
+            || !self.deny_read_dirs.is_empty()
     }
 
     /// Resolve allow_* paths to absolute paths relative to cwd.
@@ -98,12 +143,21 @@ impl SandboxConfig {
         };
         resolve(&mut self.allow_read);
         resolve(&mut self.allow_write);
+        resolve(&mut self.allow_list_dir);
+        resolve(&mut self.deny_read_dirs);
     }
 
-    /// Compute effective deny flags, accounting for allow_* implying deny_*.
+    /// Compute effective deny flags, accounting for allow_* implying deny_*. `allow_read` only
+    /// makes exceptions to `deny_read_dirs` when those are set.
     #[cfg_attr(windows, allow(dead_code))]
     pub fn effective_deny_read(&self) -> bool {
-        self.deny_read || !self.allow_read.is_empty()
+        self.deny_read || (!self.allow_read.is_empty() && self.deny_read_dirs.is_empty())
+    }
+
+    /// Returns true if reads are restricted, either everywhere or in `deny_read_dirs`.
+    #[cfg_attr(windows, allow(dead_code))]
+    pub fn restricts_read(&self) -> bool {
+        self.effective_deny_read() || !self.deny_read_dirs.is_empty()
     }
 
     #[cfg_attr(windows, allow(dead_code))]
@@ -120,6 +174,18 @@ impl SandboxConfig {
         self.deny_env || !self.allow_env.is_empty()
     }
 
+    /// Returns true if an env var survives an active env sandbox: one of the essential vars,
+    /// a hashed cache env var, or a var matching `allow_env` or `pass_through_env`.
+    pub fn allows_env(&self, key: &str) -> bool {
+        DEFAULT_ENV_KEYS.contains(&key)
+            || self.cache_env.iter().any(|name| name == key)
+            || self
+                .allow_env
+                .iter()
+                .chain(&self.pass_through_env)
+                .any(|pattern| env_pattern_matches(pattern, key))
+    }
+
     /// Filter environment variables based on sandbox config.
     ///
     /// When deny_env is active, starts with the mise-computed env (tool paths etc.),
@@ -132,16 +198,9 @@ impl SandboxConfig {
         if !self.effective_deny_env() {
             return env.clone();
         }
-        let env_patterns = self.allow_env.iter().chain(&self.pass_through_env);
-        let env_matches = |k: &str| {
-            self.cache_env.iter().any(|name| name == k)
-                || env_patterns
-                    .clone()
-                    .any(|pattern| env_pattern_matches(pattern, k))
-        };
         let mut filtered: std::collections::BTreeMap<String, String> = env
             .iter()
-            .filter(|(k, _)| DEFAULT_ENV_KEYS.contains(&k.as_str()) || env_matches(k))
+            .filter(|(k, _)| self.allows_env(k))
             .map(|(k, v)| (k.clone(), v.clone()))
             .collect();
         // Pull in allowed vars from parent env that might not be in mise's env map.
@@ -190,7 +249,7 @@ impl SandboxConfig {
         program: &str,
         args: &[String],
     ) -> eyre::Result<Option<SandboxedCommand>> {
-        if !self.is_active() {
+        if !self.restricts_access() {
             return Ok(None);
         }
 
@@ -214,14 +273,15 @@ impl SandboxConfig {
 
     #[cfg(all(not(test), target_os = "linux"))]
     fn apply_linux(&self) -> eyre::Result<()> {
-        if self.effective_deny_read() || self.effective_deny_write() {
+        if self.restricts_read() || self.effective_deny_write() {
             landlock::apply_landlock(self)?;
         }
         if self.effective_deny_net() {
             if !self.allow_net.is_empty() {
+                // the allow_net proxy runs in mise, so the program must be spawned, not exec'd
                 eyre::bail!(
-                    "per-host network filtering (--allow-net=<host>) is not supported on Linux. \
-                     Use --deny-net to block all network, or remove --allow-net."
+                    "per-host network filtering (--allow-net=<host>) cannot apply to a process \
+                     that replaces mise"
                 );
             }
             seccomp::apply_seccomp_net_filter()?;
@@ -273,12 +333,76 @@ pub fn seccomp_apply() -> eyre::Result<()> {
     seccomp::apply_seccomp_net_filter()
 }
 
+/// Run `cmd` in a network namespace whose only egress is a proxy enforcing `allow_net`
+/// (Linux only). The proxy serves the command until the returned guard is dropped.
+#[cfg(target_os = "linux")]
+pub fn net_proxy_apply(
+    cmd: &mut std::process::Command,
+    config: &SandboxConfig,
+) -> eyre::Result<NetProxy> {
+    net_proxy::apply(cmd, &config.allow_net)
+}
+
+/// Apply resource limits to a command before it is spawned (Unix only). The returned limits
+/// report whether the command was stopped by one of them.
+#[cfg(unix)]
+pub fn limits_apply(
+    cmd: &mut std::process::Command,
+    config: &SandboxConfig,
+) -> eyre::Result<AppliedLimits> {
+    limits::apply(cmd, &config.limits)
+}
+
 /// Generate a macOS Seatbelt profile string (macOS only).
 #[cfg(target_os = "macos")]
 pub async fn macos_generate_profile(config: &SandboxConfig) -> String {
     macos::generate_seatbelt_profile(config).await
 }
 
+/// Describe the platform rules that enforce a sandbox config: Landlock rules and network
+/// filtering on Linux, the Seatbelt profile on macOS. None when the filesystem and network are
+/// unrestricted.
+#[cfg_attr(
+    not(any(target_os = "linux", target_os = "macos")),
+    allow(unused_variables)
+)]
+pub async fn describe_platform_rules(config: &SandboxConfig) -> Option<String> {
+    #[cfg(target_os = "linux")]
+    {
+        let mut sections: Vec<String> = landlock::describe(config).into_iter().collect();
+        if config.effective_deny_net() {
+            sections.push(if config.allow_net.is_empty() {
+                "# seccomp: deny AF_INET and AF_INET6 sockets".to_string()
+            } else {
+                format!(
+                    "# network namespace: egress only through a proxy allowing {}",
+                    config.allow_net.join(", ")
+                )
+            });
+        }
+        (!sections.is_empty()).then(|| sections.join("\n\n"))
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        if !(config.restricts_read()
+            || config.effective_deny_write()
+            || config.effective_deny_net())
+        {
+            return None;
+        }
+        Some(format!(
+            "# seatbelt profile\n{}",
+            macos::generate_seatbelt_profile(config).await
+        ))
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    {
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
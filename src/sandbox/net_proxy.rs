@@ -0,0 +1,611 @@
+//! Per-host network filtering for the Linux sandbox.
+//!
+//! The sandboxed process runs in a fresh user and network namespace whose only interface is
+//! loopback. Before exec, the child creates a listener on [`PROXY_PORT`] inside that namespace
+//! and hands it to mise over a socketpair. mise accepts on it and forwards HTTP CONNECT, plain
+//! HTTP and SOCKS5 requests to the hosts in `allow_net`, refusing everything else.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, Ipv6Addr, Shutdown, TcpListener, TcpStream};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::os::unix::process::CommandExt;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+
+use eyre::{Result, bail, eyre};
+use nix::libc;
+
+/// Port of the proxy listener inside the sandbox's network namespace, which is always free there.
+pub const PROXY_PORT: u16 = 3128;
+
+const SOCKS_VERSION: u8 = 0x05;
+const HOP_BY_HOP_HEADERS: &[&str] = &[
+    "connection",
+    "keep-alive",
+    "proxy-connection",
+    "proxy-authorization",
+];
+const FD_CONTROL_LEN: usize =
+    unsafe { libc::CMSG_SPACE(size_of::<libc::c_int>() as libc::c_uint) } as usize;
+const FD_CONTROL_WORDS: usize = FD_CONTROL_LEN.div_ceil(size_of::<usize>());
+
+/// Keeps the proxy for one sandboxed process accepting connections until dropped.
+pub struct NetProxy {
+    state: Arc<ProxyState>,
+}
+
+#[derive(Default)]
+struct ProxyState {
+    stopped: AtomicBool,
+    listener: OnceLock<TcpListener>,
+}
+
+impl Drop for NetProxy {
+    fn drop(&mut self) {
+        self.state.stopped.store(true, Ordering::SeqCst);
+        if let Some(listener) = self.state.listener.get() {
+            // wakes the accept loop, which then exits
+            unsafe { libc::shutdown(listener.as_raw_fd(), libc::SHUT_RDWR) };
+        }
+    }
+}
+
+/// Moves `cmd` into its own network namespace on spawn and starts the proxy it talks through.
+///
+/// Must be called before any other `pre_exec` hook that restricts the child (Landlock would
+/// otherwise block the namespace setup).
+pub fn apply(cmd: &mut Command, allow_net: &[String]) -> Result<NetProxy> {
+    let rules = allow_net
+        .iter()
+        .map(|host| HostRule::parse(host))
+        .collect::<Result<Vec<_>>>()?;
+    let maps = IdMaps::current();
+    if let Err(err) = probe_namespaces(&maps) {
+        bail!(
+            "per-host network filtering (--allow-net=<host>) on Linux needs unprivileged user \
+             namespaces, which are unavailable here: {err}. \
+             Use --deny-net to block all network, or remove --allow-net."
+        );
+    }
+
+    let (parent_end, child_end) = socketpair()?;
+    unsafe {
+        cmd.pre_exec(move || {
+            enter_namespace(&maps)?;
+            let listener = listen_on_loopback()?;
+            send_fd(child_end.as_raw_fd(), listener.as_raw_fd())
+        });
+    }
+    let http_proxy = format!("http://127.0.0.1:{PROXY_PORT}");
+    let socks_proxy = format!("socks5h://127.0.0.1:{PROXY_PORT}");
+    for key in ["HTTP_PROXY", "HTTPS_PROXY", "http_proxy", "https_proxy"] {
+        cmd.env(key, &http_proxy);
+    }
+    for key in ["ALL_PROXY", "all_proxy"] {
+        cmd.env(key, &socks_proxy);
+    }
+    // loopback inside the namespace is private to the sandbox, so it never needs the proxy
+    for key in ["NO_PROXY", "no_proxy"] {
+        cmd.env(key, "localhost,127.0.0.1,::1");
+    }
+
+    let state = Arc::new(ProxyState::default());
+    let proxy_state = state.clone();
+    std::thread::Builder::new()
+        .name("mise-net-proxy".into())
+        .spawn(move || serve(parent_end, proxy_state, Arc::new(rules)))?;
+    Ok(NetProxy { state })
+}
+
+fn serve(socket: OwnedFd, state: Arc<ProxyState>, rules: Arc<Vec<HostRule>>) {
+    // returns None once the command is dropped without having been spawned
+    let listener = match recv_fd(&socket) {
+        Ok(Some(fd)) => TcpListener::from(fd),
+        Ok(None) => return,
+        Err(err) => {
+            debug!("sandbox network proxy: failed to receive listener: {err}");
+            return;
+        }
+    };
+    drop(socket);
+    let _ = state.listener.set(listener);
+    let listener = state.listener.get().unwrap();
+    while !state.stopped.load(Ordering::SeqCst) {
+        match listener.accept() {
+            Ok((client, _)) => {
+                let rules = rules.clone();
+                std::thread::spawn(move || {
+                    if let Err(err) = handle_client(client, &rules) {
+                        trace!("sandbox network proxy: {err}");
+                    }
+                });
+            }
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) if err.kind() == io::ErrorKind::ConnectionAborted => continue,
+            Err(err) => {
+                if !state.stopped.load(Ordering::SeqCst) {
+                    debug!("sandbox network proxy stopped: {err}");
+                }
+                break;
+            }
+        }
+    }
+}
+
+/// An `allow_net` entry: `host`, `host:port`, `*.domain[:port]`, `[v6]:port` or `*`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HostRule {
+    host: String,
+    port: Option<u16>,
+}
+
+impl HostRule {
+    fn parse(value: &str) -> Result<Self> {
+        let value = value.trim();
+        let (host, port) = if let Some(rest) = value.strip_prefix('[') {
+            match rest.split_once(']') {
+                Some((host, "")) => (host, None),
+                Some((host, rest)) if rest.starts_with(':') => (host, Some(&rest[1..])),
+                _ => bail!("invalid --allow-net host {value:?}"),
+            }
+        } else if value.matches(':').count() == 1 {
+            let (host, port) = value.split_once(':').unwrap();
+            (host, Some(port))
+        } else {
+            // a bare IPv6 address has several colons and no port
+            (value, None)
+        };
+        let port = port
+            .map(|port| {
+                port.parse::<u16>()
+                    .map_err(|_| eyre!("invalid port in --allow-net host {value:?}"))
+            })
+            .transpose()?;
+        let host = normalize_host(host);
+        if host.is_empty() || host.contains('/') {
+            bail!("invalid --allow-net host {value:?}");
+        }
+        Ok(Self { host, port })
+    }
+
+    fn matches(&self, host: &str, port: u16) -> bool {
+        if self.port.is_some_and(|p| p != port) {
+            return false;
+        }
+        let host = normalize_host(host);
+        match self.host.strip_prefix("*.") {
+            Some(domain) => host
+                .strip_suffix(domain)
+                .is_some_and(|sub| sub.len() > 1 && sub.ends_with('.')),
+            None => self.host == "*" || self.host == host,
+        }
+    }
+}
+
+fn normalize_host(host: &str) -> String {
+    host.trim_start_matches('[')
+        .trim_end_matches(']')
+        .trim_end_matches('.')
+        .to_ascii_lowercase()
+}
+
+fn is_allowed(rules: &[HostRule], host: &str, port: u16) -> bool {
+    let allowed = rules.iter().any(|rule| rule.matches(host, port));
+    if !allowed {
+        warn!("sandbox blocked network access to {host}:{port} (not in --allow-net)");
+    }
+    allowed
+}
+
+fn handle_client(client: TcpStream, rules: &[HostRule]) -> io::Result<()> {
+    let mut first = [0u8; 1];
+    if client.peek(&mut first)? == 0 {
+        return Ok(());
+    }
+    if first[0] == SOCKS_VERSION {
+        handle_socks5(client, rules)
+    } else {
+        handle_http(client, rules)
+    }
+}
+
+fn handle_http(mut client: TcpStream, rules: &[HostRule]) -> io::Result<()> {
+    let mut reader = BufReader::new(client.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut headers = vec![];
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line.trim_end().is_empty() {
+            break;
+        }
+        headers.push(line);
+    }
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target), Some(version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return http_status(&mut client, "400 Bad Request");
+    };
+
+    if method.eq_ignore_ascii_case("CONNECT") {
+        let Some((host, port)) = split_authority(target, None) else {
+            return http_status(&mut client, "400 Bad Request");
+        };
+        if !is_allowed(rules, &host, port) {
+            return http_status(&mut client, "403 Forbidden");
+        }
+        let Ok(mut upstream) = TcpStream::connect((host.as_str(), port)) else {
+            return http_status(&mut client, "502 Bad Gateway");
+        };
+        client.write_all(b"HTTP/1.1 200 Connection established\r\n\r\n")?;
+        // anything the client sent right after the request, e.g. a TLS ClientHello
+        upstream.write_all(reader.buffer())?;
+        return relay(client, upstream);
+    }
+
+    // plain HTTP through a proxy uses an absolute URI: `GET http://host[:port]/path HTTP/1.1`
+    let rest = match target.get(..7) {
+        Some(scheme) if scheme.eq_ignore_ascii_case("http://") => &target[7..],
+        _ => return http_status(&mut client, "400 Bad Request"),
+    };
+    let (authority, path) = match rest.find(['/', '?']) {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let Some((host, port)) = split_authority(authority, Some(80)) else {
+        return http_status(&mut client, "400 Bad Request");
+    };
+    if !is_allowed(rules, &host, port) {
+        return http_status(&mut client, "403 Forbidden");
+    }
+    let Ok(mut upstream) = TcpStream::connect((host.as_str(), port)) else {
+        return http_status(&mut client, "502 Bad Gateway");
+    };
+    let path = if path.starts_with('?') {
+        format!("/{path}")
+    } else {
+        path.to_string()
+    };
+    let mut head = format!("{method} {path} {version}\r\n");
+    for header in headers {
+        let name = header.split(':').next().unwrap_or_default().trim();
+        if !HOP_BY_HOP_HEADERS
+            .iter()
+            .any(|h| name.eq_ignore_ascii_case(h))
+        {
+            head.push_str(&header);
+        }
+    }
+    // one request per connection keeps the forwarded stream aligned with the checked host
+    head.push_str("Connection: close\r\n\r\n");
+    upstream.write_all(head.as_bytes())?;
+    upstream.write_all(reader.buffer())?;
+    relay(client, upstream)
+}
+
+fn http_status(client: &mut TcpStream, status: &str) -> io::Result<()> {
+    client.write_all(
+        format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n").as_bytes(),
+    )
+}
+
+/// Splits `host:port` or `[v6]:port`, using `default_port` when there is none.
+fn split_authority(authority: &str, default_port: Option<u16>) -> Option<(String, u16)> {
+    let (host, port) = if let Some(rest) = authority.strip_prefix('[') {
+        let (host, rest) = rest.split_once(']')?;
+        (host, rest.strip_prefix(':'))
+    } else {
+        match authority.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        }
+    };
+    let port = match port {
+        Some(port) => port.parse().ok()?,
+        None => default_port?,
+    };
+    (!host.is_empty()).then(|| (host.to_string(), port))
+}
+
+fn handle_socks5(mut client: TcpStream, rules: &[HostRule]) -> io::Result<()> {
+    let mut greeting = [0u8; 2];
+    client.read_exact(&mut greeting)?;
+    let mut methods = vec![0u8; greeting[1] as usize];
+    client.read_exact(&mut methods)?;
+    // only "no authentication" is offered
+    if !methods.contains(&0) {
+        return client.write_all(&[SOCKS_VERSION, 0xff]);
+    }
+    client.write_all(&[SOCKS_VERSION, 0])?;
+
+    let mut request = [0u8; 4];
+    client.read_exact(&mut request)?;
+    let host = match request[3] {
+        1 => {
+            let mut ip = [0u8; 4];
+            client.read_exact(&mut ip)?;
+            Ipv4Addr::from(ip).to_string()
+        }
+        3 => {
+            let mut len = [0u8; 1];
+            client.read_exact(&mut len)?;
+            let mut name = vec![0u8; len[0] as usize];
+            client.read_exact(&mut name)?;
+            String::from_utf8_lossy(&name).into_owned()
+        }
+        4 => {
+            let mut ip = [0u8; 16];
+            client.read_exact(&mut ip)?;
+            Ipv6Addr::from(ip).to_string()
+        }
+        _ => return socks_reply(&mut client, 0x08),
+    };
+    let mut port = [0u8; 2];
+    client.read_exact(&mut port)?;
+    let port = u16::from_be_bytes(port);
+    // only CONNECT is supported
+    if request[1] != 1 {
+        return socks_reply(&mut client, 0x07);
+    }
+    if !is_allowed(rules, &host, port) {
+        return socks_reply(&mut client, 0x02);
+    }
+    match TcpStream::connect((host.as_str(), port)) {
+        Ok(upstream) => {
+            socks_reply(&mut client, 0)?;
+            relay(client, upstream)
+        }
+        Err(_) => socks_reply(&mut client, 0x04),
+    }
+}
+
+fn socks_reply(client: &mut TcpStream, status: u8) -> io::Result<()> {
+    client.write_all(&[SOCKS_VERSION, status, 0, 1, 0, 0, 0, 0, 0, 0])
+}
+
+/// Copies both directions until the upstream side closes.
+fn relay(client: TcpStream, upstream: TcpStream) -> io::Result<()> {
+    let mut client_read = client.try_clone()?;
+    let mut upstream_write = upstream.try_clone()?;
+    let upload = std::thread::spawn(move || {
+        let _ = io::copy(&mut client_read, &mut upstream_write);
+        let _ = upstream_write.shutdown(Shutdown::Write);
+    });
+    let (mut upstream, mut client) = (upstream, client);
+    let _ = io::copy(&mut upstream, &mut client);
+    let _ = client.shutdown(Shutdown::Both);
+    let _ = upload.join();
+    Ok(())
+}
+
+/// `/proc/self/{uid,gid}_map` contents that keep the caller's ids inside the namespace.
+///
+/// Built before fork, since the `pre_exec` hook must not allocate.
+#[derive(Clone)]
+struct IdMaps {
+    uid: String,
+    gid: String,
+}
+
+impl IdMaps {
+    fn current() -> Self {
+        let uid = nix::unistd::getuid().as_raw();
+        let gid = nix::unistd::getgid().as_raw();
+        Self {
+            uid: format!("{uid} {uid} 1"),
+            gid: format!("{gid} {gid} 1"),
+        }
+    }
+}
+
+/// Spawns a throwaway child through the same namespace setup, once per process.
+fn probe_namespaces(maps: &IdMaps) -> std::result::Result<(), String> {
+    static PROBE: OnceLock<std::result::Result<(), String>> = OnceLock::new();
+    PROBE
+        .get_or_init(|| {
+            let maps = maps.clone();
+            let mut probe = Command::new("/bin/sh");
+            probe.args(["-c", ":"]);
+            unsafe {
+                probe.pre_exec(move || enter_namespace(&maps));
+            }
+            match probe.status() {
+                Ok(status) if status.success() => Ok(()),
+                Ok(status) => Err(format!("probe exited with {status}")),
+                Err(err) => Err(err.to_string()),
+            }
+        })
+        .clone()
+}
+
+/// Runs in the forked child: enters new user and network namespaces and brings up loopback.
+fn enter_namespace(maps: &IdMaps) -> io::Result<()> {
+    if unsafe { libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNET) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    write_proc(c"/proc/self/setgroups", b"deny")?;
+    write_proc(c"/proc/self/uid_map", maps.uid.as_bytes())?;
+    write_proc(c"/proc/self/gid_map", maps.gid.as_bytes())?;
+    loopback_up()
+}
+
+fn write_proc(path: &std::ffi::CStr, contents: &[u8]) -> io::Result<()> {
+    let fd = unsafe { libc::open(path.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC) };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+    let written = unsafe { libc::write(fd.as_raw_fd(), contents.as_ptr().cast(), contents.len()) };
+    if written != contents.len() as isize {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+fn loopback_up() -> io::Result<()> {
+    let socket = unsafe { libc::socket(libc::AF_INET, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, 0) };
+    if socket < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let socket = unsafe { OwnedFd::from_raw_fd(socket) };
+    let mut request: libc::ifreq = unsafe { std::mem::zeroed() };
+    for (dst, src) in request.ifr_name.iter_mut().zip(b"lo") {
+        *dst = *src as libc::c_char;
+    }
+    request.ifr_ifru.ifru_flags = (libc::IFF_UP | libc::IFF_RUNNING) as libc::c_short;
+    if unsafe { libc::ioctl(socket.as_raw_fd(), libc::SIOCSIFFLAGS as _, &request) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+fn listen_on_loopback() -> io::Result<OwnedFd> {
+    let socket = unsafe { libc::socket(libc::AF_INET, libc::SOCK_STREAM | libc::SOCK_CLOEXEC, 0) };
+    if socket < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let socket = unsafe { OwnedFd::from_raw_fd(socket) };
+    let mut addr: libc::sockaddr_in = unsafe { std::mem::zeroed() };
+    addr.sin_family = libc::AF_INET as libc::sa_family_t;
+    addr.sin_port = PROXY_PORT.to_be();
+    addr.sin_addr.s_addr = u32::from(Ipv4Addr::LOCALHOST).to_be();
+    let bound = unsafe {
+        libc::bind(
+            socket.as_raw_fd(),
+            (&addr as *const libc::sockaddr_in).cast(),
+            size_of::<libc::sockaddr_in>() as libc::socklen_t,
+        )
+    };
+    if bound != 0 || unsafe { libc::listen(socket.as_raw_fd(), 128) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(socket)
+}
+
+fn socketpair() -> io::Result<(OwnedFd, OwnedFd)> {
+    let mut fds = [0 as RawFd; 2];
+    let created = unsafe {
+        libc::socketpair(
+            libc::AF_UNIX,
+            libc::SOCK_STREAM | libc::SOCK_CLOEXEC,
+            0,
+            fds.as_mut_ptr(),
+        )
+    };
+    if created != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(unsafe { (OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) })
+}
+
+fn fd_message(byte: &mut [u8; 1], iov: &mut libc::iovec, control: &mut [usize]) -> libc::msghdr {
+    *iov = libc::iovec {
+        iov_base: byte.as_mut_ptr().cast(),
+        iov_len: byte.len(),
+    };
+    let mut msg: libc::msghdr = unsafe { std::mem::zeroed() };
+    msg.msg_iov = iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = control.as_mut_ptr().cast();
+    msg.msg_controllen = FD_CONTROL_LEN as _;
+    msg
+}
+
+fn send_fd(socket: RawFd, fd: RawFd) -> io::Result<()> {
+    let mut byte = [0u8];
+    let mut iov = unsafe { std::mem::zeroed() };
+    let mut control = [0usize; FD_CONTROL_WORDS];
+    let msg = fd_message(&mut byte, &mut iov, &mut control);
+    unsafe {
+        let cmsg = libc::CMSG_FIRSTHDR(&msg);
+        (*cmsg).cmsg_level = libc::SOL_SOCKET;
+        (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+        (*cmsg).cmsg_len = libc::CMSG_LEN(size_of::<libc::c_int>() as libc::c_uint) as _;
+        std::ptr::write_unaligned(libc::CMSG_DATA(cmsg).cast::<libc::c_int>(), fd);
+        if libc::sendmsg(socket, &msg, 0) < 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+fn recv_fd(socket: &OwnedFd) -> io::Result<Option<OwnedFd>> {
+    let mut byte = [0u8];
+    let mut iov = unsafe { std::mem::zeroed() };
+    let mut control = [0usize; FD_CONTROL_WORDS];
+    let mut msg = fd_message(&mut byte, &mut iov, &mut control);
+    let received = unsafe { libc::recvmsg(socket.as_raw_fd(), &mut msg, libc::MSG_CMSG_CLOEXEC) };
+    if received < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    unsafe {
+        let cmsg = libc::CMSG_FIRSTHDR(&msg);
+        if received == 0
+            || cmsg.is_null()
+            || (*cmsg).cmsg_level != libc::SOL_SOCKET
+            || (*cmsg).cmsg_type != libc::SCM_RIGHTS
+        {
+            return Ok(None);
+        }
+        let fd = std::ptr::read_unaligned(libc::CMSG_DATA(cmsg).cast::<libc::c_int>());
+        Ok(Some(OwnedFd::from_raw_fd(fd)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(value: &str) -> HostRule {
+        HostRule::parse(value).unwrap()
+    }
+
+    #[test]
+    fn test_parse_host_rules() {
+        assert_eq!(
+            rule("Registry.npmjs.org."),
+            HostRule {
+                host: "registry.npmjs.org".into(),
+                port: None
+            }
+        );
+        assert_eq!(rule("example.com:8443").port, Some(8443));
+        assert_eq!(rule("[::1]:80").host, "::1");
+        assert_eq!(rule("[::1]:80").port, Some(80));
+        assert_eq!(rule("::1").port, None);
+        assert!(HostRule::parse("example.com:http").is_err());
+        assert!(HostRule::parse("https://example.com").is_err());
+        assert!(HostRule::parse("").is_err());
+    }
+
+    #[test]
+    fn test_host_rule_matches() {
+        assert!(rule("example.com").matches("EXAMPLE.com.", 443));
+        assert!(!rule("example.com").matches("api.example.com", 443));
+        assert!(rule("example.com:443").matches("example.com", 443));
+        assert!(!rule("example.com:443").matches("example.com", 80));
+        assert!(rule("*.example.com").matches("api.example.com", 443));
+        assert!(!rule("*.example.com").matches("example.com", 443));
+        assert!(!rule("*.example.com").matches("badexample.com", 443));
+        assert!(rule("*").matches("anything.test", 22));
+        assert!(rule("[2001:db8::1]:443").matches("[2001:DB8::1]", 443));
+    }
+
+    #[test]
+    fn test_split_authority() {
+        assert_eq!(
+            split_authority("example.com:443", None),
+            Some(("example.com".into(), 443))
+        );
+        assert_eq!(
+            split_authority("example.com", Some(80)),
+            Some(("example.com".into(), 80))
+        );
+        assert_eq!(split_authority("example.com", None), None);
+        assert_eq!(
+            split_authority("[::1]:8080", None),
+            Some(("::1".into(), 8080))
+        );
+    }
+}
@@ -0,0 +1,295 @@
+//! Named sandbox profiles, defined in `[sandbox.profiles.<name>]` config tables.
+//!
+//! A profile is a reusable [`SandboxConfig`] that tasks select with `sandbox = "<name>"` and
+//! `mise run`/`mise exec` select with `--sandbox-profile`. Profiles can build on each other with
+//! `extends`, in which case the restrictions of both apply.
+
+use std::path::{Path, PathBuf};
+
+use eyre::{Result, WrapErr, bail};
+use indexmap::IndexMap;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use super::limits::display_size;
+use super::{ResourceLimits, SandboxConfig};
+use crate::file::replace_path;
+use crate::tera::{BASE_CONTEXT, contains_template_syntax, get_tera, render_str};
+
+/// The `[sandbox]` table of a config file.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SandboxToml {
+    #[serde(default)]
+    pub profiles: IndexMap<String, SandboxProfile>,
+}
+
+/// A reusable sandbox policy, written the same way as the sandbox fields of a task.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct SandboxProfile {
+    /// Name of another profile whose restrictions this one adds to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub deny_all: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub deny_read: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub deny_write: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub deny_net: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub deny_env: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow_read: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow_write: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow_net: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow_env: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pass_through_env: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit_memory: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit_cpus: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit_pids: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit_open_files: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit_output_bytes: Option<String>,
+    /// Root of the config file that defined the profile, which relative paths are resolved against
+    #[serde(skip)]
+    config_root: PathBuf,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+impl SandboxProfile {
+    /// Describes a resolved sandbox config as a profile, for display.
+    pub fn from_config(config: &SandboxConfig) -> Self {
+        let paths = |paths: &[PathBuf]| {
+            paths
+                .iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect()
+        };
+        Self {
+            deny_read: config.effective_deny_read(),
+            deny_write: config.effective_deny_write(),
+            deny_net: config.effective_deny_net(),
+            deny_env: config.effective_deny_env(),
+            allow_read: paths(&config.allow_read),
+            allow_write: paths(&config.allow_write),
+            allow_net: config.allow_net.clone(),
+            allow_env: config.allow_env.clone(),
+            pass_through_env: config.pass_through_env.clone(),
+            limit_memory: config.limits.memory.map(display_size),
+            limit_cpus: config.limits.cpus,
+            limit_pids: config.limits.pids,
+            limit_open_files: config.limits.open_files,
+            limit_output_bytes: config.limits.output_bytes.map(display_size),
+            ..Default::default()
+        }
+    }
+
+    /// The restrictions of this profile alone, without the profile it extends.
+    fn to_config(&self) -> Result<SandboxConfig> {
+        Ok(SandboxConfig {
+            deny_read: self.deny_all || self.deny_read,
+            deny_write: self.deny_all || self.deny_write,
+            deny_net: self.deny_all || self.deny_net,
+            deny_env: self.deny_all || self.deny_env,
+            allow_read: self.render_paths(&self.allow_read)?,
+            allow_write: self.render_paths(&self.allow_write)?,
+            allow_net: self.allow_net.clone(),
+            allow_env: self.allow_env.clone(),
+            pass_through_env: self.pass_through_env.clone(),
+            limits: ResourceLimits::parse(
+                self.limit_memory.as_deref(),
+                self.limit_cpus,
+                self.limit_pids,
+                self.limit_open_files,
+                self.limit_output_bytes.as_deref(),
+            )?,
+            ..Default::default()
+        })
+    }
+
+    /// Renders templates like `{{config_root}}` in paths, then resolves relative paths against
+    /// the root of the config file that defined the profile.
+    fn render_paths(&self, paths: &[String]) -> Result<Vec<PathBuf>> {
+        let mut tera = get_tera(Some(&self.config_root));
+        let mut ctx = BASE_CONTEXT.clone();
+        ctx.insert("config_root", &self.config_root);
+        let mut rendered = Vec::with_capacity(paths.len());
+        for path in paths {
+            let path = if contains_template_syntax(path) {
+                render_str(&mut tera, path, &ctx)?
+            } else {
+                path.clone()
+            };
+            if path.trim().is_empty() {
+                continue;
+            }
+            let path = replace_path(Path::new(&path));
+            rendered.push(if path.is_absolute() {
+                path
+            } else {
+                self.config_root.join(path)
+            });
+        }
+        Ok(rendered)
+    }
+}
+
+/// The sandbox profiles of every config file, where a profile in a local config replaces a
+/// global profile with the same name.
+#[derive(Debug, Clone, Default)]
+pub struct SandboxProfiles(IndexMap<String, SandboxProfile>);
+
+impl SandboxProfiles {
+    /// Adds the profiles of a config file, replacing any with the same name added before.
+    pub fn add(&mut self, toml: SandboxToml, config_root: &Path) {
+        for (name, mut profile) in toml.profiles {
+            profile.config_root = config_root.to_path_buf();
+            self.0.insert(name, profile);
+        }
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.0.contains_key(name)
+    }
+
+    /// Resolves a profile, and the profiles it extends, into a sandbox config.
+    pub fn resolve(&self, name: &str) -> Result<SandboxConfig> {
+        let mut chain: Vec<(&str, &SandboxProfile)> = vec![];
+        let mut next = Some(name);
+        while let Some(name) = next {
+            if chain.iter().any(|(n, _)| *n == name) {
+                let cycle = chain.iter().map(|(n, _)| *n).chain([name]).join(" -> ");
+                bail!("sandbox profile {name} extends itself: {cycle}");
+            }
+            let Some(profile) = self.0.get(name) else {
+                match chain.last() {
+                    Some((parent, _)) => {
+                        bail!("sandbox profile {parent} extends {name}, which is not defined")
+                    }
+                    None if self.0.is_empty() => bail!(
+                        "sandbox profile {name} is not defined, add it as [sandbox.profiles.{name}] in mise.toml"
+                    ),
+                    None => bail!(
+                        "sandbox profile {name} is not defined, available profiles: {}",
+                        self.0.keys().join(", ")
+                    ),
+                }
+            };
+            chain.push((name, profile));
+            next = profile.extends.as_deref();
+        }
+        let mut config = SandboxConfig::default();
+        for (name, profile) in chain.into_iter().rev() {
+            config.merge(
+                profile
+                    .to_config()
+                    .wrap_err_with(|| format!("invalid sandbox profile {name}"))?,
+            );
+        }
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profiles(source: &str) -> SandboxProfiles {
+        let mut profiles = SandboxProfiles::default();
+        profiles.add(toml::from_str(source).unwrap(), Path::new("/project"));
+        profiles
+    }
+
+    #[test]
+    fn test_resolve_combines_extended_profiles() {
+        let profiles = profiles(
+            r#"
+            [profiles.base]
+            deny_net = true
+            allow_read = ["src"]
+            limit_memory = "2GiB"
+
+            [profiles.ci]
+            extends = "base"
+            deny_env = true
+            allow_read = ["/opt/cache"]
+            allow_env = ["CI"]
+            limit_memory = "4GiB"
+            "#,
+        );
+
+        let config = profiles.resolve("ci").unwrap();
+
+        assert!(config.deny_net);
+        assert!(config.deny_env);
+        assert!(!config.deny_write);
+        assert_eq!(
+            config.allow_read,
+            [PathBuf::from("/project/src"), PathBuf::from("/opt/cache")]
+        );
+        assert_eq!(config.allow_env, ["CI"]);
+        assert_eq!(config.limits.memory, Some(2 << 30));
+    }
+
+    #[test]
+    fn test_resolve_detects_cycles() {
+        let profiles = profiles(
+            r#"
+            [profiles.a]
+            extends = "b"
+
+            [profiles.b]
+            extends = "a"
+            "#,
+        );
+
+        let err = profiles.resolve("a").unwrap_err().to_string();
+        assert_eq!(err, "sandbox profile a extends itself: a -> b -> a");
+    }
+
+    #[test]
+    fn test_resolve_reports_missing_profiles() {
+        let profiles = profiles(
+            r#"
+            [profiles.ci]
+            extends = "base"
+            "#,
+        );
+
+        assert_eq!(
+            profiles.resolve("ci").unwrap_err().to_string(),
+            "sandbox profile ci extends base, which is not defined"
+        );
+        assert_eq!(
+            profiles.resolve("dev").unwrap_err().to_string(),
+            "sandbox profile dev is not defined, available profiles: ci"
+        );
+    }
+
+    #[test]
+    fn test_local_profiles_replace_global_ones() {
+        let mut profiles = profiles("[profiles.ci]\ndeny_net = true");
+        profiles.add(
+            toml::from_str("[profiles.ci]\ndeny_write = true").unwrap(),
+            Path::new("/project/sub"),
+        );
+
+        let config = profiles.resolve("ci").unwrap();
+        assert!(!config.deny_net);
+        assert!(config.deny_write);
+    }
+}
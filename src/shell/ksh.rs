@@ -0,0 +1,99 @@
+#![allow(unknown_lints)]
+use std::fmt::Display;
+
+use crate::shell::bash::Bash;
+use crate::shell::sh::{activate_posix, deactivate_posix};
+use crate::shell::{ActivateOptions, Shell};
+
+/// ksh93 and mksh, which use the POSIX activation script
+#[derive(Default)]
+pub struct Ksh {}
+
+impl Ksh {}
+
+impl Shell for Ksh {
+    fn activate(&self, opts: ActivateOptions) -> String {
+        activate_posix(self, opts)
+    }
+
+    fn deactivate(&self) -> String {
+        deactivate_posix()
+    }
+
+    fn set_env(&self, k: &str, v: &str) -> String {
+        Bash::default().set_env(k, v)
+    }
+
+    fn prepend_env(&self, k: &str, v: &str) -> String {
+        format!("export {k}=\"{v}:${k}\"\n")
+    }
+
+    fn unset_env(&self, k: &str) -> String {
+        Bash::default().unset_env(k)
+    }
+
+    fn set_alias(&self, name: &str, cmd: &str) -> String {
+        Bash::default().set_alias(name, cmd)
+    }
+
+    fn unset_alias(&self, name: &str) -> String {
+        Bash::default().unset_alias(name)
+    }
+}
+
+impl Display for Ksh {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ksh")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_snapshot;
+    use std::path::Path;
+    use test_log::test;
+
+    use crate::test::replace_path;
+
+    use super::*;
+
+    #[test]
+    fn test_activate() {
+        unsafe {
+            std::env::remove_var("__MISE_ORIG_PATH");
+            std::env::remove_var("__MISE_DIFF");
+        }
+
+        let ksh = Ksh::default();
+        let exe = Path::new("/some/dir/mise");
+        let opts = ActivateOptions {
+            exe: exe.to_path_buf(),
+            flags: " --status".into(),
+            no_hook_env: false,
+            prelude: vec![],
+        };
+        assert_snapshot!(ksh.activate(opts));
+    }
+
+    #[test]
+    fn test_set_env() {
+        assert_snapshot!(Ksh::default().set_env("FOO", "1"));
+    }
+
+    #[test]
+    fn test_prepend_env() {
+        let ksh = Ksh::default();
+        assert_snapshot!(replace_path(&ksh.prepend_env("PATH", "/some/dir:/2/dir")));
+    }
+
+    #[test]
+    fn test_unset_env() {
+        assert_snapshot!(Ksh::default().unset_env("FOO"));
+    }
+
+    #[test]
+    fn test_deactivate() {
+        let deactivate = Ksh::default().deactivate();
+        assert_snapshot!(replace_path(&deactivate));
+    }
+}
@@ -8,8 +8,11 @@ use std::str::FromStr;
 mod bash;
 mod elvish;
 mod fish;
+mod ksh;
 mod nushell;
 mod pwsh;
+mod sh;
+mod tcsh;
 mod xonsh;
 mod zsh;
 
@@ -22,6 +25,9 @@ pub enum ShellType {
     Xonsh,
     Zsh,
     Pwsh,
+    Sh,
+    Ksh,
+    Tcsh,
 }
 
 impl ShellType {
@@ -34,6 +40,9 @@ impl ShellType {
             Self::Xonsh => Box::<xonsh::Xonsh>::default(),
             Self::Zsh => Box::<zsh::Zsh>::default(),
             Self::Pwsh => Box::<pwsh::Pwsh>::default(),
+            Self::Sh => Box::<sh::Sh>::default(),
+            Self::Ksh => Box::<ksh::Ksh>::default(),
+            Self::Tcsh => Box::<tcsh::Tcsh>::default(),
         }
     }
 }
@@ -48,6 +57,9 @@ impl Display for ShellType {
             Self::Xonsh => write!(f, "xonsh"),
             Self::Zsh => write!(f, "zsh"),
             Self::Pwsh => write!(f, "pwsh"),
+            Self::Sh => write!(f, "sh"),
+            Self::Ksh => write!(f, "ksh"),
+            Self::Tcsh => write!(f, "tcsh"),
         }
     }
 }
@@ -59,13 +71,16 @@ impl FromStr for ShellType {
         let s = s.to_lowercase();
         let s = s.rsplit_once('/').map(|(_, s)| s).unwrap_or(&s);
         match s {
-            "bash" | "sh" => Ok(Self::Bash),
+            "bash" => Ok(Self::Bash),
             "elvish" => Ok(Self::Elvish),
             "fish" => Ok(Self::Fish),
             "nu" => Ok(Self::Nu),
             "xonsh" => Ok(Self::Xonsh),
             "zsh" => Ok(Self::Zsh),
             "pwsh" => Ok(Self::Pwsh),
+            "sh" | "dash" | "ash" => Ok(Self::Sh),
+            "ksh" | "ksh93" | "mksh" => Ok(Self::Ksh),
+            "tcsh" | "csh" => Ok(Self::Tcsh),
             _ => Err(format!("unsupported shell type: {s}")),
         }
     }
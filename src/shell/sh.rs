@@ -0,0 +1,169 @@
+#![allow(unknown_lints)]
+use std::fmt::Display;
+
+use indoc::formatdoc;
+use shell_escape::unix::escape;
+
+use crate::shell::bash::Bash;
+use crate::shell::{self, ActivateOptions, Shell};
+
+/// POSIX sh, such as dash or busybox ash
+#[derive(Default)]
+pub struct Sh {}
+
+impl Sh {}
+
+/// Activation script for shells that only have POSIX features. They have no prompt hook, so
+/// hook-env runs from a `cd` wrapper instead.
+pub(super) fn activate_posix(shell: &dyn Shell, opts: ActivateOptions) -> String {
+    let exe = escape(opts.exe.to_string_lossy());
+    let flags = opts.flags;
+    let mut out = String::new();
+
+    out.push_str(&shell::build_deactivation_script(shell));
+
+    out.push_str(&shell.format_activate_prelude(&opts.prelude));
+    out.push_str(&formatdoc! {r#"
+        export MISE_SHELL={shell}
+        if [ -z "${{__MISE_ORIG_PATH:-}}" ]; then
+          export __MISE_ORIG_PATH="$PATH"
+        fi
+
+        mise() {{
+          if [ "$#" = 0 ]; then
+            command {exe}
+            return
+          fi
+
+          case "$1" in
+          deactivate|shell|sh)
+            # if argv doesn't contain -h,--help
+            case " $* " in
+            *" -h "*|*" --help "*) ;;
+            *)
+              eval "$(command {exe} "$@")"
+              return $?
+              ;;
+            esac
+            ;;
+          esac
+          command {exe} "$@"
+        }}
+    "#});
+
+    if !opts.no_hook_env {
+        out.push_str(&formatdoc! {r#"
+
+        _mise_hook() {{
+          eval "$({exe} hook-env{flags} -s {shell} "$@")"
+        }}
+        cd() {{
+          command cd "$@" || return
+          _mise_hook --reason chpwd
+        }}
+
+        _mise_hook
+        "#});
+    }
+
+    out
+}
+
+/// Undoes [`activate_posix`].
+pub(super) fn deactivate_posix() -> String {
+    formatdoc! {r#"
+        unset -f cd 2>/dev/null
+        unset -f _mise_hook 2>/dev/null
+        unset -f mise 2>/dev/null
+        unset MISE_SHELL
+        unset __MISE_DIFF
+        unset __MISE_SESSION
+        "#}
+}
+
+impl Shell for Sh {
+    fn activate(&self, opts: ActivateOptions) -> String {
+        activate_posix(self, opts)
+    }
+
+    fn deactivate(&self) -> String {
+        deactivate_posix()
+    }
+
+    fn set_env(&self, k: &str, v: &str) -> String {
+        Bash::default().set_env(k, v)
+    }
+
+    fn prepend_env(&self, k: &str, v: &str) -> String {
+        format!("export {k}=\"{v}:${k}\"\n")
+    }
+
+    fn unset_env(&self, k: &str) -> String {
+        Bash::default().unset_env(k)
+    }
+
+    fn set_alias(&self, name: &str, cmd: &str) -> String {
+        Bash::default().set_alias(name, cmd)
+    }
+
+    fn unset_alias(&self, name: &str) -> String {
+        Bash::default().unset_alias(name)
+    }
+}
+
+impl Display for Sh {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "sh")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_snapshot;
+    use std::path::Path;
+    use test_log::test;
+
+    use crate::test::replace_path;
+
+    use super::*;
+
+    #[test]
+    fn test_activate() {
+        unsafe {
+            std::env::remove_var("__MISE_ORIG_PATH");
+            std::env::remove_var("__MISE_DIFF");
+        }
+
+        let sh = Sh::default();
+        let exe = Path::new("/some/dir/mise");
+        let opts = ActivateOptions {
+            exe: exe.to_path_buf(),
+            flags: " --status".into(),
+            no_hook_env: false,
+            prelude: vec![],
+        };
+        assert_snapshot!(sh.activate(opts));
+    }
+
+    #[test]
+    fn test_set_env() {
+        assert_snapshot!(Sh::default().set_env("FOO", "1"));
+    }
+
+    #[test]
+    fn test_prepend_env() {
+        let sh = Sh::default();
+        assert_snapshot!(replace_path(&sh.prepend_env("PATH", "/some/dir:/2/dir")));
+    }
+
+    #[test]
+    fn test_unset_env() {
+        assert_snapshot!(Sh::default().unset_env("FOO"));
+    }
+
+    #[test]
+    fn test_deactivate() {
+        let deactivate = Sh::default().deactivate();
+        assert_snapshot!(replace_path(&deactivate));
+    }
+}
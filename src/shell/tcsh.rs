@@ -0,0 +1,142 @@
+#![allow(unknown_lints)]
+use std::fmt::Display;
+
+use indoc::formatdoc;
+
+use crate::shell::{self, ActivateOptions, Shell};
+
+/// tcsh and csh. Activation output is run with `eval`, which joins it into a single line, so
+/// every command ends with `;` and nothing spans lines.
+#[derive(Default)]
+pub struct Tcsh {}
+
+impl Tcsh {}
+
+/// Quotes a value for csh. `shell_escape` already leaves `!` outside the quotes, but newlines
+/// inside them also need a backslash.
+fn escape(s: &str) -> String {
+    shell_escape::unix::escape(s.into()).replace('\n', "\\\n")
+}
+
+impl Shell for Tcsh {
+    fn activate(&self, opts: ActivateOptions) -> String {
+        let exe = escape(&opts.exe.to_string_lossy());
+        let flags = opts.flags;
+        let mut out = String::new();
+
+        out.push_str(&shell::build_deactivation_script(self));
+
+        out.push_str(&self.format_activate_prelude(&opts.prelude));
+        // tcsh has no functions, so `mise` is an alias that evals the output of the commands
+        // that change the current shell
+        out.push_str(&formatdoc! {r#"
+            setenv MISE_SHELL tcsh;
+            if ( ! $?__MISE_ORIG_PATH ) setenv __MISE_ORIG_PATH "$PATH";
+            setenv __MISE_EXE {exe};
+            alias mise 'unset _mise_eval; if ( " \!* " =~ \ deactivate\ * || " \!* " =~ \ shell\ * || " \!* " =~ \ sh\ * ) set _mise_eval; if ( " \!* " =~ *\ -h\ * || " \!* " =~ *\ --help\ * ) unset _mise_eval; if ( $?_mise_eval ) eval "`$__MISE_EXE \!*`"; if ( ! $?_mise_eval ) $__MISE_EXE \!*; unset _mise_eval';
+        "#});
+
+        if !opts.no_hook_env {
+            out.push_str(&formatdoc! {r#"
+            alias _mise_hook 'eval "`$__MISE_EXE hook-env{flags} -s tcsh \!*`"';
+            alias precmd '_mise_hook --reason precmd';
+            alias cwdcmd '_mise_hook --reason chpwd';
+            _mise_hook;
+            "#});
+        }
+
+        out
+    }
+
+    fn deactivate(&self) -> String {
+        formatdoc! {r#"
+        unalias precmd;
+        unalias cwdcmd;
+        unalias _mise_hook;
+        unalias mise;
+        unsetenv MISE_SHELL;
+        unsetenv __MISE_DIFF;
+        unsetenv __MISE_SESSION;
+        unsetenv __MISE_EXE;
+        "#}
+    }
+
+    fn set_env(&self, k: &str, v: &str) -> String {
+        format!("setenv {} {};\n", escape(k), escape(v))
+    }
+
+    fn prepend_env(&self, k: &str, v: &str) -> String {
+        let k = escape(k);
+        let v = escape(&format!("{v}:"));
+        format!("if ( ! $?{k} ) setenv {k} '';\nsetenv {k} {v}\"${k}\";\n")
+    }
+
+    fn unset_env(&self, k: &str) -> String {
+        format!("unsetenv {};\n", escape(k))
+    }
+
+    fn set_alias(&self, name: &str, cmd: &str) -> String {
+        format!("alias {} {};\n", escape(name), escape(cmd))
+    }
+
+    fn unset_alias(&self, name: &str) -> String {
+        format!("unalias {};\n", escape(name))
+    }
+}
+
+impl Display for Tcsh {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "tcsh")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_snapshot;
+    use std::path::Path;
+    use test_log::test;
+
+    use crate::test::replace_path;
+
+    use super::*;
+
+    #[test]
+    fn test_activate() {
+        unsafe {
+            std::env::remove_var("__MISE_ORIG_PATH");
+            std::env::remove_var("__MISE_DIFF");
+        }
+
+        let tcsh = Tcsh::default();
+        let exe = Path::new("/some/dir/mise");
+        let opts = ActivateOptions {
+            exe: exe.to_path_buf(),
+            flags: " --status".into(),
+            no_hook_env: false,
+            prelude: vec![],
+        };
+        assert_snapshot!(tcsh.activate(opts));
+    }
+
+    #[test]
+    fn test_set_env() {
+        assert_snapshot!(Tcsh::default().set_env("FOO", "it's 1!"));
+    }
+
+    #[test]
+    fn test_prepend_env() {
+        let tcsh = Tcsh::default();
+        assert_snapshot!(replace_path(&tcsh.prepend_env("PATH", "/some/dir:/2/dir")));
+    }
+
+    #[test]
+    fn test_unset_env() {
+        assert_snapshot!(Tcsh::default().unset_env("FOO"));
+    }
+
+    #[test]
+    fn test_deactivate() {
+        let deactivate = Tcsh::default().deactivate();
+        assert_snapshot!(replace_path(&deactivate));
+    }
+}
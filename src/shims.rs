@@ -63,6 +63,8 @@ pub async fn handle_shim() -> Result<()> {
         raw: false,
         no_deps: true, // Skip deps for shims to avoid performance impact
         fresh_env: false,
+        limits: Default::default(),
+        sandbox_profile: None,
         deny_all: false,
         deny_read: false,
         deny_write: false,
@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::fmt;
 use std::fs::File;
 use std::path::{Path, PathBuf};
@@ -307,7 +308,7 @@ pub async fn run(
     };
     let session = SshSession {
         ssh,
-        host,
+        host: Cow::Borrowed(host),
         connect_timeout: options.connect_timeout,
         control_path: control_directory
             .as_ref()
@@ -415,6 +416,265 @@ fn upload_source(session: &SshSession<'_>, tar: &Path, project: &str) -> Result<
     session.status_with_stdin(&["tar", "-xzf", "-", "-C", project], File::open(archive)?)
 }
 
+/// A host prepared for tasks that `mise run` executes remotely. The project is staged and a
+/// compatible mise provisioned once per run; each task then syncs its sources, runs through the
+/// staged mise and pulls its outputs back. The staging directory is removed when this is dropped.
+pub struct RemoteTaskHost {
+    session: SshSession<'static>,
+    tar: PathBuf,
+    root: PathBuf,
+    staging: String,
+    project: String,
+    mise: String,
+    _control_directory: Option<tempfile::TempDir>,
+}
+
+impl RemoteTaskHost {
+    /// Stages `host.source` on the host and provisions mise there.
+    pub async fn connect(host: RemoteHost, connect_timeout: u16) -> Result<Self> {
+        host.validate()?;
+        let ssh =
+            crate::file::which("ssh").ok_or_else(|| eyre!("required command 'ssh' not found"))?;
+        let tar =
+            crate::file::which("tar").ok_or_else(|| eyre!("required command 'tar' not found"))?;
+        let control_directory = if cfg!(unix) {
+            Some(tempfile::tempdir()?)
+        } else {
+            None
+        };
+        info!("staging {} on remote {}", host.source.display(), host.name);
+        let root = host.source.clone();
+        let session = SshSession {
+            ssh,
+            host: Cow::Owned(host),
+            connect_timeout,
+            control_path: control_directory
+                .as_ref()
+                .map(|directory| directory.path().join("control")),
+        };
+        let staging = session
+            .output(&["sh", "-c", staging_creation_script()])?
+            .trim()
+            .to_string();
+        validate_staging_path(&staging)?;
+        let project = format!("{staging}/project");
+        let mut remote_host = Self {
+            session,
+            tar,
+            root,
+            staging,
+            project,
+            mise: String::new(),
+            _control_directory: control_directory,
+        };
+        remote_host
+            .session
+            .status(&["mkdir", "-p", &remote_host.project], false)?;
+        upload_source(&remote_host.session, &remote_host.tar, &remote_host.project)?;
+        remote_host.mise = provision_mise(
+            &remote_host.session,
+            &remote_host.staging,
+            &remote_host.project,
+            false,
+            &mut RemoteArtifactResolver::default(),
+        )
+        .await?;
+        Ok(remote_host)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.session.host.name
+    }
+
+    /// Copies local files under the staged root to the same paths on the host.
+    pub fn upload(&self, files: &[PathBuf]) -> Result<()> {
+        let files = self.relative_paths(files);
+        if files.is_empty() {
+            return Ok(());
+        }
+        let temporary = tempfile::tempdir()?;
+        let list = temporary.path().join("files");
+        crate::file::write(
+            &list,
+            files
+                .iter()
+                .map(|file| format!("{}\n", file.display()))
+                .collect::<String>(),
+        )?;
+        let archive = temporary.path().join("sources.tar.gz");
+        let status = Command::new(&self.tar)
+            .arg("-czf")
+            .arg(&archive)
+            .arg("-C")
+            .arg(&self.root)
+            .arg("-T")
+            .arg(&list)
+            .status()?;
+        if !status.success() {
+            bail!("failed to archive task sources with {status}");
+        }
+        self.session.status_with_stdin(
+            &["tar", "-xzf", "-", "-C", &self.project],
+            File::open(archive)?,
+        )
+    }
+
+    /// The ssh command that runs `task` with `args` through the staged mise, from the host's copy
+    /// of `cwd`. Dependencies are not run remotely; the local run already scheduled them.
+    pub fn run_command(
+        &self,
+        cwd: &Path,
+        task: &str,
+        args: &[String],
+    ) -> Result<(String, Vec<String>)> {
+        let cwd = match cwd.strip_prefix(&self.root) {
+            Ok(relative) if relative.as_os_str().is_empty() => self.project.clone(),
+            Ok(relative) => format!("{}/{}", self.project, relative.display()),
+            Err(_) => bail!(
+                "task directory {} is outside the project staged on remote {}",
+                cwd.display(),
+                self.name()
+            ),
+        };
+        let mut argv = vec![
+            "env".to_string(),
+            format!("MISE_TRUSTED_CONFIG_PATHS={}", self.project),
+            "MISE_YES=1".to_string(),
+            "MISE_REMOTE_TASK=1".to_string(),
+            self.mise.clone(),
+            "--cd".to_string(),
+            cwd,
+            "run".to_string(),
+            "--quiet".to_string(),
+            "--output=interleave".to_string(),
+            "--skip-deps".to_string(),
+            "--force".to_string(),
+            task.to_string(),
+        ];
+        argv.extend(args.iter().cloned());
+        let argv = argv.iter().map(String::as_str).collect::<Vec<_>>();
+        Ok((
+            self.session.ssh.to_string_lossy().to_string(),
+            self.session.args(false, &argv),
+        ))
+    }
+
+    /// Copies files under the staged root from the host back to the same local paths. Paths that
+    /// do not exist on the host are skipped.
+    ///
+    /// `paths` are only the roots to archive: a root-level glob such as `*.o` archives the whole
+    /// project, so each extracted file is written locally only when `is_output` selects it.
+    pub fn download(&self, paths: &[PathBuf], is_output: impl Fn(&Path) -> bool) -> Result<()> {
+        let paths = self.relative_paths(paths);
+        if paths.is_empty() {
+            return Ok(());
+        }
+        let mut argv = vec!["sh", "-c", download_script(), "sh", &self.project];
+        let paths = paths
+            .iter()
+            .map(|path| format!("./{}", path.display()))
+            .collect::<Vec<_>>();
+        argv.extend(paths.iter().map(String::as_str));
+        let args = self.session.args(false, &argv);
+        info!(
+            "$ {} {}",
+            self.session.ssh.display(),
+            shell_words::join(&args)
+        );
+        let output = Command::new(&self.session.ssh).args(&args).output()?;
+        if !output.status.success() {
+            bail!(
+                "failed to download task outputs from '{}' with {}: {}",
+                self.name(),
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        if output.stdout.is_empty() {
+            return Ok(());
+        }
+        let temporary = tempfile::NamedTempFile::new()?;
+        crate::file::write(temporary.path(), &output.stdout)?;
+        let extracted = tempfile::tempdir()?;
+        let status = Command::new(&self.tar)
+            .arg("-xzf")
+            .arg(temporary.path())
+            .arg("-C")
+            .arg(extracted.path())
+            .status()?;
+        if !status.success() {
+            bail!("failed to extract task outputs from '{}'", self.name());
+        }
+        for entry in walkdir::WalkDir::new(extracted.path()).follow_links(false) {
+            let entry = entry?;
+            if entry.file_type().is_dir() {
+                continue;
+            }
+            let relative = entry.path().strip_prefix(extracted.path())?;
+            let local = self.root.join(relative);
+            if !is_output(&local) {
+                trace!(
+                    "skipping {} from remote, not a task output",
+                    local.display()
+                );
+                continue;
+            }
+            if let Some(parent) = local.parent() {
+                crate::file::create_dir_all(parent)?;
+            }
+            crate::file::move_file(entry.path(), &local)?;
+        }
+        Ok(())
+    }
+
+    fn relative_paths(&self, paths: &[PathBuf]) -> Vec<PathBuf> {
+        paths
+            .iter()
+            .filter_map(|path| match path.strip_prefix(&self.root) {
+                Ok(relative) if relative.as_os_str().is_empty() => Some(PathBuf::from(".")),
+                Ok(relative) => Some(relative.to_path_buf()),
+                Err(_) => {
+                    warn!(
+                        "{} is outside the project staged on remote {}, skipping",
+                        path.display(),
+                        self.name()
+                    );
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+impl Drop for RemoteTaskHost {
+    fn drop(&mut self) {
+        if let Err(err) = self
+            .session
+            .status(&["rm", "-rf", "--", &self.staging], false)
+        {
+            warn!(
+                "failed to clean remote staging on {}: {err:#}",
+                self.session.host.name
+            );
+        }
+    }
+}
+
+/// Archives the existing paths among `$2…` under the directory `$1` to stdout.
+fn download_script() -> &'static str {
+    r#"set -eu
+cd "$1"
+shift
+count=$#
+while [ "$count" -gt 0 ]; do
+  if [ -e "$1" ]; then set -- "$@" "$1"; fi
+  shift
+  count=$((count - 1))
+done
+[ "$#" -gt 0 ] || exit 0
+exec tar -czf - "$@""#
+}
+
 async fn provision_mise(
     session: &SshSession<'_>,
     staging: &str,
@@ -1302,7 +1562,8 @@ fn parse_libc_flavor(output: &str) -> Option<LibcFlavor> {
 
 struct SshSession<'a> {
     ssh: PathBuf,
-    host: &'a RemoteHost,
+    /// Borrowed for a one-off bootstrap, owned by a [`RemoteTaskHost`] that outlives its caller.
+    host: Cow<'a, RemoteHost>,
     connect_timeout: u16,
     control_path: Option<PathBuf>,
 }
@@ -40,10 +40,13 @@ pub(crate) fn reset() {
 
 /// Type alias for tracking failed tasks with their exit codes
 pub type FailedTasks = Arc<std::sync::Mutex<Vec<(Task, Option<i32>)>>>;
+/// Tasks that were rerun after a failure, with the attempt number of their latest run.
+pub type RetriedTasks = Arc<std::sync::Mutex<IndexMap<Task, u32>>>;
 
 mod deps;
 pub mod task_cache;
 mod task_cache_audit;
+pub mod task_cache_server;
 mod task_cache_store;
 pub mod task_confirm;
 pub mod task_context_builder;
@@ -54,11 +57,18 @@ pub mod task_file_providers;
 pub mod task_helpers;
 pub mod task_list;
 mod task_load_context;
+mod task_matrix;
 pub mod task_output;
 pub mod task_output_handler;
+pub mod task_remote_host;
+pub mod task_remote_integrity;
+pub mod task_report;
+pub mod task_results;
 pub mod task_results_display;
+mod task_retry;
 pub mod task_scheduler;
 mod task_script_parser;
+pub mod task_service;
 pub mod task_source_checker;
 pub mod task_sources;
 pub mod task_template;
@@ -70,13 +80,17 @@ pub mod workspace;
 
 pub(crate) use task_cache::TaskCacheOutput;
 pub use task_cache::{TaskArtifactCache, TaskCacheConfig, TaskCacheMode};
-pub(crate) use task_cache_audit::TaskCacheAudit;
+pub(crate) use task_cache_audit::{TaskCacheAudit, enforce_cache_contract};
 pub use task_cache_store::TaskCacheRemoteMode;
 pub use task_confirm::TaskConfirm;
 pub(crate) use task_load_context::monorepo_scope;
 pub use task_load_context::{TaskLoadContext, expand_colon_task_syntax, is_workspace_project_task};
+pub use task_matrix::TaskMatrix;
+pub(crate) use task_matrix::expand_task_matrices;
 pub use task_output::TaskOutput;
+pub use task_retry::TaskRetry;
 pub use task_script_parser::{has_any_args_defined, has_any_usage_spec};
+pub use task_service::TaskService;
 pub use task_template::TaskTemplate;
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
@@ -663,10 +677,22 @@ pub struct Task {
     pub output: Option<TaskOutput>,
     #[serde(default)]
     pub tools: IndexMap<String, TaskToolValue>,
+    /// Values to run the task with; expanded into one task per combination.
+    #[serde(default)]
+    pub matrix: TaskMatrix,
     #[serde(default)]
     pub usage: String,
     #[serde(default)]
     pub timeout: Option<String>,
+    /// Name or tag of a `[bootstrap.remote.hosts]` host to run the task on
+    #[serde(default)]
+    pub remote: Option<String>,
+    /// Rerun the task when it fails.
+    #[serde(default)]
+    pub retry: Option<TaskRetry>,
+    /// Run in the background while dependents run, and stop when the run ends.
+    #[serde(default, deserialize_with = "task_service::deserialize_service")]
+    pub service: Option<TaskService>,
 
     // normal type
     #[serde(default, deserialize_with = "deserialize_arr")]
@@ -687,6 +713,10 @@ pub struct Task {
     #[serde(default)]
     pub file: Option<PathBuf>,
 
+    /// Expected sha256 of a remote `file`, checked before the task runs
+    #[serde(default, alias = "sha256")]
+    pub integrity: Option<String>,
+
     // Store the original remote file source (git::/http:/https:) before it's replaced with local path
     // This is used to determine if the task should use monorepo config file context
     #[serde(skip)]
@@ -722,6 +752,24 @@ pub struct Task {
     /// Preserve ambient env vars when env inheritance is denied without hashing their values
     #[serde(default)]
     pub pass_through_env: Vec<String>,
+    /// Kill the task when it uses more memory than this, e.g. "2GiB"
+    #[serde(default)]
+    pub limit_memory: Option<String>,
+    /// Limit CPU time to this many CPUs
+    #[serde(default)]
+    pub limit_cpus: Option<f64>,
+    /// Limit the number of processes and threads
+    #[serde(default)]
+    pub limit_pids: Option<u64>,
+    /// Limit the number of files each process can open
+    #[serde(default)]
+    pub limit_open_files: Option<u64>,
+    /// Kill the task when it writes more than this to stdout and stderr, e.g. "10MiB"
+    #[serde(default)]
+    pub limit_output_bytes: Option<String>,
+    /// Name of a `[sandbox.profiles]` profile whose restrictions also apply to the task
+    #[serde(default)]
+    pub sandbox: Option<String>,
 
     /// Name of the task template to extend
     #[serde(default)]
@@ -1247,8 +1295,32 @@ impl Task {
                     .map_err(|e| eyre!("failed to parse cache field in task header: {e}"))
             })
             .transpose()?;
+        task.matrix = p
+            .get_raw("matrix")
+            .map(|v| {
+                TaskMatrix::deserialize(v.clone())
+                    .map_err(|e| eyre!("failed to parse matrix field in task header: {e}"))
+            })
+            .transpose()?
+            .unwrap_or_default();
+        task.retry = p
+            .get_raw("retry")
+            .map(|v| {
+                TaskRetry::deserialize(v.clone())
+                    .map_err(|e| eyre!("failed to parse retry field in task header: {e}"))
+            })
+            .transpose()?;
+        task.service = p
+            .get_raw("service")
+            .map(|v| {
+                task_service::deserialize_service(v.clone())
+                    .map_err(|e| eyre!("failed to parse service field in task header: {e}"))
+            })
+            .transpose()?
+            .flatten();
         task.file = Some(path.to_path_buf());
         task.shell = p.parse_str("shell");
+        task.remote = p.parse_str("remote");
         task.quiet = p.parse_bool("quiet").unwrap_or_default();
         task.silent = p
             .get_raw("silent")
@@ -2309,12 +2381,24 @@ impl Task {
         if other.timeout.is_some() {
             self.timeout = other.timeout;
         }
+        if other.remote.is_some() {
+            self.remote = other.remote;
+        }
+        if other.retry.is_some() {
+            self.retry = other.retry;
+        }
+        if other.service.is_some() {
+            self.service = other.service;
+        }
         if other.confirm.is_some() {
             self.confirm = other.confirm;
         }
         for (k, v) in other.tools {
             self.tools.insert(k, v);
         }
+        if !other.matrix.is_empty() {
+            self.matrix = other.matrix;
+        }
         if !other.usage.is_empty() {
             self.usage = other.usage;
         }
@@ -2329,6 +2413,24 @@ impl Task {
         self.allow_net.extend(other.allow_net);
         self.allow_env.extend(other.allow_env);
         self.pass_through_env.extend(other.pass_through_env);
+        if other.limit_memory.is_some() {
+            self.limit_memory = other.limit_memory;
+        }
+        if other.limit_cpus.is_some() {
+            self.limit_cpus = other.limit_cpus;
+        }
+        if other.limit_pids.is_some() {
+            self.limit_pids = other.limit_pids;
+        }
+        if other.limit_open_files.is_some() {
+            self.limit_open_files = other.limit_open_files;
+        }
+        if other.limit_output_bytes.is_some() {
+            self.limit_output_bytes = other.limit_output_bytes;
+        }
+        if other.sandbox.is_some() {
+            self.sandbox = other.sandbox;
+        }
     }
 
     fn has_render_templates(&self) -> bool {
@@ -2872,10 +2974,15 @@ impl Default for Task {
             run_windows: vec![],
             args: vec![],
             file: None,
+            integrity: None,
             quiet: false,
             tools: Default::default(),
+            matrix: Default::default(),
             usage: "".to_string(),
             timeout: None,
+            remote: None,
+            retry: None,
+            service: None,
             remote_file_source: None,
             deny_all: false,
             deny_read: false,
@@ -2887,6 +2994,12 @@ impl Default for Task {
             allow_net: vec![],
             allow_env: vec![],
             pass_through_env: vec![],
+            limit_memory: None,
+            limit_cpus: None,
+            limit_pids: None,
+            limit_open_files: None,
+            limit_output_bytes: None,
+            sandbox: None,
             extends: None,
             show_args_in_prefix: false,
             depends_raw: None,
@@ -3325,7 +3438,7 @@ mod tests {
     #[cfg(unix)]
     use super::TaskConfirm;
     #[cfg(unix)]
-    use super::{TaskCacheConfig, TaskOutput};
+    use super::{TaskCacheConfig, TaskOutput, TaskRetry};
     use super::{
         clear_usage_env, env_contains_key, name_from_path, tera_tag_has_usage_ref,
         tera_template_has_usage_ref,
@@ -4647,6 +4760,9 @@ echo "hello world"
 #MISE output="prefix"
 #MISE tools={node={prefix="20"}, python="3.11"}
 #MISE confirm="Are you sure?"
+#MISE retry={attempts=3, backoff="1s"}
+#MISE matrix={db=["pg", "sqlite"]}
+#MISE service={ready={port=5432}}
 echo "test"
 "#;
         fs::write(&task_file, script_content).unwrap();
@@ -4679,6 +4795,7 @@ echo "test"
             Some(TaskCacheConfig {
                 enabled: true,
                 audit: false,
+                enforce: false,
                 env: vec!["PROFILE".to_string()],
                 command_inputs: vec![],
             })
@@ -4699,6 +4816,25 @@ echo "test"
             task.confirm,
             Some(TaskConfirm::Message("Are you sure?".to_string()))
         );
+        assert_eq!(
+            task.retry,
+            Some(TaskRetry {
+                attempts: 3,
+                backoff: Some("1s".to_string()),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            task.matrix.0.get("db"),
+            Some(&vec!["pg".to_string(), "sqlite".to_string()])
+        );
+        assert_eq!(
+            task.service.and_then(|s| s.ready),
+            Some(task_service::ServiceProbe::Port {
+                host: "127.0.0.1".to_string(),
+                port: 5432
+            })
+        );
 
         let mut parsed_fields =
             take_captured_fields().expect("Parser fields should have been captured");
@@ -40,6 +40,8 @@ pub struct TaskCacheConfig {
     pub enabled: bool,
     /// Report project files read or written outside the declared cache contract.
     pub audit: bool,
+    /// Restrict the task to reading its declared sources and writing its declared outputs.
+    pub enforce: bool,
     /// Ambient environment variables whose resolved values affect the cache key.
     pub env: Vec<String>,
     /// Commands whose stdout and stderr affect the cache key.
@@ -1590,11 +1592,12 @@ mod tests {
     #[test]
     fn config_deserializes_and_rejects_unknown_fields() {
         let config: TaskCacheConfig = toml::from_str(
-            "enabled = true\naudit = true\nenv = ['PROFILE']\ncommand_inputs = ['node --version']",
+            "enabled = true\naudit = true\nenforce = true\nenv = ['PROFILE']\ncommand_inputs = ['node --version']",
         )
         .unwrap();
         assert!(config.enabled);
         assert!(config.audit);
+        assert!(config.enforce);
         assert_eq!(config.env, ["PROFILE"]);
         assert_eq!(config.command_inputs, ["node --version"]);
         assert!(toml::from_str::<TaskCacheConfig>("remote = true").is_err());
@@ -1,12 +1,14 @@
 use crate::config::Config;
 #[cfg(target_os = "linux")]
 use crate::file;
+use crate::sandbox::SandboxConfig;
 use crate::task::Task;
 #[cfg(target_os = "linux")]
 use crate::task::task_source_checker::{
-    build_output_matcher, build_source_matcher, task_cwd, task_source_match_root,
+    build_output_matcher, build_source_matcher, output_write_dirs, task_cwd, task_source_files,
+    task_source_match_root,
 };
-use eyre::Result;
+use eyre::{Result, bail};
 use ignore::overrides::Override;
 use std::collections::BTreeSet;
 use std::ffi::OsString;
@@ -30,6 +32,15 @@ enum AccessKind {
     Write,
 }
 
+impl std::fmt::Display for AccessKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            AccessKind::Read => "read",
+            AccessKind::Write => "write",
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct TraceAccess {
     kind: AccessKind,
@@ -40,6 +51,10 @@ struct TraceAccess {
 pub(crate) struct TaskCacheAudit {
     #[cfg(target_os = "linux")]
     strace: PathBuf,
+    /// Warn about undeclared accesses that succeeded.
+    audit: bool,
+    /// Fail on accesses that the `cache.enforce` sandbox denied.
+    enforce: bool,
     trace: NamedTempFile,
     root: PathBuf,
     source_root: PathBuf,
@@ -50,22 +65,25 @@ pub(crate) struct TaskCacheAudit {
 
 impl TaskCacheAudit {
     pub(crate) async fn prepare(task: &Task, config: &Arc<Config>) -> Result<Option<Self>> {
-        if !task
+        let Some(cache) = task
             .cache
             .as_ref()
-            .is_some_and(|cache| cache.enabled && cache.audit)
-        {
+            .filter(|cache| cache.enabled && (cache.audit || cache.enforce))
+        else {
             return Ok(None);
-        }
+        };
         #[cfg(not(target_os = "linux"))]
         {
-            let _ = (task, config);
-            warn_once!("task cache audit is currently supported only on Linux with strace");
+            let _ = config;
+            if cache.audit {
+                warn_once!("task cache audit is currently supported only on Linux with strace");
+            }
             Ok(None)
         }
         #[cfg(target_os = "linux")]
         {
             let Some(strace) = usable_strace().await else {
+                strace_unavailable(&task.name, cache.enforce)?;
                 return Ok(None);
             };
             let root = task_cwd(task, config).await?;
@@ -85,6 +103,8 @@ impl TaskCacheAudit {
                 .collect();
             Ok(Some(Self {
                 strace,
+                audit: cache.audit,
+                enforce: cache.enforce,
                 trace: NamedTempFile::new()?,
                 root,
                 source_root,
@@ -119,7 +139,11 @@ impl TaskCacheAudit {
         (program, args.to_vec())
     }
 
-    pub(crate) fn report(&self, task: &Task) {
+    /// Warns about undeclared accesses when auditing, and fails when the `cache.enforce`
+    /// sandbox denied any access or the task wrote an undeclared path. Writes are checked too
+    /// because the sandbox allows writing anywhere in an output's directory, which for an output
+    /// like `app` or `*.o` is the task root.
+    pub(crate) fn report(&self, task: &Task) -> Result<()> {
         let trace = match fs::read_to_string(self.trace.path()) {
             Ok(trace) => trace,
             Err(err) => {
@@ -127,20 +151,50 @@ impl TaskCacheAudit {
                     "task {} cache audit could not read its trace: {err}",
                     task.name
                 );
-                return;
+                return Ok(());
             }
         };
+        let undeclared = self.undeclared(&trace);
+        if self.audit {
+            report_undeclared(task, &undeclared);
+        }
+        if self.enforce {
+            let violations = trace
+                .lines()
+                .flat_map(parse_denied_trace_line)
+                .map(|access| {
+                    let path = self.resolve(access.path, access.base);
+                    let display_path = path.strip_prefix(&self.root).unwrap_or(&path);
+                    (access.kind, display_path.to_path_buf())
+                })
+                .chain(
+                    undeclared
+                        .into_iter()
+                        .filter(|(kind, _)| *kind == AccessKind::Write),
+                )
+                .collect::<BTreeSet<_>>();
+            if let Some((kind, path)) = violations.iter().next() {
+                bail!(
+                    "task {} violated its cache contract: undeclared {kind} of {}{}",
+                    task.name,
+                    path.display(),
+                    match violations.len() {
+                        1 => String::new(),
+                        n => format!(" and {} more paths", n - 1),
+                    }
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Accesses that succeeded but are not covered by the task's sources or outputs, relative to
+    /// the task root.
+    fn undeclared(&self, trace: &str) -> BTreeSet<(AccessKind, PathBuf)> {
         let mut undeclared = BTreeSet::new();
         for line in trace.lines() {
             for access in parse_trace_line(line) {
-                let path = lexical_normalize(&if access.path.is_absolute() {
-                    access.path
-                } else {
-                    access
-                        .base
-                        .unwrap_or_else(|| self.root.clone())
-                        .join(access.path)
-                });
+                let path = self.resolve(access.path, access.base);
                 let scope_root = if access.kind == AccessKind::Read {
                     &self.source_root
                 } else {
@@ -159,25 +213,15 @@ impl TaskCacheAudit {
                 undeclared.insert((access.kind, display_path.to_path_buf()));
             }
         }
-        let total = undeclared.len();
-        for (kind, path) in undeclared.into_iter().take(MAX_REPORTED_PATHS) {
-            let kind = match kind {
-                AccessKind::Read => "read",
-                AccessKind::Write => "write",
-            };
-            warn!(
-                "task {} cache audit detected undeclared {kind}: {}",
-                task.name,
-                path.display()
-            );
-        }
-        if total > MAX_REPORTED_PATHS {
-            warn!(
-                "task {} cache audit omitted {} additional paths",
-                task.name,
-                total - MAX_REPORTED_PATHS
-            );
-        }
+        undeclared
+    }
+
+    fn resolve(&self, path: PathBuf, base: Option<PathBuf>) -> PathBuf {
+        lexical_normalize(&if path.is_absolute() {
+            path
+        } else {
+            base.unwrap_or_else(|| self.root.clone()).join(path)
+        })
     }
 
     fn is_declared(&self, kind: AccessKind, path: &Path) -> bool {
@@ -196,12 +240,74 @@ impl TaskCacheAudit {
     }
 }
 
+fn report_undeclared(task: &Task, undeclared: &BTreeSet<(AccessKind, PathBuf)>) {
+    for (kind, path) in undeclared.iter().take(MAX_REPORTED_PATHS) {
+        warn!(
+            "task {} cache audit detected undeclared {kind}: {}",
+            task.name,
+            path.display()
+        );
+    }
+    if undeclared.len() > MAX_REPORTED_PATHS {
+        warn!(
+            "task {} cache audit omitted {} additional paths",
+            task.name,
+            undeclared.len() - MAX_REPORTED_PATHS
+        );
+    }
+}
+
+/// Sandboxes a task with `cache.enforce` so it can only read its declared sources and write its
+/// declared outputs and the temp directory, besides the system and toolchain paths the sandbox
+/// always allows.
+pub(crate) async fn enforce_cache_contract(
+    task: &Task,
+    config: &Arc<Config>,
+    sandbox: &mut SandboxConfig,
+) -> Result<()> {
+    if !task
+        .cache
+        .as_ref()
+        .is_some_and(|cache| cache.enabled && cache.enforce)
+    {
+        return Ok(());
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = (config, sandbox);
+        warn_once!("task cache enforcement is currently supported only on Linux with landlock");
+        Ok(())
+    }
+    #[cfg(target_os = "linux")]
+    {
+        let root = task_cwd(task, config).await?;
+        sandbox.deny_read = true;
+        sandbox.deny_write = true;
+        sandbox
+            .allow_read
+            .extend(task_source_files(task, config).await?);
+        sandbox.allow_read.extend(task.file.clone());
+        // tasks may call back into mise
+        sandbox.allow_read.extend(std::env::current_exe().ok());
+        sandbox
+            .allow_list_dir
+            .push(task_source_match_root(&root, config));
+        sandbox.allow_write.push(std::env::temp_dir());
+        // landlock rules need existing paths, so output directories are created upfront
+        for dir in output_write_dirs(&root, &task.outputs.patterns()) {
+            file::create_dir_all(&dir)?;
+            sandbox.allow_write.push(dir);
+        }
+        Ok(())
+    }
+}
+
 #[cfg(target_os = "linux")]
 async fn usable_strace() -> Option<PathBuf> {
     STRACE
         .get_or_init(|| async {
             let Some(strace) = file::which("strace") else {
-                warn_once!("task cache audit requires strace; running without filesystem auditing");
+                debug!("strace not found on PATH");
                 return None;
             };
             let status = tokio::process::Command::new(&strace)
@@ -212,9 +318,7 @@ async fn usable_strace() -> Option<PathBuf> {
                 .status()
                 .await;
             if !status.is_ok_and(|status| status.success()) {
-                warn_once!(
-                    "task cache audit could not start strace; running without filesystem auditing"
-                );
+                debug!("{} could not trace a test process", strace.display());
                 return None;
             }
             Some(strace)
@@ -223,6 +327,19 @@ async fn usable_strace() -> Option<PathBuf> {
         .clone()
 }
 
+/// Without strace, a denied access that the task tolerates goes unnoticed and its result would
+/// be cached, so `cache.enforce` fails up front instead of silently losing that guarantee.
+#[cfg(target_os = "linux")]
+fn strace_unavailable(task: &str, enforce: bool) -> Result<()> {
+    if enforce {
+        bail!(
+            "task {task} sets cache.enforce, which needs a working strace on PATH to detect denied accesses; install strace or remove cache.enforce"
+        );
+    }
+    warn_once!("task cache audit requires a working strace; running without filesystem auditing");
+    Ok(())
+}
+
 fn matches_override(matcher: &Override, path: &Path) -> bool {
     matcher.matched(path, false).is_whitelist() || matcher.matched(path, true).is_whitelist()
 }
@@ -231,6 +348,18 @@ fn parse_trace_line(line: &str) -> Vec<TraceAccess> {
     if line.contains(" = -1 ") || line.ends_with(" = -1") {
         return Vec::new();
     }
+    parse_trace_accesses(line)
+}
+
+/// Accesses that failed with `EACCES`, which is how landlock denies them.
+fn parse_denied_trace_line(line: &str) -> Vec<TraceAccess> {
+    if !line.contains(" = -1 EACCES") {
+        return Vec::new();
+    }
+    parse_trace_accesses(line)
+}
+
+fn parse_trace_accesses(line: &str) -> Vec<TraceAccess> {
     let Some(open) = line.find('(') else {
         return Vec::new();
     };
@@ -383,9 +512,63 @@ fn lexical_normalize(path: &Path) -> PathBuf {
 
 #[cfg(test)]
 mod tests {
-    use super::{AccessKind, TraceAccess, parse_trace_line, quoted_strings};
+    use super::{
+        AccessKind, TraceAccess, parse_denied_trace_line, parse_trace_line, quoted_strings,
+    };
     use std::path::PathBuf;
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn enforce_without_strace_fails() {
+        let err = super::strace_unavailable("build", true).unwrap_err();
+        assert!(err.to_string().contains("task build sets cache.enforce"));
+        assert!(err.to_string().contains("install strace"));
+        assert!(super::strace_unavailable("build", false).is_ok());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn enforce_fails_on_undeclared_writes() {
+        use crate::task::Task;
+        use crate::task::task_source_checker::build_output_matcher;
+        use ignore::overrides::OverrideBuilder;
+        use std::io::Write;
+
+        let root = PathBuf::from("/workspace/app");
+        let audit = |trace: &str| {
+            let mut file = tempfile::NamedTempFile::new().unwrap();
+            file.write_all(trace.as_bytes()).unwrap();
+            super::TaskCacheAudit {
+                strace: PathBuf::from("strace"),
+                audit: false,
+                enforce: true,
+                trace: file,
+                root: root.clone(),
+                source_root: root.clone(),
+                sources: OverrideBuilder::new(&root).build().unwrap(),
+                outputs: build_output_matcher(&root, &["app".to_string()]).unwrap(),
+                config_sources: Default::default(),
+            }
+        };
+        let task = Task {
+            name: "build".to_string(),
+            ..Default::default()
+        };
+        let declared =
+            r#"openat(AT_FDCWD</workspace/app>, "app", O_WRONLY|O_CREAT|O_TRUNC, 0666) = 3"#;
+        assert!(audit(declared).report(&task).is_ok());
+
+        let undeclared = format!(
+            "{declared}\n{}",
+            r#"openat(AT_FDCWD</workspace/app>, "notes.txt", O_WRONLY|O_CREAT, 0666) = 4"#
+        );
+        let err = audit(&undeclared).report(&task).unwrap_err();
+        assert!(
+            err.to_string().contains("undeclared write of notes.txt"),
+            "{err}"
+        );
+    }
+
     #[test]
     fn parses_strace_file_accesses() {
         assert_eq!(
@@ -432,6 +615,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parses_accesses_denied_by_the_sandbox() {
+        assert_eq!(
+            parse_denied_trace_line(
+                r#"123 openat(AT_FDCWD, "secret.txt", O_RDONLY) = -1 EACCES (Permission denied)"#
+            ),
+            vec![TraceAccess {
+                kind: AccessKind::Read,
+                path: PathBuf::from("secret.txt"),
+                base: None,
+            }]
+        );
+        assert!(
+            parse_denied_trace_line(
+                r#"openat(AT_FDCWD, "missing", O_RDONLY) = -1 ENOENT (No such file or directory)"#
+            )
+            .is_empty()
+        );
+        assert!(
+            parse_denied_trace_line(r#"openat(AT_FDCWD, "input.txt", O_RDONLY) = 3"#).is_empty()
+        );
+    }
+
     #[test]
     fn parses_escaped_quoted_paths() {
         assert_eq!(quoted_strings(r#"AT_FDCWD, "a\"b", O_RDONLY"#), ["a\"b"]);
@@ -0,0 +1,966 @@
+//! Reference server for the remote task cache protocol.
+//!
+//! `mise task-cache serve` stores CAS blobs and action results in a local directory so a team can
+//! share task outputs without deploying a separate cache service. Every namespace gets its own
+//! store, uploaded blobs are verified against their digest before they are published, and an
+//! action result is only readable while every object it references is present. Reading a result
+//! refreshes the access time of everything it references, so least-recently-used eviction removes
+//! whole results rather than pieces of them.
+use std::collections::BTreeSet;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+
+use eyre::{Result, bail};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::file;
+use crate::file::display_path;
+use crate::http_server::{Request, read_request};
+use crate::task::task_cache_store::{
+    CacheDigest, REMOTE_CACHE_ACTION_RESULT_MEDIA_TYPE, REMOTE_CACHE_BLOB_MEDIA_TYPE,
+    REMOTE_CACHE_NAMESPACE_HEADER, REMOTE_CACHE_PROTOCOL_HEADER, REMOTE_CACHE_PROTOCOL_VERSION,
+    RemoteActionResult, RemoteActionResultEnvelope, RemoteDirectory,
+};
+
+const MAX_JSON_BYTES: u64 = 4 * 1024 * 1024;
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+const STALE_UPLOAD_AGE: Duration = Duration::from_secs(60 * 60);
+const JSON_MEDIA_TYPE: &str = "application/json";
+
+/// How `mise task-cache serve` stores and protects cache entries.
+pub struct TaskCacheServerOptions {
+    pub dir: PathBuf,
+    /// Bearer tokens that may use the server. Anyone may read and write when this is empty.
+    pub grants: Vec<TokenGrant>,
+    pub read_only: bool,
+    pub max_size: Option<u64>,
+    pub max_age: Option<Duration>,
+}
+
+/// Access to one namespace, or to every namespace, for a bearer token.
+#[derive(Debug, Clone)]
+pub struct TokenGrant {
+    token: blake3::Hash,
+    namespace: Option<String>,
+    write: bool,
+}
+
+impl TokenGrant {
+    pub fn new(token: &str, namespace: Option<String>, write: bool) -> Self {
+        Self {
+            token: blake3::hash(token.trim().as_bytes()),
+            namespace,
+            write,
+        }
+    }
+
+    /// Parses one `<namespace> <read|write> <token>` grant per line, where `*` is every namespace.
+    pub fn parse_file(content: &str) -> Result<Vec<Self>> {
+        let mut grants = Vec::new();
+        for (i, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let parts = line.split_whitespace().collect::<Vec<_>>();
+            let [namespace, access, token] = parts[..] else {
+                bail!(
+                    "line {}: expected `<namespace> <read|write> <token>`",
+                    i + 1
+                );
+            };
+            let write = match access {
+                "read" => false,
+                "write" => true,
+                _ => bail!(
+                    "line {}: unknown access {access:?}, expected read or write",
+                    i + 1
+                ),
+            };
+            let namespace = (namespace != "*").then(|| namespace.to_string());
+            grants.push(Self::new(token, namespace, write));
+        }
+        Ok(grants)
+    }
+}
+
+#[derive(Default)]
+struct Counters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    blobs_stored: AtomicU64,
+    blobs_rejected: AtomicU64,
+    action_results_stored: AtomicU64,
+    evicted: AtomicU64,
+}
+
+struct Server {
+    options: TaskCacheServerOptions,
+    counters: Counters,
+}
+
+/// Serves the task cache in `options.dir` until the process is stopped.
+pub async fn serve(listener: TcpListener, options: TaskCacheServerOptions) -> Result<()> {
+    file::create_dir_all(options.dir.join("tmp"))?;
+    let server = Arc::new(Server {
+        options,
+        counters: Counters::default(),
+    });
+    let sweeper = server.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SWEEP_INTERVAL);
+        loop {
+            interval.tick().await;
+            let server = sweeper.clone();
+            match tokio::task::spawn_blocking(move || server.sweep()).await {
+                Ok(Ok(())) => {}
+                Ok(Err(err)) => warn!("task cache eviction failed: {err:#}"),
+                Err(err) => warn!("task cache eviction failed: {err}"),
+            }
+        }
+    });
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let server = server.clone();
+        tokio::spawn(async move {
+            if let Err(err) = server.handle_connection(stream, peer).await {
+                debug!("task cache: {peer}: {err:#}");
+            }
+        });
+    }
+}
+
+enum Body {
+    Bytes(&'static str, Vec<u8>),
+    File(tokio::fs::File, u64),
+}
+
+struct Response {
+    status: StatusCode,
+    body: Body,
+}
+
+impl Response {
+    fn text(status: StatusCode, text: &str) -> Self {
+        Self {
+            status,
+            body: Body::Bytes("text/plain", text.as_bytes().to_vec()),
+        }
+    }
+
+    fn json<T: Serialize>(media_type: &'static str, value: &T) -> Result<Self> {
+        Ok(Self {
+            status: StatusCode::OK,
+            body: Body::Bytes(media_type, serde_json::to_vec(value)?),
+        })
+    }
+
+    async fn write(self, stream: &mut TcpStream, head_only: bool) -> Result<()> {
+        let (content_type, len) = match &self.body {
+            Body::Bytes(content_type, bytes) => (*content_type, bytes.len() as u64),
+            Body::File(_, len) => (REMOTE_CACHE_BLOB_MEDIA_TYPE, *len),
+        };
+        let head = format!(
+            "HTTP/1.1 {}\r\n{REMOTE_CACHE_PROTOCOL_HEADER}: {REMOTE_CACHE_PROTOCOL_VERSION}\r\ncontent-type: {content_type}\r\ncontent-length: {len}\r\nconnection: close\r\n\r\n",
+            self.status
+        );
+        stream.write_all(head.as_bytes()).await?;
+        if !head_only {
+            match self.body {
+                Body::Bytes(_, bytes) => stream.write_all(&bytes).await?,
+                Body::File(mut file, _) => {
+                    tokio::io::copy(&mut file, stream).await?;
+                }
+            }
+        }
+        stream.flush().await?;
+        Ok(())
+    }
+}
+
+/// Which namespaces an authorized request may see.
+enum Access {
+    All,
+    Namespaces(BTreeSet<String>),
+}
+
+#[derive(Serialize, Deserialize)]
+struct Digests {
+    digests: Vec<CacheDigest>,
+}
+
+#[derive(Serialize)]
+struct Missing {
+    missing: Vec<CacheDigest>,
+}
+
+#[derive(Serialize)]
+struct NamespaceStats {
+    namespace: String,
+    action_results: u64,
+    blobs: u64,
+    bytes: u64,
+}
+
+#[derive(Serialize)]
+struct Stats {
+    namespaces: Vec<NamespaceStats>,
+    hits: u64,
+    misses: u64,
+    blobs_stored: u64,
+    blobs_rejected: u64,
+    action_results_stored: u64,
+    evicted: u64,
+    max_size: Option<u64>,
+    max_age_seconds: Option<u64>,
+}
+
+impl Server {
+    async fn handle_connection(&self, stream: TcpStream, peer: SocketAddr) -> Result<()> {
+        let mut reader = BufReader::new(stream);
+        let request = read_request(&mut reader).await?;
+        debug!("task cache: {peer} {} {}", request.method, request.path);
+        let head_only = request.method == "HEAD";
+        let response = match self.handle(&request, &mut reader).await {
+            Ok(response) => response,
+            Err(err) => {
+                warn!(
+                    "task cache: {peer} {} {}: {err:#}",
+                    request.method, request.path
+                );
+                Response::text(StatusCode::INTERNAL_SERVER_ERROR, "internal error\n")
+            }
+        };
+        response.write(reader.get_mut(), head_only).await
+    }
+
+    async fn handle(
+        &self,
+        request: &Request,
+        reader: &mut BufReader<TcpStream>,
+    ) -> Result<Response> {
+        let method = request.method.as_str();
+        let path = request.path.as_str();
+        match (method, path) {
+            ("GET", "/") => return Ok(Response::text(StatusCode::OK, "mise task cache\n")),
+            ("GET", "/v1/status") => {
+                return Response::json(JSON_MEDIA_TYPE, &serde_json::json!({ "status": "ok" }));
+            }
+            ("GET", "/v1/capabilities") => {
+                return Response::json(JSON_MEDIA_TYPE, &self.capabilities());
+            }
+            ("GET", "/stats") => {
+                return match self.authorize(request, None, false) {
+                    Ok(access) => {
+                        let dir = self.options.dir.clone();
+                        let namespaces =
+                            tokio::task::spawn_blocking(move || namespace_stats(&dir, &access))
+                                .await??;
+                        Response::json(JSON_MEDIA_TYPE, &self.stats(namespaces))
+                    }
+                    Err(status) => Ok(Response::text(status, "")),
+                };
+            }
+            _ => {}
+        }
+        let Some(rest) = path.strip_prefix("/v1/") else {
+            return Ok(Response::text(StatusCode::NOT_FOUND, "not found\n"));
+        };
+        if request
+            .header(REMOTE_CACHE_PROTOCOL_HEADER)
+            .is_some_and(|version| version != REMOTE_CACHE_PROTOCOL_VERSION.to_string())
+        {
+            return Ok(Response::text(StatusCode::UPGRADE_REQUIRED, ""));
+        }
+        let Some(namespace) = request
+            .header(REMOTE_CACHE_NAMESPACE_HEADER)
+            .filter(|namespace| !namespace.is_empty())
+        else {
+            return Ok(Response::text(
+                StatusCode::BAD_REQUEST,
+                "missing Mise-Cache-Namespace header\n",
+            ));
+        };
+        let write = method == "PUT";
+        if let Err(status) = self.authorize(request, Some(namespace), write) {
+            return Ok(Response::text(status, ""));
+        }
+        let store = self.namespace_dir(namespace);
+        if rest == "blobs:missing" {
+            return match method {
+                "POST" => self.find_missing(request, reader, &store).await,
+                _ => Ok(Response::text(StatusCode::METHOD_NOT_ALLOWED, "")),
+            };
+        }
+        if let Some(digest) = rest.strip_prefix("blobs/").and_then(parse_digest) {
+            return match method {
+                "GET" | "HEAD" => self.get_blob(&store, &digest).await,
+                "PUT" => {
+                    self.put_blob(request, reader, &store, namespace, digest)
+                        .await
+                }
+                _ => Ok(Response::text(StatusCode::METHOD_NOT_ALLOWED, "")),
+            };
+        }
+        if let Some(digest) = rest.strip_prefix("action-results/").and_then(parse_digest) {
+            return match method {
+                "GET" | "HEAD" => self.get_action_result(&store, &digest).await,
+                "PUT" => {
+                    self.put_action_result(request, reader, &store, namespace, &digest)
+                        .await
+                }
+                _ => Ok(Response::text(StatusCode::METHOD_NOT_ALLOWED, "")),
+            };
+        }
+        Ok(Response::text(StatusCode::NOT_FOUND, "not found\n"))
+    }
+
+    fn authorize(
+        &self,
+        request: &Request,
+        namespace: Option<&str>,
+        write: bool,
+    ) -> std::result::Result<Access, StatusCode> {
+        if write && self.options.read_only {
+            return Err(StatusCode::FORBIDDEN);
+        }
+        if self.options.grants.is_empty() {
+            return Ok(Access::All);
+        }
+        let Some(token) = request
+            .header("authorization")
+            .and_then(|value| value.strip_prefix("Bearer "))
+        else {
+            return Err(StatusCode::UNAUTHORIZED);
+        };
+        let token = blake3::hash(token.trim().as_bytes());
+        let grants = self
+            .options
+            .grants
+            .iter()
+            .filter(|grant| grant.token == token)
+            .filter(|grant| grant.write || !write)
+            .collect::<Vec<_>>();
+        if grants.is_empty() {
+            let known = self.options.grants.iter().any(|grant| grant.token == token);
+            return Err(if known {
+                StatusCode::FORBIDDEN
+            } else {
+                StatusCode::UNAUTHORIZED
+            });
+        }
+        if grants.iter().any(|grant| grant.namespace.is_none()) {
+            return Ok(Access::All);
+        }
+        let namespaces = grants
+            .into_iter()
+            .filter_map(|grant| grant.namespace.clone())
+            .collect::<BTreeSet<_>>();
+        match namespace {
+            Some(namespace) if !namespaces.contains(namespace) => Err(StatusCode::FORBIDDEN),
+            _ => Ok(Access::Namespaces(namespaces)),
+        }
+    }
+
+    fn namespace_dir(&self, namespace: &str) -> PathBuf {
+        namespace_dir(&self.options.dir, namespace)
+    }
+
+    fn capabilities(&self) -> serde_json::Value {
+        serde_json::json!({
+            "protocol": { "major": REMOTE_CACHE_PROTOCOL_VERSION, "minor": 0 },
+            "digest_algorithms": ["blake3", "sha256"],
+            "compressors": ["identity"],
+            "features": {
+                "batch": false,
+                "resumable_uploads": false,
+                "delegated_transfers": false,
+                "signed_results": [],
+            },
+            "limits": {
+                "max_blob_bytes": self.options.max_size,
+            },
+        })
+    }
+
+    fn stats(&self, namespaces: Vec<NamespaceStats>) -> Stats {
+        let counters = &self.counters;
+        Stats {
+            namespaces,
+            hits: counters.hits.load(Ordering::Relaxed),
+            misses: counters.misses.load(Ordering::Relaxed),
+            blobs_stored: counters.blobs_stored.load(Ordering::Relaxed),
+            blobs_rejected: counters.blobs_rejected.load(Ordering::Relaxed),
+            action_results_stored: counters.action_results_stored.load(Ordering::Relaxed),
+            evicted: counters.evicted.load(Ordering::Relaxed),
+            max_size: self.options.max_size,
+            max_age_seconds: self.options.max_age.map(|age| age.as_secs()),
+        }
+    }
+
+    async fn find_missing(
+        &self,
+        request: &Request,
+        reader: &mut BufReader<TcpStream>,
+        store: &Path,
+    ) -> Result<Response> {
+        let body = match read_json_body(request, reader).await? {
+            Ok(body) => body,
+            Err(response) => return Ok(response),
+        };
+        let Ok(Digests { digests }) = serde_json::from_slice(&body) else {
+            return Ok(Response::text(StatusCode::BAD_REQUEST, "invalid digests\n"));
+        };
+        if digests.iter().any(|digest| digest.validate().is_err()) {
+            return Ok(Response::text(StatusCode::BAD_REQUEST, "invalid digest\n"));
+        }
+        let missing = digests
+            .into_iter()
+            .filter(|digest| !blob_path(store, digest).is_file())
+            .collect();
+        Response::json(JSON_MEDIA_TYPE, &Missing { missing })
+    }
+
+    async fn get_blob(&self, store: &Path, digest: &CacheDigest) -> Result<Response> {
+        let path = blob_path(store, digest);
+        let Ok(file) = tokio::fs::File::open(&path).await else {
+            return Ok(Response::text(StatusCode::NOT_FOUND, "not found\n"));
+        };
+        touch(&path);
+        let len = file.metadata().await?.len();
+        Ok(Response {
+            status: StatusCode::OK,
+            body: Body::File(file, len),
+        })
+    }
+
+    async fn put_blob(
+        &self,
+        request: &Request,
+        reader: &mut BufReader<TcpStream>,
+        store: &Path,
+        namespace: &str,
+        digest: CacheDigest,
+    ) -> Result<Response> {
+        let Some(len) = request.content_length() else {
+            return Ok(Response::text(StatusCode::LENGTH_REQUIRED, ""));
+        };
+        if len != digest.size {
+            return Ok(Response::text(
+                StatusCode::BAD_REQUEST,
+                "content length does not match digest\n",
+            ));
+        }
+        if self.options.max_size.is_some_and(|max| len > max) {
+            return Ok(Response::text(StatusCode::PAYLOAD_TOO_LARGE, ""));
+        }
+        let path = blob_path(store, &digest);
+        if path.is_file() {
+            // Drain the body so the client sees the response rather than a reset connection.
+            tokio::io::copy(&mut (&mut *reader).take(len), &mut tokio::io::sink()).await?;
+            touch(&path);
+            return Ok(Response::text(StatusCode::NO_CONTENT, ""));
+        }
+        let temp_file = tempfile::NamedTempFile::new_in(self.options.dir.join("tmp"))?;
+        let mut out = tokio::fs::File::from_std(temp_file.reopen()?);
+        let copied = tokio::io::copy(&mut (&mut *reader).take(len), &mut out).await?;
+        out.shutdown().await?;
+        drop(out);
+        if copied != len {
+            bail!("upload ended after {copied} of {len} bytes");
+        }
+        let verified = tokio::task::spawn_blocking(move || {
+            digest
+                .matches_file(temp_file.path())
+                .map(|matches| matches.then_some(temp_file))
+        })
+        .await??;
+        let Some(temp_file) = verified else {
+            self.counters.blobs_rejected.fetch_add(1, Ordering::Relaxed);
+            warn!("rejected blob upload to namespace {namespace}: digest mismatch");
+            return Ok(Response::text(
+                StatusCode::BAD_REQUEST,
+                "content does not match digest\n",
+            ));
+        };
+        write_namespace_name(store, namespace)?;
+        file::create_dir_all(path.parent().unwrap())?;
+        temp_file.persist(&path)?;
+        self.counters.blobs_stored.fetch_add(1, Ordering::Relaxed);
+        trace!("stored {}", display_path(&path));
+        Ok(Response::text(StatusCode::CREATED, ""))
+    }
+
+    async fn get_action_result(&self, store: &Path, digest: &CacheDigest) -> Result<Response> {
+        let path = action_result_path(store, digest);
+        let Ok(bytes) = tokio::fs::read(&path).await else {
+            self.counters.misses.fetch_add(1, Ordering::Relaxed);
+            return Ok(Response::text(StatusCode::NOT_FOUND, "not found\n"));
+        };
+        let envelope: RemoteActionResultEnvelope = serde_json::from_slice(&bytes)?;
+        let store_dir = store.to_path_buf();
+        let reachable =
+            tokio::task::spawn_blocking(move || reachable_blobs(&store_dir, &envelope.result))
+                .await??;
+        let Some(reachable) = reachable else {
+            // Eviction removed part of this result, so it can no longer be restored.
+            debug!("removing incomplete action result {}", display_path(&path));
+            let _ = std::fs::remove_file(&path);
+            self.counters.misses.fetch_add(1, Ordering::Relaxed);
+            return Ok(Response::text(StatusCode::NOT_FOUND, "not found\n"));
+        };
+        touch(&path);
+        for blob in &reachable {
+            touch(blob);
+        }
+        self.counters.hits.fetch_add(1, Ordering::Relaxed);
+        Ok(Response {
+            status: StatusCode::OK,
+            body: Body::Bytes(REMOTE_CACHE_ACTION_RESULT_MEDIA_TYPE, bytes),
+        })
+    }
+
+    async fn put_action_result(
+        &self,
+        request: &Request,
+        reader: &mut BufReader<TcpStream>,
+        store: &Path,
+        namespace: &str,
+        digest: &CacheDigest,
+    ) -> Result<Response> {
+        let body = match read_json_body(request, reader).await? {
+            Ok(body) => body,
+            Err(response) => return Ok(response),
+        };
+        let Ok(envelope) = serde_json::from_slice::<RemoteActionResultEnvelope>(&body) else {
+            return Ok(Response::text(
+                StatusCode::BAD_REQUEST,
+                "invalid action result\n",
+            ));
+        };
+        if envelope.result.version != 1 || &envelope.result.action != digest {
+            return Ok(Response::text(
+                StatusCode::BAD_REQUEST,
+                "action result does not match its URL\n",
+            ));
+        }
+        let store_dir = store.to_path_buf();
+        let reachable =
+            tokio::task::spawn_blocking(move || reachable_blobs(&store_dir, &envelope.result))
+                .await??;
+        if reachable.is_none() {
+            return Ok(Response::text(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "action result references missing objects\n",
+            ));
+        }
+        let path = action_result_path(store, digest);
+        write_namespace_name(store, namespace)?;
+        file::create_dir_all(path.parent().unwrap())?;
+        let mut temp_file = tempfile::NamedTempFile::new_in(self.options.dir.join("tmp"))?;
+        std::io::Write::write_all(&mut temp_file, &body)?;
+        if temp_file.persist_noclobber(&path).is_err() {
+            // Only the first commit of an action wins; repeating it is not an error.
+            return Ok(match std::fs::read(&path) {
+                Ok(existing) if existing == body => Response::text(StatusCode::NO_CONTENT, ""),
+                _ => Response::text(StatusCode::CONFLICT, "action result already exists\n"),
+            });
+        }
+        self.counters
+            .action_results_stored
+            .fetch_add(1, Ordering::Relaxed);
+        trace!("stored {}", display_path(&path));
+        Ok(Response::text(StatusCode::CREATED, ""))
+    }
+
+    /// Removes abandoned uploads and evicts least-recently-used entries over the size and age limits.
+    fn sweep(&self) -> Result<()> {
+        for entry in std::fs::read_dir(self.options.dir.join("tmp"))? {
+            let path = entry?.path();
+            if modified(&path).elapsed().unwrap_or_default() > STALE_UPLOAD_AGE {
+                let _ = std::fs::remove_file(&path);
+            }
+        }
+        let evicted = evict(
+            &self.options.dir,
+            self.options.max_size,
+            self.options.max_age,
+        )?;
+        if evicted > 0 {
+            debug!("evicted {evicted} task cache entries");
+            self.counters.evicted.fetch_add(evicted, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+}
+
+/// Reads a JSON request body, or the response that rejects it.
+async fn read_json_body(
+    request: &Request,
+    reader: &mut BufReader<TcpStream>,
+) -> Result<std::result::Result<Vec<u8>, Response>> {
+    let Some(len) = request.content_length() else {
+        return Ok(Err(Response::text(StatusCode::LENGTH_REQUIRED, "")));
+    };
+    if len > MAX_JSON_BYTES {
+        return Ok(Err(Response::text(StatusCode::PAYLOAD_TOO_LARGE, "")));
+    }
+    let mut body = Vec::with_capacity(len as usize);
+    (&mut *reader).take(len).read_to_end(&mut body).await?;
+    if body.len() as u64 != len {
+        bail!("request body ended after {} of {len} bytes", body.len());
+    }
+    Ok(Ok(body))
+}
+
+/// Parses `{algorithm}/{hash}/{size}` from a blob or action-result URL.
+fn parse_digest(path: &str) -> Option<CacheDigest> {
+    let mut parts = path.split('/');
+    let (Some(algorithm), Some(hash), Some(size), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return None;
+    };
+    let digest = CacheDigest {
+        algorithm: algorithm.to_string(),
+        hash: hash.to_string(),
+        size: size.parse().ok()?,
+    };
+    digest.validate().ok()?;
+    Some(digest)
+}
+
+/// Namespaces are opaque, so their directories are named by a hash of the namespace.
+fn namespace_dir(dir: &Path, namespace: &str) -> PathBuf {
+    let hash = blake3::hash(namespace.as_bytes()).to_hex();
+    dir.join("namespaces").join(&hash[..32])
+}
+
+fn write_namespace_name(store: &Path, namespace: &str) -> Result<()> {
+    let path = store.join("namespace");
+    if !path.exists() {
+        file::write(&path, namespace)?;
+    }
+    Ok(())
+}
+
+fn blob_path(store: &Path, digest: &CacheDigest) -> PathBuf {
+    store
+        .join("cas")
+        .join(&digest.algorithm)
+        .join(&digest.hash[..2])
+        .join(format!("{}-{}", digest.hash, digest.size))
+}
+
+fn action_result_path(store: &Path, digest: &CacheDigest) -> PathBuf {
+    store
+        .join("ac")
+        .join(&digest.algorithm)
+        .join(&digest.hash[..2])
+        .join(format!("{}-{}.json", digest.hash, digest.size))
+}
+
+/// Every stored blob an action result references, or `None` if any of them is missing or invalid.
+fn reachable_blobs(store: &Path, result: &RemoteActionResult) -> Result<Option<Vec<PathBuf>>> {
+    let mut blobs = Vec::new();
+    let mut visited = BTreeSet::new();
+    let mut pending_files = vec![&result.action];
+    pending_files.extend(&result.metadata);
+    let mut pending_directories = result.output_root.iter().cloned().collect::<Vec<_>>();
+    for digest in pending_files {
+        if digest.validate().is_err() {
+            return Ok(None);
+        }
+        let path = blob_path(store, digest);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        blobs.push(path);
+    }
+    while let Some(digest) = pending_directories.pop() {
+        if digest.validate().is_err() {
+            return Ok(None);
+        }
+        if !visited.insert(digest.clone()) {
+            continue;
+        }
+        let path = blob_path(store, &digest);
+        let Ok(bytes) = std::fs::read(&path) else {
+            return Ok(None);
+        };
+        let Ok(directory) = serde_json::from_slice::<RemoteDirectory>(&bytes) else {
+            return Ok(None);
+        };
+        blobs.push(path);
+        for file in &directory.files {
+            if file.digest.validate().is_err() {
+                return Ok(None);
+            }
+            let path = blob_path(store, &file.digest);
+            if !path.is_file() {
+                return Ok(None);
+            }
+            blobs.push(path);
+        }
+        pending_directories.extend(directory.directories.into_iter().map(|node| node.digest));
+    }
+    Ok(Some(blobs))
+}
+
+fn touch(path: &Path) {
+    let now = filetime::FileTime::now();
+    if let Err(err) = filetime::set_file_mtime(path, now) {
+        debug!(
+            "failed to update access time of {}: {err}",
+            display_path(path)
+        );
+    }
+}
+
+fn modified(path: &Path) -> SystemTime {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
+/// Removes entries unused for longer than `max_age`, then the least recently used entries until
+/// the store fits in `max_size`. Returns how many files were removed.
+fn evict(dir: &Path, max_size: Option<u64>, max_age: Option<Duration>) -> Result<u64> {
+    if max_size.is_none() && max_age.is_none() {
+        return Ok(0);
+    }
+    let namespaces = dir.join("namespaces");
+    if !namespaces.exists() {
+        return Ok(0);
+    }
+    let mut entries = Vec::new();
+    for entry in walkdir::WalkDir::new(&namespaces).min_depth(3) {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let metadata = entry.metadata()?;
+        let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+        entries.push((modified, metadata.len(), entry.into_path()));
+    }
+    entries.sort();
+    let mut total_size = entries.iter().map(|(_, len, _)| len).sum::<u64>();
+    let mut evicted = 0;
+    for (modified, len, path) in entries {
+        let expired = max_age.is_some_and(|age| modified.elapsed().unwrap_or_default() > age);
+        let oversized = max_size.is_some_and(|max| total_size > max);
+        if !expired && !oversized {
+            // Entries are sorted oldest first, so nothing later is expired either.
+            break;
+        }
+        std::fs::remove_file(&path)?;
+        total_size -= len;
+        evicted += 1;
+    }
+    Ok(evicted)
+}
+
+fn namespace_stats(dir: &Path, access: &Access) -> Result<Vec<NamespaceStats>> {
+    let namespaces = dir.join("namespaces");
+    if !namespaces.exists() {
+        return Ok(Vec::new());
+    }
+    let mut stats = Vec::new();
+    for entry in std::fs::read_dir(&namespaces)? {
+        let store = entry?.path();
+        let Ok(namespace) = std::fs::read_to_string(store.join("namespace")) else {
+            continue;
+        };
+        if let Access::Namespaces(allowed) = access
+            && !allowed.contains(&namespace)
+        {
+            continue;
+        }
+        let mut ns = NamespaceStats {
+            namespace,
+            action_results: 0,
+            blobs: 0,
+            bytes: 0,
+        };
+        for (kind, count) in [("ac", &mut ns.action_results), ("cas", &mut ns.blobs)] {
+            for entry in walkdir::WalkDir::new(store.join(kind)) {
+                let Ok(entry) = entry else {
+                    continue;
+                };
+                if entry.file_type().is_file() {
+                    *count += 1;
+                    ns.bytes += entry.metadata().map(|m| m.len()).unwrap_or_default();
+                }
+            }
+        }
+        stats.push(ns);
+    }
+    stats.sort_by(|a, b| a.namespace.cmp(&b.namespace));
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn start(grants: Vec<TokenGrant>) -> (tempfile::TempDir, String) {
+        let dir = tempfile::tempdir().unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let options = TaskCacheServerOptions {
+            dir: dir.path().to_path_buf(),
+            grants,
+            read_only: false,
+            max_size: None,
+            max_age: None,
+        };
+        tokio::spawn(serve(listener, options));
+        (dir, url)
+    }
+
+    fn put_blob(
+        client: &reqwest::Client,
+        url: &str,
+        namespace: &str,
+        token: &str,
+        digest: &CacheDigest,
+        body: &'static [u8],
+    ) -> reqwest::RequestBuilder {
+        client
+            .put(format!(
+                "{url}/v1/blobs/{}/{}/{}",
+                digest.algorithm, digest.hash, digest.size
+            ))
+            .header(REMOTE_CACHE_PROTOCOL_HEADER, "1")
+            .header(REMOTE_CACHE_NAMESPACE_HEADER, namespace)
+            .bearer_auth(token)
+            .body(body)
+    }
+
+    #[test]
+    fn test_parse_token_file() {
+        let grants = TokenGrant::parse_file(
+            "# ci may write everywhere\n* write ci-token\nmyorg/app read dev-token\n",
+        )
+        .unwrap();
+        assert_eq!(grants.len(), 2);
+        assert!(grants[0].namespace.is_none() && grants[0].write);
+        assert_eq!(grants[1].namespace.as_deref(), Some("myorg/app"));
+        assert!(!grants[1].write);
+        assert!(TokenGrant::parse_file("* admin token\n").is_err());
+        assert!(TokenGrant::parse_file("* write\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_digest() {
+        let hash = "a".repeat(64);
+        let digest = parse_digest(&format!("blake3/{hash}/12")).unwrap();
+        assert_eq!(digest.size, 12);
+        assert!(parse_digest(&format!("md5/{hash}/12")).is_none());
+        assert!(parse_digest("blake3/../../etc/12").is_none());
+        assert!(parse_digest(&format!("blake3/{hash}/12/extra")).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_blob_uploads_are_verified_and_isolated_by_namespace() {
+        let (_dir, url) = start(vec![
+            TokenGrant::new("writer", Some("team-a".into()), true),
+            TokenGrant::new("reader", Some("team-a".into()), false),
+        ])
+        .await;
+        let client = reqwest::Client::new();
+        let digest = CacheDigest::blake3(b"output");
+
+        let status = |response: reqwest::Response| response.status();
+        let wrong = CacheDigest::blake3(b"tampered");
+        let res = put_blob(&client, &url, "team-a", "writer", &wrong, b"output!!")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(status(res), StatusCode::BAD_REQUEST);
+        let res = put_blob(&client, &url, "team-a", "nope", &digest, b"output")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(status(res), StatusCode::UNAUTHORIZED);
+        let res = put_blob(&client, &url, "team-a", "reader", &digest, b"output")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(status(res), StatusCode::FORBIDDEN);
+        let res = put_blob(&client, &url, "team-b", "writer", &digest, b"output")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(status(res), StatusCode::FORBIDDEN);
+        let res = put_blob(&client, &url, "team-a", "writer", &digest, b"output")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(status(res), StatusCode::CREATED);
+        let res = put_blob(&client, &url, "team-a", "writer", &digest, b"output")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(status(res), StatusCode::NO_CONTENT);
+
+        let get = |namespace: &str| {
+            client
+                .get(format!(
+                    "{url}/v1/blobs/blake3/{}/{}",
+                    digest.hash, digest.size
+                ))
+                .header(REMOTE_CACHE_NAMESPACE_HEADER, namespace.to_string())
+                .bearer_auth("reader")
+                .send()
+        };
+        let res = get("team-a").await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.bytes().await.unwrap().as_ref(), b"output");
+        assert_eq!(get("team-b").await.unwrap().status(), StatusCode::FORBIDDEN);
+
+        let stats: serde_json::Value = client
+            .get(format!("{url}/stats"))
+            .bearer_auth("reader")
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(stats["blobs_stored"], 1);
+        assert_eq!(stats["blobs_rejected"], 1);
+        assert_eq!(stats["namespaces"][0]["namespace"], "team-a");
+        assert_eq!(stats["namespaces"][0]["blobs"], 1);
+    }
+
+    #[test]
+    fn test_evict_removes_least_recently_used_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = namespace_dir(dir.path(), "team-a");
+        let old = CacheDigest::blake3(b"old");
+        let new = CacheDigest::blake3(b"new");
+        for (digest, age) in [(&old, 120), (&new, 0)] {
+            let path = blob_path(&store, digest);
+            file::write(&path, "0123456789").unwrap();
+            let time =
+                filetime::FileTime::from_system_time(SystemTime::now() - Duration::from_secs(age));
+            filetime::set_file_mtime(&path, time).unwrap();
+        }
+
+        assert_eq!(evict(dir.path(), Some(100), None).unwrap(), 0);
+        assert_eq!(evict(dir.path(), Some(15), None).unwrap(), 1);
+        assert!(!blob_path(&store, &old).exists());
+        assert!(blob_path(&store, &new).exists());
+        assert_eq!(
+            evict(dir.path(), None, Some(Duration::from_secs(60))).unwrap(),
+            0
+        );
+    }
+}
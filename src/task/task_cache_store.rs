@@ -1,3 +1,4 @@
+use crate::backend::s3::{self, S3Url};
 use crate::file;
 use crate::task::task_cache::{
     CACHE_FORMAT_VERSION, CacheManifest, TaskCacheOutput, calculate_artifact_checksum,
@@ -5,6 +6,7 @@ use crate::task::task_cache::{
 };
 use crate::{config::Settings, http};
 use async_trait::async_trait;
+use aws_sdk_s3::primitives::ByteStream;
 use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
 use eyre::{Result, bail, eyre};
 use jdx_tar::{Archive, Builder, EntryType, Header};
@@ -23,15 +25,15 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::io::AsyncWriteExt;
 use url::{Host, Url};
 
-const REMOTE_CACHE_PROTOCOL_VERSION: u8 = 1;
-const REMOTE_CACHE_PROTOCOL_HEADER: &str = "Mise-Cache-Protocol";
-const REMOTE_CACHE_NAMESPACE_HEADER: &str = "Mise-Cache-Namespace";
-const REMOTE_CACHE_ACTION_RESULT_MEDIA_TYPE: &str =
+pub(crate) const REMOTE_CACHE_PROTOCOL_VERSION: u8 = 1;
+pub(crate) const REMOTE_CACHE_PROTOCOL_HEADER: &str = "Mise-Cache-Protocol";
+pub(crate) const REMOTE_CACHE_NAMESPACE_HEADER: &str = "Mise-Cache-Namespace";
+pub(crate) const REMOTE_CACHE_ACTION_RESULT_MEDIA_TYPE: &str =
     "application/vnd.mise.cache-action-result.v1+json";
 const REMOTE_CACHE_DIRECTORY_MEDIA_TYPE: &str = "application/vnd.mise.cache-directory.v1+json";
 const REMOTE_CACHE_CLIENT_METADATA_MEDIA_TYPE: &str =
     "application/vnd.mise.cache-client-metadata.v1+json";
-const REMOTE_CACHE_BLOB_MEDIA_TYPE: &str = "application/octet-stream";
+pub(crate) const REMOTE_CACHE_BLOB_MEDIA_TYPE: &str = "application/octet-stream";
 
 /// Version of the cache-store contract. This is independent of the artifact
 /// manifest format so stores and transports can evolve without changing keys.
@@ -110,14 +112,14 @@ struct JwtExpiry {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
-struct CacheDigest {
-    algorithm: String,
-    hash: String,
-    size: u64,
+pub(crate) struct CacheDigest {
+    pub(crate) algorithm: String,
+    pub(crate) hash: String,
+    pub(crate) size: u64,
 }
 
 impl CacheDigest {
-    fn blake3(bytes: &[u8]) -> Self {
+    pub(crate) fn blake3(bytes: &[u8]) -> Self {
         Self {
             algorithm: "blake3".into(),
             hash: blake3::hash(bytes).to_hex().to_string(),
@@ -125,7 +127,7 @@ impl CacheDigest {
         }
     }
 
-    fn validate(&self) -> Result<()> {
+    pub(crate) fn validate(&self) -> Result<()> {
         if self.algorithm != "blake3" && self.algorithm != "sha256" {
             bail!("unsupported remote cache digest algorithm");
         }
@@ -153,7 +155,7 @@ impl CacheDigest {
         Ok(self.hash == hash)
     }
 
-    fn matches_file(&self, path: &Path) -> Result<bool> {
+    pub(crate) fn matches_file(&self, path: &Path) -> Result<bool> {
         self.validate()?;
         if self.size != fs::metadata(path)?.len() {
             return Ok(false);
@@ -168,20 +170,20 @@ impl CacheDigest {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-struct RemoteActionResultEnvelope {
-    result: RemoteActionResult,
+pub(crate) struct RemoteActionResultEnvelope {
+    pub(crate) result: RemoteActionResult,
     #[serde(default)]
     signatures: Vec<RemoteSignature>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-struct RemoteActionResult {
-    action: CacheDigest,
+pub(crate) struct RemoteActionResult {
+    pub(crate) action: CacheDigest,
     #[serde(default)]
-    metadata: Option<CacheDigest>,
+    pub(crate) metadata: Option<CacheDigest>,
     #[serde(default)]
-    output_root: Option<CacheDigest>,
-    version: u8,
+    pub(crate) output_root: Option<CacheDigest>,
+    pub(crate) version: u8,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -236,23 +238,23 @@ impl RemoteClientMetadata {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-struct RemoteDirectory {
-    directories: Vec<RemoteDirectoryNode>,
-    files: Vec<RemoteFileNode>,
+pub(crate) struct RemoteDirectory {
+    pub(crate) directories: Vec<RemoteDirectoryNode>,
+    pub(crate) files: Vec<RemoteFileNode>,
     symlinks: Vec<RemoteSymlinkNode>,
     version: u8,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-struct RemoteDirectoryNode {
-    digest: CacheDigest,
+pub(crate) struct RemoteDirectoryNode {
+    pub(crate) digest: CacheDigest,
     mode: u32,
     name: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-struct RemoteFileNode {
-    digest: CacheDigest,
+pub(crate) struct RemoteFileNode {
+    pub(crate) digest: CacheDigest,
     executable: bool,
     mode: u32,
     name: String,
@@ -557,8 +559,23 @@ pub(crate) fn compose_task_cache_stores(
     local: Arc<dyn TaskCacheStore>,
     remote: Option<RemoteTaskCacheConfig>,
 ) -> Result<Arc<dyn TaskCacheStore>> {
-    match remote {
-        Some(remote_config) => {
+    let Some(remote_config) = remote else {
+        return Ok(local);
+    };
+    let namespace = &remote_config.namespace;
+    let staging_dir = remote_config.staging_dir.clone();
+    let remote: Arc<dyn TaskCacheStore> = match remote_config.base_url.scheme() {
+        "file" => Arc::new(FsTaskCacheStore::new(
+            &remote_config.base_url,
+            namespace,
+            staging_dir,
+        )?),
+        "s3" => Arc::new(S3TaskCacheStore::new(
+            &remote_config.base_url,
+            namespace,
+            staging_dir,
+        )?),
+        _ => {
             let authenticated = remote_config
                 .token
                 .as_deref()
@@ -575,21 +592,20 @@ pub(crate) fn compose_task_cache_stores(
                 .redirect(reqwest::redirect::Policy::none())
                 .build()?;
             let credential = remote_credential(&remote_config, client.clone())?;
-            let remote = Arc::new(HttpTaskCacheStore {
-                base_url: normalized_base_url(remote_config.base_url),
-                namespace: remote_config.namespace,
-                staging_dir: remote_config.staging_dir,
+            Arc::new(HttpTaskCacheStore {
+                base_url: normalized_base_url(remote_config.base_url.clone()),
+                namespace: namespace.clone(),
+                staging_dir,
                 client,
                 credential,
-            });
-            Ok(Arc::new(CompositeTaskCacheStore::new(
-                local,
-                remote,
-                remote_config.mode,
-            )?))
+            })
         }
-        None => Ok(local),
-    }
+    };
+    Ok(Arc::new(CompositeTaskCacheStore::new(
+        local,
+        remote,
+        remote_config.mode,
+    )?))
 }
 
 fn remote_credential(
@@ -775,7 +791,7 @@ fn validate_remote_url(base_url: &Url, authenticated: bool) -> Result<()> {
         return Ok(());
     }
     if base_url.scheme() != "http" {
-        bail!("task.cache_remote_url must use HTTPS");
+        bail!("task.cache_remote_url must use https://, file:// or s3://");
     }
     let is_loopback = base_url.host().is_some_and(|host| match host {
         Host::Domain(host) => host.eq_ignore_ascii_case("localhost"),
@@ -794,6 +810,64 @@ fn validate_remote_url(base_url: &Url, authenticated: bool) -> Result<()> {
     Ok(())
 }
 
+/// Content-addressed storage behind a remote task cache, in the layout of the remote cache
+/// protocol. Blob reads verify the blob against its digest, and writes never replace an existing
+/// object, so every backend publishes the same immutable graph.
+#[async_trait]
+trait RemoteCasStore: Send + Sync {
+    fn staging_dir(&self) -> &Path;
+    async fn get_blob(&self, digest: &CacheDigest, media_type: &'static str) -> Result<Vec<u8>>;
+    async fn get_blob_file(&self, digest: &CacheDigest) -> Result<tempfile::NamedTempFile>;
+    async fn put_blob(&self, upload: &CasBlobUpload) -> Result<()>;
+    async fn get_action_result(
+        &self,
+        key: &str,
+        action_size: u64,
+    ) -> Result<Option<RemoteActionResultEnvelope>>;
+    async fn put_action_result(
+        &self,
+        key: &str,
+        action_size: u64,
+        result: &RemoteActionResultEnvelope,
+    ) -> Result<()>;
+}
+
+fn blob_path(digest: &CacheDigest) -> Result<String> {
+    digest.validate()?;
+    Ok(format!(
+        "blobs/{}/{}/{}",
+        digest.algorithm, digest.hash, digest.size
+    ))
+}
+
+fn action_result_path(key: &str, action_size: u64) -> Result<String> {
+    validate_remote_key(key)?;
+    Ok(format!("action-results/blake3/{key}/{action_size}"))
+}
+
+/// The namespace as a single path segment, for stores that keep every namespace under one root.
+fn namespace_path_segment(namespace: &str) -> Result<String> {
+    let segment: String = url::form_urlencoded::byte_serialize(namespace.as_bytes()).collect();
+    if segment.is_empty() || segment == "." || segment == ".." {
+        bail!("invalid task.cache_remote_namespace {namespace:?}");
+    }
+    Ok(segment)
+}
+
+fn verify_blob(digest: &CacheDigest, bytes: &[u8]) -> Result<()> {
+    if !digest.matches_bytes(bytes)? {
+        bail!("remote cache blob failed digest verification");
+    }
+    Ok(())
+}
+
+fn verify_blob_file(digest: &CacheDigest, path: &Path) -> Result<()> {
+    if !digest.matches_file(path)? {
+        bail!("remote cache blob failed digest verification");
+    }
+    Ok(())
+}
+
 struct HttpTaskCacheStore {
     base_url: Url,
     namespace: String,
@@ -804,17 +878,16 @@ struct HttpTaskCacheStore {
 
 impl HttpTaskCacheStore {
     fn action_result_endpoint(&self, key: &str, action_size: u64) -> Result<Url> {
-        validate_remote_key(key)?;
         Ok(self.base_url.join(&format!(
-            "v{REMOTE_CACHE_PROTOCOL_VERSION}/action-results/blake3/{key}/{action_size}"
+            "v{REMOTE_CACHE_PROTOCOL_VERSION}/{}",
+            action_result_path(key, action_size)?
         ))?)
     }
 
     fn blob_endpoint(&self, digest: &CacheDigest) -> Result<Url> {
-        digest.validate()?;
         Ok(self.base_url.join(&format!(
-            "v{REMOTE_CACHE_PROTOCOL_VERSION}/blobs/{}/{}/{}",
-            digest.algorithm, digest.hash, digest.size
+            "v{REMOTE_CACHE_PROTOCOL_VERSION}/{}",
+            blob_path(digest)?
         ))?)
     }
 
@@ -836,9 +909,15 @@ impl HttpTaskCacheStore {
             Ok(request)
         }
     }
+}
+
+#[async_trait]
+impl RemoteCasStore for HttpTaskCacheStore {
+    fn staging_dir(&self) -> &Path {
+        &self.staging_dir
+    }
 
     async fn get_blob(&self, digest: &CacheDigest, media_type: &'static str) -> Result<Vec<u8>> {
-        digest.validate()?;
         let url = self.blob_endpoint(digest)?;
         http::retry_async("GET", &url, || async {
             let response = self
@@ -848,9 +927,7 @@ impl HttpTaskCacheStore {
                 .await?
                 .error_for_status()?;
             let bytes = response.bytes().await?.to_vec();
-            if !digest.matches_bytes(&bytes)? {
-                bail!("remote cache blob failed digest verification");
-            }
+            verify_blob(digest, &bytes)?;
             Ok(bytes)
         })
         .await
@@ -877,9 +954,7 @@ impl HttpTaskCacheStore {
             }
             output.flush().await?;
             drop(output);
-            if !digest.matches_file(temporary.path())? {
-                bail!("remote cache blob failed digest verification");
-            }
+            verify_blob_file(digest, temporary.path())?;
             Ok(temporary)
         });
         tokio::time::timeout(Settings::get().http_download_timeout(), download)
@@ -922,6 +997,30 @@ impl HttpTaskCacheStore {
         .await
     }
 
+    async fn get_action_result(
+        &self,
+        key: &str,
+        action_size: u64,
+    ) -> Result<Option<RemoteActionResultEnvelope>> {
+        let url = self.action_result_endpoint(key, action_size)?;
+        http::retry_async("GET", &url, || async {
+            let response = self
+                .request(
+                    reqwest::Method::GET,
+                    url.clone(),
+                    REMOTE_CACHE_ACTION_RESULT_MEDIA_TYPE,
+                )
+                .await?
+                .send()
+                .await?;
+            if response.status() == StatusCode::NOT_FOUND {
+                return Ok(None);
+            }
+            Ok(Some(response.error_for_status()?.json().await?))
+        })
+        .await
+    }
+
     async fn put_action_result(
         &self,
         key: &str,
@@ -952,31 +1051,302 @@ impl HttpTaskCacheStore {
     }
 }
 
+/// A remote cache in a shared directory such as an NFS mount. Objects are written to a temporary
+/// file next to their destination and published with a rename that never replaces an object
+/// another writer published first.
+struct FsTaskCacheStore {
+    root: PathBuf,
+    staging_dir: PathBuf,
+}
+
+impl FsTaskCacheStore {
+    fn new(base_url: &Url, namespace: &str, staging_dir: PathBuf) -> Result<Self> {
+        let path = base_url
+            .to_file_path()
+            .map_err(|_| eyre!("task.cache_remote_url must be an absolute file:// URL"))?;
+        Ok(Self {
+            root: path
+                .join(format!("v{REMOTE_CACHE_PROTOCOL_VERSION}"))
+                .join(namespace_path_segment(namespace)?),
+            staging_dir,
+        })
+    }
+
+    fn publish(&self, path: &str, write: impl FnOnce(&mut File) -> Result<()>) -> Result<()> {
+        let path = self.root.join(path);
+        if path.exists() {
+            return Ok(());
+        }
+        let tmp = self.root.join("tmp");
+        file::create_dir_all(&tmp)?;
+        if let Some(parent) = path.parent() {
+            file::create_dir_all(parent)?;
+        }
+        let mut temporary = tempfile::NamedTempFile::new_in(&tmp)?;
+        write(temporary.as_file_mut())?;
+        temporary.as_file().sync_all()?;
+        match temporary.persist_noclobber(&path) {
+            Ok(_) => Ok(()),
+            Err(err) if err.error.kind() == std::io::ErrorKind::AlreadyExists => Ok(()),
+            Err(err) => Err(err.error.into()),
+        }
+    }
+}
+
 #[async_trait]
-impl TaskCacheStore for HttpTaskCacheStore {
+impl RemoteCasStore for FsTaskCacheStore {
+    fn staging_dir(&self) -> &Path {
+        &self.staging_dir
+    }
+
+    async fn get_blob(&self, digest: &CacheDigest, _media_type: &'static str) -> Result<Vec<u8>> {
+        let bytes = file::read(self.root.join(blob_path(digest)?))?;
+        verify_blob(digest, &bytes)?;
+        Ok(bytes)
+    }
+
+    async fn get_blob_file(&self, digest: &CacheDigest) -> Result<tempfile::NamedTempFile> {
+        let path = self.root.join(blob_path(digest)?);
+        file::create_dir_all(&self.staging_dir)?;
+        let temporary = tempfile::NamedTempFile::new_in(&self.staging_dir)?;
+        file::copy(&path, temporary.path())?;
+        verify_blob_file(digest, temporary.path())?;
+        Ok(temporary)
+    }
+
+    async fn put_blob(&self, upload: &CasBlobUpload) -> Result<()> {
+        self.publish(&blob_path(&upload.digest)?, |output| {
+            match &upload.source {
+                CasBlobSource::Bytes(bytes) => output.write_all(bytes)?,
+                CasBlobSource::File(file) => {
+                    std::io::copy(&mut File::open(file.path())?, output)?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    async fn get_action_result(
+        &self,
+        key: &str,
+        action_size: u64,
+    ) -> Result<Option<RemoteActionResultEnvelope>> {
+        let path = self.root.join(action_result_path(key, action_size)?);
+        match fs::read(&path) {
+            Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(eyre!("failed to read {}: {err}", path.display())),
+        }
+    }
+
+    async fn put_action_result(
+        &self,
+        key: &str,
+        action_size: u64,
+        result: &RemoteActionResultEnvelope,
+    ) -> Result<()> {
+        let body = serde_json::to_vec(result)?;
+        self.publish(&action_result_path(key, action_size)?, |output| {
+            output.write_all(&body)?;
+            Ok(())
+        })
+    }
+}
+
+/// A remote cache in an S3 bucket or S3-compatible object store. Credentials, region and endpoint
+/// are resolved like the `s3` backend's, and objects are published with a conditional PUT so an
+/// existing object is never replaced.
+struct S3TaskCacheStore {
+    bucket: String,
+    prefix: String,
+    region: Option<String>,
+    endpoint: Option<String>,
+    staging_dir: PathBuf,
+    client: tokio::sync::OnceCell<aws_sdk_s3::Client>,
+}
+
+impl S3TaskCacheStore {
+    fn new(base_url: &Url, namespace: &str, staging_dir: PathBuf) -> Result<Self> {
+        let s3_url = S3Url::parse(base_url.as_str())?;
+        let query = base_url.query_pairs().collect::<HashMap<_, _>>();
+        let mut prefix = s3_url.key.trim_matches('/').to_string();
+        if !prefix.is_empty() {
+            prefix.push('/');
+        }
+        Ok(Self {
+            bucket: s3_url.bucket,
+            prefix: format!(
+                "{prefix}v{REMOTE_CACHE_PROTOCOL_VERSION}/{}/",
+                namespace_path_segment(namespace)?
+            ),
+            region: query.get("region").map(|region| region.to_string()),
+            endpoint: query.get("endpoint").map(|endpoint| endpoint.to_string()),
+            staging_dir,
+            client: tokio::sync::OnceCell::new(),
+        })
+    }
+
+    async fn client(&self) -> Result<&aws_sdk_s3::Client> {
+        self.client
+            .get_or_try_init(|| {
+                s3::create_s3_client(self.region.as_deref(), self.endpoint.as_deref())
+            })
+            .await
+    }
+
+    async fn get_object(&self, path: &str) -> Result<Option<ByteStream>> {
+        let key = format!("{}{path}", self.prefix);
+        let response = self
+            .client()
+            .await?
+            .get_object()
+            .bucket(&self.bucket)
+            .key(&key)
+            .send()
+            .await;
+        match response {
+            Ok(output) => Ok(Some(output.body)),
+            Err(err)
+                if err
+                    .as_service_error()
+                    .is_some_and(|error| error.is_no_such_key())
+                    || err
+                        .raw_response()
+                        .is_some_and(|response| response.status().as_u16() == 404) =>
+            {
+                Ok(None)
+            }
+            Err(err) => Err(s3::handle_s3_error(err, &self.bucket, &key)),
+        }
+    }
+
+    async fn get_blob_object(&self, digest: &CacheDigest) -> Result<ByteStream> {
+        let path = blob_path(digest)?;
+        self.get_object(&path).await?.ok_or_else(|| {
+            eyre!(
+                "remote cache blob not found: s3://{}/{}{path}",
+                self.bucket,
+                self.prefix
+            )
+        })
+    }
+
+    async fn put_object(&self, path: &str, body: ByteStream, media_type: &str) -> Result<()> {
+        let key = format!("{}{path}", self.prefix);
+        let response = self
+            .client()
+            .await?
+            .put_object()
+            .bucket(&self.bucket)
+            .key(&key)
+            .content_type(media_type)
+            .if_none_match("*")
+            .body(body)
+            .send()
+            .await;
+        match response {
+            Ok(_) => Ok(()),
+            // Another writer already published this object.
+            Err(err)
+                if err
+                    .raw_response()
+                    .is_some_and(|response| response.status().as_u16() == 412) =>
+            {
+                Ok(())
+            }
+            Err(err) => Err(s3::handle_s3_error(err, &self.bucket, &key)),
+        }
+    }
+}
+
+#[async_trait]
+impl RemoteCasStore for S3TaskCacheStore {
+    fn staging_dir(&self) -> &Path {
+        &self.staging_dir
+    }
+
+    async fn get_blob(&self, digest: &CacheDigest, _media_type: &'static str) -> Result<Vec<u8>> {
+        let bytes = self
+            .get_blob_object(digest)
+            .await?
+            .collect()
+            .await?
+            .into_bytes()
+            .to_vec();
+        verify_blob(digest, &bytes)?;
+        Ok(bytes)
+    }
+
+    async fn get_blob_file(&self, digest: &CacheDigest) -> Result<tempfile::NamedTempFile> {
+        let download = async {
+            let mut body = self.get_blob_object(digest).await?;
+            file::create_dir_all(&self.staging_dir)?;
+            let temporary = tempfile::NamedTempFile::new_in(&self.staging_dir)?;
+            let mut output = tokio::fs::File::from_std(temporary.reopen()?);
+            while let Some(chunk) = body.try_next().await? {
+                output.write_all(&chunk).await?;
+            }
+            output.flush().await?;
+            drop(output);
+            verify_blob_file(digest, temporary.path())?;
+            Ok(temporary)
+        };
+        tokio::time::timeout(Settings::get().http_download_timeout(), download)
+            .await
+            .map_err(|_| eyre!("remote task cache blob download timed out"))?
+    }
+
+    async fn put_blob(&self, upload: &CasBlobUpload) -> Result<()> {
+        let body = match &upload.source {
+            CasBlobSource::Bytes(bytes) => ByteStream::from(bytes.clone()),
+            CasBlobSource::File(file) => ByteStream::from_path(file.path()).await?,
+        };
+        self.put_object(
+            &blob_path(&upload.digest)?,
+            body,
+            REMOTE_CACHE_BLOB_MEDIA_TYPE,
+        )
+        .await
+    }
+
+    async fn get_action_result(
+        &self,
+        key: &str,
+        action_size: u64,
+    ) -> Result<Option<RemoteActionResultEnvelope>> {
+        let Some(body) = self
+            .get_object(&action_result_path(key, action_size)?)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let bytes = body.collect().await?.into_bytes();
+        Ok(Some(serde_json::from_slice(&bytes)?))
+    }
+
+    async fn put_action_result(
+        &self,
+        key: &str,
+        action_size: u64,
+        result: &RemoteActionResultEnvelope,
+    ) -> Result<()> {
+        self.put_object(
+            &action_result_path(key, action_size)?,
+            ByteStream::from(serde_json::to_vec(result)?),
+            REMOTE_CACHE_ACTION_RESULT_MEDIA_TYPE,
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl<S: RemoteCasStore> TaskCacheStore for S {
     fn version(&self) -> u8 {
         TASK_CACHE_STORE_VERSION
     }
 
     async fn get(&self, key: &str, action_size: u64) -> Result<Option<TaskCacheStoreEntry>> {
-        let url = self.action_result_endpoint(key, action_size)?;
-        let envelope = http::retry_async("GET", &url, || async {
-            let response = self
-                .request(
-                    reqwest::Method::GET,
-                    url.clone(),
-                    REMOTE_CACHE_ACTION_RESULT_MEDIA_TYPE,
-                )
-                .await?
-                .send()
-                .await?;
-            if response.status() == StatusCode::NOT_FOUND {
-                return Ok(None);
-            }
-            Ok(Some(response.error_for_status()?.json().await?))
-        })
-        .await?;
-        let Some(envelope): Option<RemoteActionResultEnvelope> = envelope else {
+        let Some(envelope) = self.get_action_result(key, action_size).await? else {
             return Ok(None);
         };
         if envelope.result.version != 1
@@ -1020,11 +1390,13 @@ impl TaskCacheStore for HttpTaskCacheStore {
 
     fn begin_write(&self, key: &str) -> Result<TaskCacheStoreWrite> {
         validate_remote_key(key)?;
-        file::create_dir_all(&self.staging_dir)?;
+        file::create_dir_all(self.staging_dir())?;
         let nonce = crate::rand::random_string(8);
         Ok(TaskCacheStoreWrite {
-            artifact_path: self.staging_dir.join(format!("{key}.part-{nonce}.tar.zst")),
-            manifest_path: self.staging_dir.join(format!("{key}.part-{nonce}.json")),
+            artifact_path: self
+                .staging_dir()
+                .join(format!("{key}.part-{nonce}.tar.zst")),
+            manifest_path: self.staging_dir().join(format!("{key}.part-{nonce}.json")),
         })
     }
 
@@ -1061,7 +1433,7 @@ impl TaskCacheStore for HttpTaskCacheStore {
         let metadata = uploads[1].digest.clone();
         let output_root = if has_artifact {
             let (root, mut artifact_uploads) =
-                archive_to_cas(write.artifact_path(), &self.staging_dir)?;
+                archive_to_cas(write.artifact_path(), self.staging_dir())?;
             uploads.append(&mut artifact_uploads);
             Some(root)
         } else {
@@ -1304,7 +1676,7 @@ enum RestoredNode {
 }
 
 async fn materialize_remote_tree(
-    store: &HttpTaskCacheStore,
+    store: &impl RemoteCasStore,
     root: &CacheDigest,
 ) -> Result<tempfile::NamedTempFile> {
     let mut pending = vec![(PathBuf::new(), root.clone(), BTreeSet::new())];
@@ -1374,7 +1746,7 @@ async fn materialize_remote_tree(
         }
     }
 
-    file::create_dir_all(&store.staging_dir)?;
+    file::create_dir_all(store.staging_dir())?;
     let mut downloaded = BTreeMap::new();
     for (path, node) in &nodes {
         if let RestoredNode::File { digest, .. } = node {
@@ -1382,7 +1754,7 @@ async fn materialize_remote_tree(
         }
     }
 
-    let archive_file = tempfile::NamedTempFile::new_in(&store.staging_dir)?;
+    let archive_file = tempfile::NamedTempFile::new_in(store.staging_dir())?;
     let encoder = zstd::Encoder::new(archive_file.reopen()?, 0)?;
     let mut archive = Builder::new(encoder);
     for (path, node) in nodes {
@@ -2053,6 +2425,144 @@ mod tests {
         blob_get.assert_async().await;
     }
 
+    #[tokio::test]
+    async fn http_store_round_trips_through_task_cache_server() {
+        use crate::task::task_cache_server::{self, TaskCacheServerOptions, TokenGrant};
+
+        let server_dir = tempfile::tempdir().unwrap();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url: Url = format!("http://{}", listener.local_addr().unwrap())
+            .parse()
+            .unwrap();
+        tokio::spawn(task_cache_server::serve(
+            listener,
+            TaskCacheServerOptions {
+                dir: server_dir.path().to_path_buf(),
+                grants: vec![TokenGrant::new("ci-token", Some("team".into()), true)],
+                read_only: false,
+                max_size: None,
+                max_age: None,
+            },
+        ));
+        let staging = tempfile::tempdir().unwrap();
+        let store = |namespace: &str| HttpTaskCacheStore {
+            base_url: normalized_base_url(base_url.clone()),
+            namespace: namespace.into(),
+            staging_dir: staging.path().to_path_buf(),
+            client: reqwest::Client::new(),
+            credential: RemoteTaskCacheCredential::Static(HeaderValue::from_static(
+                "Bearer ci-token",
+            )),
+        };
+        let action = br#"{"task":"build"}"#;
+        let key = CacheDigest::blake3(action).hash;
+        let manifest = format!(
+            r#"{{"format":2,"key":"{key}","task_identity":"build","artifact_checksum":null,"roots":["dist"],"output":[],"restored_bytes":5,"execution_duration_ns":1}}"#
+        );
+
+        let team = store("team");
+        assert!(team.get(&key, action.len() as u64).await.unwrap().is_none());
+        let write = team.begin_write(&key).unwrap();
+        let encoder = zstd::Encoder::new(File::create(write.artifact_path()).unwrap(), 0).unwrap();
+        let mut archive = Builder::new(encoder);
+        let mut file_header = Header::new_gnu(EntryType::File);
+        file_header.set_mode(0o644);
+        file_header.set_size(5);
+        archive
+            .append_data(&mut file_header, "dist/app", b"hello".as_slice())
+            .unwrap();
+        archive.into_inner().unwrap().finish().unwrap();
+        team.commit(&key, action, &write, manifest.as_bytes(), true)
+            .await
+            .unwrap();
+
+        let entry = team.get(&key, action.len() as u64).await.unwrap().unwrap();
+        let restored: CacheManifest = serde_json::from_slice(&entry.manifest).unwrap();
+        assert_eq!(restored.roots, [PathBuf::from("dist")]);
+        assert!(entry.artifact.is_some());
+
+        let other = store("other-team");
+        assert!(other.get(&key, action.len() as u64).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn filesystem_store_round_trips_through_a_shared_directory() {
+        let shared = tempfile::tempdir().unwrap();
+        let staging = tempfile::tempdir().unwrap();
+        let base_url = Url::from_directory_path(shared.path()).unwrap();
+        let store = |namespace: &str| {
+            FsTaskCacheStore::new(&base_url, namespace, staging.path().to_path_buf()).unwrap()
+        };
+        let action = br#"{"task":"build"}"#;
+        let key = CacheDigest::blake3(action).hash;
+        let manifest = format!(
+            r#"{{"format":2,"key":"{key}","task_identity":"build","artifact_checksum":null,"roots":["dist"],"output":[],"restored_bytes":5,"execution_duration_ns":1}}"#
+        );
+
+        let team = store("acme/widgets");
+        assert!(team.get(&key, action.len() as u64).await.unwrap().is_none());
+        let write = team.begin_write(&key).unwrap();
+        let encoder = zstd::Encoder::new(File::create(write.artifact_path()).unwrap(), 0).unwrap();
+        let mut archive = Builder::new(encoder);
+        let mut file_header = Header::new_gnu(EntryType::File);
+        file_header.set_mode(0o644);
+        file_header.set_size(5);
+        archive
+            .append_data(&mut file_header, "dist/app", b"hello".as_slice())
+            .unwrap();
+        archive.into_inner().unwrap().finish().unwrap();
+        team.commit(&key, action, &write, manifest.as_bytes(), true)
+            .await
+            .unwrap();
+        // A second writer of the same action keeps the first published result.
+        team.commit(&key, action, &write, manifest.as_bytes(), true)
+            .await
+            .unwrap();
+
+        let namespace_root = shared.path().join("v1/acme%2Fwidgets");
+        assert!(
+            namespace_root
+                .join(format!("action-results/blake3/{key}/{}", action.len()))
+                .is_file()
+        );
+        let hello = CacheDigest::blake3(b"hello");
+        let blob = namespace_root.join(format!("blobs/blake3/{}/5", hello.hash));
+        assert_eq!(fs::read(&blob).unwrap(), b"hello");
+        let entry = team.get(&key, action.len() as u64).await.unwrap().unwrap();
+        let restored: CacheManifest = serde_json::from_slice(&entry.manifest).unwrap();
+        assert_eq!(restored.roots, [PathBuf::from("dist")]);
+        assert!(entry.artifact.is_some());
+        assert!(
+            store("other-team")
+                .get(&key, action.len() as u64)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        fs::write(&blob, b"jello").unwrap();
+        let err = team.get(&key, action.len() as u64).await.err().unwrap();
+        assert!(err.to_string().contains("failed digest verification"));
+    }
+
+    #[test]
+    fn s3_store_keys_objects_under_prefix_and_namespace() {
+        let staging = PathBuf::from("/tmp/staging");
+        let url = "s3://cache-bucket/mise/tasks/?region=eu-west-1&endpoint=http://localhost:9000"
+            .parse()
+            .unwrap();
+        let store = S3TaskCacheStore::new(&url, "acme/widgets", staging.clone()).unwrap();
+        assert_eq!(store.bucket, "cache-bucket");
+        assert_eq!(store.prefix, "mise/tasks/v1/acme%2Fwidgets/");
+        assert_eq!(store.region.as_deref(), Some("eu-west-1"));
+        assert_eq!(store.endpoint.as_deref(), Some("http://localhost:9000"));
+
+        let url = "s3://cache-bucket".parse().unwrap();
+        let store = S3TaskCacheStore::new(&url, "team", staging.clone()).unwrap();
+        assert_eq!(store.prefix, "v1/team/");
+        assert!(S3TaskCacheStore::new(&url, "..", staging).is_err());
+    }
+
     #[tokio::test]
     async fn composite_store_local_removal_preserves_and_repromotes_remote_entry() {
         let local_root = tempfile::tempdir().unwrap();
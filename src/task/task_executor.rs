@@ -4,7 +4,7 @@ use crate::config::{Config, Settings, env_directive::EnvDirective};
 use crate::duration;
 use crate::env_diff::EnvDiff;
 use crate::file::{can_execute_directly, display_path, replace_path};
-use crate::sandbox::SandboxConfig;
+use crate::sandbox::{ResourceLimits, SandboxConfig};
 use crate::task::TaskArtifactCache;
 use crate::task::task_cache::{
     CommandInput, TaskCacheContext, TaskCacheMissReason, TaskCacheRestore,
@@ -13,16 +13,22 @@ use crate::task::task_context_builder::TaskContextBuilder;
 use crate::task::task_list::split_task_spec;
 use crate::task::task_output::{TaskOutput, trunc};
 use crate::task::task_output_handler::OutputHandler;
+use crate::task::task_remote_host::RemoteTaskHosts;
+use crate::task::task_results::{TaskCacheResult, TaskResults, TaskSkipReason};
 use crate::task::task_scheduler::SchedMsg;
 use crate::task::task_script_parser::subcommand_name_from_parse;
+use crate::task::task_service::ServiceRegistry;
 use crate::task::task_source_checker::{
-    remove_auto_output, save_checksum, sources_are_fresh, task_cwd,
+    build_output_matcher, is_output, output_roots, remove_auto_output, save_checksum,
+    sources_are_fresh, task_cwd, task_source_files, task_source_match_root,
 };
 use crate::task::{
-    Deps, FailedTasks, GetMatchingExt, Task, TaskCacheAudit, TaskCacheMode, TaskCacheOutput,
+    Deps, FailedTasks, GetMatchingExt, RetriedTasks, Task, TaskCacheAudit, TaskCacheMode,
+    TaskCacheOutput, TaskRetry, enforce_cache_contract,
 };
 use crate::task::{TaskCompletionState, TaskDependencyState};
 use crate::tera::{contains_template_syntax, render_str};
+use crate::timeline;
 use crate::toolset::env_cache::CachedEnv;
 use crate::ui::{style, time};
 use duct::IntoExecutablePath;
@@ -69,6 +75,8 @@ struct TaskExecContext<'a> {
     prefix: &'a str,
     output_capture: Option<&'a TaskOutputCapture>,
     allow_during_interruption: bool,
+    /// The program is ssh to a remote host, so the local sandbox and audit do not apply
+    remote: bool,
 }
 
 struct TaskRunEntriesContext<'a> {
@@ -119,6 +127,92 @@ fn resolve_task_sandbox_path(p: &Path, task_base: Option<&Path>) -> PathBuf {
     }
 }
 
+/// Build a SandboxConfig for a task by merging task-level config with CLI overrides.
+///
+/// Task-level relative `allow_read`/`allow_write` paths are resolved against the task's
+/// effective working directory (`task.dir(config)`, which itself falls back to `config_root`)
+/// so that `allow_read = ["."]` means "the directory the task runs in", matching how `dir`
+/// resolves. CLI-supplied paths are left as-is and resolved against cwd by `resolve_paths()`.
+/// The restrictions of the task's `sandbox` profile apply on top of both.
+pub(crate) async fn build_task_sandbox(
+    task: &Task,
+    config: &Arc<Config>,
+    cli: &SandboxConfig,
+    enforce_cache: bool,
+) -> Result<SandboxConfig> {
+    let task_base = task.dir(config).await?;
+    let resolve_task_path =
+        |p: &PathBuf| -> PathBuf { resolve_task_sandbox_path(p, task_base.as_deref()) };
+    let mut sandbox = SandboxConfig {
+        deny_read: task.deny_all || task.deny_read || cli.deny_read,
+        deny_write: task.deny_all || task.deny_write || cli.deny_write,
+        deny_net: task.deny_all || task.deny_net || cli.deny_net,
+        deny_env: task.deny_all || task.deny_env || cli.deny_env,
+        allow_read: task
+            .allow_read
+            .iter()
+            .map(&resolve_task_path)
+            .chain(cli.allow_read.iter().cloned())
+            .collect(),
+        allow_write: task
+            .allow_write
+            .iter()
+            .map(&resolve_task_path)
+            .chain(cli.allow_write.iter().cloned())
+            .collect(),
+        allow_net: task
+            .allow_net
+            .iter()
+            .chain(cli.allow_net.iter())
+            .cloned()
+            .collect(),
+        allow_env: task
+            .allow_env
+            .iter()
+            .chain(cli.allow_env.iter())
+            .cloned()
+            .collect(),
+        pass_through_env: task
+            .pass_through_env
+            .iter()
+            .chain(cli.pass_through_env.iter())
+            .cloned()
+            .collect(),
+        cache_env: task
+            .cache
+            .iter()
+            .filter(|cache| cache.enabled)
+            .flat_map(|cache| &cache.env)
+            .chain(cli.cache_env.iter())
+            .cloned()
+            .collect(),
+        allow_list_dir: cli.allow_list_dir.clone(),
+        deny_read_dirs: cli.deny_read_dirs.clone(),
+        limits: ResourceLimits::parse(
+            task.limit_memory.as_deref(),
+            task.limit_cpus,
+            task.limit_pids,
+            task.limit_open_files,
+            task.limit_output_bytes.as_deref(),
+        )
+        .wrap_err_with(|| format!("invalid resource limit in task {}", task.name))?
+        .stricter(&cli.limits),
+    };
+    if let Some(profile) = &task.sandbox {
+        sandbox.merge(
+            config
+                .sandbox_profiles()
+                .resolve(profile)
+                .wrap_err_with(|| format!("invalid sandbox for task {}", task.name))?,
+        );
+    }
+    if enforce_cache {
+        enforce_cache_contract(task, config, &mut sandbox).await?;
+    }
+    sandbox.resolve_paths();
+    Ok(sandbox)
+}
+
 /// Build the single-line command shown in a task's header (the `$ ...` line).
 ///
 /// Skips leading shebang/blank/`set ...` boilerplate so the first real command is
@@ -215,6 +309,10 @@ pub struct TaskExecutorConfig {
     pub task_cache: TaskCacheMode,
     pub task_cache_explain: bool,
     pub task_cache_explain_json: bool,
+    /// `--retry` override for the number of attempts of every task
+    pub retry: Option<u32>,
+    /// Keep the stderr of each task for `--report`
+    pub capture_stderr: bool,
     /// CLI-level sandbox overrides (merged with task-level sandbox config)
     pub sandbox: crate::sandbox::SandboxConfig,
 }
@@ -224,6 +322,14 @@ pub struct TaskExecutor {
     pub context_builder: TaskContextBuilder,
     pub output_handler: OutputHandler,
     pub failed_tasks: FailedTasks,
+    /// Tasks that were rerun after a failure, with the number of their latest attempt
+    pub retried_tasks: RetriedTasks,
+    /// Service tasks started during the run
+    pub services: ServiceRegistry,
+    /// Remote hosts that tasks with `remote` run on
+    pub remote_hosts: RemoteTaskHosts,
+    /// Results of the tasks that finished, for the summary and `--report`
+    pub results: TaskResults,
     pub(crate) cache_stats: Arc<StdMutex<TaskCacheStats>>,
     interrupted: AtomicBool,
 
@@ -239,6 +345,7 @@ pub struct TaskExecutor {
     pub task_cache: TaskCacheMode,
     pub task_cache_explain: bool,
     pub task_cache_explain_json: bool,
+    pub retry: Option<u32>,
     pub sandbox: crate::sandbox::SandboxConfig,
 }
 
@@ -246,6 +353,8 @@ pub struct TaskExecutor {
 pub struct TaskRunOutcome {
     pub did_work: bool,
     pub cache_key: Option<String>,
+    pub skipped: Option<TaskSkipReason>,
+    pub cache: Option<TaskCacheResult>,
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
@@ -278,6 +387,10 @@ impl TaskExecutor {
             context_builder,
             output_handler,
             failed_tasks: Arc::new(StdMutex::new(Vec::new())),
+            retried_tasks: Arc::new(StdMutex::new(IndexMap::new())),
+            services: ServiceRegistry::default(),
+            remote_hosts: RemoteTaskHosts::default(),
+            results: TaskResults::new(config.capture_stderr),
             cache_stats: Arc::new(StdMutex::new(TaskCacheStats::default())),
             interrupted: AtomicBool::new(false),
             force: config.force,
@@ -291,6 +404,7 @@ impl TaskExecutor {
             task_cache: config.task_cache,
             task_cache_explain: config.task_cache_explain,
             task_cache_explain_json: config.task_cache_explain_json,
+            retry: config.retry,
             sandbox: config.sandbox,
         }
     }
@@ -319,6 +433,29 @@ impl TaskExecutor {
         failed.push((task, status.or(Some(1))));
     }
 
+    /// The retry policy for a task after applying the `--retry` override.
+    pub fn retry_policy(&self, task: &Task) -> Option<TaskRetry> {
+        TaskRetry::effective(task.retry.as_ref(), self.retry)
+    }
+
+    /// The 1-based attempt number of the task's current run.
+    pub fn attempt(&self, task: &Task) -> u32 {
+        self.retried_tasks
+            .lock()
+            .unwrap()
+            .get(task)
+            .copied()
+            .unwrap_or(1)
+    }
+
+    /// Records that the task will run again and returns the attempt number of the next run.
+    pub fn record_retry(&self, task: &Task) -> u32 {
+        let mut retried = self.retried_tasks.lock().unwrap();
+        let attempt = retried.entry(task.clone()).or_insert(1);
+        *attempt += 1;
+        *attempt
+    }
+
     fn eprint(&self, task: &Task, prefix: &str, line: &str) {
         self.output_handler.eprint(task, prefix, line);
     }
@@ -335,66 +472,12 @@ impl TaskExecutor {
         self.output_handler.raw(task)
     }
 
-    /// Build a SandboxConfig for a task by merging task-level config with CLI overrides.
-    ///
-    /// Task-level relative `allow_read`/`allow_write` paths are resolved against the task's
-    /// effective working directory (`task.dir(config)`, which itself falls back to `config_root`)
-    /// so that `allow_read = ["."]` means "the directory the task runs in", matching how `dir`
-    /// resolves. CLI-supplied paths are left as-is and resolved against cwd by `resolve_paths()`.
     async fn build_sandbox_for_task(
         &self,
         task: &Task,
         config: &Arc<Config>,
     ) -> Result<SandboxConfig> {
-        let task_base = task.dir(config).await?;
-        let resolve_task_path =
-            |p: &PathBuf| -> PathBuf { resolve_task_sandbox_path(p, task_base.as_deref()) };
-        let mut sandbox = SandboxConfig {
-            deny_read: task.deny_all || task.deny_read || self.sandbox.deny_read,
-            deny_write: task.deny_all || task.deny_write || self.sandbox.deny_write,
-            deny_net: task.deny_all || task.deny_net || self.sandbox.deny_net,
-            deny_env: task.deny_all || task.deny_env || self.sandbox.deny_env,
-            allow_read: task
-                .allow_read
-                .iter()
-                .map(&resolve_task_path)
-                .chain(self.sandbox.allow_read.iter().cloned())
-                .collect(),
-            allow_write: task
-                .allow_write
-                .iter()
-                .map(&resolve_task_path)
-                .chain(self.sandbox.allow_write.iter().cloned())
-                .collect(),
-            allow_net: task
-                .allow_net
-                .iter()
-                .chain(self.sandbox.allow_net.iter())
-                .cloned()
-                .collect(),
-            allow_env: task
-                .allow_env
-                .iter()
-                .chain(self.sandbox.allow_env.iter())
-                .cloned()
-                .collect(),
-            pass_through_env: task
-                .pass_through_env
-                .iter()
-                .chain(self.sandbox.pass_through_env.iter())
-                .cloned()
-                .collect(),
-            cache_env: task
-                .cache
-                .iter()
-                .filter(|cache| cache.enabled)
-                .flat_map(|cache| &cache.env)
-                .chain(self.sandbox.cache_env.iter())
-                .cloned()
-                .collect(),
-        };
-        sandbox.resolve_paths();
-        Ok(sandbox)
+        build_task_sandbox(task, config, &self.sandbox, !self.dry_run).await
     }
 
     pub fn task_timings(&self, task: Option<&Task>) -> bool {
@@ -432,13 +515,19 @@ impl TaskExecutor {
             if !self.quiet(Some(task)) {
                 self.eprint(task, &prefix, "skipping task");
             }
-            return Ok(TaskRunOutcome::default());
+            return Ok(TaskRunOutcome {
+                skipped: Some(TaskSkipReason::Setting),
+                ..Default::default()
+            });
         }
         // If any dependency executed or restored, skip the source freshness check
-        // so that downstream tasks are invalidated by upstream changes.
-        let artifact_cache_enabled =
-            self.task_cache.enabled() && task.cache.as_ref().is_some_and(|cache| cache.enabled);
-        if !artifact_cache_enabled
+        // so that downstream tasks are invalidated by upstream changes. Services
+        // are never skipped or cached since their dependents need them running.
+        let artifact_cache_enabled = task.service.is_none()
+            && self.task_cache.enabled()
+            && task.cache.as_ref().is_some_and(|cache| cache.enabled);
+        if task.service.is_none()
+            && !artifact_cache_enabled
             && !self.force
             && !dependency_state.any_did_work
             && sources_are_fresh(task, config).await?
@@ -446,7 +535,10 @@ impl TaskExecutor {
             if !self.quiet(Some(task)) {
                 self.eprint(task, &prefix, "sources up-to-date, skipping");
             }
-            return Ok(TaskRunOutcome::default());
+            return Ok(TaskRunOutcome {
+                skipped: Some(TaskSkipReason::Fresh),
+                ..Default::default()
+            });
         }
 
         let mut tools = self.tool.clone();
@@ -635,9 +727,8 @@ impl TaskExecutor {
         };
         self.check_confirmation(config, task, &env).await?;
 
-        let artifact_cache = if self.task_cache.enabled()
-            && task.cache.as_ref().is_some_and(|cache| cache.enabled)
-        {
+        let artifact_cache = if artifact_cache_enabled {
+            let _span = timeline::span("cache lookup", "cache");
             match TaskArtifactCache::prepare(task, config, self.dry_run).await? {
                 Some(_)
                     if self.dry_run
@@ -710,6 +801,8 @@ impl TaskExecutor {
                         return Ok(TaskRunOutcome {
                             did_work: false,
                             cache_key: Some(cache.key().to_string()),
+                            skipped: Some(TaskSkipReason::Fresh),
+                            cache: None,
                         });
                     }
                     if bypass_cache {
@@ -723,7 +816,11 @@ impl TaskExecutor {
                             TaskCacheMissReason::DependencyWithoutKey
                         } else {
                             Self::check_interruption(allow_during_interruption)?;
-                            match cache.restore(task).await? {
+                            let restore = {
+                                let _span = timeline::span("cache restore", "cache");
+                                cache.restore(task).await?
+                            };
+                            match restore {
                                 TaskCacheRestore::Hit(hit) => {
                                     self.cache_stats
                                         .lock()
@@ -763,6 +860,8 @@ impl TaskExecutor {
                                     return Ok(TaskRunOutcome {
                                         did_work: true,
                                         cache_key: Some(cache.key().to_string()),
+                                        skipped: None,
+                                        cache: Some(TaskCacheResult::Hit),
                                     });
                                 }
                                 TaskCacheRestore::Miss(reason) => reason,
@@ -780,6 +879,12 @@ impl TaskExecutor {
         } else {
             None
         };
+        let cache_result = artifact_cache.as_ref().map(|_| TaskCacheResult::Miss);
+        // A run that only passed after a retry is not a trustworthy cache entry
+        // unless the task opts in with `retry.cache`.
+        let artifact_cache = artifact_cache.filter(|_| {
+            self.attempt(task) == 1 || self.retry_policy(task).is_some_and(|retry| retry.cache)
+        });
         let output_capture = artifact_cache
             .as_ref()
             .filter(|_| self.task_cache.writes())
@@ -790,12 +895,31 @@ impl TaskExecutor {
             prefix: &prefix,
             output_capture: output_capture.as_ref(),
             allow_during_interruption,
+            remote: false,
         };
 
         let timer = std::time::Instant::now();
 
-        if let Some(file) = task_file {
+        if let Some(target) = task
+            .remote
+            .as_ref()
+            .filter(|_| !*crate::env::MISE_REMOTE_TASK)
+        {
             let exec_start = std::time::Instant::now();
+            let _span = timeline::span("exec", "exec");
+            Self::check_interruption(allow_during_interruption)?;
+            remove_auto_output(task, config).await?;
+            self.exec_remote(config, target, confirm_guard, exec_ctx)
+                .await?;
+            trace!(
+                "task {} exec_remote took {}ms (total {}ms)",
+                task.name,
+                exec_start.elapsed().as_millis(),
+                total_start.elapsed().as_millis()
+            );
+        } else if let Some(file) = task_file {
+            let exec_start = std::time::Instant::now();
+            let _span = timeline::span("exec", "exec");
             Self::check_interruption(allow_during_interruption)?;
             remove_auto_output(task, config).await?;
             self.exec_file(config, &file, confirm_guard, exec_ctx)
@@ -818,6 +942,7 @@ impl TaskExecutor {
                 .await?;
 
             let exec_start = std::time::Instant::now();
+            let _span = timeline::span("exec", "exec");
             Self::check_interruption(allow_during_interruption)?;
             remove_auto_output(task, config).await?;
             self.exec_task_run_entries(
@@ -861,7 +986,11 @@ impl TaskExecutor {
                 .as_ref()
                 .map(|output| output.lock().unwrap().clone())
                 .unwrap_or_default();
-            match cache.store(task, &output, execution_duration).await {
+            let stored = {
+                let _span = timeline::span("cache store", "cache");
+                cache.store(task, &output, execution_duration).await
+            };
+            match stored {
                 Ok(()) => {
                     if let Err(err) = cache.mark_current() {
                         warn!(
@@ -883,6 +1012,8 @@ impl TaskExecutor {
         Ok(TaskRunOutcome {
             did_work: true,
             cache_key,
+            skipped: None,
+            cache: cache_result,
         })
     }
 
@@ -1497,6 +1628,60 @@ impl TaskExecutor {
         self.exec(file, &args, ctx).await
     }
 
+    /// Runs the task through mise on a `[bootstrap.remote.hosts]` host: its sources are synced
+    /// there first and its outputs are copied back afterwards.
+    async fn exec_remote(
+        &self,
+        config: &Arc<Config>,
+        target: &str,
+        guard: Option<RuntimeLockGuard<'static>>,
+        ctx: TaskExecContext<'_>,
+    ) -> Result<()> {
+        let task = ctx.task;
+        let cwd = task_cwd(task, config).await?;
+        let root = task_source_match_root(&cwd, config);
+        let args = task.args.iter().cloned().collect_vec();
+        let print_header = |host: &str| {
+            if !self.quiet(Some(task)) {
+                let cmd = format!("mise run {} {}", task.name, args.join(" "));
+                let cmd = style::ebold(format!("$ {} on {host}", cmd.trim()))
+                    .bright()
+                    .to_string();
+                let cmd = trunc(ctx.prefix, config.redact(&cmd).trim());
+                self.eprint(task, ctx.prefix, &cmd);
+            }
+        };
+        if self.dry_run {
+            print_header(target);
+            return Ok(());
+        }
+        let host = self.remote_hosts.get(config, target, &root).await?;
+        host.upload(&task_source_files(task, config).await?)?;
+        print_header(host.name());
+
+        let _guard = if guard.is_some() {
+            guard
+        } else {
+            Some(acquire_runtime_lock(task.interactive).await)
+        };
+        let (program, args) = host.run_command(&cwd, &task.name, &args)?;
+        self.exec_program(
+            &program,
+            &args,
+            false,
+            TaskExecContext {
+                remote: true,
+                ..ctx
+            },
+        )
+        .await?;
+        let outputs = task.outputs.patterns();
+        let matcher = build_output_matcher(&cwd, &outputs)?;
+        host.download(&output_roots(&cwd, &outputs), |path| {
+            is_output(&matcher, path, false)
+        })
+    }
+
     async fn exec(&self, file: &Path, args: &[String], ctx: TaskExecContext<'_>) -> Result<()> {
         let (program, args) = self.get_file_program_and_args(file, ctx.task, args)?;
         self.exec_program(&program, &args, false, ctx).await
@@ -1545,6 +1730,7 @@ impl TaskExecutor {
             prefix,
             output_capture,
             allow_during_interruption,
+            remote,
         } = ctx;
         #[cfg(not(windows))]
         let _ = cmd_verbatim;
@@ -1552,7 +1738,11 @@ impl TaskExecutor {
         let program = program.to_executable();
         let redactions = config.redactions();
         let raw = self.raw(Some(task));
-        let sandbox = self.build_sandbox_for_task(task, &config).await?;
+        let sandbox = if remote {
+            SandboxConfig::default()
+        } else {
+            self.build_sandbox_for_task(task, &config).await?
+        };
         let env = if sandbox.is_active() {
             &sandbox.filter_env(env)
         } else {
@@ -1566,7 +1756,7 @@ impl TaskExecutor {
         let program =
             crate::path::resolve_posix_shell_program_path(&program, env).unwrap_or(program);
         let env = maybe_convert_env_for_msys_shell(Path::new(&program), env);
-        let audit = if raw || self.dry_run {
+        let audit = if raw || remote || self.dry_run {
             None
         } else {
             TaskCacheAudit::prepare(task, &config).await?
@@ -1600,6 +1790,8 @@ impl TaskExecutor {
             })
             .map(|(key, _)| key);
         let runner = inherited_usage_keys.fold(runner, |runner, key| runner.env_remove(key));
+        // An output limit needs mise to read the task's output, so it is never inherited.
+        let output_limited = sandbox.limits.output_bytes.is_some();
         let mut cmd = runner
             .envs(env.as_ref())
             .redact(redactions.deref().clone())
@@ -1754,36 +1946,62 @@ impl TaskExecutor {
                     if task.silent.suppresses_stderr() {
                         cmd = cmd.with_on_stderr(|_| {});
                     }
-                } else if raw || redactions.is_empty() {
+                } else if (raw || redactions.is_empty()) && !output_limited {
                     if !task.silent.suppresses_stdout() {
                         cmd = cmd.stdout(Stdio::inherit());
                     } else {
                         cmd = cmd.stdout(Stdio::null());
                     }
-                    if !task.silent.suppresses_stderr() {
-                        cmd = cmd.stderr(Stdio::inherit());
-                    } else {
+                    // Keep stderr piped when it is observed for `--report`.
+                    if task.silent.suppresses_stderr() {
                         cmd = cmd.stderr(Stdio::null());
+                    } else if !self.results.captures_stderr() {
+                        cmd = cmd.stderr(Stdio::inherit());
                     }
                 }
             }
         }
-        if let Some(output_capture) = output_capture {
-            let stdout = output_capture.clone();
-            let stderr = output_capture.clone();
-            cmd = cmd
-                .with_stdout_observer(move |line| {
-                    stdout
+        // Output is observed for the task output cache, `--report` and
+        // service log probes. Services are never cached.
+        let service = self.services.get(task);
+        let log_probe = service.clone().filter(|service| service.uses_log_probe());
+        if let Some(service) = &service {
+            let on_spawn = service.clone();
+            cmd = cmd.with_on_spawn(move |pid| on_spawn.set_pid(Some(pid)));
+        }
+        if output_capture.is_some() || log_probe.is_some() {
+            let output_capture = output_capture.cloned();
+            let log_probe = log_probe.clone();
+            cmd = cmd.with_stdout_observer(move |line| {
+                if let Some(output_capture) = &output_capture {
+                    output_capture
                         .lock()
                         .unwrap()
                         .push(TaskCacheOutput::Stdout(line.to_string()));
-                })
-                .with_stderr_observer(move |line| {
-                    stderr
+                }
+                if let Some(log_probe) = &log_probe {
+                    log_probe.observe_line(line);
+                }
+            });
+        }
+        let results = Some(self.results.clone()).filter(|results| results.captures_stderr());
+        if output_capture.is_some() || log_probe.is_some() || results.is_some() {
+            let output_capture = output_capture.cloned();
+            let task = task.clone();
+            cmd = cmd.with_stderr_observer(move |line| {
+                if let Some(output_capture) = &output_capture {
+                    output_capture
                         .lock()
                         .unwrap()
                         .push(TaskCacheOutput::Stderr(line.to_string()));
-                });
+                }
+                if let Some(log_probe) = &log_probe {
+                    log_probe.observe_line(line);
+                }
+                if let Some(results) = &results {
+                    results.observe_stderr(&task, line);
+                }
+            });
         }
         let dir = task_cwd(task, &config).await?;
         if !dir.exists() {
@@ -1797,7 +2015,7 @@ impl TaskExecutor {
                 ),
             );
         }
-        cmd = cmd.current_dir(dir);
+        cmd = cmd.current_dir(&dir);
         if self.dry_run {
             return Ok(());
         }
@@ -1816,13 +2034,15 @@ impl TaskExecutor {
         }
         // Apply sandbox async (DNS resolution for macOS) before spawning.
         cmd.apply_sandbox().await?;
-        let result = cmd
-            .execute_async_with_cancel_check(|| {
-                !allow_during_interruption && crate::ui::ctrlc::is_cancelled()
-            })
-            .await;
+        let run = cmd.execute_async_with_cancel_check(|| {
+            !allow_during_interruption && crate::ui::ctrlc::is_cancelled()
+        });
+        let result = match &service {
+            Some(service) => service.supervise(run, env.as_ref(), &dir).await,
+            None => run.await,
+        };
         if let Some(audit) = audit {
-            audit.report(task);
+            audit.report(task)?;
         }
         result?;
         trace!("{prefix} exited successfully");
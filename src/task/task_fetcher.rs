@@ -1,6 +1,8 @@
 use crate::config::{Config, Settings};
+use crate::lockfile;
 use crate::task::Task;
 use crate::task::task_file_providers::TaskFileProvidersBuilder;
+use crate::task::task_remote_integrity::{self, TaskIntegrity};
 use eyre::{Result, bail};
 use std::sync::Arc;
 
@@ -30,13 +32,34 @@ impl TaskFetcher {
                     continue;
                 }
 
+                let integrity = Self::expected_integrity(config, t, &source)?;
+                task_remote_integrity::check_remote_source(&source, integrity.is_some())?;
+
                 let provider = task_file_providers.get_provider(&source);
 
                 if provider.is_none() {
                     bail!("No provider found for file: {}", source);
                 }
 
-                let local_path = provider.unwrap().get_local_path(&source).await?;
+                let mut local_path = provider.unwrap().get_local_path(&source).await?;
+                if let Some(integrity) = &integrity
+                    && let Err(err) = integrity.verify(&source, &local_path)
+                {
+                    if no_cache {
+                        return Err(err);
+                    }
+                    // The cached copy may predate the pin, so fetch it once more before failing.
+                    debug!("{err}, fetching {source} again");
+                    local_path = TaskFileProvidersBuilder::new()
+                        .with_cache(true)
+                        .with_refresh(true)
+                        .build()
+                        .get_provider(&source)
+                        .unwrap()
+                        .get_local_path(&source)
+                        .await?;
+                    integrity.verify(&source, &local_path)?;
+                }
                 let original = t.clone();
                 let config_root = original
                     .config_root
@@ -80,6 +103,26 @@ impl TaskFetcher {
         Ok(())
     }
 
+    /// The content a remote task file must have: its `integrity`, or else the
+    /// checksum `mise tasks lock` recorded in its config's lockfile.
+    fn expected_integrity(
+        config: &Arc<Config>,
+        task: &Task,
+        source: &str,
+    ) -> Result<Option<TaskIntegrity>> {
+        if let Some(integrity) = &task.integrity {
+            return Ok(Some(integrity.parse()?));
+        }
+        if !Settings::get().lockfile_enabled() {
+            return Ok(None);
+        }
+        let lockfile = lockfile::read_lockfile_for_config_path(config, &task.config_source);
+        lockfile
+            .get_remote_task(source)
+            .map(|info| info.checksum.parse())
+            .transpose()
+    }
+
     /// Check if a source path is a remote task file (git or http/https)
     pub fn is_remote_source(source: &str) -> bool {
         source.starts_with("git::")
@@ -233,4 +276,45 @@ echo ok
         remote.assert_async().await;
         assert_eq!(tasks[0].description, "rendered from runtime context");
     }
+
+    #[tokio::test]
+    async fn test_fetch_remote_task_verifies_integrity() {
+        let mut server = mockito::Server::new_async().await;
+        let body = "#!/usr/bin/env bash\necho ok\n";
+        server
+            .mock("GET", "/pinned-task")
+            .with_status(200)
+            .with_body(body)
+            .create_async()
+            .await;
+
+        let config = Config::get().await.unwrap();
+        let config_root = tempfile::tempdir().unwrap();
+        let source = format!("{}/pinned-task", server.url());
+        let new_task = |integrity: String| Task {
+            name: "pinned".into(),
+            config_source: config_root.path().join("mise.toml"),
+            config_root: Some(config_root.path().to_path_buf()),
+            file: Some(PathBuf::from(&source)),
+            integrity: Some(integrity),
+            ..Default::default()
+        };
+
+        let sha256 = crate::hash::hash_sha256_to_str(body);
+        let mut tasks = vec![new_task(format!("sha256:{sha256}"))];
+        TaskFetcher::new(true)
+            .fetch_tasks(&config, &mut tasks)
+            .await
+            .unwrap();
+
+        let mut tasks = vec![new_task("0".repeat(64))];
+        let err = TaskFetcher::new(true)
+            .fetch_tasks(&config, &mut tasks)
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("integrity mismatch"),
+            "unexpected error: {err}"
+        );
+    }
 }
@@ -17,11 +17,15 @@ pub trait TaskFileProvider: Debug + Send + Sync {
 
 pub struct TaskFileProvidersBuilder {
     use_cache: bool,
+    refresh: bool,
 }
 
 impl TaskFileProvidersBuilder {
     pub fn new() -> Self {
-        Self { use_cache: false }
+        Self {
+            use_cache: false,
+            refresh: false,
+        }
     }
 
     pub fn with_cache(mut self, use_cache: bool) -> Self {
@@ -29,18 +33,25 @@ impl TaskFileProvidersBuilder {
         self
     }
 
+    /// Fetch files again even when they are cached, replacing the cached copy.
+    pub fn with_refresh(mut self, refresh: bool) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
     pub fn build(self) -> TaskFileProviders {
-        TaskFileProviders::new(self.use_cache)
+        TaskFileProviders::new(self.use_cache, self.refresh)
     }
 }
 
 pub struct TaskFileProviders {
     use_cache: bool,
+    refresh: bool,
 }
 
 impl TaskFileProviders {
-    pub fn new(use_cache: bool) -> Self {
-        Self { use_cache }
+    pub fn new(use_cache: bool, refresh: bool) -> Self {
+        Self { use_cache, refresh }
     }
 
     fn get_providers(&self) -> Vec<Box<dyn TaskFileProvider>> {
@@ -48,11 +59,13 @@ impl TaskFileProviders {
             Box::new(
                 RemoteTaskGitBuilder::new()
                     .with_cache(self.use_cache)
+                    .with_refresh(self.refresh)
                     .build(),
             ),
             Box::new(
                 RemoteTaskHttpBuilder::new()
                     .with_cache(self.use_cache)
+                    .with_refresh(self.refresh)
                     .build(),
             ),
             Box::new(LocalTask), // Must be the last provider
@@ -18,6 +18,7 @@ use super::TaskFileProvider;
 pub struct RemoteTaskGitBuilder {
     store_path: PathBuf,
     use_cache: bool,
+    refresh: bool,
 }
 
 impl RemoteTaskGitBuilder {
@@ -25,6 +26,7 @@ impl RemoteTaskGitBuilder {
         Self {
             store_path: env::temp_dir(),
             use_cache: false,
+            refresh: false,
         }
     }
 
@@ -36,10 +38,16 @@ impl RemoteTaskGitBuilder {
         self
     }
 
+    pub fn with_refresh(mut self, refresh: bool) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
     pub fn build(self) -> RemoteTaskGit {
         RemoteTaskGit {
             storage_path: self.store_path,
             is_cached: self.use_cache,
+            refresh: self.refresh,
         }
     }
 }
@@ -48,6 +56,7 @@ impl RemoteTaskGitBuilder {
 pub struct RemoteTaskGit {
     storage_path: PathBuf,
     is_cached: bool,
+    refresh: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -141,7 +150,7 @@ impl TaskFileProvider for RemoteTaskGit {
 
         if self.is_cached {
             trace!("Cache mode enabled");
-            if full_path.exists() {
+            if full_path.exists() && !self.refresh {
                 debug!("Using cached file: {:?}", full_path);
                 Self::prepare_remote_path(&full_path)?;
                 return Ok(full_path);
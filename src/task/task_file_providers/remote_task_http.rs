@@ -10,6 +10,7 @@ use super::TaskFileProvider;
 pub struct RemoteTaskHttpBuilder {
     store_path: PathBuf,
     use_cache: bool,
+    refresh: bool,
 }
 
 impl RemoteTaskHttpBuilder {
@@ -17,6 +18,7 @@ impl RemoteTaskHttpBuilder {
         Self {
             store_path: env::temp_dir(),
             use_cache: false,
+            refresh: false,
         }
     }
 
@@ -28,10 +30,16 @@ impl RemoteTaskHttpBuilder {
         self
     }
 
+    pub fn with_refresh(mut self, refresh: bool) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
     pub fn build(self) -> RemoteTaskHttp {
         RemoteTaskHttp {
             storage_path: self.store_path,
             is_cached: self.use_cache,
+            refresh: self.refresh,
         }
     }
 }
@@ -40,6 +48,7 @@ impl RemoteTaskHttpBuilder {
 pub struct RemoteTaskHttp {
     storage_path: PathBuf,
     is_cached: bool,
+    refresh: bool,
 }
 
 impl RemoteTaskHttp {
@@ -69,7 +78,7 @@ impl TaskFileProvider for RemoteTaskHttp {
             true => {
                 trace!("Cache mode enabled");
 
-                if destination.exists() {
+                if destination.exists() && !self.refresh {
                     debug!("Using cached file: {:?}", destination);
                     return Ok(destination);
                 }
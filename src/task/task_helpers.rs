@@ -6,7 +6,9 @@ use std::path::{Path, PathBuf};
 /// Only shell/script tasks execute external commands and need a concurrency slot.
 /// Orchestrator-only tasks (pure groups of sub-tasks) do not.
 pub fn task_needs_permit(task: &Task) -> bool {
-    task.file.is_some() || !task.run_script_strings().is_empty()
+    // Services keep running while their dependents do, so holding a job slot
+    // for their whole lifetime could starve the tasks waiting on them.
+    task.service.is_none() && (task.file.is_some() || !task.run_script_strings().is_empty())
 }
 
 /// Canonicalize a path for use as cache key
@@ -0,0 +1,317 @@
+use std::collections::BTreeMap;
+
+use eyre::bail;
+use indexmap::IndexMap;
+use itertools::Itertools;
+use serde::{Deserialize, Deserializer};
+
+use crate::Result;
+use crate::config::env_directive::EnvDirective;
+use crate::task::{Task, TaskDep, TaskToolValue};
+
+/// Values a task is run with, e.g. `matrix = { node = ["20", "22"], db = ["pg", "sqlite"] }`.
+///
+/// A task with a matrix expands into one task per combination of values, named like
+/// `test[node=20,db=pg]`. The original task name runs every combination.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TaskMatrix(pub IndexMap<String, Vec<String>>);
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum MatrixValue {
+    String(String),
+    Integer(i64),
+    Bool(bool),
+}
+
+impl<'de> Deserialize<'de> for TaskMatrix {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let raw = IndexMap::<String, Vec<MatrixValue>>::deserialize(deserializer).map_err(|e| {
+            serde::de::Error::custom(format!(
+                "matrix values must be lists of strings, integers or booleans: {e}"
+            ))
+        })?;
+        Ok(Self(
+            raw.into_iter()
+                .map(|(key, values)| {
+                    let values = values
+                        .into_iter()
+                        .map(|v| match v {
+                            MatrixValue::String(s) => s,
+                            MatrixValue::Integer(i) => i.to_string(),
+                            MatrixValue::Bool(b) => b.to_string(),
+                        })
+                        .unique()
+                        .collect();
+                    (key, values)
+                })
+                .collect(),
+        ))
+    }
+}
+
+impl TaskMatrix {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Every combination of values, with the first key varying slowest.
+    fn combinations(&self) -> Vec<Vec<(&String, &String)>> {
+        self.0
+            .iter()
+            .map(|(key, values)| values.iter().map(move |value| (key, value)))
+            .multi_cartesian_product()
+            .collect()
+    }
+}
+
+/// Environment variable that exposes a matrix value to the task, e.g. `MISE_MATRIX_NODE`.
+pub fn matrix_env_key(key: &str) -> String {
+    let key = key
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    format!("MISE_MATRIX_{key}")
+}
+
+/// Replaces every task that declares a `matrix` with one task per combination of
+/// its values. The original task is kept as a group that depends on all of them,
+/// so `mise run test` runs every combination in parallel.
+pub(crate) fn expand_task_matrices(tasks: &mut BTreeMap<String, Task>) -> Result<()> {
+    let matrix_tasks = tasks
+        .values()
+        .filter(|t| !t.matrix.is_empty())
+        .cloned()
+        .collect_vec();
+    for task in matrix_tasks {
+        for instance in expand(&task)? {
+            if tasks.contains_key(&instance.name) {
+                bail!(
+                    "matrix task {} conflicts with an existing task of the same name",
+                    instance.name
+                );
+            }
+            tasks.insert(instance.name.clone(), instance);
+        }
+        let group = tasks.get_mut(&task.name).unwrap();
+        *group = matrix_group(&task);
+    }
+    Ok(())
+}
+
+fn expand(task: &Task) -> Result<Vec<Task>> {
+    if let Some((key, _)) = task.matrix.0.iter().find(|(_, values)| values.is_empty()) {
+        bail!("matrix key {key} of task {} has no values", task.name);
+    }
+    Ok(task
+        .matrix
+        .combinations()
+        .into_iter()
+        .map(|combination| instance(task, &combination))
+        .collect())
+}
+
+fn instance_name(name: &str, combination: &[(&String, &String)]) -> String {
+    let values = combination
+        .iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .join(",");
+    format!("{name}[{values}]")
+}
+
+fn instance(task: &Task, combination: &[(&String, &String)]) -> Task {
+    let mut instance = task.clone();
+    instance.name = instance_name(&task.name, combination);
+    instance.display_name = instance.name.clone();
+    instance.aliases = vec![];
+    instance.matrix = TaskMatrix::default();
+    for (key, value) in combination {
+        if is_tool(task, key) {
+            let tool = match instance.tools.shift_remove(*key) {
+                Some(TaskToolValue::Map(mut map)) => {
+                    map.version = value.to_string();
+                    TaskToolValue::Map(map)
+                }
+                _ => TaskToolValue::String(value.to_string()),
+            };
+            instance.tools.insert(key.to_string(), tool);
+        }
+        instance.env.0.push(EnvDirective::Val(
+            matrix_env_key(key),
+            value.to_string(),
+            Default::default(),
+        ));
+    }
+    instance
+}
+
+/// Matrix keys that name one of the task's tools, or an explicit `backend:tool`, set that
+/// tool's version for the instance. Other keys only set `MISE_MATRIX_*`, even if they share
+/// a name with a registry tool like `kind` or `redis`.
+fn is_tool(task: &Task, key: &str) -> bool {
+    task.tools.contains_key(key) || key.contains(':')
+}
+
+/// The original task becomes a group that only depends on its instances.
+fn matrix_group(task: &Task) -> Task {
+    let depends = task
+        .matrix
+        .combinations()
+        .iter()
+        .map(|combination| TaskDep {
+            task: instance_name(&task.name, combination),
+            args: vec![],
+            env: Default::default(),
+            optional: false,
+        })
+        .collect_vec();
+    Task {
+        name: task.name.clone(),
+        display_name: task.display_name.clone(),
+        description: task.description.clone(),
+        aliases: task.aliases.clone(),
+        config_source: task.config_source.clone(),
+        cf: task.cf.clone(),
+        config_root: task.config_root.clone(),
+        dir: task.dir.clone(),
+        hide: task.hide,
+        global: task.global,
+        depends,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix_task(name: &str, matrix: &str) -> Task {
+        #[derive(Deserialize)]
+        struct T {
+            matrix: TaskMatrix,
+        }
+        Task {
+            name: name.to_string(),
+            matrix: toml::from_str::<T>(matrix).unwrap().matrix,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_matrix() {
+        let task = matrix_task(
+            "test",
+            r#"matrix = { node = [20, "22", 20], debug = [true, false] }"#,
+        );
+        assert_eq!(
+            task.matrix.0,
+            IndexMap::from([
+                ("node".to_string(), vec!["20".to_string(), "22".to_string()]),
+                (
+                    "debug".to_string(),
+                    vec!["true".to_string(), "false".to_string()]
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_expand_matrix() {
+        let mut task = matrix_task(
+            "test",
+            r#"matrix = { node = ["20", "22"], db = ["pg", "sqlite"] }"#,
+        );
+        task.aliases = vec!["t".into()];
+        task.tools
+            .insert("node".into(), TaskToolValue::String("20".into()));
+        let mut tasks = BTreeMap::from([(task.name.clone(), task)]);
+        expand_task_matrices(&mut tasks).unwrap();
+
+        assert_eq!(
+            tasks.keys().collect_vec(),
+            vec![
+                "test",
+                "test[node=20,db=pg]",
+                "test[node=20,db=sqlite]",
+                "test[node=22,db=pg]",
+                "test[node=22,db=sqlite]",
+            ]
+        );
+        let group = &tasks["test"];
+        assert!(group.matrix.is_empty());
+        assert!(group.run.is_empty());
+        assert_eq!(group.aliases, vec!["t"]);
+        assert_eq!(
+            group.depends.iter().map(|d| d.task.as_str()).collect_vec(),
+            vec![
+                "test[node=20,db=pg]",
+                "test[node=20,db=sqlite]",
+                "test[node=22,db=pg]",
+                "test[node=22,db=sqlite]",
+            ]
+        );
+
+        let instance = &tasks["test[node=22,db=sqlite]"];
+        assert!(instance.aliases.is_empty());
+        assert_eq!(
+            instance.tools.get("node"),
+            Some(&TaskToolValue::String("22".to_string()))
+        );
+        assert!(!instance.tools.contains_key("db"));
+        let env = instance
+            .env
+            .0
+            .iter()
+            .filter_map(|d| match d {
+                EnvDirective::Val(k, v, _) => Some((k.as_str(), v.as_str())),
+                _ => None,
+            })
+            .collect_vec();
+        assert_eq!(
+            env,
+            vec![("MISE_MATRIX_NODE", "22"), ("MISE_MATRIX_DB", "sqlite")]
+        );
+    }
+
+    #[test]
+    fn test_expand_matrix_registry_named_key() {
+        let task = matrix_task("test", r#"matrix = { kind = ["unit", "integration"] }"#);
+        let mut tasks = BTreeMap::from([(task.name.clone(), task)]);
+        expand_task_matrices(&mut tasks).unwrap();
+
+        let instance = &tasks["test[kind=unit]"];
+        assert!(instance.tools.is_empty());
+        assert!(matches!(
+            instance.env.0.as_slice(),
+            [EnvDirective::Val(k, v, _)] if k == "MISE_MATRIX_KIND" && v == "unit"
+        ));
+    }
+
+    #[test]
+    fn test_expand_matrix_errors() {
+        let task = matrix_task("test", r#"matrix = { node = [] }"#);
+        let mut tasks = BTreeMap::from([(task.name.clone(), task)]);
+        assert!(expand_task_matrices(&mut tasks).is_err());
+
+        let task = matrix_task("test", r#"matrix = { db = ["pg"] }"#);
+        let existing = Task {
+            name: "test[db=pg]".to_string(),
+            ..Default::default()
+        };
+        let mut tasks =
+            BTreeMap::from([(task.name.clone(), task), (existing.name.clone(), existing)]);
+        assert!(expand_task_matrices(&mut tasks).is_err());
+    }
+
+    #[test]
+    fn test_matrix_env_key() {
+        assert_eq!(matrix_env_key("node"), "MISE_MATRIX_NODE");
+        assert_eq!(matrix_env_key("feature-flag"), "MISE_MATRIX_FEATURE_FLAG");
+    }
+}
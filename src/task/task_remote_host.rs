@@ -0,0 +1,71 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use eyre::bail;
+use indexmap::IndexMap;
+use itertools::Itertools;
+use tokio::sync::{Mutex, OnceCell};
+
+use crate::Result;
+use crate::config::Config;
+use crate::system::remote::{self, RemoteTaskHost};
+
+const CONNECT_TIMEOUT_SECS: u16 = 10;
+
+type HostCell = Arc<OnceCell<Arc<RemoteTaskHost>>>;
+
+/// Hosts from `[bootstrap.remote.hosts]` that tasks of this run execute on.
+///
+/// Each host is staged once per project root and reused by every task that targets it.
+#[derive(Default)]
+pub struct RemoteTaskHosts(Mutex<IndexMap<(String, PathBuf), HostCell>>);
+
+impl RemoteTaskHosts {
+    /// The staged host for `target`, a host name or tag, mirroring the project at `root`.
+    pub async fn get(
+        &self,
+        config: &Arc<Config>,
+        target: &str,
+        root: &Path,
+    ) -> Result<Arc<RemoteTaskHost>> {
+        let cell = self
+            .0
+            .lock()
+            .await
+            .entry((target.to_string(), root.to_path_buf()))
+            .or_default()
+            .clone();
+        cell.get_or_try_init(|| async {
+            let mut host = resolve_host(config, target)?;
+            host.source = root.to_path_buf();
+            Ok(Arc::new(
+                RemoteTaskHost::connect(host, CONNECT_TIMEOUT_SECS).await?,
+            ))
+        })
+        .await
+        .cloned()
+    }
+
+    /// Removes the staging directories and closes the connections of every host.
+    pub async fn disconnect(&self) {
+        self.0.lock().await.clear();
+    }
+}
+
+fn resolve_host(config: &Config, target: &str) -> Result<remote::RemoteHost> {
+    let excludes = remote::excludes_from_config(config);
+    let inventory = remote::hosts_from_config(config, &excludes)?;
+    if inventory.is_empty() {
+        bail!("no remote host '{target}': [bootstrap.remote.hosts] is empty");
+    }
+    let host = inventory
+        .get(target)
+        .or_else(|| inventory.values().find(|host| host.tags.contains(target)));
+    match host {
+        Some(host) => Ok(host.clone()),
+        None => bail!(
+            "no remote host or tag '{target}', configured hosts: {}",
+            inventory.keys().join(", ")
+        ),
+    }
+}
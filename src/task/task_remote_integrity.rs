@@ -0,0 +1,171 @@
+use std::fmt::{self, Display};
+use std::path::Path;
+use std::str::FromStr;
+
+use base64::prelude::*;
+use eyre::bail;
+
+use crate::Result;
+use crate::config::Settings;
+use crate::git::looks_like_sha;
+use crate::hash;
+use crate::remote_source::RemoteSource;
+
+/// The expected content of a remote task file, from the task's `integrity` or `mise.lock`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskIntegrity {
+    sha256: String,
+}
+
+impl FromStr for TaskIntegrity {
+    type Err = eyre::Error;
+
+    /// Parses `sha256:<hex>`, a subresource integrity `sha256-<base64>` or a bare sha256 hex digest.
+    fn from_str(input: &str) -> eyre::Result<Self> {
+        let sha256 = if let Some(hex) = input.strip_prefix("sha256:") {
+            hex.to_ascii_lowercase()
+        } else if let Some(b64) = input.strip_prefix("sha256-") {
+            BASE64_STANDARD
+                .decode(b64)
+                .unwrap_or_default()
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect()
+        } else {
+            input.to_ascii_lowercase()
+        };
+        if sha256.len() != 64 || !sha256.bytes().all(|b| b.is_ascii_hexdigit()) {
+            bail!(
+                "invalid integrity {input:?}, expected sha256:<hex>, sha256-<base64> or a sha256 hex digest"
+            );
+        }
+        Ok(Self { sha256 })
+    }
+}
+
+impl Display for TaskIntegrity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "sha256:{}", self.sha256)
+    }
+}
+
+impl TaskIntegrity {
+    pub fn of_file(path: &Path) -> Result<Self> {
+        Ok(Self {
+            sha256: hash::file_hash_sha256(path, None)?,
+        })
+    }
+
+    /// Checks that the fetched file of the remote task `source` has this content.
+    pub fn verify(&self, source: &str, path: &Path) -> Result<()> {
+        let actual = Self::of_file(path)?;
+        if &actual != self {
+            bail!(
+                "integrity mismatch for remote task {source}\nexpected: {self}\n  actual: {actual}"
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Checks that `source` may be fetched under the `task.remote_allowlist` and
+/// `task.remote_require_pinned` settings. `has_integrity` is whether its
+/// content will be verified after fetching.
+pub fn check_remote_source(source: &str, has_integrity: bool) -> Result<()> {
+    let settings = Settings::get();
+    let allowlist = &settings.task.remote_allowlist;
+    if !allowlist.is_empty() && !allowlist.iter().any(|entry| is_allowed(source, entry)) {
+        bail!(
+            "remote task {source} is not allowed by task.remote_allowlist: {}",
+            allowlist.join(", ")
+        );
+    }
+    if settings.task.remote_require_pinned {
+        match RemoteSource::parse_git(source) {
+            Some(git) if !git.git_ref.as_deref().is_some_and(looks_like_sha) => bail!(
+                "remote task {source} must use ?ref= with a full commit SHA when task.remote_require_pinned is enabled"
+            ),
+            Some(_) => {}
+            None if !has_integrity => bail!(
+                "remote task {source} must have an integrity or a checksum in mise.lock when task.remote_require_pinned is enabled\n\
+                hint: run `mise tasks lock` to record its checksum"
+            ),
+            None => {}
+        }
+    }
+    Ok(())
+}
+
+/// Whether `source` is at the host and path prefix of an allowlist entry like `github.com/myorg`.
+fn is_allowed(source: &str, entry: &str) -> bool {
+    let url = match RemoteSource::parse_git(source) {
+        Some(git) => git.url,
+        None => source.to_string(),
+    };
+    let Ok(url) = url::Url::parse(&url) else {
+        return false;
+    };
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    let entry = entry.trim_end_matches('/');
+    let (entry_host, prefix) = match entry.split_once('/') {
+        Some((entry_host, prefix)) => (entry_host, Some(prefix)),
+        None => (entry, None),
+    };
+    if !host.eq_ignore_ascii_case(entry_host) {
+        return false;
+    }
+    let Some(prefix) = prefix else {
+        return true;
+    };
+    let path = url.path().trim_start_matches('/');
+    path == prefix
+        || path.strip_suffix(".git") == Some(prefix)
+        || path.starts_with(&format!("{prefix}/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_integrity() {
+        let hex = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+        let expected = format!("sha256:{hex}");
+        assert_eq!(hex.parse::<TaskIntegrity>().unwrap().to_string(), expected);
+        assert_eq!(
+            format!("sha256:{}", hex.to_uppercase())
+                .parse::<TaskIntegrity>()
+                .unwrap()
+                .to_string(),
+            expected
+        );
+        assert_eq!(
+            "sha256-LPJNul+wow4m6DsqxbninhsWHlwfp0JecwQzYpOLmCQ="
+                .parse::<TaskIntegrity>()
+                .unwrap()
+                .to_string(),
+            expected
+        );
+        assert!("sha256:abc".parse::<TaskIntegrity>().is_err());
+        assert!(
+            "md5-XUFAKrxLKna5cZ2REBfFkg=="
+                .parse::<TaskIntegrity>()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_is_allowed() {
+        let http = "https://example.com/scripts/build.sh";
+        let git = "git::ssh://git@github.com/myorg/tasks.git//lint.sh?ref=v1";
+        assert!(is_allowed(http, "example.com"));
+        assert!(is_allowed(http, "example.com/scripts"));
+        assert!(!is_allowed(http, "example.com/script"));
+        assert!(!is_allowed(http, "evil.com"));
+        assert!(is_allowed(git, "github.com/myorg"));
+        assert!(is_allowed(git, "github.com/myorg/tasks"));
+        assert!(!is_allowed(git, "github.com/otherorg"));
+    }
+}
@@ -0,0 +1,325 @@
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use eyre::{WrapErr, bail};
+use quick_xml::escape::escape;
+use serde_json::json;
+
+use crate::Result;
+use crate::file;
+use crate::task::task_results::{TaskCacheResult, TaskResult, TaskResultStatus};
+
+/// A report of the run's task results requested with `--report FORMAT=PATH`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskReport {
+    pub format: TaskReportFormat,
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::EnumString, strum::Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum TaskReportFormat {
+    Junit,
+    Ctrf,
+    Json,
+}
+
+impl FromStr for TaskReport {
+    type Err = eyre::Error;
+
+    fn from_str(input: &str) -> eyre::Result<Self> {
+        let Some((format, path)) = input.split_once('=') else {
+            bail!("invalid report {input:?}, expected FORMAT=PATH, e.g. junit=report.xml");
+        };
+        let format = format.parse().map_err(|_| {
+            eyre::eyre!("unknown report format {format:?}, expected junit, ctrf or json")
+        })?;
+        if path.is_empty() {
+            bail!("invalid report {input:?}, expected FORMAT=PATH, e.g. junit=report.xml");
+        }
+        Ok(Self {
+            format,
+            path: PathBuf::from(path),
+        })
+    }
+}
+
+impl TaskReport {
+    /// Writes the results of a run that started at `start` and took `duration`.
+    pub fn write(
+        &self,
+        results: &[TaskResult],
+        start: SystemTime,
+        duration: Duration,
+    ) -> Result<()> {
+        let body = match self.format {
+            TaskReportFormat::Junit => junit(results, start, duration),
+            TaskReportFormat::Ctrf => ctrf(results, start, duration)?,
+            TaskReportFormat::Json => json(results, duration)?,
+        };
+        if let Some(parent) = self.path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            file::create_dir_all(parent)?;
+        }
+        file::write(&self.path, body)
+            .wrap_err_with(|| format!("failed to write {} report", self.format))
+    }
+}
+
+fn count(results: &[TaskResult], status: TaskResultStatus) -> usize {
+    results.iter().filter(|r| r.status == status).count()
+}
+
+fn failure_message(result: &TaskResult) -> String {
+    if let Some(limit) = &result.limit {
+        return limit.clone();
+    }
+    match result.exit_status {
+        Some(status) => format!("exited with status {status}"),
+        None => result
+            .message
+            .clone()
+            .unwrap_or_else(|| "failed".to_string()),
+    }
+}
+
+fn junit(results: &[TaskResult], start: SystemTime, duration: Duration) -> String {
+    let tests = results.len();
+    let failures = count(results, TaskResultStatus::Failed);
+    let skipped = count(results, TaskResultStatus::Skipped);
+    let time = duration.as_secs_f64();
+    let timestamp = chrono::DateTime::<chrono::Utc>::from(start).format("%Y-%m-%dT%H:%M:%S");
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        r#"<testsuites name="mise run" tests="{tests}" failures="{failures}" errors="0" skipped="{skipped}" time="{time:.3}">"#
+    );
+    let _ = writeln!(
+        xml,
+        r#"  <testsuite name="mise run" tests="{tests}" failures="{failures}" errors="0" skipped="{skipped}" time="{time:.3}" timestamp="{timestamp}">"#
+    );
+    for result in results {
+        let _ = writeln!(
+            xml,
+            r#"    <testcase name="{}" classname="mise" time="{:.3}">"#,
+            xml_escape(&result.name),
+            result.duration.as_secs_f64()
+        );
+        let mut properties = vec![("attempts", result.attempts.to_string())];
+        if let Some(cache) = result.cache {
+            properties.push(("cache", cache_str(cache).to_string()));
+        }
+        if let Some(status) = result.exit_status {
+            properties.push(("exit_status", status.to_string()));
+        }
+        xml.push_str("      <properties>\n");
+        for (name, value) in properties {
+            let _ = writeln!(
+                xml,
+                r#"        <property name="{name}" value="{}"/>"#,
+                xml_escape(&value)
+            );
+        }
+        xml.push_str("      </properties>\n");
+        match result.status {
+            TaskResultStatus::Passed => {}
+            TaskResultStatus::Skipped => {
+                let message = result.skipped.map(|s| s.message()).unwrap_or("skipped");
+                let _ = writeln!(xml, r#"      <skipped message="{}"/>"#, xml_escape(message));
+            }
+            TaskResultStatus::Failed => {
+                let _ = writeln!(
+                    xml,
+                    r#"      <failure message="{}" type="failure">{}</failure>"#,
+                    xml_escape(&failure_message(result)),
+                    xml_escape(&result.stderr.join("\n"))
+                );
+            }
+        }
+        xml.push_str("    </testcase>\n");
+    }
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+/// Escapes text for the JUnit report. Captured output keeps its ANSI colors and may
+/// contain other control characters, which XML 1.0 does not allow even when escaped.
+fn xml_escape(text: &str) -> String {
+    let text = console::strip_ansi_codes(text)
+        .chars()
+        .map(|c| match c {
+            '\t' | '\n' | '\r' => c,
+            '\u{0}'..='\u{1f}' | '\u{fffe}' | '\u{ffff}' => char::REPLACEMENT_CHARACTER,
+            _ => c,
+        })
+        .collect::<String>();
+    escape(&text).into_owned()
+}
+
+/// A report in the Common Test Report Format, see https://ctrf.io
+fn ctrf(results: &[TaskResult], start: SystemTime, duration: Duration) -> Result<String> {
+    let start_ms = start
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    let tests = results
+        .iter()
+        .map(|result| {
+            let mut test = json!({
+                "name": result.name,
+                "status": result.status,
+                "duration": result.duration.as_millis() as u64,
+                "retries": result.attempts.saturating_sub(1),
+                "flaky": result.is_flaky(),
+                "extra": {
+                    "cache": result.cache.map(cache_str),
+                    "exit_status": result.exit_status,
+                    "skipped": result.skipped.map(|s| s.message()),
+                },
+            });
+            if result.status == TaskResultStatus::Failed {
+                test["message"] = json!(failure_message(result));
+                test["trace"] = json!(result.stderr.join("\n"));
+            }
+            test
+        })
+        .collect::<Vec<_>>();
+    let report = json!({
+        "results": {
+            "tool": {
+                "name": "mise",
+                "version": *crate::cli::version::VERSION_PLAIN,
+            },
+            "summary": {
+                "tests": results.len(),
+                "passed": count(results, TaskResultStatus::Passed),
+                "failed": count(results, TaskResultStatus::Failed),
+                "skipped": count(results, TaskResultStatus::Skipped),
+                "pending": 0,
+                "other": 0,
+                "start": start_ms,
+                "stop": start_ms + duration.as_millis() as u64,
+            },
+            "tests": tests,
+        }
+    });
+    Ok(serde_json::to_string_pretty(&report)?)
+}
+
+fn json(results: &[TaskResult], duration: Duration) -> Result<String> {
+    Ok(serde_json::to_string_pretty(&json!({
+        "duration_ms": duration.as_millis() as u64,
+        "tasks": results,
+    }))?)
+}
+
+fn cache_str(cache: TaskCacheResult) -> &'static str {
+    match cache {
+        TaskCacheResult::Hit => "hit",
+        TaskCacheResult::Miss => "miss",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Task;
+    use crate::task::task_executor::TaskRunOutcome;
+    use crate::task::task_results::{TaskResults, TaskSkipReason};
+
+    fn results() -> Vec<TaskResult> {
+        let results = TaskResults::new(true);
+        let task = |name: &str| Task {
+            name: name.to_string(),
+            display_name: name.to_string(),
+            ..Default::default()
+        };
+        results.record(
+            &task("build"),
+            Ok(&TaskRunOutcome {
+                cache: Some(TaskCacheResult::Hit),
+                ..Default::default()
+            }),
+            Duration::from_millis(1200),
+        );
+        results.record(
+            &task("lint"),
+            Ok(&TaskRunOutcome {
+                skipped: Some(TaskSkipReason::Fresh),
+                ..Default::default()
+            }),
+            Duration::ZERO,
+        );
+        let test = task("test <a>");
+        results.observe_stderr(&test, "assertion failed: 1 < 2");
+        results.record(&test, Err(&eyre::eyre!("boom")), Duration::from_secs(2));
+        results.all()
+    }
+
+    #[test]
+    fn test_parse_report() {
+        assert_eq!(
+            "junit=out/report.xml".parse::<TaskReport>().unwrap(),
+            TaskReport {
+                format: TaskReportFormat::Junit,
+                path: PathBuf::from("out/report.xml"),
+            }
+        );
+        assert!("junit".parse::<TaskReport>().is_err());
+        assert!("junit=".parse::<TaskReport>().is_err());
+        assert!("tap=out.tap".parse::<TaskReport>().is_err());
+    }
+
+    #[test]
+    fn test_junit_report() {
+        let xml = junit(&results(), UNIX_EPOCH, Duration::from_secs(3));
+        assert!(xml.contains(
+            r#"<testsuites name="mise run" tests="3" failures="1" errors="0" skipped="1" time="3.000">"#
+        ));
+        assert!(xml.contains(r#"<testcase name="build" classname="mise" time="1.200">"#));
+        assert!(xml.contains(r#"<property name="cache" value="hit"/>"#));
+        assert!(xml.contains(r#"<skipped message="sources up-to-date"/>"#));
+        assert!(xml.contains(r#"<testcase name="test &lt;a&gt;""#));
+        assert!(xml.contains(
+            r#"<failure message="boom" type="failure">assertion failed: 1 &lt; 2</failure>"#
+        ));
+    }
+
+    #[test]
+    fn test_junit_report_strips_control_characters() {
+        let results = TaskResults::new(true);
+        let task = Task {
+            name: "test".to_string(),
+            display_name: "test".to_string(),
+            ..Default::default()
+        };
+        results.observe_stderr(&task, "\x1b[31merror\x1b[0m: bad\x07 input");
+        results.record(&task, Err(&eyre::eyre!("boom")), Duration::ZERO);
+        let xml = junit(&results.all(), UNIX_EPOCH, Duration::ZERO);
+        assert!(xml.contains(
+            "<failure message=\"boom\" type=\"failure\">error: bad\u{fffd} input</failure>"
+        ));
+        assert!(
+            !xml.chars()
+                .any(|c| c.is_control() && !matches!(c, '\n' | '\t'))
+        );
+    }
+
+    #[test]
+    fn test_ctrf_report() {
+        let report: serde_json::Value =
+            serde_json::from_str(&ctrf(&results(), UNIX_EPOCH, Duration::from_secs(3)).unwrap())
+                .unwrap();
+        let results = &report["results"];
+        assert_eq!(results["summary"]["tests"], 3);
+        assert_eq!(results["summary"]["failed"], 1);
+        assert_eq!(results["summary"]["stop"], 3000);
+        assert_eq!(results["tests"][0]["extra"]["cache"], "hit");
+        assert_eq!(results["tests"][1]["status"], "skipped");
+        assert_eq!(results["tests"][2]["status"], "failed");
+        assert_eq!(results["tests"][2]["trace"], "assertion failed: 1 < 2");
+    }
+}
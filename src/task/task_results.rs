@@ -0,0 +1,264 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use indexmap::IndexMap;
+use serde::Serialize;
+
+use crate::task::Task;
+use crate::task::task_executor::TaskRunOutcome;
+
+/// Number of trailing stderr lines kept for each task when reports are written.
+const STDERR_TAIL_LINES: usize = 50;
+
+/// How a task in the run finished.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskResultStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+/// Why a task did not run its command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskSkipReason {
+    /// Its sources were up to date with its outputs.
+    Fresh,
+    /// It was listed in the `task.skip` setting.
+    Setting,
+}
+
+impl TaskSkipReason {
+    pub fn message(&self) -> &'static str {
+        match self {
+            TaskSkipReason::Fresh => "sources up-to-date",
+            TaskSkipReason::Setting => "skipped by task.skip setting",
+        }
+    }
+}
+
+/// Whether the task output cache had a result for the task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskCacheResult {
+    Hit,
+    Miss,
+}
+
+/// The result of one task in `mise run`, as shown in the summary and written to reports.
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskResult {
+    #[serde(skip)]
+    pub task: Task,
+    pub name: String,
+    pub status: TaskResultStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skipped: Option<TaskSkipReason>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_status: Option<i32>,
+    #[serde(rename = "duration_ms", serialize_with = "serialize_millis")]
+    pub duration: Duration,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache: Option<TaskCacheResult>,
+    pub attempts: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Why a resource limit stopped the task, e.g. "killed: memory limit 2GiB".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<String>,
+    /// Trailing stderr lines of a failed task.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub stderr: Vec<String>,
+}
+
+fn serialize_millis<S: serde::Serializer>(
+    duration: &Duration,
+    s: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    s.serialize_u64(duration.as_millis() as u64)
+}
+
+impl TaskResult {
+    pub fn new(task: &Task) -> Self {
+        Self {
+            task: task.clone(),
+            name: task.display_name.clone(),
+            status: TaskResultStatus::Passed,
+            skipped: None,
+            exit_status: None,
+            duration: Duration::ZERO,
+            cache: None,
+            attempts: 1,
+            message: None,
+            limit: None,
+            stderr: vec![],
+        }
+    }
+
+    /// Whether the task passed only after being retried.
+    pub fn is_flaky(&self) -> bool {
+        self.status == TaskResultStatus::Passed && self.attempts > 1
+    }
+}
+
+/// Results of the tasks that ran, recorded as they finish.
+///
+/// Stderr is only kept when a report was requested, since it is otherwise unused.
+#[derive(Debug, Clone, Default)]
+pub struct TaskResults {
+    capture_stderr: bool,
+    results: Arc<Mutex<IndexMap<Task, TaskResult>>>,
+    stderr: Arc<Mutex<IndexMap<Task, VecDeque<String>>>>,
+}
+
+impl TaskResults {
+    pub fn new(capture_stderr: bool) -> Self {
+        Self {
+            capture_stderr,
+            ..Default::default()
+        }
+    }
+
+    pub fn captures_stderr(&self) -> bool {
+        self.capture_stderr
+    }
+
+    /// Clears the stderr kept from a previous attempt of the task.
+    pub fn start(&self, task: &Task) {
+        if self.capture_stderr {
+            self.stderr.lock().unwrap().shift_remove(task);
+        }
+    }
+
+    /// Keeps a line of the task's stderr, dropping the oldest beyond the tail length.
+    pub fn observe_stderr(&self, task: &Task, line: &str) {
+        if !self.capture_stderr {
+            return;
+        }
+        let mut stderr = self.stderr.lock().unwrap();
+        let tail = stderr.entry(task.clone()).or_default();
+        if tail.len() == STDERR_TAIL_LINES {
+            tail.pop_front();
+        }
+        tail.push_back(line.to_string());
+    }
+
+    /// Records a task that finished, with its outcome or the error it failed with.
+    pub fn record(
+        &self,
+        task: &Task,
+        run: std::result::Result<&TaskRunOutcome, &eyre::Report>,
+        duration: Duration,
+    ) {
+        let mut result = TaskResult::new(task);
+        result.duration = duration;
+        match run {
+            Ok(outcome) => {
+                result.skipped = outcome.skipped;
+                result.cache = outcome.cache;
+                if outcome.skipped.is_some() {
+                    result.status = TaskResultStatus::Skipped;
+                }
+            }
+            Err(err) => {
+                result.status = TaskResultStatus::Failed;
+                result.exit_status = crate::errors::Error::get_exit_status(err);
+                result.message = Some(err.to_string());
+                result.limit = crate::errors::Error::get_limit_exceeded(err);
+                result.stderr = self
+                    .stderr
+                    .lock()
+                    .unwrap()
+                    .shift_remove(task)
+                    .map(Vec::from)
+                    .unwrap_or_default();
+            }
+        }
+        self.results.lock().unwrap().insert(task.clone(), result);
+    }
+
+    /// The recorded results, in the order the tasks finished.
+    pub fn all(&self) -> Vec<TaskResult> {
+        self.results.lock().unwrap().values().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(name: &str) -> Task {
+        Task {
+            name: name.to_string(),
+            display_name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_record_results() {
+        let results = TaskResults::new(true);
+        let build = task("build");
+        let lint = task("lint");
+        let test = task("test");
+        results.record(
+            &build,
+            Ok(&TaskRunOutcome {
+                cache: Some(TaskCacheResult::Miss),
+                ..Default::default()
+            }),
+            Duration::from_millis(1500),
+        );
+        results.record(
+            &lint,
+            Ok(&TaskRunOutcome {
+                skipped: Some(TaskSkipReason::Fresh),
+                ..Default::default()
+            }),
+            Duration::ZERO,
+        );
+        for i in 0..STDERR_TAIL_LINES + 2 {
+            results.observe_stderr(&test, &format!("line {i}"));
+        }
+        results.record(&test, Err(&eyre::eyre!("boom")), Duration::from_secs(1));
+
+        let all = results.all();
+        assert_eq!(all[0].status, TaskResultStatus::Passed);
+        assert_eq!(all[0].cache, Some(TaskCacheResult::Miss));
+        assert_eq!(all[1].status, TaskResultStatus::Skipped);
+        assert_eq!(all[2].status, TaskResultStatus::Failed);
+        assert_eq!(all[2].message.as_deref(), Some("boom"));
+        assert_eq!(all[2].stderr.len(), STDERR_TAIL_LINES);
+        assert_eq!(all[2].stderr[0], "line 2");
+    }
+
+    #[test]
+    fn test_stderr_not_captured_without_reports() {
+        let results = TaskResults::default();
+        let test = task("test");
+        results.observe_stderr(&test, "error");
+        results.record(&test, Err(&eyre::eyre!("boom")), Duration::ZERO);
+        assert!(results.all()[0].stderr.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_record_limit_exceeded() {
+        use std::os::unix::process::ExitStatusExt;
+
+        let results = TaskResults::default();
+        let build = task("build");
+        let status = std::process::ExitStatus::from_raw(nix::sys::signal::SIGKILL as i32);
+        let err = eyre::Report::new(crate::errors::Error::LimitExceeded(
+            "killed: memory limit 2GiB".to_string(),
+            status,
+        ));
+        results.record(&build, Err(&err), Duration::ZERO);
+        assert_eq!(
+            results.all()[0].limit.as_deref(),
+            Some("killed: memory limit 2GiB")
+        );
+    }
+}
@@ -1,5 +1,9 @@
+use std::time::{Duration, Instant, SystemTime};
+
 use crate::task::task_output_handler::OutputHandler;
-use crate::task::{FailedTasks, Task};
+use crate::task::task_report::TaskReport;
+use crate::task::task_results::{TaskResult, TaskResultStatus, TaskResults};
+use crate::task::{FailedTasks, RetriedTasks, Task};
 use crate::ui::{style, time};
 use crate::{Result, request_exit};
 
@@ -7,6 +11,9 @@ use crate::{Result, request_exit};
 pub struct TaskResultsDisplay {
     output_handler: OutputHandler,
     failed_tasks: FailedTasks,
+    retried_tasks: RetriedTasks,
+    results: TaskResults,
+    reports: Vec<TaskReport>,
     continue_on_error: bool,
     show_timings: bool,
     interrupted: bool,
@@ -16,6 +23,8 @@ impl TaskResultsDisplay {
     pub fn new(
         output_handler: OutputHandler,
         failed_tasks: FailedTasks,
+        retried_tasks: RetriedTasks,
+        results: TaskResults,
         continue_on_error: bool,
         show_timings: bool,
         interrupted: bool,
@@ -23,19 +32,30 @@ impl TaskResultsDisplay {
         Self {
             output_handler,
             failed_tasks,
+            retried_tasks,
+            results,
+            reports: vec![],
             continue_on_error,
             show_timings,
             interrupted,
         }
     }
 
+    /// Write these reports of the results when displaying them
+    pub fn with_reports(mut self, reports: Vec<TaskReport>) -> Self {
+        self.reports = reports;
+        self
+    }
+
     /// Display final results and handle failures
-    pub fn display_results(&self, num_tasks: usize, timer: std::time::Instant) -> Result<()> {
+    pub fn display_results(&self, num_tasks: usize, timer: Instant) -> Result<()> {
         self.display_keep_order_output();
         self.display_timing_summary(num_tasks, timer);
+        self.write_reports(timer.elapsed())?;
         if self.interrupted {
             return Err(request_exit(130));
         }
+        self.display_retry_summary();
         self.maybe_print_failure_summary();
         self.exit_if_failed()?;
         Ok(())
@@ -55,14 +75,81 @@ impl TaskResultsDisplay {
             .flush_all();
     }
 
+    /// The result of each task that ran, with the failures and retry counts
+    /// that the terminal summary reports.
+    pub fn results(&self) -> Vec<TaskResult> {
+        let failed = self.failed_tasks.lock().unwrap().clone();
+        let retried = self.retried_tasks.lock().unwrap().clone();
+        let mut results = self.results.all();
+        for (task, status) in &failed {
+            match results.iter_mut().find(|r| &r.task == task) {
+                Some(result) => {
+                    result.status = TaskResultStatus::Failed;
+                    result.exit_status = *status;
+                }
+                // e.g. a task that failed to start
+                None => results.push(TaskResult {
+                    status: TaskResultStatus::Failed,
+                    exit_status: *status,
+                    ..TaskResult::new(task)
+                }),
+            }
+        }
+        for result in &mut results {
+            if let Some(attempt) = retried.get(&result.task) {
+                result.attempts = *attempt;
+            }
+        }
+        results
+    }
+
+    /// Write the reports requested with `--report`
+    fn write_reports(&self, duration: Duration) -> Result<()> {
+        if self.reports.is_empty() {
+            return Ok(());
+        }
+        let results = self.results();
+        let start = SystemTime::now() - duration;
+        for report in &self.reports {
+            report.write(&results, start, duration)?;
+        }
+        Ok(())
+    }
+
     /// Display timing summary if enabled
-    fn display_timing_summary(&self, num_tasks: usize, timer: std::time::Instant) {
+    fn display_timing_summary(&self, num_tasks: usize, timer: Instant) {
         if self.show_timings && num_tasks > 1 {
             let msg = format!("Finished in {}", time::format_duration(timer.elapsed()));
             safe_eprintln!("{}", style::edim(msg));
         }
     }
 
+    /// List tasks that only passed after being retried, so flaky tasks don't go unnoticed
+    fn display_retry_summary(&self) {
+        let passed: Vec<TaskResult> = self
+            .results()
+            .into_iter()
+            .filter(TaskResult::is_flaky)
+            .collect();
+        if passed.is_empty() {
+            return;
+        }
+
+        safe_eprintln!(
+            "{} {} task(s) passed after retrying:",
+            style::eyellow("WARN"),
+            passed.len()
+        );
+        for result in &passed {
+            let prefix = result.task.estyled_prefix();
+            self.eprint(
+                &result.task,
+                &prefix,
+                &format!("passed on attempt {}", result.attempts),
+            );
+        }
+    }
+
     /// Print failure summary if in continue-on-error mode
     fn maybe_print_failure_summary(&self) {
         if !self.continue_on_error {
@@ -78,10 +165,16 @@ impl TaskResultsDisplay {
         safe_eprintln!("{} {} task(s) failed:", style::ered("ERROR"), count);
         for (task, status) in &failed {
             let prefix = task.estyled_prefix();
-            let status_str = status
-                .map(|s| s.to_string())
-                .unwrap_or_else(|| "unknown".to_string());
-            self.eprint(task, &prefix, &format!("exited with status {}", status_str));
+            let line = match self.limit_exceeded(task) {
+                Some(limit) => limit,
+                None => {
+                    let status_str = status
+                        .map(|s| s.to_string())
+                        .unwrap_or_else(|| "unknown".to_string());
+                    format!("exited with status {}", status_str)
+                }
+            };
+            self.eprint(task, &prefix, &line);
         }
     }
 
@@ -89,16 +182,28 @@ impl TaskResultsDisplay {
     fn exit_if_failed(&self) -> Result<()> {
         if let Some((task, status)) = self.failed_tasks.lock().unwrap().first() {
             let prefix = task.estyled_prefix();
+            let reason = self
+                .limit_exceeded(task)
+                .unwrap_or_else(|| "failed".to_string());
             self.eprint(
                 task,
                 &prefix,
-                &format!("{} task failed", style::ered("ERROR")),
+                &format!("{} task {reason}", style::ered("ERROR")),
             );
             return Err(request_exit(status.unwrap_or(1)));
         }
         Ok(())
     }
 
+    /// Why a resource limit stopped a failed task, e.g. "killed: memory limit 2GiB"
+    fn limit_exceeded(&self, task: &Task) -> Option<String> {
+        self.results
+            .all()
+            .into_iter()
+            .find(|result| &result.task == task)
+            .and_then(|result| result.limit)
+    }
+
     /// Print error message for a task
     fn eprint(&self, task: &Task, prefix: &str, line: &str) {
         self.output_handler.eprint(task, prefix, line);
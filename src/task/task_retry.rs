@@ -0,0 +1,196 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Deserializer};
+
+use crate::Result;
+use crate::duration;
+
+/// Retry policy for a task, e.g.
+/// `retry = { attempts = 3, backoff = "2s", on_exit_codes = [75] }`.
+///
+/// `retry = 3` is shorthand for `retry = { attempts = 3 }`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskRetry {
+    /// Maximum number of times the task runs, including the first run.
+    pub attempts: u32,
+    /// Delay before the first retry. Each later retry waits twice as long as the previous one.
+    pub backoff: Option<String>,
+    /// Exit codes that trigger a retry. Any failure is retried when empty.
+    pub on_exit_codes: Vec<i32>,
+    /// Write the output of a run that needed a retry to the task output cache.
+    pub cache: bool,
+}
+
+impl Default for TaskRetry {
+    fn default() -> Self {
+        Self {
+            attempts: 1,
+            backoff: None,
+            on_exit_codes: vec![],
+            cache: false,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TaskRetryOptions {
+    attempts: u32,
+    #[serde(default)]
+    backoff: Option<String>,
+    #[serde(default)]
+    on_exit_codes: Vec<i32>,
+    #[serde(default)]
+    cache: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawTaskRetry {
+    Attempts(u32),
+    Options(TaskRetryOptions),
+}
+
+impl<'de> Deserialize<'de> for TaskRetry {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let retry = match RawTaskRetry::deserialize(deserializer)? {
+            RawTaskRetry::Attempts(attempts) => TaskRetry {
+                attempts,
+                ..Default::default()
+            },
+            RawTaskRetry::Options(opts) => TaskRetry {
+                attempts: opts.attempts,
+                backoff: opts.backoff,
+                on_exit_codes: opts.on_exit_codes,
+                cache: opts.cache,
+            },
+        };
+        if retry.attempts == 0 {
+            return Err(serde::de::Error::custom(
+                "retry attempts must be at least 1",
+            ));
+        }
+        Ok(retry)
+    }
+}
+
+impl TaskRetry {
+    /// Combines the task's policy with the `--retry` override from `mise run`.
+    pub fn effective(task: Option<&TaskRetry>, attempts_override: Option<u32>) -> Option<Self> {
+        let mut retry = task.cloned().unwrap_or_default();
+        if let Some(attempts) = attempts_override {
+            retry.attempts = attempts.max(1);
+        }
+        (retry.attempts > 1).then_some(retry)
+    }
+
+    /// Whether a task that failed on run `attempt` (1-based) with `status` should run again.
+    pub fn should_retry(&self, attempt: u32, status: Option<i32>) -> bool {
+        if attempt >= self.attempts {
+            return false;
+        }
+        self.on_exit_codes.is_empty() || status.is_some_and(|s| self.on_exit_codes.contains(&s))
+    }
+
+    /// Delay before running the task again after a failed run `attempt` (1-based).
+    pub fn delay(&self, attempt: u32) -> Result<Duration> {
+        let Some(backoff) = &self.backoff else {
+            return Ok(Duration::ZERO);
+        };
+        let base = duration::parse_duration(backoff)?;
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        Ok(base.saturating_mul(factor))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> std::result::Result<TaskRetry, toml::de::Error> {
+        #[derive(Deserialize)]
+        struct T {
+            retry: TaskRetry,
+        }
+        toml::from_str::<T>(s).map(|t| t.retry)
+    }
+
+    #[test]
+    fn test_parse_retry() {
+        assert_eq!(
+            parse("retry = 3").unwrap(),
+            TaskRetry {
+                attempts: 3,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            parse(
+                r#"retry = { attempts = 2, backoff = "1s", on_exit_codes = [75], cache = true }"#
+            )
+            .unwrap(),
+            TaskRetry {
+                attempts: 2,
+                backoff: Some("1s".into()),
+                on_exit_codes: vec![75],
+                cache: true,
+            }
+        );
+        assert!(parse("retry = 0").is_err());
+        assert!(parse("retry = { attempts = 2, delay = \"1s\" }").is_err());
+    }
+
+    #[test]
+    fn test_should_retry() {
+        let retry = TaskRetry {
+            attempts: 3,
+            on_exit_codes: vec![75],
+            ..Default::default()
+        };
+        assert!(retry.should_retry(1, Some(75)));
+        assert!(retry.should_retry(2, Some(75)));
+        assert!(!retry.should_retry(3, Some(75)));
+        assert!(!retry.should_retry(1, Some(1)));
+        assert!(!retry.should_retry(1, None));
+
+        let any = TaskRetry {
+            attempts: 2,
+            ..Default::default()
+        };
+        assert!(any.should_retry(1, Some(1)));
+        assert!(any.should_retry(1, None));
+    }
+
+    #[test]
+    fn test_delay_doubles() {
+        let retry = TaskRetry {
+            attempts: 4,
+            backoff: Some("2s".into()),
+            ..Default::default()
+        };
+        assert_eq!(retry.delay(1).unwrap(), Duration::from_secs(2));
+        assert_eq!(retry.delay(2).unwrap(), Duration::from_secs(4));
+        assert_eq!(retry.delay(3).unwrap(), Duration::from_secs(8));
+        assert_eq!(TaskRetry::default().delay(1).unwrap(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_effective() {
+        assert_eq!(TaskRetry::effective(None, None), None);
+        assert_eq!(TaskRetry::effective(None, Some(1)), None);
+        let task = TaskRetry {
+            attempts: 3,
+            backoff: Some("1s".into()),
+            ..Default::default()
+        };
+        assert_eq!(TaskRetry::effective(Some(&task), None), Some(task.clone()));
+        assert_eq!(
+            TaskRetry::effective(Some(&task), Some(5)).unwrap(),
+            TaskRetry {
+                attempts: 5,
+                ..task.clone()
+            }
+        );
+        assert_eq!(TaskRetry::effective(Some(&task), Some(0)), None);
+    }
+}
@@ -285,6 +285,18 @@ pub struct SpawnContext {
     pub in_flight: Arc<AtomicUsize>,
 }
 
+impl SpawnContext {
+    /// Send a failed task back to the scheduler so it runs again.
+    ///
+    /// The task stays in the dependency graph, so its dependencies are not
+    /// rerun and its dependents keep waiting for the retried run.
+    pub fn requeue(&self, task: Task, deps: Arc<Mutex<Deps>>, allow_during_interruption: bool) {
+        let _ = self
+            .sched_tx
+            .send(SchedMsg::new(task, deps, allow_during_interruption));
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
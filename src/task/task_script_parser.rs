@@ -742,9 +742,13 @@ impl TaskScriptParser {
                         v.to_string()
                     }
                     _ => match shell_type {
-                        Some(ShellType::Zsh | ShellType::Bash | ShellType::Fish) => {
-                            shell_words::quote(&v.to_string()).to_string()
-                        }
+                        Some(
+                            ShellType::Zsh
+                            | ShellType::Bash
+                            | ShellType::Fish
+                            | ShellType::Sh
+                            | ShellType::Ksh,
+                        ) => shell_words::quote(&v.to_string()).to_string(),
                         _ => v.to_string(),
                     },
                 }
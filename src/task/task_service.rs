@@ -0,0 +1,520 @@
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use eyre::{bail, eyre};
+use indexmap::IndexMap;
+use regex::Regex;
+use serde::{Deserialize, Deserializer};
+use tokio::sync::watch;
+
+use crate::Result;
+use crate::duration;
+use crate::task::Task;
+
+const DEFAULT_READY_TIMEOUT: Duration = Duration::from_secs(30);
+const DEFAULT_GRACE_PERIOD: Duration = Duration::from_secs(5);
+const PROBE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// A task that keeps running in the background while its dependents run, e.g.
+/// `service = { ready = { port = 5432 }, grace_period = "10s" }`.
+///
+/// `service = true` is a service that is ready as soon as it starts.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TaskService {
+    /// Probe that must pass before dependents start.
+    pub ready: Option<ServiceProbe>,
+    /// How long the probe may take to pass before the service is considered failed.
+    pub ready_timeout: Option<String>,
+    /// How long to wait after SIGTERM before sending SIGKILL at teardown.
+    pub grace_period: Option<String>,
+}
+
+/// Readiness check for a service task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServiceProbe {
+    /// A TCP connection to `host:port` succeeds.
+    Port { host: String, port: u16 },
+    /// A GET request to the URL responds with 200 OK.
+    Http(String),
+    /// The service prints a line matching the regex.
+    Log(String),
+    /// The command exits successfully.
+    Run(String),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ServiceProbeOptions {
+    #[serde(default)]
+    port: Option<u16>,
+    #[serde(default)]
+    host: Option<String>,
+    #[serde(default)]
+    http: Option<String>,
+    #[serde(default)]
+    log: Option<String>,
+    #[serde(default)]
+    run: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TaskServiceOptions {
+    #[serde(default)]
+    ready: Option<ServiceProbeOptions>,
+    #[serde(default)]
+    ready_timeout: Option<String>,
+    #[serde(default)]
+    grace_period: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawTaskService {
+    Enabled(bool),
+    Options(TaskServiceOptions),
+}
+
+impl TryFrom<ServiceProbeOptions> for ServiceProbe {
+    type Error = String;
+
+    fn try_from(opts: ServiceProbeOptions) -> std::result::Result<Self, Self::Error> {
+        let probe = match (opts.port, opts.http, opts.log, opts.run) {
+            (Some(port), None, None, None) => ServiceProbe::Port {
+                host: opts.host.unwrap_or_else(|| "127.0.0.1".to_string()),
+                port,
+            },
+            (None, Some(url), None, None) => ServiceProbe::Http(url),
+            (None, None, Some(pattern), None) => ServiceProbe::Log(pattern),
+            (None, None, None, Some(cmd)) => ServiceProbe::Run(cmd),
+            _ => return Err("service ready must set exactly one of port, http, log or run".into()),
+        };
+        if opts.host.is_some() && !matches!(probe, ServiceProbe::Port { .. }) {
+            return Err("service ready host can only be used with port".into());
+        }
+        Ok(probe)
+    }
+}
+
+/// Deserializes the `service` field. `service = false` is the same as leaving it out.
+pub fn deserialize_service<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<TaskService>, D::Error> {
+    Ok(match RawTaskService::deserialize(deserializer)? {
+        RawTaskService::Enabled(false) => None,
+        RawTaskService::Enabled(true) => Some(TaskService::default()),
+        RawTaskService::Options(opts) => Some(TaskService {
+            ready: opts
+                .ready
+                .map(ServiceProbe::try_from)
+                .transpose()
+                .map_err(serde::de::Error::custom)?,
+            ready_timeout: opts.ready_timeout,
+            grace_period: opts.grace_period,
+        }),
+    })
+}
+
+impl TaskService {
+    pub fn ready_timeout(&self) -> Result<Duration> {
+        self.ready_timeout
+            .as_deref()
+            .map(duration::parse_duration)
+            .transpose()
+            .map(|d| d.unwrap_or(DEFAULT_READY_TIMEOUT))
+    }
+
+    pub fn grace_period(&self) -> Result<Duration> {
+        self.grace_period
+            .as_deref()
+            .map(duration::parse_duration)
+            .transpose()
+            .map(|d| d.unwrap_or(DEFAULT_GRACE_PERIOD))
+    }
+
+    /// The regex of a `log` probe, if the service uses one.
+    pub fn log_regex(&self) -> Result<Option<Regex>> {
+        match &self.ready {
+            Some(ServiceProbe::Log(pattern)) => Regex::new(pattern)
+                .map(Some)
+                .map_err(|e| eyre!("invalid service log pattern {pattern:?}: {e}")),
+            _ => Ok(None),
+        }
+    }
+}
+
+impl ServiceProbe {
+    /// Runs the probe once. `log` probes never pass here; they are checked as
+    /// the service prints output.
+    async fn check(&self, env: &BTreeMap<String, String>, dir: &Path) -> bool {
+        match self {
+            ServiceProbe::Port { host, port } => tokio::time::timeout(
+                PROBE_INTERVAL,
+                tokio::net::TcpStream::connect((host.as_str(), *port)),
+            )
+            .await
+            .is_ok_and(|r| r.is_ok()),
+            ServiceProbe::Http(url) => {
+                let Ok(client) = crate::http::HTTP.reqwest() else {
+                    return false;
+                };
+                client
+                    .get(url)
+                    .timeout(Duration::from_secs(5))
+                    .send()
+                    .await
+                    .is_ok_and(|resp| resp.status() == reqwest::StatusCode::OK)
+            }
+            ServiceProbe::Log(_) => false,
+            ServiceProbe::Run(cmd) => {
+                let (program, args) = probe_shell(cmd);
+                tokio::process::Command::new(program)
+                    .args(args)
+                    .envs(env)
+                    .current_dir(dir)
+                    .stdin(std::process::Stdio::null())
+                    .stdout(std::process::Stdio::null())
+                    .stderr(std::process::Stdio::null())
+                    .kill_on_drop(true)
+                    .status()
+                    .await
+                    .is_ok_and(|s| s.success())
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+fn probe_shell(cmd: &str) -> (&str, [&str; 2]) {
+    ("sh", ["-c", cmd])
+}
+
+#[cfg(windows)]
+fn probe_shell(cmd: &str) -> (&str, [&str; 2]) {
+    ("cmd", ["/c", cmd])
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ServiceState {
+    Starting,
+    Ready,
+    Exited,
+}
+
+/// A service task that is running during `mise run`.
+#[derive(Debug)]
+pub struct ServiceHandle {
+    service: TaskService,
+    log_regex: Option<Regex>,
+    started: Instant,
+    state: watch::Sender<ServiceState>,
+    pid: Mutex<Option<u32>>,
+    released: AtomicBool,
+    stopping: AtomicBool,
+}
+
+impl ServiceHandle {
+    fn new(service: TaskService) -> Result<Self> {
+        Ok(Self {
+            log_regex: service.log_regex()?,
+            service,
+            started: Instant::now(),
+            state: watch::channel(ServiceState::Starting).0,
+            pid: Mutex::new(None),
+            released: AtomicBool::new(false),
+            stopping: AtomicBool::new(false),
+        })
+    }
+
+    pub fn uses_log_probe(&self) -> bool {
+        self.log_regex.is_some()
+    }
+
+    pub fn set_pid(&self, pid: Option<u32>) {
+        *self.pid.lock().unwrap() = pid;
+    }
+
+    fn mark_ready(&self) {
+        self.state.send_if_modified(|state| {
+            let starting = *state == ServiceState::Starting;
+            if starting {
+                *state = ServiceState::Ready;
+            }
+            starting
+        });
+    }
+
+    fn is_ready(&self) -> bool {
+        *self.state.borrow() == ServiceState::Ready
+    }
+
+    /// Checks a line of service output against the `log` probe.
+    pub fn observe_line(&self, line: &str) {
+        if self.log_regex.as_ref().is_some_and(|re| re.is_match(line)) {
+            self.mark_ready();
+        }
+    }
+
+    /// Resolves once the service is ready. Returns false if it exited first.
+    pub async fn wait_ready(&self) -> bool {
+        let mut rx = self.state.subscribe();
+        rx.wait_for(|state| *state != ServiceState::Starting)
+            .await
+            .is_ok_and(|state| *state == ServiceState::Ready)
+    }
+
+    /// Marks the service as exited, returning whether it had become ready.
+    pub fn finish(&self) -> bool {
+        let mut was_ready = false;
+        self.state.send_modify(|state| {
+            was_ready = *state == ServiceState::Ready;
+            *state = ServiceState::Exited;
+        });
+        was_ready
+    }
+
+    /// Claims the job of releasing the service's dependents. Returns true for
+    /// the first caller only.
+    pub fn release(&self) -> bool {
+        !self.released.swap(true, Ordering::SeqCst)
+    }
+
+    /// Whether the service is being torn down by mise.
+    pub fn is_stopping(&self) -> bool {
+        self.stopping.load(Ordering::SeqCst)
+    }
+
+    /// Runs the service command while probing it until it is ready. A service
+    /// that does not become ready in time is terminated.
+    pub async fn supervise(
+        &self,
+        run: impl Future<Output = Result<()>>,
+        env: &BTreeMap<String, String>,
+        dir: &Path,
+    ) -> Result<()> {
+        tokio::pin!(run);
+        let ready = tokio::select! {
+            result = &mut run => {
+                self.set_pid(None);
+                return result;
+            }
+            ready = self.probe_until_ready(env, dir) => ready,
+        };
+        if let Err(err) = ready {
+            self.terminate();
+            if tokio::time::timeout(self.service.grace_period()?, &mut run)
+                .await
+                .is_err()
+            {
+                self.kill();
+                let _ = run.await;
+            }
+            self.set_pid(None);
+            return Err(err);
+        }
+        let result = run.await;
+        self.set_pid(None);
+        result
+    }
+
+    async fn probe_until_ready(&self, env: &BTreeMap<String, String>, dir: &Path) -> Result<()> {
+        let Some(probe) = &self.service.ready else {
+            self.mark_ready();
+            return Ok(());
+        };
+        let timeout = self.service.ready_timeout()?;
+        loop {
+            if self.is_ready() {
+                return Ok(());
+            }
+            if probe.check(env, dir).await {
+                self.mark_ready();
+                return Ok(());
+            }
+            if self.started.elapsed() >= timeout {
+                bail!(
+                    "service was not ready after {}",
+                    crate::ui::time::format_duration(timeout)
+                );
+            }
+            tokio::time::sleep(PROBE_INTERVAL).await;
+        }
+    }
+
+    /// Sends SIGTERM, then SIGKILL if the service is still running after its grace period.
+    async fn stop(&self) {
+        self.stopping.store(true, Ordering::SeqCst);
+        if *self.state.borrow() == ServiceState::Exited {
+            return;
+        }
+        self.terminate();
+        let grace_period = self.service.grace_period().unwrap_or(DEFAULT_GRACE_PERIOD);
+        let mut rx = self.state.subscribe();
+        let exited = rx.wait_for(|state| *state == ServiceState::Exited);
+        if tokio::time::timeout(grace_period, exited).await.is_err() {
+            debug!("service did not stop after {grace_period:?}, killing it");
+            self.kill();
+        }
+    }
+
+    fn terminate(&self) {
+        if let Some(pid) = *self.pid.lock().unwrap() {
+            #[cfg(unix)]
+            crate::cmd::signal_process_tree(pid, nix::sys::signal::SIGTERM);
+            #[cfg(windows)]
+            crate::cmd::kill_process_tree(pid);
+        }
+    }
+
+    fn kill(&self) {
+        if let Some(pid) = *self.pid.lock().unwrap() {
+            #[cfg(unix)]
+            crate::cmd::signal_process_tree(pid, nix::sys::signal::SIGKILL);
+            #[cfg(windows)]
+            crate::cmd::kill_process_tree(pid);
+        }
+    }
+}
+
+/// Service tasks started during a run, so they can be torn down when it ends.
+#[derive(Debug, Clone, Default)]
+pub struct ServiceRegistry {
+    running: Arc<Mutex<IndexMap<Task, Arc<ServiceHandle>>>>,
+    /// services named on the command line, which keep running until they exit
+    requested: Arc<Mutex<HashSet<Task>>>,
+}
+
+impl ServiceRegistry {
+    /// Records the tasks the user asked for, so services among them are not
+    /// stopped when the rest of the graph finishes.
+    pub fn set_requested<'a>(&self, tasks: impl IntoIterator<Item = &'a Task>) {
+        let mut requested = self.requested.lock().unwrap();
+        requested.extend(tasks.into_iter().filter(|t| t.service.is_some()).cloned());
+    }
+
+    /// Registers a run of `task` if it is a service.
+    pub fn start(&self, task: &Task) -> Result<Option<Arc<ServiceHandle>>> {
+        let Some(service) = &task.service else {
+            return Ok(None);
+        };
+        let handle = Arc::new(ServiceHandle::new(service.clone())?);
+        self.running
+            .lock()
+            .unwrap()
+            .insert(task.clone(), handle.clone());
+        Ok(Some(handle))
+    }
+
+    pub fn get(&self, task: &Task) -> Option<Arc<ServiceHandle>> {
+        self.running.lock().unwrap().get(task).cloned()
+    }
+
+    /// Stops the services that were started as dependencies of other tasks.
+    pub async fn stop_dependencies(&self) {
+        let requested = self.requested.lock().unwrap().clone();
+        let handles = self
+            .running
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(task, _)| !requested.contains(*task))
+            .map(|(_, handle)| handle.clone())
+            .collect::<Vec<_>>();
+        futures_util::future::join_all(handles.iter().map(|h| h.stop())).await;
+    }
+
+    /// Stops every service that is still running.
+    pub async fn stop_all(&self) {
+        let handles = self
+            .running
+            .lock()
+            .unwrap()
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+        futures_util::future::join_all(handles.iter().map(|h| h.stop())).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> std::result::Result<Option<TaskService>, toml::de::Error> {
+        #[derive(Deserialize)]
+        struct T {
+            #[serde(deserialize_with = "deserialize_service")]
+            service: Option<TaskService>,
+        }
+        toml::from_str::<T>(s).map(|t| t.service)
+    }
+
+    #[test]
+    fn test_parse_service() {
+        assert_eq!(parse("service = false").unwrap(), None);
+        assert_eq!(
+            parse("service = true").unwrap(),
+            Some(TaskService::default())
+        );
+        assert_eq!(
+            parse(r#"service = { ready = { port = 5432 }, grace_period = "1s" }"#)
+                .unwrap()
+                .unwrap(),
+            TaskService {
+                ready: Some(ServiceProbe::Port {
+                    host: "127.0.0.1".to_string(),
+                    port: 5432
+                }),
+                ready_timeout: None,
+                grace_period: Some("1s".to_string()),
+            }
+        );
+        assert_eq!(
+            parse(r#"service = { ready = { log = "listening on \\d+" } }"#)
+                .unwrap()
+                .unwrap()
+                .ready,
+            Some(ServiceProbe::Log(r"listening on \d+".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_service_errors() {
+        assert!(parse(r#"service = { ready = { port = 1, http = "http://x" } }"#).is_err());
+        assert!(parse(r#"service = { ready = { host = "localhost" } }"#).is_err());
+        assert!(parse(r#"service = { ready = { run = "true", host = "localhost" } }"#).is_err());
+        assert!(parse(r#"service = { ready = {}, timeout = "1s" }"#).is_err());
+    }
+
+    #[test]
+    fn test_service_durations() {
+        let service = TaskService::default();
+        assert_eq!(service.ready_timeout().unwrap(), DEFAULT_READY_TIMEOUT);
+        assert_eq!(service.grace_period().unwrap(), DEFAULT_GRACE_PERIOD);
+        let service = TaskService {
+            ready_timeout: Some("nope".to_string()),
+            ..Default::default()
+        };
+        assert!(service.ready_timeout().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_service_log_probe() {
+        let handle = ServiceHandle::new(TaskService {
+            ready: Some(ServiceProbe::Log("listening on \\d+".to_string())),
+            ..Default::default()
+        })
+        .unwrap();
+        handle.observe_line("starting");
+        assert!(!handle.is_ready());
+        handle.observe_line("listening on 8080");
+        assert!(handle.wait_ready().await);
+        assert!(handle.release());
+        assert!(!handle.release());
+        assert!(handle.finish());
+        assert!(!handle.wait_ready().await);
+    }
+}
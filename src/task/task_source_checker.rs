@@ -348,6 +348,41 @@ pub(crate) fn output_glob_patterns(outputs: &[String]) -> Vec<String> {
     source_glob_patterns(outputs)
 }
 
+/// The paths that contain a task's outputs: each include-side output pattern up to its first
+/// glob component, resolved against the task directory `root`.
+pub(crate) fn output_roots(root: &Path, outputs: &[String]) -> Vec<PathBuf> {
+    output_glob_patterns(outputs)
+        .iter()
+        .map(|pattern| resolve_task_path(root, output_literal_prefix(pattern)))
+        .unique()
+        .collect()
+}
+
+/// The directories a task writes its outputs to: the output root of each glob pattern, or the
+/// parent directory of a literal output that is not an existing directory.
+pub(crate) fn output_write_dirs(root: &Path, outputs: &[String]) -> Vec<PathBuf> {
+    output_glob_patterns(outputs)
+        .iter()
+        .map(|pattern| {
+            let literal = output_literal_prefix(pattern);
+            let path = resolve_task_path(root, &literal);
+            if literal == Path::new(pattern) && !path.is_dir() {
+                path.parent().map(Path::to_path_buf).unwrap_or(path)
+            } else {
+                path
+            }
+        })
+        .unique()
+        .collect()
+}
+
+fn output_literal_prefix(pattern: &str) -> PathBuf {
+    Path::new(pattern)
+        .components()
+        .take_while(|component| !is_glob_pattern(&component.as_os_str().to_string_lossy()))
+        .collect()
+}
+
 /// Returns true when an output path is selected by the ordered matcher.
 pub(crate) fn is_output(matcher: &Override, path: &Path, is_dir: bool) -> bool {
     if path.is_absolute() && !path.starts_with(matcher.path()) {
@@ -432,6 +467,12 @@ async fn collect_source_metadatas(
     Ok((root, match_root_owned, source_metadatas))
 }
 
+/// The files matched by a task's `sources`, including the files that define the task.
+pub(crate) async fn task_source_files(task: &Task, config: &Arc<Config>) -> Result<Vec<PathBuf>> {
+    let (_, _, source_metadatas) = collect_source_metadatas(task, config).await?;
+    Ok(source_metadatas.into_iter().map(|(path, _)| path).collect())
+}
+
 /// Compute the current source hash for a task. Returns `(hash, hash_file_path)`
 /// or `None` if the task has no sources or no matching files were found.
 async fn compute_source_hash(
@@ -1163,6 +1204,48 @@ mod tests {
         assert!(is_output(&matcher, &output, false));
     }
 
+    #[test]
+    fn output_roots_stop_at_the_first_glob_component() {
+        let root = Path::new("/project");
+        let patterns = vec![
+            "dist/**/*.js".to_string(),
+            "dist/app.js".to_string(),
+            "!dist/**/*.map".to_string(),
+            "/tmp/report.xml".to_string(),
+            "*.o".to_string(),
+        ];
+
+        assert_eq!(
+            output_roots(root, &patterns),
+            [
+                PathBuf::from("/project/dist"),
+                PathBuf::from("/project/dist/app.js"),
+                PathBuf::from("/tmp/report.xml"),
+                PathBuf::from("/project"),
+            ]
+        );
+    }
+
+    #[test]
+    fn output_write_dirs_use_the_parent_of_literal_files() {
+        let root = Path::new("/project");
+        let patterns = vec![
+            "dist/**/*.js".to_string(),
+            "dist/app.js".to_string(),
+            "/tmp/report.xml".to_string(),
+            "*.o".to_string(),
+        ];
+
+        assert_eq!(
+            output_write_dirs(root, &patterns),
+            [
+                PathBuf::from("/project/dist"),
+                PathBuf::from("/tmp"),
+                PathBuf::from("/project"),
+            ]
+        );
+    }
+
     #[test]
     fn metadata_hash_notices_a_same_size_change_with_an_older_mtime() {
         // https://github.com/jdx/mise/discussions/4209 — restoring an older tree with tar,
@@ -2,8 +2,8 @@ use crate::config::config_file::mise_toml::{EnvList, deserialize_vars};
 use crate::config::config_file::toml::deserialize_arr;
 use crate::task::task_sources::TaskOutputs;
 use crate::task::{
-    RunEntry, Silent, Task, TaskCacheConfig, TaskConfirm, TaskDep, TaskOutput, TaskToolValue,
-    TaskWatchOptions,
+    RunEntry, Silent, Task, TaskCacheConfig, TaskConfirm, TaskDep, TaskOutput, TaskRetry,
+    TaskService, TaskToolValue, TaskWatchOptions, task_service,
 };
 use indexmap::IndexMap;
 use serde::Deserialize;
@@ -50,6 +50,12 @@ pub struct TaskTemplate {
     pub usage: String,
     #[serde(default)]
     pub timeout: Option<String>,
+    #[serde(default)]
+    pub remote: Option<String>,
+    #[serde(default)]
+    pub retry: Option<TaskRetry>,
+    #[serde(default, deserialize_with = "task_service::deserialize_service")]
+    pub service: Option<TaskService>,
     #[serde(default, deserialize_with = "deserialize_arr")]
     pub run: Vec<RunEntry>,
     #[serde(default, deserialize_with = "deserialize_arr")]
@@ -86,6 +92,24 @@ pub struct TaskTemplate {
     /// Preserve ambient env vars when env inheritance is denied without hashing their values
     #[serde(default)]
     pub pass_through_env: Vec<String>,
+    /// Kill the task when it uses more memory than this, e.g. "2GiB"
+    #[serde(default)]
+    pub limit_memory: Option<String>,
+    /// Limit CPU time to this many CPUs
+    #[serde(default)]
+    pub limit_cpus: Option<f64>,
+    /// Limit the number of processes and threads
+    #[serde(default)]
+    pub limit_pids: Option<u64>,
+    /// Limit the number of files each process can open
+    #[serde(default)]
+    pub limit_open_files: Option<u64>,
+    /// Kill the task when it writes more than this to stdout and stderr, e.g. "10MiB"
+    #[serde(default)]
+    pub limit_output_bytes: Option<String>,
+    /// Name of a `[sandbox.profiles]` profile whose restrictions also apply to the task
+    #[serde(default)]
+    pub sandbox: Option<String>,
 }
 
 impl Task {
@@ -216,6 +240,21 @@ impl Task {
             self.timeout = template.timeout.clone();
         }
 
+        // remote: use template only if local not set
+        if self.remote.is_none() {
+            self.remote = template.remote.clone();
+        }
+
+        // retry: use template only if local not set
+        if self.retry.is_none() {
+            self.retry = template.retry.clone();
+        }
+
+        // service: use template only if local not set
+        if self.service.is_none() {
+            self.service = template.service.clone();
+        }
+
         // file: use template only if local not set
         if self.file.is_none()
             && let Some(ref file) = template.file
@@ -237,6 +276,28 @@ impl Task {
         self.allow_env.splice(0..0, template.allow_env.clone());
         self.pass_through_env
             .splice(0..0, template.pass_through_env.clone());
+
+        // resource limits: use template only if local not set
+        if self.limit_memory.is_none() {
+            self.limit_memory = template.limit_memory.clone();
+        }
+        if self.limit_cpus.is_none() {
+            self.limit_cpus = template.limit_cpus;
+        }
+        if self.limit_pids.is_none() {
+            self.limit_pids = template.limit_pids;
+        }
+        if self.limit_open_files.is_none() {
+            self.limit_open_files = template.limit_open_files;
+        }
+        if self.limit_output_bytes.is_none() {
+            self.limit_output_bytes = template.limit_output_bytes.clone();
+        }
+
+        // sandbox profile: use template only if local not set
+        if self.sandbox.is_none() {
+            self.sandbox = template.sandbox.clone();
+        }
     }
 }
 
@@ -382,6 +443,7 @@ mod tests {
             cache: Some(TaskCacheConfig {
                 enabled: true,
                 audit: false,
+                enforce: false,
                 env: vec!["PROFILE".to_string()],
                 command_inputs: vec![],
             }),
@@ -548,4 +610,42 @@ mod tests {
             vec!["TEMPLATE_SECRET".to_string(), "TASK_SECRET".to_string()]
         );
     }
+
+    #[test]
+    fn test_merge_template_limits() {
+        let mut task = Task {
+            limit_memory: Some("1GiB".to_string()),
+            ..Default::default()
+        };
+        let template = TaskTemplate {
+            limit_memory: Some("4GiB".to_string()),
+            limit_pids: Some(256),
+            ..Default::default()
+        };
+
+        task.merge_template(&template);
+
+        assert_eq!(task.limit_memory.as_deref(), Some("1GiB"));
+        assert_eq!(task.limit_pids, Some(256));
+        assert_eq!(task.limit_cpus, None);
+    }
+
+    #[test]
+    fn test_merge_template_sandbox_profile() {
+        let template = TaskTemplate {
+            sandbox: Some("ci".to_string()),
+            ..Default::default()
+        };
+
+        let mut task = Task::default();
+        task.merge_template(&template);
+        assert_eq!(task.sandbox.as_deref(), Some("ci"));
+
+        let mut task = Task {
+            sandbox: Some("local".to_string()),
+            ..Default::default()
+        };
+        task.merge_template(&template);
+        assert_eq!(task.sandbox.as_deref(), Some("local"));
+    }
 }
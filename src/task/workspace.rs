@@ -16,8 +16,13 @@ use super::{
 };
 
 pub mod cargo;
+pub mod dart;
+pub mod deno;
+pub mod dotnet;
 pub mod git;
 pub mod go;
+pub mod gradle;
+pub mod maven;
 pub mod node;
 pub mod uv;
 
@@ -424,6 +429,19 @@ fn normalize_filesystem_path(path: &Path) -> PathBuf {
     normalized
 }
 
+/// Returns the relative path from a project root back to the workspace root.
+fn path_to_root(project_root: &Path) -> PathBuf {
+    let depth = project_root
+        .components()
+        .filter(|component| matches!(component, Component::Normal(_)))
+        .count();
+    if depth == 0 {
+        PathBuf::from(".")
+    } else {
+        (0..depth).map(|_| "..").collect()
+    }
+}
+
 /// A validated, deterministically ordered project graph from workspace providers.
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct WorkspaceProjectGraph {
@@ -1614,6 +1632,7 @@ mod tests {
             Some(TaskCacheConfig {
                 enabled: true,
                 audit: false,
+                enforce: false,
                 env: vec!["NODE_ENV".to_string()],
                 command_inputs: vec!["node --version".to_string()],
             })
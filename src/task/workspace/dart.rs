@@ -0,0 +1,490 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+use eyre::{Context, Result, bail};
+use glob::{MatchOptions, Pattern};
+use serde::Deserialize;
+
+use super::{
+    ProjectId, WorkspaceDiscoveryContext, WorkspaceProject, WorkspaceProjectGraph,
+    WorkspaceProvenance, WorkspaceProvider, WorkspaceTask, WorkspaceTaskSuggestions,
+};
+
+const PUBSPEC: &str = "pubspec.yaml";
+const PUBSPEC_LOCK: &str = "pubspec.lock";
+
+/// Discovers Dart and Flutter packages from a pub workspace's `pubspec.yaml`.
+#[derive(Debug, Default)]
+pub struct DartWorkspaceProvider;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Pubspec {
+    name: String,
+    workspace: Vec<String>,
+    resolution: Option<String>,
+    dependencies: BTreeMap<String, serde_yaml::Value>,
+    dev_dependencies: BTreeMap<String, serde_yaml::Value>,
+    dependency_overrides: BTreeMap<String, serde_yaml::Value>,
+}
+
+impl Pubspec {
+    fn all_dependencies(&self) -> impl Iterator<Item = (&String, &serde_yaml::Value)> {
+        self.dependencies
+            .iter()
+            .chain(&self.dev_dependencies)
+            .chain(&self.dependency_overrides)
+    }
+
+    fn is_flutter(&self) -> bool {
+        self.dependencies
+            .get("flutter")
+            .and_then(|dependency| dependency.get("sdk"))
+            .and_then(serde_yaml::Value::as_str)
+            == Some("flutter")
+    }
+}
+
+struct DartPackage {
+    root: PathBuf,
+    source: PathBuf,
+    pubspec: Pubspec,
+    path_dependencies: BTreeSet<PathBuf>,
+}
+
+impl WorkspaceProvider for DartWorkspaceProvider {
+    fn id(&self) -> &str {
+        "dart"
+    }
+
+    fn discover(&self, workspace_root: &Path) -> Result<Vec<WorkspaceProject>> {
+        let context = WorkspaceDiscoveryContext::new();
+        self.discover_with_context(workspace_root, &context)
+    }
+
+    fn discover_with_context(
+        &self,
+        workspace_root: &Path,
+        context: &WorkspaceDiscoveryContext,
+    ) -> Result<Vec<WorkspaceProject>> {
+        let root_pubspec_path = workspace_root.join(PUBSPEC);
+        if !context.is_file(&root_pubspec_path) {
+            return Ok(Vec::new());
+        }
+        let root_pubspec = read_pubspec(context, &root_pubspec_path)?;
+        if root_pubspec.workspace.is_empty() {
+            return Ok(Vec::new());
+        }
+        let canonical_root = context.canonicalize(workspace_root).wrap_err_with(|| {
+            format!(
+                "failed to resolve Dart workspace root {}",
+                workspace_root.display()
+            )
+        })?;
+
+        // Members may declare nested workspaces, and path dependencies are analyzed
+        // alongside the packages that use them even when they aren't members.
+        let mut queue = VecDeque::from([(canonical_root.clone(), Some(root_pubspec))]);
+        let mut packages = BTreeMap::new();
+        while let Some((package_root, pubspec)) = queue.pop_front() {
+            if packages.contains_key(&package_root) {
+                continue;
+            }
+            let Ok(relative) = package_root.strip_prefix(&canonical_root) else {
+                continue;
+            };
+            let relative = normalize_relative(relative);
+            let source = relative.join(PUBSPEC);
+            let pubspec = match pubspec {
+                Some(pubspec) => pubspec,
+                None => read_pubspec(context, &package_root.join(PUBSPEC))?,
+            };
+            for member in workspace_members(&package_root, &pubspec.workspace, context)? {
+                let member_pubspec = read_pubspec(context, &member.join(PUBSPEC))?;
+                if member_pubspec.resolution.as_deref() != Some("workspace") {
+                    bail!(
+                        "Dart workspace member {} must set `resolution: workspace`",
+                        member.display()
+                    );
+                }
+                queue.push_back((member, Some(member_pubspec)));
+            }
+            let mut path_dependencies = BTreeSet::new();
+            for (_, dependency) in pubspec.all_dependencies() {
+                let Some(path) = dependency.get("path").and_then(serde_yaml::Value::as_str) else {
+                    continue;
+                };
+                let candidate = package_root.join(path);
+                if !context.is_file(&candidate.join(PUBSPEC)) {
+                    continue;
+                }
+                let candidate = context.canonicalize(&candidate).wrap_err_with(|| {
+                    format!(
+                        "failed to resolve Dart path dependency {}",
+                        candidate.display()
+                    )
+                })?;
+                path_dependencies.insert(candidate.clone());
+                queue.push_back((candidate, None));
+            }
+            packages.insert(
+                package_root,
+                DartPackage {
+                    root: relative,
+                    source,
+                    pubspec,
+                    path_dependencies,
+                },
+            );
+        }
+
+        let ids_by_path = packages
+            .iter()
+            .map(|(path, package)| {
+                Ok((
+                    path.clone(),
+                    ProjectId::new(self.id(), &package.pubspec.name)?,
+                ))
+            })
+            .collect::<Result<BTreeMap<_, _>>>()?;
+        let ids_by_name = packages
+            .iter()
+            .map(|(path, package)| (package.pubspec.name.clone(), ids_by_path[path].clone()))
+            .collect::<BTreeMap<_, _>>();
+
+        packages
+            .into_iter()
+            .map(|(path, package)| {
+                let id = ids_by_path[&path].clone();
+                let dependencies = package
+                    .pubspec
+                    .all_dependencies()
+                    .filter_map(|(name, _)| ids_by_name.get(name))
+                    .chain(
+                        package
+                            .path_dependencies
+                            .iter()
+                            .filter_map(|path| ids_by_path.get(path)),
+                    )
+                    .filter(|dependency| **dependency != id)
+                    .cloned()
+                    .collect::<BTreeSet<_>>();
+                let provenance = WorkspaceProvenance {
+                    provider: Some(self.id().to_string()),
+                    source: Some(package.source.clone()),
+                };
+                let mut project = WorkspaceProject::new(id, package.root.clone());
+                project.dependency_provenance = dependencies
+                    .iter()
+                    .cloned()
+                    .map(|dependency| (dependency, provenance.clone()))
+                    .collect();
+                project.dependencies = dependencies;
+                project.provenance = provenance;
+                project
+                    .metadata
+                    .insert("workspace_source".to_string(), PUBSPEC.to_string());
+                project.tasks = workspace_tasks(
+                    &package.pubspec,
+                    &package.source,
+                    context.is_dir(&path.join("test")),
+                );
+                Ok(project)
+            })
+            .collect()
+    }
+
+    fn discover_project_tasks(
+        &self,
+        workspace_root: &Path,
+        project_root: &Path,
+    ) -> Result<BTreeMap<String, WorkspaceTask>> {
+        let context = WorkspaceDiscoveryContext::new();
+        self.discover_project_tasks_with_context(workspace_root, project_root, &context)
+    }
+
+    fn discover_project_tasks_with_context(
+        &self,
+        workspace_root: &Path,
+        project_root: &Path,
+        context: &WorkspaceDiscoveryContext,
+    ) -> Result<BTreeMap<String, WorkspaceTask>> {
+        let source = project_root.join(PUBSPEC);
+        let pubspec_path = workspace_root.join(&source);
+        if !context.is_file(&workspace_root.join(PUBSPEC)) || !context.is_file(&pubspec_path) {
+            return Ok(BTreeMap::new());
+        }
+        let pubspec = read_pubspec(context, &pubspec_path)?;
+        let has_tests = context.is_dir(&workspace_root.join(project_root).join("test"));
+        Ok(workspace_tasks(&pubspec, &source, has_tests))
+    }
+
+    fn affected_projects_for_lockfile(
+        &self,
+        lockfile_path: &Path,
+        _before: Option<&str>,
+        _after: Option<&str>,
+        graph: &WorkspaceProjectGraph,
+    ) -> Result<Option<BTreeSet<ProjectId>>> {
+        if lockfile_path
+            .file_name()
+            .is_none_or(|name| name != PUBSPEC_LOCK)
+        {
+            return Ok(None);
+        }
+        // The workspace shares one resolution, and transitive packages can't be traced
+        // back to the members that use them.
+        let directory = normalize_relative(lockfile_path.parent().unwrap_or(Path::new("")));
+        Ok(Some(
+            graph
+                .projects()
+                .filter(|project| project.id.as_str().starts_with("dart:"))
+                .filter(|project| {
+                    directory == Path::new(".") || project.root.starts_with(&directory)
+                })
+                .map(|project| project.id.clone())
+                .collect(),
+        ))
+    }
+}
+
+fn read_pubspec(context: &WorkspaceDiscoveryContext, path: &Path) -> Result<Pubspec> {
+    let contents = context
+        .read_to_string(path)
+        .wrap_err_with(|| format!("failed to read Dart pubspec {}", path.display()))?;
+    serde_yaml::from_str(&contents)
+        .wrap_err_with(|| format!("failed to parse Dart pubspec {}", path.display()))
+}
+
+/// Returns the canonical roots of the packages a `workspace:` list matches.
+fn workspace_members(
+    package_root: &Path,
+    members: &[String],
+    context: &WorkspaceDiscoveryContext,
+) -> Result<BTreeSet<PathBuf>> {
+    let options = MatchOptions {
+        case_sensitive: true,
+        require_literal_separator: true,
+        require_literal_leading_dot: true,
+    };
+    let escaped_root = Pattern::escape(&package_root.to_string_lossy());
+    let mut roots = BTreeSet::new();
+    for member in members {
+        if Path::new(member).is_absolute() {
+            bail!("Dart workspace member {member:?} must be relative");
+        }
+        let member = member.trim_end_matches('/');
+        let pattern = format!("{escaped_root}/{member}");
+        let mut matched = false;
+        for candidate in glob::glob_with(&pattern, options)
+            .wrap_err_with(|| format!("invalid Dart workspace member pattern {member:?}"))?
+        {
+            let candidate = candidate.wrap_err_with(|| {
+                format!("failed to evaluate Dart workspace member pattern {member:?}")
+            })?;
+            if !context.is_file(&candidate.join(PUBSPEC)) {
+                continue;
+            }
+            matched = true;
+            roots.insert(context.canonicalize(&candidate).wrap_err_with(|| {
+                format!(
+                    "failed to resolve Dart workspace member {}",
+                    candidate.display()
+                )
+            })?);
+        }
+        // Pub rejects a literal member without a pubspec, so report it the same way.
+        if !matched && !member.contains(['*', '?', '[']) {
+            bail!(
+                "Dart workspace member {member:?} in {} has no {PUBSPEC}",
+                package_root.display()
+            );
+        }
+    }
+    Ok(roots)
+}
+
+fn workspace_tasks(
+    pubspec: &Pubspec,
+    source: &Path,
+    has_tests: bool,
+) -> BTreeMap<String, WorkspaceTask> {
+    let program = if pubspec.is_flutter() {
+        "flutter"
+    } else {
+        "dart"
+    };
+    let commands: &[&str] = if has_tests {
+        &["analyze", "test"]
+    } else {
+        &["analyze"]
+    };
+    commands
+        .iter()
+        .map(|command| {
+            (
+                command.to_string(),
+                WorkspaceTask {
+                    command: shell_words::join([program, command]),
+                    description: format!("Run {program} {command} for {}", pubspec.name),
+                    source: source.to_path_buf(),
+                    provenance: WorkspaceProvenance {
+                        provider: Some("dart".to_string()),
+                        source: Some(source.to_path_buf()),
+                    },
+                    suggestions: WorkspaceTaskSuggestions::default(),
+                },
+            )
+        })
+        .collect()
+}
+
+fn normalize_relative(path: &Path) -> PathBuf {
+    if path.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        path.to_path_buf()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::tempdir;
+
+    use super::*;
+
+    fn write(path: &Path, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    fn id(name: &str) -> ProjectId {
+        ProjectId::new("dart", name).unwrap()
+    }
+
+    fn workspace(root: &Path) {
+        write(
+            &root.join(PUBSPEC),
+            "name: acme_workspace\n\
+             environment:\n  sdk: ^3.6.0\n\
+             workspace:\n  - apps/mobile\n  - packages/*\n",
+        );
+        write(
+            &root.join("apps/mobile/pubspec.yaml"),
+            "name: mobile\nresolution: workspace\n\
+             dependencies:\n  flutter:\n    sdk: flutter\n  models: ^1.0.0\n  \
+             design:\n    path: ../../tools/design\n",
+        );
+        write(&root.join("apps/mobile/test/widget_test.dart"), "");
+        write(
+            &root.join("packages/models/pubspec.yaml"),
+            "name: models\nresolution: workspace\n\
+             dev_dependencies:\n  test: ^1.25.0\n",
+        );
+        write(&root.join("packages/models/test/models_test.dart"), "");
+        write(&root.join("tools/design/pubspec.yaml"), "name: design\n");
+    }
+
+    #[test]
+    fn discovers_members_and_path_dependencies() {
+        let temp = tempdir().unwrap();
+        workspace(temp.path());
+
+        let projects = DartWorkspaceProvider.discover(temp.path()).unwrap();
+        let summary = projects
+            .iter()
+            .map(|project| {
+                (
+                    project.id.as_str(),
+                    project.root.as_path(),
+                    project.dependencies.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            summary,
+            vec![
+                ("dart:acme_workspace", Path::new("."), BTreeSet::new()),
+                (
+                    "dart:mobile",
+                    Path::new("apps/mobile"),
+                    BTreeSet::from([id("design"), id("models")])
+                ),
+                ("dart:models", Path::new("packages/models"), BTreeSet::new()),
+                ("dart:design", Path::new("tools/design"), BTreeSet::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn infers_flutter_and_dart_tasks() {
+        let temp = tempdir().unwrap();
+        workspace(temp.path());
+
+        let projects = DartWorkspaceProvider.discover(temp.path()).unwrap();
+        let commands = |name: &str| {
+            projects
+                .iter()
+                .find(|project| project.id == id(name))
+                .map(|project| {
+                    project
+                        .tasks
+                        .values()
+                        .map(|task| task.command.clone())
+                        .collect::<Vec<_>>()
+                })
+                .unwrap()
+        };
+
+        assert_eq!(commands("mobile"), ["flutter analyze", "flutter test"]);
+        assert_eq!(commands("models"), ["dart analyze", "dart test"]);
+        assert_eq!(commands("design"), ["dart analyze"]);
+    }
+
+    #[test]
+    fn requires_workspace_resolution_for_members() {
+        let temp = tempdir().unwrap();
+        write(
+            &temp.path().join(PUBSPEC),
+            "name: acme_workspace\nworkspace:\n  - packages/models\n",
+        );
+        write(
+            &temp.path().join("packages/models/pubspec.yaml"),
+            "name: models\n",
+        );
+
+        let error = DartWorkspaceProvider.discover(temp.path()).unwrap_err();
+
+        assert!(error.to_string().contains("resolution: workspace"));
+    }
+
+    #[test]
+    fn attributes_pubspec_locks_to_packages_beneath_them() {
+        let temp = tempdir().unwrap();
+        workspace(temp.path());
+        let graph = WorkspaceProjectGraph::discover(&DartWorkspaceProvider, temp.path()).unwrap();
+        let affected = |path: &str| {
+            graph
+                .affected_projects_for_lockfile(
+                    &[&DartWorkspaceProvider],
+                    Path::new(path),
+                    None,
+                    None,
+                )
+                .unwrap()
+        };
+
+        assert_eq!(
+            affected(PUBSPEC_LOCK).map(|projects| projects.len()),
+            Some(4)
+        );
+        assert_eq!(
+            affected("tools/design/pubspec.lock"),
+            Some(BTreeSet::from([id("design")]))
+        );
+        assert_eq!(affected(PUBSPEC), None);
+    }
+}
@@ -0,0 +1,708 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use eyre::{Context, Result, bail};
+use glob::{MatchOptions, Pattern};
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::{
+    ProjectId, WorkspaceDiscoveryContext, WorkspaceProject, WorkspaceProjectGraph,
+    WorkspaceProvenance, WorkspaceProvider, WorkspaceTask, WorkspaceTaskSuggestions,
+};
+
+const DENO_CONFIGS: [&str; 2] = ["deno.json", "deno.jsonc"];
+const DENO_LOCK: &str = "deno.lock";
+
+/// Discovers Deno packages from the `workspace` members of the root `deno.json`.
+#[derive(Debug, Default)]
+pub struct DenoWorkspaceProvider;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct DenoConfig {
+    name: Option<String>,
+    workspace: Option<DenoWorkspace>,
+    #[serde(rename = "importMap")]
+    import_map: Option<String>,
+    #[serde(flatten)]
+    imports: ImportMap,
+    tasks: BTreeMap<String, DenoTask>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum DenoWorkspace {
+    Members(Vec<String>),
+    Object { members: Vec<String> },
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ImportMap {
+    imports: BTreeMap<String, String>,
+    scopes: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum DenoTask {
+    Command(String),
+    Definition {
+        command: Option<String>,
+        description: Option<String>,
+    },
+}
+
+struct DenoPackage {
+    root: PathBuf,
+    canonical_root: PathBuf,
+    source: PathBuf,
+    config: DenoConfig,
+    id: ProjectId,
+}
+
+impl WorkspaceProvider for DenoWorkspaceProvider {
+    fn id(&self) -> &str {
+        "deno"
+    }
+
+    fn discover(&self, workspace_root: &Path) -> Result<Vec<WorkspaceProject>> {
+        let context = WorkspaceDiscoveryContext::new();
+        self.discover_with_context(workspace_root, &context)
+    }
+
+    fn discover_with_context(
+        &self,
+        workspace_root: &Path,
+        context: &WorkspaceDiscoveryContext,
+    ) -> Result<Vec<WorkspaceProject>> {
+        let Some(root_source) = config_file(workspace_root, context) else {
+            return Ok(Vec::new());
+        };
+        let root_config = read_config(context, &workspace_root.join(root_source))?;
+        let members = match &root_config.workspace {
+            Some(DenoWorkspace::Members(members) | DenoWorkspace::Object { members }) => {
+                members.clone()
+            }
+            None => return Ok(Vec::new()),
+        };
+        let canonical_root = context.canonicalize(workspace_root).wrap_err_with(|| {
+            format!(
+                "failed to resolve Deno workspace root {}",
+                workspace_root.display()
+            )
+        })?;
+
+        let mut roots = discover_members(workspace_root, &canonical_root, &members, context)?;
+        if root_config.name.is_some() {
+            roots.insert(canonical_root.clone(), root_source);
+        }
+        let mut root_config = Some(root_config);
+        let packages = roots
+            .into_iter()
+            .map(|(package_root, source)| {
+                let relative = normalize_relative(
+                    package_root
+                        .strip_prefix(&canonical_root)
+                        .unwrap_or(Path::new("")),
+                );
+                let config = match root_config.take_if(|_| package_root == canonical_root) {
+                    Some(config) => config,
+                    None => read_config(context, &package_root.join(source))?,
+                };
+                // Unnamed members, like applications, can't be imported by name, so their
+                // location is their only identity.
+                let local_id = match &config.name {
+                    Some(name) => name.clone(),
+                    None => relative.to_string_lossy().into_owned(),
+                };
+                Ok(DenoPackage {
+                    source: relative.join(source),
+                    root: relative,
+                    canonical_root: package_root,
+                    id: ProjectId::new(self.id(), &local_id)?,
+                    config,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let ids_by_name = packages
+            .iter()
+            .filter_map(|package| Some((package.config.name.clone()?, package.id.clone())))
+            .collect::<BTreeMap<_, _>>();
+        let mut ids_by_root = packages
+            .iter()
+            .map(|package| (package.canonical_root.clone(), package.id.clone()))
+            .collect::<Vec<_>>();
+        // The deepest package root owns a path.
+        ids_by_root.sort_by_key(|(root, _)| std::cmp::Reverse(root.components().count()));
+
+        packages
+            .into_iter()
+            .map(|package| {
+                let mut dependencies = BTreeSet::new();
+                for (base, specifier) in import_specifiers(context, &package)? {
+                    let dependency = if let Some(name) = jsr_package_name(&specifier) {
+                        ids_by_name.get(name)
+                    } else if specifier.starts_with("./") || specifier.starts_with("../") {
+                        let target = base.join(&specifier);
+                        if !context.exists(&target) {
+                            continue;
+                        }
+                        let target = context.canonicalize(&target).wrap_err_with(|| {
+                            format!("failed to resolve Deno import {}", target.display())
+                        })?;
+                        ids_by_root
+                            .iter()
+                            .find(|(root, _)| target.starts_with(root))
+                            .map(|(_, id)| id)
+                    } else {
+                        None
+                    };
+                    if let Some(dependency) = dependency
+                        && *dependency != package.id
+                    {
+                        dependencies.insert(dependency.clone());
+                    }
+                }
+
+                let provenance = WorkspaceProvenance {
+                    provider: Some(self.id().to_string()),
+                    source: Some(package.source.clone()),
+                };
+                let mut project = WorkspaceProject::new(package.id, package.root);
+                project.dependency_provenance = dependencies
+                    .iter()
+                    .cloned()
+                    .map(|dependency| (dependency, provenance.clone()))
+                    .collect();
+                project.dependencies = dependencies;
+                project.provenance = provenance;
+                project
+                    .metadata
+                    .insert("workspace_source".to_string(), root_source.to_string());
+                project.tasks = workspace_tasks(&package.config, &package.source);
+                Ok(project)
+            })
+            .collect()
+    }
+
+    fn discover_project_tasks(
+        &self,
+        workspace_root: &Path,
+        project_root: &Path,
+    ) -> Result<BTreeMap<String, WorkspaceTask>> {
+        let context = WorkspaceDiscoveryContext::new();
+        self.discover_project_tasks_with_context(workspace_root, project_root, &context)
+    }
+
+    fn discover_project_tasks_with_context(
+        &self,
+        workspace_root: &Path,
+        project_root: &Path,
+        context: &WorkspaceDiscoveryContext,
+    ) -> Result<BTreeMap<String, WorkspaceTask>> {
+        if config_file(workspace_root, context).is_none() {
+            return Ok(BTreeMap::new());
+        }
+        let Some(source) = config_file(&workspace_root.join(project_root), context) else {
+            return Ok(BTreeMap::new());
+        };
+        let source = project_root.join(source);
+        let config = read_config(context, &workspace_root.join(&source))?;
+        Ok(workspace_tasks(&config, &source))
+    }
+
+    fn affected_projects_for_lockfile(
+        &self,
+        lockfile_path: &Path,
+        before: Option<&str>,
+        after: Option<&str>,
+        graph: &WorkspaceProjectGraph,
+    ) -> Result<Option<BTreeSet<ProjectId>>> {
+        if lockfile_path != Path::new(DENO_LOCK) {
+            return Ok(None);
+        }
+        Ok(Some(deno_lock_affected_projects(before, after, graph)))
+    }
+}
+
+/// Attributes a `deno.lock` change to the workspace members whose recorded dependencies
+/// changed or resolve differently. Anything else falls back to every Deno project.
+fn deno_lock_affected_projects(
+    before: Option<&str>,
+    after: Option<&str>,
+    graph: &WorkspaceProjectGraph,
+) -> BTreeSet<ProjectId> {
+    let all_projects = deno_project_ids(graph);
+    let (Some(before), Some(after)) = (before, after) else {
+        return all_projects;
+    };
+    let (Ok(mut before), Ok(mut after)) = (
+        serde_json::from_str::<Value>(before),
+        serde_json::from_str::<Value>(after),
+    ) else {
+        return all_projects;
+    };
+    let take = |lock: &mut Value, parent: &str, key: &str| {
+        lock.pointer_mut(parent)
+            .and_then(Value::as_object_mut)
+            .and_then(|parent| parent.remove(key))
+            .and_then(|value| match value {
+                Value::Object(map) => Some(map),
+                _ => None,
+            })
+            .unwrap_or_default()
+    };
+    let (before_members, after_members) = (
+        take(&mut before, "/workspace", "members"),
+        take(&mut after, "/workspace", "members"),
+    );
+    let (before_specifiers, after_specifiers) = (
+        take(&mut before, "", "specifiers"),
+        take(&mut after, "", "specifiers"),
+    );
+    let (before_packages, after_packages) = (
+        ["jsr", "npm"].map(|key| take(&mut before, "", key)),
+        ["jsr", "npm"].map(|key| take(&mut after, "", key)),
+    );
+    // The lockfile version, remote modules and root dependencies apply to every member.
+    if before != after {
+        return all_projects;
+    }
+    let changed_specifiers = before_specifiers
+        .keys()
+        .chain(after_specifiers.keys())
+        .filter(|key| before_specifiers.get(*key) != after_specifiers.get(*key))
+        .map(String::as_str)
+        .collect::<BTreeSet<_>>();
+    if changed_specifiers.is_empty() && before_packages != after_packages {
+        return all_projects;
+    }
+
+    let projects_by_root = graph
+        .projects()
+        .filter(|project| project.id.as_str().starts_with("deno:"))
+        .map(|project| (project.root.clone(), project.id.clone()))
+        .collect::<BTreeMap<_, _>>();
+    let mut declared = BTreeSet::new();
+    let mut affected = BTreeSet::new();
+    for member in before_members.keys().chain(after_members.keys()) {
+        let (old, new) = (before_members.get(member), after_members.get(member));
+        let mut specifiers = BTreeSet::new();
+        old.into_iter()
+            .chain(new)
+            .for_each(|entry| collect_strings(entry, &mut specifiers));
+        let resolution_changed = specifiers
+            .iter()
+            .any(|specifier| changed_specifiers.contains(specifier));
+        declared.extend(specifiers);
+        if old == new && !resolution_changed {
+            continue;
+        }
+        let root = normalize_relative(Path::new(member.trim_start_matches("./")));
+        let Some(project) = projects_by_root.get(&root) else {
+            return all_projects;
+        };
+        affected.insert(project.clone());
+    }
+    // A changed transitive resolution can't be traced back to the members that use it.
+    if changed_specifiers
+        .iter()
+        .any(|specifier| !declared.contains(*specifier))
+    {
+        return all_projects;
+    }
+    affected
+}
+
+fn collect_strings<'a>(value: &'a Value, strings: &mut BTreeSet<&'a str>) {
+    match value {
+        Value::String(string) => {
+            strings.insert(string);
+        }
+        Value::Array(values) => values
+            .iter()
+            .for_each(|value| collect_strings(value, strings)),
+        Value::Object(map) => map
+            .values()
+            .for_each(|value| collect_strings(value, strings)),
+        _ => {}
+    }
+}
+
+fn deno_project_ids(graph: &WorkspaceProjectGraph) -> BTreeSet<ProjectId> {
+    graph
+        .projects()
+        .filter(|project| project.id.as_str().starts_with("deno:"))
+        .map(|project| project.id.clone())
+        .collect()
+}
+
+fn config_file(directory: &Path, context: &WorkspaceDiscoveryContext) -> Option<&'static str> {
+    DENO_CONFIGS
+        .into_iter()
+        .find(|name| context.is_file(&directory.join(name)))
+}
+
+fn read_config(context: &WorkspaceDiscoveryContext, path: &Path) -> Result<DenoConfig> {
+    let contents = context
+        .read_to_string(path)
+        .wrap_err_with(|| format!("failed to read Deno config {}", path.display()))?;
+    serde_json::from_str(&strip_jsonc(&contents))
+        .wrap_err_with(|| format!("failed to parse Deno config {}", path.display()))
+}
+
+fn discover_members(
+    pattern_root: &Path,
+    canonical_root: &Path,
+    members: &[String],
+    context: &WorkspaceDiscoveryContext,
+) -> Result<BTreeMap<PathBuf, &'static str>> {
+    let options = MatchOptions {
+        case_sensitive: true,
+        require_literal_separator: true,
+        require_literal_leading_dot: true,
+    };
+    let escaped_root = Pattern::escape(&pattern_root.to_string_lossy());
+    let mut roots = BTreeMap::new();
+    for member in members {
+        if Path::new(member).is_absolute() {
+            bail!("Deno workspace member {member:?} must be relative");
+        }
+        let member = member.trim_start_matches("./").trim_end_matches('/');
+        let pattern = format!("{escaped_root}/{member}");
+        for candidate in glob::glob_with(&pattern, options)
+            .wrap_err_with(|| format!("invalid Deno workspace member pattern {member:?}"))?
+        {
+            let candidate = candidate.wrap_err_with(|| {
+                format!("failed to evaluate Deno workspace member pattern {member:?}")
+            })?;
+            // Members with only a package.json are npm packages, which the Node provider owns.
+            let Some(source) = config_file(&candidate, context) else {
+                continue;
+            };
+            let candidate = context.canonicalize(&candidate).wrap_err_with(|| {
+                format!(
+                    "failed to resolve Deno workspace member {}",
+                    candidate.display()
+                )
+            })?;
+            if !candidate.starts_with(canonical_root) {
+                bail!(
+                    "Deno workspace member {} is outside workspace root {}",
+                    candidate.display(),
+                    canonical_root.display()
+                );
+            }
+            roots.insert(candidate, source);
+        }
+    }
+    Ok(roots)
+}
+
+/// Returns the specifiers a package's import map can resolve to, with the directory
+/// relative specifiers resolve from.
+fn import_specifiers(
+    context: &WorkspaceDiscoveryContext,
+    package: &DenoPackage,
+) -> Result<Vec<(PathBuf, String)>> {
+    let external = match &package.config.import_map {
+        Some(import_map) => {
+            let path = package.canonical_root.join(import_map);
+            let contents = context
+                .read_to_string(&path)
+                .wrap_err_with(|| format!("failed to read Deno import map {}", path.display()))?;
+            let parsed = serde_json::from_str::<ImportMap>(&strip_jsonc(&contents))
+                .wrap_err_with(|| format!("failed to parse Deno import map {}", path.display()))?;
+            let base = path.parent().unwrap_or(&package.canonical_root);
+            Some((base.to_path_buf(), parsed))
+        }
+        None => None,
+    };
+    let import_maps = std::iter::once((package.canonical_root.clone(), &package.config.imports))
+        .chain(external.iter().map(|(base, parsed)| (base.clone(), parsed)));
+    Ok(import_maps
+        .flat_map(|(base, import_map)| {
+            import_map
+                .imports
+                .values()
+                .chain(import_map.scopes.values().flat_map(BTreeMap::values))
+                .map(move |specifier| (base.clone(), specifier.clone()))
+        })
+        .collect())
+}
+
+/// Returns the package name of a `jsr:` specifier, like `@scope/name` for
+/// `jsr:@scope/name@^1/mod.ts`.
+fn jsr_package_name(specifier: &str) -> Option<&str> {
+    let rest = specifier.strip_prefix("jsr:")?.trim_start_matches('/');
+    let scope_end = rest.strip_prefix('@')?.find('/')? + 1;
+    let name_end = rest[scope_end + 1..]
+        .find(['@', '/'])
+        .map_or(rest.len(), |end| scope_end + 1 + end);
+    Some(&rest[..name_end])
+}
+
+fn workspace_tasks(config: &DenoConfig, source: &Path) -> BTreeMap<String, WorkspaceTask> {
+    config
+        .tasks
+        .iter()
+        .map(|(name, task)| {
+            let description = match task {
+                DenoTask::Command(command) => command.clone(),
+                DenoTask::Definition {
+                    command,
+                    description,
+                } => description
+                    .clone()
+                    .or_else(|| command.clone())
+                    .unwrap_or_default(),
+            };
+            (
+                name.clone(),
+                WorkspaceTask {
+                    command: shell_words::join(["deno", "task", name]),
+                    description,
+                    source: source.to_path_buf(),
+                    provenance: WorkspaceProvenance {
+                        provider: Some("deno".to_string()),
+                        source: Some(source.to_path_buf()),
+                    },
+                    suggestions: WorkspaceTaskSuggestions::default(),
+                },
+            )
+        })
+        .collect()
+}
+
+/// Converts JSONC, which `deno.jsonc` and `deno.json` both accept, to JSON by removing
+/// comments and trailing commas outside of strings.
+fn strip_jsonc(contents: &str) -> String {
+    let mut out = String::with_capacity(contents.len());
+    let mut chars = contents.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => while chars.next_if(|c| *c != '\n').is_some() {},
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = None;
+                for c in chars.by_ref() {
+                    if previous == Some('*') && c == '/' {
+                        break;
+                    }
+                    previous = Some(c);
+                }
+                out.push(' ');
+            }
+            (']' | '}', _) => {
+                let trimmed = out.trim_end().len();
+                if out[..trimmed].ends_with(',') {
+                    out.truncate(trimmed - 1);
+                }
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn normalize_relative(path: &Path) -> PathBuf {
+    if path.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        path.to_path_buf()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::tempdir;
+
+    use super::*;
+
+    fn write(path: &Path, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    fn id(local: &str) -> ProjectId {
+        ProjectId::new("deno", local).unwrap()
+    }
+
+    fn workspace(root: &Path) {
+        write(
+            &root.join("deno.jsonc"),
+            r#"{
+  // shared settings for every member
+  "workspace": ["./packages/*", "./functions/hello"],
+  "imports": { "@std/assert": "jsr:@std/assert@^1" },
+}"#,
+        );
+        write(
+            &root.join("packages/core/deno.json"),
+            r#"{"name":"@acme/core","exports":"./mod.ts"}"#,
+        );
+        write(&root.join("packages/core/mod.ts"), "");
+        write(
+            &root.join("packages/web/deno.json"),
+            r#"{"name":"@acme/web","imports":{"@acme/core":"jsr:@acme/core@^1"},
+                "tasks":{"dev":"deno run -A main.ts","build":{"command":"deno bundle","description":"Bundle the app"}}}"#,
+        );
+        write(
+            &root.join("functions/hello/deno.json"),
+            r#"{"importMap":"./import_map.json"}"#,
+        );
+        write(
+            &root.join("functions/hello/import_map.json"),
+            r#"{"imports":{"core/":"../../packages/core/"}}"#,
+        );
+    }
+
+    #[test]
+    fn discovers_members_and_import_map_dependencies() {
+        let temp = tempdir().unwrap();
+        workspace(temp.path());
+
+        let projects = DenoWorkspaceProvider.discover(temp.path()).unwrap();
+        let summary = projects
+            .iter()
+            .map(|project| {
+                (
+                    project.id.as_str(),
+                    project.root.as_path(),
+                    project.dependencies.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "deno:functions/hello",
+                    Path::new("functions/hello"),
+                    BTreeSet::from([id("@acme/core")])
+                ),
+                (
+                    "deno:@acme/core",
+                    Path::new("packages/core"),
+                    BTreeSet::new()
+                ),
+                (
+                    "deno:@acme/web",
+                    Path::new("packages/web"),
+                    BTreeSet::from([id("@acme/core")])
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn infers_deno_tasks() {
+        let temp = tempdir().unwrap();
+        workspace(temp.path());
+
+        let projects = DenoWorkspaceProvider.discover(temp.path()).unwrap();
+        let web = projects
+            .iter()
+            .find(|project| project.id == id("@acme/web"))
+            .unwrap();
+
+        assert_eq!(
+            web.tasks
+                .iter()
+                .map(|(name, task)| (
+                    name.as_str(),
+                    task.command.as_str(),
+                    task.description.as_str()
+                ))
+                .collect::<Vec<_>>(),
+            [
+                ("build", "deno task build", "Bundle the app"),
+                ("dev", "deno task dev", "deno run -A main.ts"),
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_configs_without_a_workspace() {
+        let temp = tempdir().unwrap();
+        write(&temp.path().join("deno.json"), r#"{"name":"@acme/solo"}"#);
+
+        assert!(
+            DenoWorkspaceProvider
+                .discover(temp.path())
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn attributes_lockfile_changes_to_members() {
+        let temp = tempdir().unwrap();
+        workspace(temp.path());
+        let graph = WorkspaceProjectGraph::discover(&DenoWorkspaceProvider, temp.path()).unwrap();
+        let lock = |web_assert: &str, hono: &str| {
+            format!(
+                r#"{{"version":"4",
+                  "specifiers":{{"jsr:@std/assert@1":"{web_assert}","npm:hono@4":"{hono}"}},
+                  "workspace":{{"dependencies":["jsr:@std/assert@^1"],"members":{{
+                    "packages/web":{{"dependencies":["jsr:@std/assert@1"]}},
+                    "functions/hello":{{"dependencies":["npm:hono@4"]}}}}}}}}"#
+            )
+        };
+        let affected = |before: &str, after: &str| {
+            graph
+                .affected_projects_for_lockfile(
+                    &[&DenoWorkspaceProvider],
+                    Path::new(DENO_LOCK),
+                    Some(before),
+                    Some(after),
+                )
+                .unwrap()
+                .unwrap()
+        };
+
+        assert_eq!(
+            affected(&lock("1.0.0", "4.0.0"), &lock("1.0.1", "4.0.0")),
+            BTreeSet::from([id("@acme/web")])
+        );
+        assert_eq!(
+            affected(&lock("1.0.0", "4.0.0"), &lock("1.0.0", "4.1.0")),
+            BTreeSet::from([id("functions/hello")])
+        );
+        assert_eq!(
+            affected(
+                &lock("1.0.0", "4.0.0"),
+                &lock("1.0.0", "4.0.0").replace(r#""version":"4""#, r#""version":"5""#)
+            ),
+            deno_project_ids(&graph)
+        );
+    }
+
+    #[test]
+    fn strips_jsonc_comments_and_trailing_commas() {
+        assert_eq!(
+            strip_jsonc("{\"a\": \"//not a comment\", /* c */ \"b\": [1, 2,], // c\n}"),
+            "{\"a\": \"//not a comment\",   \"b\": [1, 2]}"
+        );
+    }
+}
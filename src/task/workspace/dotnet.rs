@@ -0,0 +1,645 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+use eyre::{Context, Result, bail};
+use glob::{MatchOptions, Pattern};
+use xx::regex;
+
+use super::{
+    ProjectId, WorkspaceDiscoveryContext, WorkspaceProject, WorkspaceProjectGraph,
+    WorkspaceProvenance, WorkspaceProvider, WorkspaceTask, WorkspaceTaskSuggestions,
+};
+use crate::plugins::core::dotnet_bin;
+use crate::xml::XmlElement;
+
+const SOLUTION_PATTERNS: [&str; 2] = ["*.sln", "*.slnx"];
+const PROJECT_EXTENSIONS: [&str; 2] = ["csproj", "fsproj"];
+const PROJECT_PATTERNS: [&str; 2] = ["*.csproj", "*.fsproj"];
+const CENTRAL_PACKAGES: &str = "Directory.Packages.props";
+const PACKAGES_LOCKFILE: &str = "packages.lock.json";
+const GLOBAL_JSON: &str = "global.json";
+const TEST_SDK_PACKAGE: &str = "Microsoft.NET.Test.Sdk";
+
+/// Discovers .NET projects listed by solution files and their project references.
+#[derive(Debug, Default)]
+pub struct DotnetWorkspaceProvider;
+
+struct DotnetProject {
+    root: PathBuf,
+    source: PathBuf,
+    solution: PathBuf,
+    name: String,
+    references: BTreeSet<PathBuf>,
+    is_test: bool,
+}
+
+impl WorkspaceProvider for DotnetWorkspaceProvider {
+    fn id(&self) -> &str {
+        "dotnet"
+    }
+
+    fn discover(&self, workspace_root: &Path) -> Result<Vec<WorkspaceProject>> {
+        let context = WorkspaceDiscoveryContext::new();
+        self.discover_with_context(workspace_root, &context)
+    }
+
+    fn discover_with_context(
+        &self,
+        workspace_root: &Path,
+        context: &WorkspaceDiscoveryContext,
+    ) -> Result<Vec<WorkspaceProject>> {
+        let solutions = solution_files(workspace_root)?;
+        if solutions.is_empty() {
+            return Ok(Vec::new());
+        }
+        let canonical_root = context.canonicalize(workspace_root).wrap_err_with(|| {
+            format!(
+                "failed to resolve .NET workspace root {}",
+                workspace_root.display()
+            )
+        })?;
+
+        let mut queue = VecDeque::new();
+        for solution in solutions {
+            for path in read_solution(context, &workspace_root.join(&solution))? {
+                let candidate = workspace_root.join(&path);
+                if !context.is_file(&candidate) {
+                    bail!(
+                        ".NET project {} listed in {} does not exist",
+                        path.display(),
+                        solution.display()
+                    );
+                }
+                let candidate = context.canonicalize(&candidate).wrap_err_with(|| {
+                    format!("failed to resolve .NET project {}", candidate.display())
+                })?;
+                queue.push_back((candidate, solution.clone()));
+            }
+        }
+
+        // Referenced projects are built with the solution even when it does not list them.
+        let mut projects = BTreeMap::new();
+        while let Some((project_path, solution)) = queue.pop_front() {
+            if projects.contains_key(&project_path) {
+                continue;
+            }
+            // Projects outside the repository cannot be represented by a relative root.
+            let Ok(source) = project_path.strip_prefix(&canonical_root) else {
+                continue;
+            };
+            let source = source.to_path_buf();
+            let project_dir = project_path.parent().unwrap_or(&canonical_root);
+            let xml = read_project(context, &project_path)?;
+            let mut references = BTreeSet::new();
+            for reference in item_includes(&xml, "ProjectReference") {
+                // MSBuild properties are only known while building.
+                if reference.contains("$(") {
+                    continue;
+                }
+                let candidate = project_dir.join(msbuild_path(reference));
+                if !is_project_file(&candidate) || !context.is_file(&candidate) {
+                    continue;
+                }
+                let candidate = context.canonicalize(&candidate).wrap_err_with(|| {
+                    format!(
+                        "failed to resolve .NET project reference {}",
+                        candidate.display()
+                    )
+                })?;
+                references.insert(candidate.clone());
+                queue.push_back((candidate, solution.clone()));
+            }
+            let is_test = is_test_project(&xml);
+            let name = project_path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let root = normalize_relative(source.parent().unwrap_or(Path::new("")));
+            projects.insert(
+                project_path.clone(),
+                DotnetProject {
+                    root,
+                    source,
+                    solution,
+                    name,
+                    references,
+                    is_test,
+                },
+            );
+        }
+
+        // Projects are named after their file, unless several files share a name, which then
+        // identify their projects by path instead.
+        let mut name_counts = BTreeMap::<&str, usize>::new();
+        for project in projects.values() {
+            *name_counts.entry(project.name.as_str()).or_default() += 1;
+        }
+        let ids_by_path = projects
+            .iter()
+            .map(|(path, project)| {
+                let local = if name_counts[project.name.as_str()] > 1 {
+                    project
+                        .source
+                        .components()
+                        .map(|component| component.as_os_str().to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join("/")
+                } else {
+                    project.name.clone()
+                };
+                Ok((path.clone(), ProjectId::new(self.id(), &local)?))
+            })
+            .collect::<Result<BTreeMap<_, _>>>()?;
+
+        projects
+            .into_iter()
+            .map(|(path, dotnet_project)| {
+                let id = ids_by_path[&path].clone();
+                let dependencies = dotnet_project
+                    .references
+                    .iter()
+                    .filter_map(|reference| ids_by_path.get(reference))
+                    .filter(|dependency| **dependency != id)
+                    .cloned()
+                    .collect::<BTreeSet<_>>();
+                let provenance = WorkspaceProvenance {
+                    provider: Some(self.id().to_string()),
+                    source: Some(dotnet_project.source.clone()),
+                };
+                let mut project = WorkspaceProject::new(id, dotnet_project.root.clone());
+                project.dependency_provenance = dependencies
+                    .iter()
+                    .cloned()
+                    .map(|dependency| (dependency, provenance.clone()))
+                    .collect();
+                project.dependencies = dependencies;
+                project.provenance = provenance;
+                project.metadata.insert(
+                    "workspace_source".to_string(),
+                    dotnet_project.solution.display().to_string(),
+                );
+                project.tasks = workspace_tasks(&dotnet_project.source, dotnet_project.is_test);
+                Ok(project)
+            })
+            .collect()
+    }
+
+    fn discover_project_tasks(
+        &self,
+        workspace_root: &Path,
+        project_root: &Path,
+    ) -> Result<BTreeMap<String, WorkspaceTask>> {
+        let context = WorkspaceDiscoveryContext::new();
+        self.discover_project_tasks_with_context(workspace_root, project_root, &context)
+    }
+
+    fn discover_project_tasks_with_context(
+        &self,
+        workspace_root: &Path,
+        project_root: &Path,
+        context: &WorkspaceDiscoveryContext,
+    ) -> Result<BTreeMap<String, WorkspaceTask>> {
+        let directory = workspace_root.join(project_root);
+        if solution_files(workspace_root)?.is_empty() || !context.is_dir(&directory) {
+            return Ok(BTreeMap::new());
+        }
+        let project_files = matching_files(&directory, &PROJECT_PATTERNS)?;
+        let mut project_files = project_files.into_iter();
+        let Some(project_file) = project_files.next() else {
+            return Ok(BTreeMap::new());
+        };
+        if let Some(other) = project_files.next() {
+            bail!(
+                "{} contains several .NET projects, including {} and {}",
+                project_root.display(),
+                project_file.display(),
+                other.display()
+            );
+        }
+        let source = project_root.join(project_file);
+        let xml = read_project(context, &workspace_root.join(&source))?;
+        Ok(workspace_tasks(&source, is_test_project(&xml)))
+    }
+
+    fn affected_projects_for_lockfile(
+        &self,
+        lockfile_path: &Path,
+        _before: Option<&str>,
+        _after: Option<&str>,
+        graph: &WorkspaceProjectGraph,
+    ) -> Result<Option<BTreeSet<ProjectId>>> {
+        let Some(name) = lockfile_path.file_name() else {
+            return Ok(None);
+        };
+        let directory = normalize_relative(lockfile_path.parent().unwrap_or(Path::new("")));
+        // MSBuild and the SDK resolve these files from the nearest ancestor directory, so
+        // they apply to every project beneath them.
+        let beneath = || {
+            graph
+                .projects()
+                .filter(|project| project.id.as_str().starts_with("dotnet:"))
+                .filter(|project| {
+                    directory == Path::new(".") || project.root.starts_with(&directory)
+                })
+                .map(|project| project.id.clone())
+                .collect::<BTreeSet<_>>()
+        };
+        if name == PACKAGES_LOCKFILE {
+            let owner = graph
+                .projects()
+                .find(|project| {
+                    project.id.as_str().starts_with("dotnet:") && project.root == directory
+                })
+                .map(|project| BTreeSet::from([project.id.clone()]));
+            return Ok(Some(owner.unwrap_or_else(beneath)));
+        }
+        if name == CENTRAL_PACKAGES || name == GLOBAL_JSON {
+            return Ok(Some(beneath()));
+        }
+        Ok(None)
+    }
+}
+
+/// Returns the solution files in the workspace root, relative to it.
+fn solution_files(workspace_root: &Path) -> Result<BTreeSet<PathBuf>> {
+    matching_files(workspace_root, &SOLUTION_PATTERNS)
+}
+
+/// Returns the names of the files in `dir` that match any of `patterns`.
+fn matching_files(dir: &Path, patterns: &[&str]) -> Result<BTreeSet<PathBuf>> {
+    let options = MatchOptions {
+        case_sensitive: true,
+        require_literal_separator: true,
+        require_literal_leading_dot: true,
+    };
+    let escaped_dir = Pattern::escape(&dir.to_string_lossy());
+    let mut files = BTreeSet::new();
+    for pattern in patterns {
+        for file in glob::glob_with(&format!("{escaped_dir}/{pattern}"), options)
+            .wrap_err_with(|| format!("invalid .NET file pattern {pattern:?}"))?
+        {
+            let file =
+                file.wrap_err_with(|| format!("failed to find .NET files in {}", dir.display()))?;
+            if let Some(name) = file.file_name() {
+                files.insert(PathBuf::from(name));
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Returns the project files a `.sln` or `.slnx` solution lists, relative to the workspace.
+fn read_solution(context: &WorkspaceDiscoveryContext, path: &Path) -> Result<Vec<PathBuf>> {
+    let contents = context
+        .read_to_string(path)
+        .wrap_err_with(|| format!("failed to read .NET solution {}", path.display()))?;
+    let projects = if path.extension().is_some_and(|ext| ext == "slnx") {
+        let solution = XmlElement::parse(&contents)
+            .wrap_err_with(|| format!("failed to parse .NET solution {}", path.display()))?;
+        let mut projects = Vec::new();
+        let mut pending = vec![&solution];
+        while let Some(element) = pending.pop() {
+            for child in &element.children {
+                match (child.name.as_str(), child.attribute("Path")) {
+                    ("Project", Some(project)) => projects.push(msbuild_path(project)),
+                    ("Folder", _) => pending.push(child),
+                    _ => {}
+                }
+            }
+        }
+        projects
+    } else {
+        regex!(r#"(?m)^\s*Project\("[^"]*"\)\s*=\s*"[^"]*"\s*,\s*"([^"]+)""#)
+            .captures_iter(&contents)
+            .map(|captures| msbuild_path(&captures[1]))
+            .collect()
+    };
+    // Solution folders are listed like projects, but have no project file extension.
+    Ok(projects
+        .into_iter()
+        .filter(|project| is_project_file(project))
+        .collect())
+}
+
+fn read_project(context: &WorkspaceDiscoveryContext, path: &Path) -> Result<XmlElement> {
+    let contents = context
+        .read_to_string(path)
+        .wrap_err_with(|| format!("failed to read .NET project {}", path.display()))?;
+    XmlElement::parse(&contents)
+        .wrap_err_with(|| format!("failed to parse .NET project {}", path.display()))
+}
+
+/// Whether a project declares itself a test project or references the test SDK.
+fn is_test_project(project: &XmlElement) -> bool {
+    project
+        .children("PropertyGroup")
+        .filter_map(|group| group.child_text("IsTestProject"))
+        .any(|value| value.eq_ignore_ascii_case("true"))
+        || item_includes(project, "PackageReference").any(|name| name == TEST_SDK_PACKAGE)
+}
+
+/// Returns the `Include` of every `item` in the project's item groups.
+fn item_includes<'a>(project: &'a XmlElement, item: &'a str) -> impl Iterator<Item = &'a str> {
+    project
+        .children("ItemGroup")
+        .flat_map(move |group| group.children(item))
+        .filter_map(|item| item.attribute("Include"))
+}
+
+/// Converts an MSBuild path, which always uses `\` separators, to a native path.
+fn msbuild_path(path: &str) -> PathBuf {
+    path.trim().split(['\\', '/']).collect()
+}
+
+fn is_project_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| PROJECT_EXTENSIONS.iter().any(|project| ext == *project))
+}
+
+fn workspace_tasks(source: &Path, is_test: bool) -> BTreeMap<String, WorkspaceTask> {
+    let project_file = source
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let commands: &[&str] = if is_test {
+        &["build", "test"]
+    } else {
+        &["build"]
+    };
+    commands
+        .iter()
+        .map(|command| {
+            (
+                command.to_string(),
+                WorkspaceTask {
+                    command: shell_words::join([dotnet_bin(), command, &project_file]),
+                    description: format!("Run dotnet {command} for {project_file}"),
+                    source: source.to_path_buf(),
+                    provenance: WorkspaceProvenance {
+                        provider: Some("dotnet".to_string()),
+                        source: Some(source.to_path_buf()),
+                    },
+                    suggestions: WorkspaceTaskSuggestions::default(),
+                },
+            )
+        })
+        .collect()
+}
+
+fn normalize_relative(path: &Path) -> PathBuf {
+    if path.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        path.to_path_buf()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::tempdir;
+
+    use super::*;
+
+    fn write(path: &Path, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    fn project(path: &Path, items: &str) {
+        write(
+            path,
+            &format!(
+                "<Project Sdk=\"Microsoft.NET.Sdk\">\n  \
+                 <PropertyGroup><TargetFramework>net8.0</TargetFramework></PropertyGroup>\n  \
+                 <ItemGroup>{items}</ItemGroup>\n</Project>\n"
+            ),
+        );
+    }
+
+    fn id(name: &str) -> ProjectId {
+        ProjectId::new("dotnet", name).unwrap()
+    }
+
+    fn solution(root: &Path) {
+        write(
+            &root.join("Acme.sln"),
+            "Microsoft Visual Studio Solution File, Format Version 12.00\n\
+             Project(\"{2150E333-8FDC-42A3-9474-1A3956D46DE8}\") = \"src\", \"src\", \"{11111111-1111-1111-1111-111111111111}\"\n\
+             EndProject\n\
+             Project(\"{FAE04EC0-301F-11D3-BF4B-00C04F79EFBC}\") = \"Acme.Api\", \"src\\Acme.Api\\Acme.Api.csproj\", \"{22222222-2222-2222-2222-222222222222}\"\n\
+             EndProject\n\
+             Project(\"{FAE04EC0-301F-11D3-BF4B-00C04F79EFBC}\") = \"Acme.Api.Tests\", \"tests\\Acme.Api.Tests\\Acme.Api.Tests.csproj\", \"{33333333-3333-3333-3333-333333333333}\"\n\
+             EndProject\n",
+        );
+        project(
+            &root.join("src/Acme.Api/Acme.Api.csproj"),
+            r#"<ProjectReference Include="..\Acme.Core\Acme.Core.fsproj" />
+               <PackageReference Include="Serilog" Version="3.0.0" />"#,
+        );
+        project(&root.join("src/Acme.Core/Acme.Core.fsproj"), "");
+        project(
+            &root.join("tests/Acme.Api.Tests/Acme.Api.Tests.csproj"),
+            r#"<ProjectReference Include="..\..\src\Acme.Api\Acme.Api.csproj" />
+               <PackageReference Include="Microsoft.NET.Test.Sdk" Version="17.0.0" />"#,
+        );
+    }
+
+    #[test]
+    fn discovers_solution_projects_and_project_references() {
+        let temp = tempdir().unwrap();
+        solution(temp.path());
+
+        let projects = DotnetWorkspaceProvider.discover(temp.path()).unwrap();
+        let summary = projects
+            .iter()
+            .map(|project| {
+                (
+                    project.id.as_str(),
+                    project.root.as_path(),
+                    project.dependencies.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "dotnet:Acme.Api",
+                    Path::new("src/Acme.Api"),
+                    BTreeSet::from([id("Acme.Core")])
+                ),
+                (
+                    "dotnet:Acme.Core",
+                    Path::new("src/Acme.Core"),
+                    BTreeSet::new()
+                ),
+                (
+                    "dotnet:Acme.Api.Tests",
+                    Path::new("tests/Acme.Api.Tests"),
+                    BTreeSet::from([id("Acme.Api")])
+                ),
+            ]
+        );
+        assert!(projects.iter().all(|project| {
+            project.metadata.get("workspace_source").map(String::as_str) == Some("Acme.sln")
+        }));
+    }
+
+    #[test]
+    fn reads_slnx_folders() {
+        let temp = tempdir().unwrap();
+        write(
+            &temp.path().join("Acme.slnx"),
+            r#"<Solution>
+  <Folder Name="/src/">
+    <Project Path="src/Acme.Core/Acme.Core.fsproj" />
+  </Folder>
+</Solution>"#,
+        );
+        project(&temp.path().join("src/Acme.Core/Acme.Core.fsproj"), "");
+
+        let projects = DotnetWorkspaceProvider.discover(temp.path()).unwrap();
+
+        assert_eq!(
+            projects
+                .iter()
+                .map(|project| project.id.as_str())
+                .collect::<Vec<_>>(),
+            ["dotnet:Acme.Core"]
+        );
+    }
+
+    #[test]
+    fn infers_test_tasks_only_for_test_projects() {
+        let temp = tempdir().unwrap();
+        solution(temp.path());
+
+        let projects = DotnetWorkspaceProvider.discover(temp.path()).unwrap();
+        let tasks = |name: &str| {
+            projects
+                .iter()
+                .find(|project| project.id == id(name))
+                .map(|project| {
+                    project
+                        .tasks
+                        .iter()
+                        .map(|(name, task)| (name.clone(), task.command.clone()))
+                        .collect::<Vec<_>>()
+                })
+                .unwrap()
+        };
+
+        assert_eq!(
+            tasks("Acme.Api"),
+            [(
+                "build".to_string(),
+                format!("{} build Acme.Api.csproj", dotnet_bin())
+            )]
+        );
+        assert_eq!(
+            tasks("Acme.Api.Tests")[1],
+            (
+                "test".to_string(),
+                format!("{} test Acme.Api.Tests.csproj", dotnet_bin())
+            )
+        );
+    }
+
+    #[test]
+    fn identifies_projects_sharing_a_file_name_by_path() {
+        let temp = tempdir().unwrap();
+        write(
+            &temp.path().join("Acme.slnx"),
+            r#"<Solution>
+  <Project Path="src/Api/Api.csproj" />
+  <Project Path="samples/Api/Api.csproj" />
+  <Project Path="src/Core/Core.csproj" />
+</Solution>"#,
+        );
+        project(&temp.path().join("src/Api/Api.csproj"), "");
+        project(&temp.path().join("samples/Api/Api.csproj"), "");
+        project(&temp.path().join("src/Core/Core.csproj"), "");
+
+        let projects = DotnetWorkspaceProvider.discover(temp.path()).unwrap();
+
+        assert_eq!(
+            projects
+                .iter()
+                .map(|project| project.id.as_str())
+                .collect::<BTreeSet<_>>(),
+            BTreeSet::from([
+                "dotnet:samples/Api/Api.csproj",
+                "dotnet:src/Api/Api.csproj",
+                "dotnet:Core",
+            ])
+        );
+    }
+
+    #[test]
+    fn root_overrides_keep_inferred_tasks() {
+        let temp = tempdir().unwrap();
+        solution(temp.path());
+        project(
+            &temp.path().join("tests/Moved.Tests/Moved.Tests.csproj"),
+            r#"<PackageReference Include="Microsoft.NET.Test.Sdk" Version="17.0.0" />"#,
+        );
+        let overrides = BTreeMap::from([(
+            "dotnet:Acme.Api.Tests".to_string(),
+            super::super::WorkspaceProjectOverride {
+                root: Some("tests/Moved.Tests".into()),
+                ..Default::default()
+            },
+        )]);
+
+        let graph = WorkspaceProjectGraph::discover_all_with_overrides(
+            &[&DotnetWorkspaceProvider],
+            temp.path(),
+            &overrides,
+        )
+        .unwrap();
+        let project = graph.get(&id("Acme.Api.Tests")).unwrap();
+
+        assert_eq!(
+            project.tasks.get("test").map(|task| task.command.clone()),
+            Some(format!("{} test Moved.Tests.csproj", dotnet_bin()))
+        );
+        assert_eq!(
+            project.tasks.get("build").map(|task| task.source.clone()),
+            Some(PathBuf::from("tests/Moved.Tests/Moved.Tests.csproj"))
+        );
+    }
+
+    #[test]
+    fn attributes_package_locks_and_sdk_settings() {
+        let temp = tempdir().unwrap();
+        solution(temp.path());
+        let graph = WorkspaceProjectGraph::discover(&DotnetWorkspaceProvider, temp.path()).unwrap();
+        let affected = |path: &str| {
+            graph
+                .affected_projects_for_lockfile(
+                    &[&DotnetWorkspaceProvider],
+                    Path::new(path),
+                    None,
+                    None,
+                )
+                .unwrap()
+        };
+
+        assert_eq!(
+            affected("src/Acme.Api/packages.lock.json"),
+            Some(BTreeSet::from([id("Acme.Api")]))
+        );
+        assert_eq!(
+            affected("src/Directory.Packages.props"),
+            Some(BTreeSet::from([id("Acme.Api"), id("Acme.Core")]))
+        );
+        assert_eq!(
+            affected(GLOBAL_JSON).map(|projects| projects.len()),
+            Some(3)
+        );
+        assert_eq!(affected("src/Acme.Api/Acme.Api.csproj"), None);
+    }
+}
@@ -0,0 +1,668 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Component, Path, PathBuf};
+
+use eyre::{Context, Result};
+use xx::regex;
+
+use super::{
+    ProjectId, WorkspaceDiscoveryContext, WorkspaceProject, WorkspaceProjectGraph,
+    WorkspaceProvenance, WorkspaceProvider, WorkspaceTask, WorkspaceTaskSuggestions, path_to_root,
+};
+
+const SETTINGS_FILES: [&str; 2] = ["settings.gradle.kts", "settings.gradle"];
+const BUILD_FILES: [&str; 2] = ["build.gradle.kts", "build.gradle"];
+const GRADLE_LOCKFILE: &str = "gradle.lockfile";
+const VERSION_CATALOG: &str = "gradle/libs.versions.toml";
+const GRADLE_WRAPPER: &str = "gradlew";
+
+/// Discovers Gradle projects included by a multi-project build's settings script.
+///
+/// Settings and build scripts are parsed statically, so Gradle is never run.
+#[derive(Debug, Default)]
+pub struct GradleWorkspaceProvider;
+
+struct GradleSettings {
+    source: &'static str,
+    root_name: Option<String>,
+    includes: BTreeSet<String>,
+    project_dirs: BTreeMap<String, PathBuf>,
+}
+
+impl WorkspaceProvider for GradleWorkspaceProvider {
+    fn id(&self) -> &str {
+        "gradle"
+    }
+
+    fn discover(&self, workspace_root: &Path) -> Result<Vec<WorkspaceProject>> {
+        let context = WorkspaceDiscoveryContext::new();
+        self.discover_with_context(workspace_root, &context)
+    }
+
+    fn discover_with_context(
+        &self,
+        workspace_root: &Path,
+        context: &WorkspaceDiscoveryContext,
+    ) -> Result<Vec<WorkspaceProject>> {
+        let Some(settings) = read_settings(workspace_root, context)? else {
+            return Ok(Vec::new());
+        };
+        if settings.includes.is_empty() {
+            return Ok(Vec::new());
+        }
+        let canonical_root = context.canonicalize(workspace_root).wrap_err_with(|| {
+            format!(
+                "failed to resolve Gradle build root {}",
+                workspace_root.display()
+            )
+        })?;
+        let roots = project_roots(workspace_root, &canonical_root, &settings, context)?;
+
+        let ids = roots
+            .keys()
+            .map(|path| {
+                let local = match path.as_str() {
+                    ":" => settings.root_name.clone().unwrap_or_else(|| {
+                        canonical_root
+                            .file_name()
+                            .map(|name| name.to_string_lossy().into_owned())
+                            .unwrap_or_else(|| "root".to_string())
+                    }),
+                    path => path.trim_start_matches(':').to_string(),
+                };
+                Ok((path.clone(), ProjectId::new(self.id(), &local)?))
+            })
+            .collect::<Result<BTreeMap<_, _>>>()?;
+        let wrapper = context.is_file(&workspace_root.join(GRADLE_WRAPPER));
+
+        roots
+            .iter()
+            .map(|(path, root)| {
+                let id = ids[path].clone();
+                let build_file = BUILD_FILES
+                    .iter()
+                    .map(|name| root.join(name))
+                    .find(|source| context.is_file(&workspace_root.join(source)));
+                let mut dependencies = BTreeSet::new();
+                if let Some(build_file) = &build_file {
+                    let script = read_script(context, &workspace_root.join(build_file))?;
+                    for dependency in project_dependencies(&script, path, ids.keys()) {
+                        if let Some(dependency) = ids.get(&dependency)
+                            && *dependency != id
+                        {
+                            dependencies.insert(dependency.clone());
+                        }
+                    }
+                }
+
+                let source = build_file.unwrap_or_else(|| PathBuf::from(settings.source));
+                let provenance = WorkspaceProvenance {
+                    provider: Some(self.id().to_string()),
+                    source: Some(source.clone()),
+                };
+                let mut project = WorkspaceProject::new(id, root.clone());
+                project.dependency_provenance = dependencies
+                    .iter()
+                    .cloned()
+                    .map(|dependency| (dependency, provenance.clone()))
+                    .collect();
+                project.dependencies = dependencies;
+                project.provenance = provenance;
+                project
+                    .metadata
+                    .insert("workspace_source".to_string(), settings.source.to_string());
+                project
+                    .metadata
+                    .insert("gradle_path".to_string(), path.clone());
+                project.tasks = workspace_tasks(path, root, &source, wrapper);
+                Ok(project)
+            })
+            .collect()
+    }
+
+    fn discover_project_tasks(
+        &self,
+        workspace_root: &Path,
+        project_root: &Path,
+    ) -> Result<BTreeMap<String, WorkspaceTask>> {
+        let context = WorkspaceDiscoveryContext::new();
+        self.discover_project_tasks_with_context(workspace_root, project_root, &context)
+    }
+
+    fn discover_project_tasks_with_context(
+        &self,
+        workspace_root: &Path,
+        project_root: &Path,
+        context: &WorkspaceDiscoveryContext,
+    ) -> Result<BTreeMap<String, WorkspaceTask>> {
+        let Some(settings) = read_settings(workspace_root, context)? else {
+            return Ok(BTreeMap::new());
+        };
+        if settings.includes.is_empty() || !context.is_dir(&workspace_root.join(project_root)) {
+            return Ok(BTreeMap::new());
+        }
+        let canonical_root = context.canonicalize(workspace_root).wrap_err_with(|| {
+            format!(
+                "failed to resolve Gradle build root {}",
+                workspace_root.display()
+            )
+        })?;
+        // Prefer the project the settings place at this directory, and otherwise the path
+        // Gradle would give a project there by default.
+        let gradle_path = project_roots(workspace_root, &canonical_root, &settings, context)?
+            .into_iter()
+            .find(|(_, root)| root == project_root)
+            .map(|(path, _)| path)
+            .unwrap_or_else(|| default_gradle_path(project_root));
+        let source = BUILD_FILES
+            .iter()
+            .map(|name| project_root.join(name))
+            .find(|source| context.is_file(&workspace_root.join(source)))
+            .unwrap_or_else(|| PathBuf::from(settings.source));
+        let wrapper = context.is_file(&workspace_root.join(GRADLE_WRAPPER));
+        Ok(workspace_tasks(
+            &gradle_path,
+            project_root,
+            &source,
+            wrapper,
+        ))
+    }
+
+    fn affected_projects_for_lockfile(
+        &self,
+        lockfile_path: &Path,
+        before: Option<&str>,
+        after: Option<&str>,
+        graph: &WorkspaceProjectGraph,
+    ) -> Result<Option<BTreeSet<ProjectId>>> {
+        let all_projects = || gradle_project_ids(graph);
+        let project_at = |root: &Path| {
+            graph
+                .projects()
+                .find(|project| project.id.as_str().starts_with("gradle:") && project.root == root)
+                .map(|project| BTreeSet::from([project.id.clone()]))
+        };
+        if lockfile_path == Path::new(VERSION_CATALOG) {
+            return Ok(Some(all_projects()));
+        }
+        if lockfile_path
+            .file_name()
+            .is_some_and(|name| name == GRADLE_LOCKFILE)
+        {
+            let root = normalize_relative(lockfile_path.parent().unwrap_or(Path::new("")));
+            return Ok(Some(project_at(&root).unwrap_or_else(all_projects)));
+        }
+        if BUILD_FILES
+            .iter()
+            .any(|name| lockfile_path == Path::new(name))
+        {
+            let Some(root_project) = project_at(Path::new(".")) else {
+                return Ok(None);
+            };
+            // Conventional cross-project configuration goes through these blocks.
+            let configures_others = [before, after].into_iter().flatten().any(|script| {
+                regex!(r"\b(allprojects|subprojects)\b").is_match(&strip_comments(script))
+            });
+            return Ok(Some(if configures_others {
+                all_projects()
+            } else {
+                root_project
+            }));
+        }
+        Ok(None)
+    }
+}
+
+fn gradle_project_ids(graph: &WorkspaceProjectGraph) -> BTreeSet<ProjectId> {
+    graph
+        .projects()
+        .filter(|project| project.id.as_str().starts_with("gradle:"))
+        .map(|project| project.id.clone())
+        .collect()
+}
+
+fn read_settings(
+    workspace_root: &Path,
+    context: &WorkspaceDiscoveryContext,
+) -> Result<Option<GradleSettings>> {
+    let Some(source) = SETTINGS_FILES
+        .into_iter()
+        .find(|name| context.is_file(&workspace_root.join(name)))
+    else {
+        return Ok(None);
+    };
+    let script = read_script(context, &workspace_root.join(source))?;
+    let includes = regex!(r#"\binclude\s*\(?\s*((?:['"][^'"]*['"]\s*,\s*)*['"][^'"]*['"])"#)
+        .captures_iter(&script)
+        .flat_map(|captures| string_literals(captures.get(1).map_or("", |m| m.as_str())))
+        .map(|include| {
+            if include.starts_with(':') {
+                include
+            } else {
+                format!(":{include}")
+            }
+        })
+        .collect();
+    let root_name = regex!(r#"\brootProject\.name\s*=\s*['"]([^'"]+)['"]"#)
+        .captures(&script)
+        .map(|captures| captures[1].to_string());
+    let project_dirs = regex!(
+        r#"\bproject\s*\(\s*['"]([^'"]+)['"]\s*\)\s*\.projectDir\s*=\s*file\s*\(\s*['"]([^'"]+)['"]\s*\)"#
+    )
+    .captures_iter(&script)
+    .map(|captures| {
+        let path = &captures[1];
+        let path = if path.starts_with(':') {
+            path.to_string()
+        } else {
+            format!(":{path}")
+        };
+        (path, PathBuf::from(&captures[2]))
+    })
+    .collect();
+    Ok(Some(GradleSettings {
+        source,
+        root_name,
+        includes,
+        project_dirs,
+    }))
+}
+
+/// Maps the Gradle path of every project in the build to its directory relative to the build
+/// root. Including `:a:b` also creates its parent project `:a`.
+fn project_roots(
+    workspace_root: &Path,
+    canonical_root: &Path,
+    settings: &GradleSettings,
+    context: &WorkspaceDiscoveryContext,
+) -> Result<BTreeMap<String, PathBuf>> {
+    let mut paths = BTreeSet::from([":".to_string()]);
+    for include in &settings.includes {
+        let mut path = String::new();
+        for segment in include.split(':').filter(|segment| !segment.is_empty()) {
+            path = format!("{path}:{segment}");
+            paths.insert(path.clone());
+        }
+    }
+    let mut roots = BTreeMap::new();
+    for path in paths {
+        let root = match settings.project_dirs.get(&path) {
+            Some(dir) => dir.clone(),
+            None => path.split(':').filter(|s| !s.is_empty()).collect(),
+        };
+        let root = normalize_relative(&root);
+        let directory = workspace_root.join(&root);
+        if !context.is_dir(&directory) {
+            continue;
+        }
+        let canonical = context.canonicalize(&directory).wrap_err_with(|| {
+            format!("failed to resolve Gradle project {}", directory.display())
+        })?;
+        // Project directories outside the repository cannot be represented by a relative root.
+        let Ok(relative) = canonical.strip_prefix(canonical_root) else {
+            continue;
+        };
+        roots.insert(path, normalize_relative(relative));
+    }
+    Ok(roots)
+}
+
+/// The Gradle path of a project in `root` without a `projectDir` override, e.g. `:libs:core`.
+fn default_gradle_path(root: &Path) -> String {
+    let segments = root
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(segment) => Some(segment.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    format!(":{}", segments.join(":"))
+}
+
+fn read_script(context: &WorkspaceDiscoveryContext, path: &Path) -> Result<String> {
+    let contents = context
+        .read_to_string(path)
+        .wrap_err_with(|| format!("failed to read Gradle script {}", path.display()))?;
+    Ok(strip_comments(&contents))
+}
+
+/// Returns the Gradle paths of projects referenced with `project(":x")` or
+/// type-safe `projects.x` accessors.
+fn project_dependencies<'a>(
+    script: &str,
+    project_path: &str,
+    known_paths: impl Iterator<Item = &'a String>,
+) -> BTreeSet<String> {
+    let mut dependencies = regex!(r#"\bproject\s*\(\s*(?:path\s*[:=]\s*)?['"]([^'"]+)['"]"#)
+        .captures_iter(script)
+        .map(|captures| {
+            let path = &captures[1];
+            match (path.starts_with(':'), project_path) {
+                (true, _) => path.to_string(),
+                (false, ":") => format!(":{path}"),
+                (false, parent) => format!("{parent}:{path}"),
+            }
+        })
+        .collect::<BTreeSet<_>>();
+    for path in known_paths.filter(|path| *path != ":") {
+        let accessor = std::iter::once("projects".to_string())
+            .chain(path.split(':').filter(|s| !s.is_empty()).map(camel_case))
+            .collect::<Vec<_>>()
+            .join(".");
+        let referenced = script.match_indices(&accessor).any(|(start, _)| {
+            let before = script[..start].chars().next_back();
+            let after = script[start + accessor.len()..].chars().next();
+            !before.is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '.')
+                && !after.is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '.')
+        });
+        if referenced {
+            dependencies.insert(path.clone());
+        }
+    }
+    dependencies
+}
+
+/// Converts a project name to its type-safe accessor segment, like Gradle does for
+/// kebab-case and snake_case names.
+fn camel_case(name: &str) -> String {
+    let mut segments = name.split(['-', '_']).filter(|s| !s.is_empty());
+    let mut accessor = segments.next().unwrap_or_default().to_string();
+    for segment in segments {
+        let mut chars = segment.chars();
+        if let Some(first) = chars.next() {
+            accessor.extend(first.to_uppercase());
+            accessor.push_str(chars.as_str());
+        }
+    }
+    accessor
+}
+
+fn string_literals(value: &str) -> Vec<String> {
+    regex!(r#"['"]([^'"]*)['"]"#)
+        .captures_iter(value)
+        .map(|captures| captures[1].to_string())
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+/// Removes `//` and `/* */` comments outside of string literals.
+fn strip_comments(script: &str) -> String {
+    let mut out = String::with_capacity(script.len());
+    let mut chars = script.chars().peekable();
+    let mut quote = None;
+    while let Some(c) = chars.next() {
+        if let Some(q) = quote {
+            out.push(c);
+            if c == '\\' {
+                out.extend(chars.next());
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('/', Some('/')) => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        out.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = None;
+                for c in chars.by_ref() {
+                    if previous == Some('*') && c == '/' {
+                        break;
+                    }
+                    previous = Some(c);
+                }
+                out.push(' ');
+            }
+            ('"' | '\'', _) => {
+                quote = Some(c);
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn workspace_tasks(
+    gradle_path: &str,
+    project_root: &Path,
+    source: &Path,
+    wrapper: bool,
+) -> BTreeMap<String, WorkspaceTask> {
+    let gradle = if wrapper {
+        path_to_root(project_root)
+            .join(GRADLE_WRAPPER)
+            .to_string_lossy()
+            .into_owned()
+    } else {
+        "gradle".to_string()
+    };
+    ["build", "test"]
+        .into_iter()
+        .map(|name| {
+            let task_path = match gradle_path {
+                ":" => format!(":{name}"),
+                path => format!("{path}:{name}"),
+            };
+            (
+                name.to_string(),
+                WorkspaceTask {
+                    command: shell_words::join([gradle.as_str(), &task_path]),
+                    description: format!("Run the Gradle {task_path} task"),
+                    source: source.to_path_buf(),
+                    provenance: WorkspaceProvenance {
+                        provider: Some("gradle".to_string()),
+                        source: Some(source.to_path_buf()),
+                    },
+                    suggestions: WorkspaceTaskSuggestions::default(),
+                },
+            )
+        })
+        .collect()
+}
+
+fn normalize_relative(path: &Path) -> PathBuf {
+    if path.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        path.to_path_buf()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::tempdir;
+
+    use super::*;
+
+    fn write(path: &Path, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    fn id(local: &str) -> ProjectId {
+        ProjectId::new("gradle", local).unwrap()
+    }
+
+    fn build(root: &Path) {
+        write(
+            &root.join("settings.gradle.kts"),
+            "rootProject.name = \"acme\"\n\
+             // include(\":commented\")\n\
+             include(\n    \":app\",\n    \":libs:core\",\n)\n\
+             include(\":shared-utils\")\n\
+             project(\":shared-utils\").projectDir = file(\"tools/shared\")\n",
+        );
+        write(
+            &root.join("app/build.gradle.kts"),
+            "dependencies {\n    implementation(project(\":libs:core\"))\n    \
+             implementation(projects.sharedUtils)\n    \
+             implementation(\"org.slf4j:slf4j-api:2.0.0\") // project(\":ignored\")\n}\n",
+        );
+        write(
+            &root.join("libs/core/build.gradle"),
+            "dependencies { api project(path: ':libs') }\n",
+        );
+        fs::create_dir_all(root.join("tools/shared")).unwrap();
+    }
+
+    #[test]
+    fn discovers_included_projects_and_project_dependencies() {
+        let temp = tempdir().unwrap();
+        build(temp.path());
+
+        let projects = GradleWorkspaceProvider.discover(temp.path()).unwrap();
+        let summary = projects
+            .iter()
+            .map(|project| (project.id.as_str(), project.root.as_path()))
+            .collect::<BTreeSet<_>>();
+
+        assert_eq!(
+            summary,
+            BTreeSet::from([
+                ("gradle:acme", Path::new(".")),
+                ("gradle:app", Path::new("app")),
+                ("gradle:libs", Path::new("libs")),
+                ("gradle:libs:core", Path::new("libs/core")),
+                ("gradle:shared-utils", Path::new("tools/shared")),
+            ])
+        );
+        let dependencies = |local: &str| {
+            projects
+                .iter()
+                .find(|project| project.id == id(local))
+                .map(|project| project.dependencies.clone())
+                .unwrap()
+        };
+        assert_eq!(
+            dependencies("app"),
+            BTreeSet::from([id("libs:core"), id("shared-utils")])
+        );
+        assert_eq!(dependencies("libs:core"), BTreeSet::from([id("libs")]));
+    }
+
+    #[test]
+    fn infers_build_and_test_tasks() {
+        let temp = tempdir().unwrap();
+        build(temp.path());
+        write(&temp.path().join(GRADLE_WRAPPER), "");
+
+        let projects = GradleWorkspaceProvider.discover(temp.path()).unwrap();
+        let task = |local: &str, name: &str| {
+            projects
+                .iter()
+                .find(|project| project.id == id(local))
+                .and_then(|project| project.tasks.get(name))
+                .map(|task| task.command.clone())
+                .unwrap()
+        };
+
+        assert_eq!(task("libs:core", "build"), "../../gradlew :libs:core:build");
+        assert_eq!(task("acme", "test"), "./gradlew :test");
+    }
+
+    #[test]
+    fn root_overrides_keep_inferred_tasks() {
+        let temp = tempdir().unwrap();
+        build(temp.path());
+        fs::create_dir_all(temp.path().join("services/api")).unwrap();
+        let overrides = BTreeMap::from([
+            (
+                "gradle:shared-utils".to_string(),
+                super::super::WorkspaceProjectOverride {
+                    root: Some("tools/shared".into()),
+                    ..Default::default()
+                },
+            ),
+            (
+                "gradle:app".to_string(),
+                super::super::WorkspaceProjectOverride {
+                    root: Some("services/api".into()),
+                    ..Default::default()
+                },
+            ),
+        ]);
+
+        let graph = WorkspaceProjectGraph::discover_all_with_overrides(
+            &[&GradleWorkspaceProvider],
+            temp.path(),
+            &overrides,
+        )
+        .unwrap();
+        let task = |local: &str, name: &str| {
+            graph
+                .get(&id(local))
+                .and_then(|project| project.tasks.get(name))
+                .map(|task| task.command.clone())
+        };
+
+        assert_eq!(
+            task("shared-utils", "build").as_deref(),
+            Some("gradle :shared-utils:build")
+        );
+        assert_eq!(
+            task("app", "test").as_deref(),
+            Some("gradle :services:api:test")
+        );
+    }
+
+    #[test]
+    fn ignores_single_project_builds() {
+        let temp = tempdir().unwrap();
+        write(
+            &temp.path().join("settings.gradle"),
+            "rootProject.name = 'solo'\n",
+        );
+
+        assert!(
+            GradleWorkspaceProvider
+                .discover(temp.path())
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn attributes_lockfiles_catalogs_and_root_scripts() {
+        let temp = tempdir().unwrap();
+        build(temp.path());
+        let graph = WorkspaceProjectGraph::discover(&GradleWorkspaceProvider, temp.path()).unwrap();
+        let affected = |path: &str, script: Option<&str>| {
+            graph
+                .affected_projects_for_lockfile(
+                    &[&GradleWorkspaceProvider],
+                    Path::new(path),
+                    script,
+                    script,
+                )
+                .unwrap()
+        };
+        let all = gradle_project_ids(&graph);
+
+        assert_eq!(
+            affected("app/gradle.lockfile", None),
+            Some(BTreeSet::from([id("app")]))
+        );
+        assert_eq!(affected(VERSION_CATALOG, None), Some(all.clone()));
+        assert_eq!(
+            affected("build.gradle.kts", Some("plugins { java }\n")),
+            Some(BTreeSet::from([id("acme")]))
+        );
+        assert_eq!(
+            affected(
+                "build.gradle.kts",
+                Some("subprojects { apply(plugin = \"java\") }\n")
+            ),
+            Some(all)
+        );
+        assert_eq!(affected("app/build.gradle.kts", None), None);
+    }
+}
@@ -0,0 +1,601 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+use eyre::{Context, Result, bail};
+
+use super::{
+    ProjectId, WorkspaceDiscoveryContext, WorkspaceProject, WorkspaceProjectGraph,
+    WorkspaceProvenance, WorkspaceProvider, WorkspaceTask, WorkspaceTaskSuggestions, path_to_root,
+};
+use crate::backend::maven::interpolate;
+use crate::xml::XmlElement;
+
+const POM_XML: &str = "pom.xml";
+const MAVEN_WRAPPER: &str = "mvnw";
+
+/// Discovers Maven reactor modules from an aggregator POM.
+#[derive(Debug, Default)]
+pub struct MavenWorkspaceProvider;
+
+struct Module {
+    root: PathBuf,
+    source: PathBuf,
+    coordinates: String,
+    dependencies: BTreeSet<String>,
+}
+
+impl WorkspaceProvider for MavenWorkspaceProvider {
+    fn id(&self) -> &str {
+        "maven"
+    }
+
+    fn discover(&self, workspace_root: &Path) -> Result<Vec<WorkspaceProject>> {
+        let context = WorkspaceDiscoveryContext::new();
+        self.discover_with_context(workspace_root, &context)
+    }
+
+    fn discover_with_context(
+        &self,
+        workspace_root: &Path,
+        context: &WorkspaceDiscoveryContext,
+    ) -> Result<Vec<WorkspaceProject>> {
+        let root_pom_path = workspace_root.join(POM_XML);
+        if !context.is_file(&root_pom_path) {
+            return Ok(Vec::new());
+        }
+        let root_pom = read_pom(context, &root_pom_path)?;
+        if module_entries(&root_pom).next().is_none() {
+            return Ok(Vec::new());
+        }
+        let canonical_root = context.canonicalize(workspace_root).wrap_err_with(|| {
+            format!(
+                "failed to resolve Maven workspace root {}",
+                workspace_root.display()
+            )
+        })?;
+
+        let mut queue = VecDeque::from([canonical_root.join(POM_XML)]);
+        let mut modules = BTreeMap::new();
+        while let Some(pom_path) = queue.pop_front() {
+            if modules.contains_key(&pom_path) {
+                continue;
+            }
+            let pom = read_pom(context, &pom_path)?;
+            let module_root = pom_path.parent().unwrap_or(&canonical_root);
+            let relative = relative_root(&canonical_root, module_root)?;
+            let source = relative_root(&canonical_root, &pom_path)?;
+            let coordinates = coordinates(&pom).ok_or_else(|| {
+                eyre::eyre!(
+                    "Maven module {} is missing a groupId or artifactId",
+                    source.display()
+                )
+            })?;
+
+            for module in module_entries(&pom) {
+                let mut candidate = module_root.join(module);
+                if context.is_dir(&candidate) {
+                    candidate = candidate.join(POM_XML);
+                }
+                if !context.is_file(&candidate) {
+                    bail!(
+                        "Maven module {module:?} declared in {} has no POM",
+                        source.display()
+                    );
+                }
+                let candidate = context.canonicalize(&candidate).wrap_err_with(|| {
+                    format!("failed to resolve Maven module {}", candidate.display())
+                })?;
+                // Modules outside the repository cannot be represented by a relative root.
+                if candidate.starts_with(&canonical_root) {
+                    queue.push_back(candidate);
+                }
+            }
+
+            let properties = pom_properties(&pom);
+            let dependencies = pom
+                .path(&["dependencies"])
+                .into_iter()
+                .flat_map(|dependencies| dependencies.children("dependency"))
+                .chain(
+                    pom.path(&["build", "plugins"])
+                        .into_iter()
+                        .flat_map(|plugins| plugins.children("plugin")),
+                )
+                .filter_map(|dependency| {
+                    // only plugins may omit the groupId, which defaults to Maven's own plugins
+                    let group = dependency
+                        .child_text("groupId")
+                        .unwrap_or("org.apache.maven.plugins");
+                    let artifact = dependency.child_text("artifactId")?;
+                    Some(format!(
+                        "{}:{}",
+                        interpolate(group, &properties),
+                        interpolate(artifact, &properties)
+                    ))
+                })
+                .collect();
+            modules.insert(
+                pom_path,
+                Module {
+                    root: relative,
+                    source,
+                    coordinates,
+                    dependencies,
+                },
+            );
+        }
+
+        let ids_by_coordinates = modules
+            .values()
+            .map(|module| {
+                Ok((
+                    module.coordinates.clone(),
+                    ProjectId::new(self.id(), &module.coordinates)?,
+                ))
+            })
+            .collect::<Result<BTreeMap<_, _>>>()?;
+        let wrapper = context.is_file(&workspace_root.join(MAVEN_WRAPPER));
+
+        modules
+            .into_values()
+            .map(|module| {
+                let id = ids_by_coordinates[&module.coordinates].clone();
+                let dependencies = module
+                    .dependencies
+                    .iter()
+                    .filter_map(|coordinates| ids_by_coordinates.get(coordinates))
+                    .filter(|dependency| **dependency != id)
+                    .cloned()
+                    .collect::<BTreeSet<_>>();
+                let provenance = WorkspaceProvenance {
+                    provider: Some(self.id().to_string()),
+                    source: Some(module.source.clone()),
+                };
+                let mut project = WorkspaceProject::new(id, module.root.clone());
+                project.dependency_provenance = dependencies
+                    .iter()
+                    .cloned()
+                    .map(|dependency| (dependency, provenance.clone()))
+                    .collect();
+                project.dependencies = dependencies;
+                project.provenance = provenance;
+                project
+                    .metadata
+                    .insert("workspace_source".to_string(), POM_XML.to_string());
+                project.tasks =
+                    workspace_tasks(&module.coordinates, &module.root, &module.source, wrapper);
+                Ok(project)
+            })
+            .collect()
+    }
+
+    fn discover_project_tasks(
+        &self,
+        workspace_root: &Path,
+        project_root: &Path,
+    ) -> Result<BTreeMap<String, WorkspaceTask>> {
+        let context = WorkspaceDiscoveryContext::new();
+        self.discover_project_tasks_with_context(workspace_root, project_root, &context)
+    }
+
+    fn discover_project_tasks_with_context(
+        &self,
+        workspace_root: &Path,
+        project_root: &Path,
+        context: &WorkspaceDiscoveryContext,
+    ) -> Result<BTreeMap<String, WorkspaceTask>> {
+        let source = project_root.join(POM_XML);
+        let pom_path = workspace_root.join(&source);
+        if !context.is_file(&workspace_root.join(POM_XML)) || !context.is_file(&pom_path) {
+            return Ok(BTreeMap::new());
+        }
+        let Some(coordinates) = coordinates(&read_pom(context, &pom_path)?) else {
+            return Ok(BTreeMap::new());
+        };
+        let wrapper = context.is_file(&workspace_root.join(MAVEN_WRAPPER));
+        Ok(workspace_tasks(
+            &coordinates,
+            project_root,
+            &source,
+            wrapper,
+        ))
+    }
+
+    fn affected_projects_for_lockfile(
+        &self,
+        lockfile_path: &Path,
+        before: Option<&str>,
+        after: Option<&str>,
+        graph: &WorkspaceProjectGraph,
+    ) -> Result<Option<BTreeSet<ProjectId>>> {
+        if lockfile_path.file_name().is_none_or(|name| name != POM_XML) {
+            return Ok(None);
+        }
+        let root = normalize_relative(lockfile_path.parent().unwrap_or(Path::new("")));
+        let Some(owner) = graph
+            .projects()
+            .find(|project| project.id.as_str().starts_with("maven:") && project.root == root)
+        else {
+            return Ok(None);
+        };
+        let owner = BTreeSet::from([owner.id.clone()]);
+        let (Some(before), Some(after)) = (before, after) else {
+            return Ok(Some(owner));
+        };
+        let (Ok(before), Ok(after)) = (XmlElement::parse(before), XmlElement::parse(after)) else {
+            return Ok(Some(inheriting_projects(&root, graph)));
+        };
+        // Only the module list changed, which child modules do not inherit.
+        if same_element(&before, &after, &["modules", "subprojects"]) {
+            return Ok(Some(owner));
+        }
+        Ok(Some(inheriting_projects(&root, graph)))
+    }
+}
+
+fn read_pom(context: &WorkspaceDiscoveryContext, path: &Path) -> Result<XmlElement> {
+    let contents = context
+        .read_to_string(path)
+        .wrap_err_with(|| format!("failed to read Maven POM {}", path.display()))?;
+    XmlElement::parse(&contents)
+        .wrap_err_with(|| format!("failed to parse Maven POM {}", path.display()))
+}
+
+/// Returns `<modules>` entries and their Maven 4 `<subprojects>` equivalent.
+fn module_entries(pom: &XmlElement) -> impl Iterator<Item = &str> {
+    pom.path(&["modules"])
+        .into_iter()
+        .flat_map(|modules| modules.children("module"))
+        .chain(
+            pom.path(&["subprojects"])
+                .into_iter()
+                .flat_map(|subprojects| subprojects.children("subproject")),
+        )
+        .map(|module| module.text.trim())
+        .filter(|module| !module.is_empty())
+}
+
+/// Returns `groupId:artifactId`, inheriting the group from `<parent>`.
+fn coordinates(pom: &XmlElement) -> Option<String> {
+    let properties = pom_properties(pom);
+    let group = properties.get("project.groupId")?;
+    let artifact = properties.get("project.artifactId")?;
+    Some(format!(
+        "{}:{}",
+        interpolate(group, &properties),
+        interpolate(artifact, &properties)
+    ))
+}
+
+/// Properties a POM can reference as `${name}`: its `<properties>` and the `project.*` and
+/// `project.parent.*` coordinates, which reactor modules commonly use to refer to each other.
+fn pom_properties(pom: &XmlElement) -> BTreeMap<String, String> {
+    let mut properties = pom
+        .path(&["properties"])
+        .map(|properties| {
+            properties
+                .children
+                .iter()
+                .map(|p| (p.name.clone(), p.text.trim().to_string()))
+                .collect::<BTreeMap<_, _>>()
+        })
+        .unwrap_or_default();
+    let parent = pom.child("parent");
+    for name in ["groupId", "artifactId", "version"] {
+        // the artifactId is the only coordinate a module does not inherit
+        let inherited = match name {
+            "artifactId" => None,
+            _ => parent.and_then(|parent| parent.child_text(name)),
+        };
+        if let Some(value) = pom.child_text(name).or(inherited) {
+            properties.insert(format!("project.{name}"), value.to_string());
+            properties.insert(format!("pom.{name}"), value.to_string());
+        }
+        if let Some(value) = parent.and_then(|parent| parent.child_text(name)) {
+            properties.insert(format!("project.parent.{name}"), value.to_string());
+        }
+    }
+    properties
+}
+
+fn workspace_tasks(
+    coordinates: &str,
+    project_root: &Path,
+    source: &Path,
+    wrapper: bool,
+) -> BTreeMap<String, WorkspaceTask> {
+    // Tasks run in the module directory, but build through the reactor so sibling
+    // modules resolve from the workspace instead of the local repository.
+    let to_root = path_to_root(project_root);
+    let maven = if wrapper {
+        to_root.join(MAVEN_WRAPPER).to_string_lossy().into_owned()
+    } else {
+        "mvn".to_string()
+    };
+    let root_pom = to_root.join(POM_XML).to_string_lossy().into_owned();
+    [("build", "package"), ("test", "test")]
+        .into_iter()
+        .map(|(name, phase)| {
+            let command = shell_words::join([
+                maven.as_str(),
+                "--file",
+                &root_pom,
+                "--projects",
+                coordinates,
+                "--also-make",
+                phase,
+            ]);
+            (
+                name.to_string(),
+                WorkspaceTask {
+                    command,
+                    description: format!("Run the Maven {phase} phase for {coordinates}"),
+                    source: source.to_path_buf(),
+                    provenance: WorkspaceProvenance {
+                        provider: Some("maven".to_string()),
+                        source: Some(source.to_path_buf()),
+                    },
+                    suggestions: WorkspaceTaskSuggestions::default(),
+                },
+            )
+        })
+        .collect()
+}
+
+/// Maven projects that may inherit from the POM at `root`, following the default
+/// `../pom.xml` parent location.
+fn inheriting_projects(root: &Path, graph: &WorkspaceProjectGraph) -> BTreeSet<ProjectId> {
+    graph
+        .projects()
+        .filter(|project| project.id.as_str().starts_with("maven:"))
+        .filter(|project| root == Path::new(".") || project.root.starts_with(root))
+        .map(|project| project.id.clone())
+        .collect()
+}
+
+/// Compares POM elements while ignoring formatting and the `ignored` top-level elements.
+fn same_element(left: &XmlElement, right: &XmlElement, ignored: &[&str]) -> bool {
+    fn children<'a>(element: &'a XmlElement, ignored: &[&str]) -> Vec<&'a XmlElement> {
+        element
+            .children
+            .iter()
+            .filter(|child| !ignored.contains(&child.name.as_str()))
+            .collect()
+    }
+    let (left_children, right_children) = (children(left, ignored), children(right, ignored));
+    left.name == right.name
+        && left.attributes == right.attributes
+        && left.text.trim() == right.text.trim()
+        && left_children.len() == right_children.len()
+        && left_children
+            .iter()
+            .zip(&right_children)
+            .all(|(left, right)| same_element(left, right, &[]))
+}
+
+fn relative_root(workspace_root: &Path, path: &Path) -> Result<PathBuf> {
+    let relative = path.strip_prefix(workspace_root).map_err(|_| {
+        eyre::eyre!(
+            "Maven module {} is outside workspace root {}",
+            path.display(),
+            workspace_root.display()
+        )
+    })?;
+    Ok(normalize_relative(relative))
+}
+
+fn normalize_relative(path: &Path) -> PathBuf {
+    if path.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        path.to_path_buf()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::tempdir;
+
+    use super::*;
+
+    fn write(path: &Path, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    fn pom(artifact: &str, extra: &str) -> String {
+        format!(
+            "<project><modelVersion>4.0.0</modelVersion><groupId>com.acme</groupId>\
+             <artifactId>{artifact}</artifactId>{extra}</project>"
+        )
+    }
+
+    fn reactor(root: &Path) {
+        write(
+            &root.join(POM_XML),
+            &pom(
+                "parent",
+                "<packaging>pom</packaging>\
+                 <modules><module>core</module><module>services</module></modules>",
+            ),
+        );
+        write(
+            &root.join("core/pom.xml"),
+            "<project><parent><groupId>com.acme</groupId><artifactId>parent</artifactId>\
+             </parent><artifactId>core</artifactId></project>",
+        );
+        write(
+            &root.join("services/pom.xml"),
+            &pom(
+                "services",
+                "<packaging>pom</packaging><modules><module>api</module></modules>",
+            ),
+        );
+        write(
+            &root.join("services/api/pom.xml"),
+            &pom(
+                "api",
+                "<dependencies>\
+                 <dependency><groupId>com.acme</groupId><artifactId>core</artifactId></dependency>\
+                 <dependency><groupId>org.slf4j</groupId><artifactId>slf4j-api</artifactId></dependency>\
+                 </dependencies>",
+            ),
+        );
+    }
+
+    fn id(coordinates: &str) -> ProjectId {
+        ProjectId::new("maven", coordinates).unwrap()
+    }
+
+    #[test]
+    fn discovers_nested_modules_and_internal_dependencies() {
+        let temp = tempdir().unwrap();
+        reactor(temp.path());
+
+        let projects = MavenWorkspaceProvider.discover(temp.path()).unwrap();
+        let summary = projects
+            .iter()
+            .map(|project| (project.id.as_str(), project.root.as_path()))
+            .collect::<BTreeSet<_>>();
+
+        assert_eq!(
+            summary,
+            BTreeSet::from([
+                ("maven:com.acme:parent", Path::new(".")),
+                ("maven:com.acme:core", Path::new("core")),
+                ("maven:com.acme:services", Path::new("services")),
+                ("maven:com.acme:api", Path::new("services/api")),
+            ])
+        );
+        let api = projects
+            .iter()
+            .find(|project| project.id == id("com.acme:api"))
+            .unwrap();
+        assert_eq!(api.dependencies, BTreeSet::from([id("com.acme:core")]));
+        assert_eq!(
+            api.dependency_provenance[&id("com.acme:core")].source,
+            Some(PathBuf::from("services/api/pom.xml"))
+        );
+    }
+
+    #[test]
+    fn resolves_property_references_in_dependencies() {
+        let temp = tempdir().unwrap();
+        reactor(temp.path());
+        write(
+            &temp.path().join("services/api/pom.xml"),
+            &pom(
+                "api",
+                "<properties><core.artifact>core</core.artifact></properties>\
+                 <dependencies>\
+                 <dependency><groupId>${project.groupId}</groupId>\
+                 <artifactId>${core.artifact}</artifactId></dependency>\
+                 </dependencies>",
+            ),
+        );
+        write(
+            &temp.path().join("core/pom.xml"),
+            "<project><parent><groupId>com.acme</groupId><artifactId>parent</artifactId>\
+             </parent><artifactId>core</artifactId><dependencies><dependency>\
+             <groupId>${project.parent.groupId}</groupId><artifactId>parent</artifactId>\
+             </dependency></dependencies></project>",
+        );
+
+        let projects = MavenWorkspaceProvider.discover(temp.path()).unwrap();
+        let dependencies = |coordinates: &str| {
+            projects
+                .iter()
+                .find(|project| project.id == id(coordinates))
+                .unwrap()
+                .dependencies
+                .clone()
+        };
+        assert_eq!(
+            dependencies("com.acme:api"),
+            BTreeSet::from([id("com.acme:core")])
+        );
+        assert_eq!(
+            dependencies("com.acme:core"),
+            BTreeSet::from([id("com.acme:parent")])
+        );
+    }
+
+    #[test]
+    fn infers_reactor_build_and_test_tasks() {
+        let temp = tempdir().unwrap();
+        reactor(temp.path());
+        write(&temp.path().join(MAVEN_WRAPPER), "");
+
+        let projects = MavenWorkspaceProvider.discover(temp.path()).unwrap();
+        let api = projects
+            .iter()
+            .find(|project| project.id == id("com.acme:api"))
+            .unwrap();
+
+        assert_eq!(
+            api.tasks.get("build").map(|task| task.command.as_str()),
+            Some("../../mvnw --file ../../pom.xml --projects com.acme:api --also-make package")
+        );
+        assert_eq!(
+            api.tasks.get("test").map(|task| task.source.as_path()),
+            Some(Path::new("services/api/pom.xml"))
+        );
+    }
+
+    #[test]
+    fn ignores_poms_without_modules() {
+        let temp = tempdir().unwrap();
+        write(&temp.path().join(POM_XML), &pom("standalone", ""));
+
+        assert!(
+            MavenWorkspaceProvider
+                .discover(temp.path())
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn pom_changes_affect_inheriting_modules_unless_only_modules_changed() {
+        let temp = tempdir().unwrap();
+        reactor(temp.path());
+        let graph = WorkspaceProjectGraph::discover(&MavenWorkspaceProvider, temp.path()).unwrap();
+        let affected = |before: &str, after: &str| {
+            graph
+                .affected_projects_for_lockfile(
+                    &[&MavenWorkspaceProvider],
+                    Path::new("services/pom.xml"),
+                    Some(before),
+                    Some(after),
+                )
+                .unwrap()
+                .unwrap()
+        };
+
+        assert_eq!(
+            affected(
+                &pom("services", "<modules><module>api</module></modules>"),
+                &pom(
+                    "services",
+                    "<modules>\n  <module>api</module>\n  <module>web</module>\n</modules>"
+                ),
+            ),
+            BTreeSet::from([id("com.acme:services")])
+        );
+        assert_eq!(
+            affected(
+                &pom(
+                    "services",
+                    "<properties><java.version>17</java.version></properties>"
+                ),
+                &pom(
+                    "services",
+                    "<properties><java.version>21</java.version></properties>"
+                ),
+            ),
+            BTreeSet::from([id("com.acme:services"), id("com.acme:api")])
+        );
+    }
+}
@@ -0,0 +1,438 @@
+//! Records when tasks and tool installs run so `--trace-file` can export the
+//! timeline as a Chrome trace or as OpenTelemetry (OTLP JSON) spans.
+//!
+//! Spans are laid out on lanes: a [`scope`] takes a lane that is free for its
+//! whole duration and every [`span`] inside it is drawn on that lane, so spans
+//! that share a lane never overlap unless they are nested.
+
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use serde_json::{Value, json};
+
+use crate::Result;
+use crate::file;
+
+/// Format of the timeline written by `--trace-file`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum, strum::Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum TimelineFormat {
+    /// Chrome Trace Event format, for chrome://tracing or https://ui.perfetto.dev
+    #[default]
+    Chrome,
+    /// OpenTelemetry OTLP/JSON spans
+    Otlp,
+}
+
+static TIMELINE: Mutex<Option<Timeline>> = Mutex::new(None);
+
+tokio::task_local! {
+    static LANE: Lane;
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Lane {
+    index: usize,
+    span_id: u64,
+}
+
+#[derive(Debug)]
+struct Timeline {
+    target: String,
+    format: TimelineFormat,
+    name: String,
+    start: Instant,
+    start_time: SystemTime,
+    trace_id: u128,
+    next_span_id: u64,
+    /// When each lane became free, or `None` while it is in use.
+    lanes: Vec<Option<Instant>>,
+    events: Vec<Event>,
+}
+
+#[derive(Debug, Clone)]
+struct Event {
+    name: String,
+    category: &'static str,
+    lane: usize,
+    span_id: u64,
+    parent: Option<u64>,
+    start: Instant,
+    end: Instant,
+    args: Vec<(&'static str, String)>,
+}
+
+/// Root span covering the whole command, drawn on lane 0.
+const ROOT_SPAN_ID: u64 = 1;
+
+impl Timeline {
+    fn new(target: &str, format: TimelineFormat, name: &str) -> Self {
+        Self {
+            target: target.to_string(),
+            format,
+            name: name.to_string(),
+            start: Instant::now(),
+            start_time: SystemTime::now(),
+            trace_id: rand::random(),
+            next_span_id: ROOT_SPAN_ID + 1,
+            lanes: vec![None],
+            events: vec![],
+        }
+    }
+
+    fn span_id(&mut self) -> u64 {
+        let id = self.next_span_id;
+        self.next_span_id += 1;
+        id
+    }
+
+    /// Takes the first lane that has been free since `start`.
+    fn acquire_lane(&mut self, start: Instant) -> usize {
+        match self
+            .lanes
+            .iter()
+            .position(|free| free.is_some_and(|since| since <= start))
+        {
+            Some(index) => {
+                self.lanes[index] = None;
+                index
+            }
+            None => {
+                self.lanes.push(None);
+                self.lanes.len() - 1
+            }
+        }
+    }
+
+    fn release_lane(&mut self, index: usize, end: Instant) {
+        self.lanes[index] = Some(end);
+    }
+
+    fn micros(&self, instant: Instant) -> u64 {
+        instant.saturating_duration_since(self.start).as_micros() as u64
+    }
+
+    fn unix_nanos(&self, instant: Instant) -> u128 {
+        (self.start_time + instant.saturating_duration_since(self.start))
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos()
+    }
+
+    fn root(&self, end: Instant) -> Event {
+        Event {
+            name: self.name.clone(),
+            category: "mise",
+            lane: 0,
+            span_id: ROOT_SPAN_ID,
+            parent: None,
+            start: self.start,
+            end,
+            args: vec![],
+        }
+    }
+
+    fn to_chrome(&self, end: Instant) -> Value {
+        let mut events = vec![json!({
+            "name": "process_name",
+            "ph": "M",
+            "pid": 1,
+            "args": {"name": self.name},
+        })];
+        for event in std::iter::once(&self.root(end)).chain(&self.events) {
+            let args = event
+                .args
+                .iter()
+                .map(|(k, v)| (k.to_string(), json!(v)))
+                .collect::<serde_json::Map<_, _>>();
+            events.push(json!({
+                "name": event.name,
+                "cat": event.category,
+                "ph": "X",
+                "ts": self.micros(event.start),
+                "dur": event.end.saturating_duration_since(event.start).as_micros() as u64,
+                "pid": 1,
+                "tid": event.lane,
+                "args": args,
+            }));
+        }
+        json!({
+            "traceEvents": events,
+            "displayTimeUnit": "ms",
+        })
+    }
+
+    fn to_otlp(&self, end: Instant) -> Value {
+        let spans = std::iter::once(&self.root(end))
+            .chain(&self.events)
+            .map(|event| {
+                let mut attributes = vec![json!({
+                    "key": "mise.category",
+                    "value": {"stringValue": event.category},
+                })];
+                attributes.extend(event.args.iter().map(|(k, v)| {
+                    json!({
+                        "key": format!("mise.{k}"),
+                        "value": {"stringValue": v},
+                    })
+                }));
+                let mut span = json!({
+                    "traceId": format!("{:032x}", self.trace_id),
+                    "spanId": format!("{:016x}", event.span_id),
+                    "name": event.name,
+                    "kind": 1,
+                    "startTimeUnixNano": self.unix_nanos(event.start).to_string(),
+                    "endTimeUnixNano": self.unix_nanos(event.end).to_string(),
+                    "attributes": attributes,
+                });
+                if let Some(parent) = event.parent {
+                    span["parentSpanId"] = json!(format!("{parent:016x}"));
+                }
+                span
+            })
+            .collect::<Vec<_>>();
+        json!({
+            "resourceSpans": [{
+                "resource": {
+                    "attributes": [{
+                        "key": "service.name",
+                        "value": {"stringValue": "mise"},
+                    }],
+                },
+                "scopeSpans": [{
+                    "scope": {"name": "mise", "version": *crate::cli::version::VERSION_PLAIN},
+                    "spans": spans,
+                }],
+            }],
+        })
+    }
+}
+
+/// Starts recording a timeline for `name` (e.g. "mise run") that [`finish`] writes to `target`.
+///
+/// A `target` that is an http(s) URL is an OTLP collector endpoint, e.g.
+/// `http://localhost:4318/v1/traces`, and always receives OTLP spans.
+pub fn init(target: &str, format: TimelineFormat, name: &str) {
+    let format = if is_collector(target) {
+        TimelineFormat::Otlp
+    } else {
+        format
+    };
+    *TIMELINE.lock().unwrap() = Some(Timeline::new(target, format, name));
+}
+
+fn is_collector(target: &str) -> bool {
+    target.starts_with("http://") || target.starts_with("https://")
+}
+
+/// A span that is recorded when dropped.
+#[must_use]
+pub struct Span {
+    event: Option<Event>,
+    owns_lane: bool,
+}
+
+impl Span {
+    /// Adds an attribute shown with the span.
+    pub fn arg(mut self, key: &'static str, value: impl Into<String>) -> Self {
+        if let Some(event) = &mut self.event {
+            event.args.push((key, value.into()));
+        }
+        self
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let Some(mut event) = self.event.take() else {
+            return;
+        };
+        event.end = Instant::now();
+        if let Some(timeline) = TIMELINE.lock().unwrap().as_mut() {
+            if self.owns_lane {
+                timeline.release_lane(event.lane, event.end);
+            }
+            timeline.events.push(event);
+        }
+    }
+}
+
+/// Starts a span on the lane of the current [`scope`], or on a lane of its own
+/// outside of one.
+pub fn span(name: impl Into<String>, category: &'static str) -> Span {
+    let mut guard = TIMELINE.lock().unwrap();
+    let Some(timeline) = guard.as_mut() else {
+        return Span {
+            event: None,
+            owns_lane: false,
+        };
+    };
+    let start = Instant::now();
+    let (lane, parent, owns_lane) = match LANE.try_with(|lane| *lane) {
+        Ok(lane) => (lane.index, Some(lane.span_id), false),
+        Err(_) => (timeline.acquire_lane(start), Some(ROOT_SPAN_ID), true),
+    };
+    Span {
+        event: Some(Event {
+            name: name.into(),
+            category,
+            lane,
+            span_id: timeline.span_id(),
+            parent,
+            start,
+            end: start,
+            args: vec![],
+        }),
+        owns_lane,
+    }
+}
+
+/// Records a span that has already ended on the lane of the current [`scope`].
+pub fn record(name: impl Into<String>, category: &'static str, start: Instant, end: Instant) {
+    let Ok(lane) = LANE.try_with(|lane| *lane) else {
+        return;
+    };
+    if let Some(timeline) = TIMELINE.lock().unwrap().as_mut() {
+        let span_id = timeline.span_id();
+        timeline.events.push(Event {
+            name: name.into(),
+            category,
+            lane: lane.index,
+            span_id,
+            parent: Some(lane.span_id),
+            start,
+            end,
+            args: vec![],
+        });
+    }
+}
+
+/// Runs `fut` as a span on a lane of its own, which the spans started inside it share.
+///
+/// `start` backdates the span, e.g. to when a task was queued.
+pub async fn scope<F: Future>(
+    name: impl Into<String>,
+    category: &'static str,
+    start: Option<Instant>,
+    fut: F,
+) -> F::Output {
+    let start = start.unwrap_or_else(Instant::now);
+    let lane = {
+        let mut guard = TIMELINE.lock().unwrap();
+        guard.as_mut().map(|timeline| Lane {
+            index: timeline.acquire_lane(start),
+            span_id: timeline.span_id(),
+        })
+    };
+    let Some(lane) = lane else {
+        return fut.await;
+    };
+    let parent = LANE.try_with(|lane| lane.span_id).unwrap_or(ROOT_SPAN_ID);
+    let output = LANE.scope(lane, fut).await;
+    let end = Instant::now();
+    if let Some(timeline) = TIMELINE.lock().unwrap().as_mut() {
+        timeline.release_lane(lane.index, end);
+        timeline.events.push(Event {
+            name: name.into(),
+            category,
+            lane: lane.index,
+            span_id: lane.span_id,
+            parent: Some(parent),
+            start,
+            end,
+            args: vec![],
+        });
+    }
+    output
+}
+
+/// Writes the recorded timeline to its file or sends it to its collector.
+pub async fn finish() -> Result<()> {
+    let timeline = TIMELINE.lock().unwrap().take();
+    let Some(timeline) = timeline else {
+        return Ok(());
+    };
+    let end = Instant::now();
+    let body = match timeline.format {
+        TimelineFormat::Chrome => timeline.to_chrome(end),
+        TimelineFormat::Otlp => timeline.to_otlp(end),
+    };
+    if is_collector(&timeline.target) {
+        let sent = crate::http::HTTP
+            .post_json(timeline.target.as_str(), &body)
+            .await?;
+        eyre::ensure!(
+            sent,
+            "OTLP collector {} rejected the trace",
+            timeline.target
+        );
+    } else {
+        file::write(&timeline.target, serde_json::to_string(&body)?)?;
+    }
+    debug!(
+        "wrote {} trace with {} spans to {}",
+        timeline.format,
+        timeline.events.len() + 1,
+        timeline.target
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_lanes_are_reused_once_free() {
+        let mut timeline = Timeline::new("trace.json", TimelineFormat::Chrome, "mise run");
+        let t0 = Instant::now();
+        let a = timeline.acquire_lane(t0);
+        let b = timeline.acquire_lane(t0);
+        assert_eq!((a, b), (1, 2));
+        let t1 = t0 + Duration::from_millis(10);
+        timeline.release_lane(a, t1);
+        // a span that started before lane 1 was free cannot be drawn on it
+        assert_eq!(timeline.acquire_lane(t0), 3);
+        assert_eq!(timeline.acquire_lane(t1), 1);
+    }
+
+    #[test]
+    fn test_chrome_and_otlp_output() {
+        let mut timeline = Timeline::new("trace.json", TimelineFormat::Chrome, "mise run");
+        let start = timeline.start;
+        timeline.events.push(Event {
+            name: "build".to_string(),
+            category: "task",
+            lane: 1,
+            span_id: 2,
+            parent: Some(ROOT_SPAN_ID),
+            start: start + Duration::from_millis(1),
+            end: start + Duration::from_millis(3),
+            args: vec![("attempt", "1".to_string())],
+        });
+        let end = start + Duration::from_millis(5);
+
+        let chrome = timeline.to_chrome(end);
+        let events = chrome["traceEvents"].as_array().unwrap();
+        assert_eq!(events[1]["name"], "mise run");
+        assert_eq!(events[1]["dur"], 5000);
+        assert_eq!(events[2]["name"], "build");
+        assert_eq!(events[2]["ts"], 1000);
+        assert_eq!(events[2]["dur"], 2000);
+        assert_eq!(events[2]["tid"], 1);
+        assert_eq!(events[2]["args"]["attempt"], "1");
+
+        let otlp = timeline.to_otlp(end);
+        let spans = otlp["resourceSpans"][0]["scopeSpans"][0]["spans"]
+            .as_array()
+            .unwrap();
+        assert_eq!(spans.len(), 2);
+        assert!(spans[0].get("parentSpanId").is_none());
+        assert_eq!(spans[1]["parentSpanId"], format!("{ROOT_SPAN_ID:016x}"));
+        assert_eq!(spans[1]["traceId"].as_str().unwrap().len(), 32);
+        assert_eq!(spans[1]["attributes"][1]["key"], "mise.attempt");
+    }
+}
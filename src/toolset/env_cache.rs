@@ -1,6 +1,6 @@
 use std::collections::{BTreeMap, BTreeSet};
 use std::path::{Path, PathBuf};
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
 use base64::Engine;
 use base64::prelude::BASE64_STANDARD;
@@ -436,6 +436,128 @@ impl CachedNonToolEnv {
     }
 }
 
+/// A value resolved by an `_.secret` env directive. Secrets are cached separately from the
+/// env so each one can have its own TTL.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedSecret {
+    /// The secret value
+    pub value: String,
+    /// Time when the cache was created
+    pub created_at: u64,
+    /// mise version when cache was created
+    pub mise_version: String,
+}
+
+impl CachedSecret {
+    /// Computes the cache key for a secret from its provider and ref, the config file that
+    /// declares it and the env vars that select the provider's account or server, so the same
+    /// ref read from another vault or store is not served from the cache
+    pub fn compute_cache_key(
+        provider: &str,
+        reference: &str,
+        source: &Path,
+        provider_env: &[(&str, Option<&str>)],
+    ) -> String {
+        let mut hasher = Hasher::new();
+
+        // scope to secrets
+        hasher.update(b"secret");
+
+        // mise version
+        hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+
+        hasher.update(provider.as_bytes());
+        hasher.update(b"\0");
+        hasher.update(reference.as_bytes());
+        hasher.update(b"\0");
+        hasher.update(source.as_os_str().as_encoded_bytes());
+        for (key, value) in provider_env {
+            hasher.update(b"\0");
+            hasher.update(key.as_bytes());
+            // distinguishes an unset var from an empty one
+            if let Some(value) = value {
+                hasher.update(b"=");
+                hasher.update(value.as_bytes());
+            }
+        }
+
+        let hash = hasher.finalize();
+        hex::encode(hash.as_bytes())
+    }
+
+    /// Loads a cached secret if it exists and is younger than `ttl`
+    pub fn load(cache_key: &str, ttl: Duration) -> Result<Option<Self>> {
+        let key = match get_encryption_key() {
+            Some(k) => k,
+            None => {
+                trace!("env_cache: no encryption key set, skipping secret cache load");
+                return Ok(None);
+            }
+        };
+
+        let cache_file = CachedEnv::cache_dir().join(cache_key);
+        if !cache_file.exists() {
+            return Ok(None);
+        }
+
+        let encrypted_data = file::read(&cache_file)?;
+        let cached: CachedSecret = match decrypt_data(&encrypted_data, &key)
+            .and_then(|data| Ok(rmp_serde::from_slice(&data)?))
+        {
+            Ok(c) => c,
+            Err(e) => {
+                debug!("env_cache: failed to read cached secret: {}", e);
+                let _ = file::remove_file(&cache_file);
+                return Ok(None);
+            }
+        };
+
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let age = now.saturating_sub(cached.created_at);
+        if cached.mise_version != env!("CARGO_PKG_VERSION") || age > ttl.as_secs() {
+            debug!(
+                "env_cache: cached secret expired (age: {}s, ttl: {}s)",
+                age,
+                ttl.as_secs()
+            );
+            let _ = file::remove_file(&cache_file);
+            return Ok(None);
+        }
+
+        trace!("env_cache: loaded secret for key {}", cache_key);
+        Ok(Some(cached))
+    }
+
+    /// Saves a cached secret to disk
+    pub fn save(&self, cache_key: &str) -> Result<()> {
+        let key = match get_encryption_key() {
+            Some(k) => k,
+            None => {
+                trace!("env_cache: no encryption key set, skipping secret cache save");
+                return Ok(());
+            }
+        };
+
+        let cache_dir = CachedEnv::cache_dir();
+        file::create_dir_all(&cache_dir)?;
+
+        let serialized = rmp_serde::to_vec(self)?;
+        let encrypted = encrypt_data(&serialized, &key)?;
+        file::write(cache_dir.join(cache_key), &encrypted)?;
+
+        trace!("env_cache: saved secret for key {}", cache_key);
+        Ok(())
+    }
+
+    /// Returns true if env caching is enabled and we have an encryption key
+    pub fn is_enabled() -> bool {
+        CachedEnv::is_enabled()
+    }
+}
+
 /// Helper to get the mtime of a file as seconds since UNIX epoch
 pub fn get_file_mtime(path: &Path) -> Option<u64> {
     std::fs::metadata(path)
@@ -497,6 +619,55 @@ mod tests {
         assert_ne!(key1, key3);
     }
 
+    #[test]
+    fn test_secret_cache_key_computation() {
+        fn key(
+            provider: &str,
+            reference: &str,
+            source: &str,
+            env: &[(&str, Option<&str>)],
+        ) -> String {
+            CachedSecret::compute_cache_key(provider, reference, Path::new(source), env)
+        }
+        let prod = [("VAULT_ADDR", Some("https://vault.prod"))];
+        let base = key("vault", "secret/app#token", "/project/mise.toml", &prod);
+        assert_eq!(
+            base,
+            key("vault", "secret/app#token", "/project/mise.toml", &prod)
+        );
+        assert_ne!(
+            base,
+            key("pass", "secret/app#token", "/project/mise.toml", &prod)
+        );
+        assert_ne!(
+            base,
+            key("vault", "secret/app#other", "/project/mise.toml", &prod)
+        );
+        assert_ne!(
+            base,
+            key("vault", "secret/app#token", "/other/mise.toml", &prod)
+        );
+        let staging = [("VAULT_ADDR", Some("https://vault.staging"))];
+        assert_ne!(
+            base,
+            key("vault", "secret/app#token", "/project/mise.toml", &staging)
+        );
+        assert_ne!(
+            key(
+                "vault",
+                "secret/app#token",
+                "/project/mise.toml",
+                &[("VAULT_ADDR", None)]
+            ),
+            key(
+                "vault",
+                "secret/app#token",
+                "/project/mise.toml",
+                &[("VAULT_ADDR", Some(""))]
+            )
+        );
+    }
+
     #[test]
     fn test_encryption_roundtrip() {
         let key: [u8; 32] = rand::random();
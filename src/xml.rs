@@ -0,0 +1,154 @@
+//! Minimal XML reading for the files mise parses itself, such as Maven POMs and repository
+//! metadata and MSBuild projects and solutions.
+//!
+//! Documents are read into an [`XmlElement`] tree. Namespaces are dropped, so elements and
+//! attributes are looked up by their local names.
+use std::collections::BTreeMap;
+
+use eyre::{Result, eyre};
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::{Reader, XmlVersion};
+
+/// A parsed element with its attributes, text and child elements
+#[derive(Debug, Default)]
+pub struct XmlElement {
+    pub name: String,
+    pub attributes: BTreeMap<String, String>,
+    pub text: String,
+    pub children: Vec<XmlElement>,
+}
+
+impl XmlElement {
+    /// Parses a document and returns its root element
+    pub fn parse(xml: &str) -> Result<Self> {
+        let mut reader = Reader::from_str(xml);
+        let mut stack = vec![XmlElement::default()];
+        loop {
+            match reader.read_event()? {
+                Event::Start(e) => stack.push(Self::from_start(&e)?),
+                Event::Empty(e) => {
+                    let el = Self::from_start(&e)?;
+                    stack.last_mut().unwrap().children.push(el);
+                }
+                Event::End(_) => {
+                    let el = stack.pop().unwrap();
+                    stack
+                        .last_mut()
+                        .ok_or_else(|| eyre!("unbalanced XML document"))?
+                        .children
+                        .push(el);
+                }
+                Event::Text(t) => stack.last_mut().unwrap().text.push_str(&t.decode()?),
+                Event::CData(t) => stack.last_mut().unwrap().text.push_str(&t.decode()?),
+                Event::GeneralRef(r) => {
+                    let text = &mut stack.last_mut().unwrap().text;
+                    if let Some(c) = r.resolve_char_ref()? {
+                        text.push(c);
+                    } else if let Some(s) = resolve_predefined_entity(&r.decode()?) {
+                        text.push_str(s);
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        let document = stack.pop().filter(|_| stack.is_empty());
+        document
+            .and_then(|d| d.children.into_iter().next())
+            .ok_or_else(|| eyre!("invalid XML document"))
+    }
+
+    fn from_start(e: &BytesStart) -> Result<Self> {
+        let attributes = e
+            .attributes()
+            .map(|attr| {
+                let attr = attr?;
+                let key = String::from_utf8_lossy(attr.key.local_name().as_ref()).into_owned();
+                let value = attr.normalized_value(XmlVersion::Implicit1_0)?;
+                Ok((key, value.into_owned()))
+            })
+            .collect::<Result<_>>()?;
+        Ok(XmlElement {
+            name: String::from_utf8_lossy(e.local_name().as_ref()).into_owned(),
+            attributes,
+            ..Default::default()
+        })
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.get(name).map(String::as_str)
+    }
+
+    pub fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a XmlElement> {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+
+    pub fn child(&self, name: &str) -> Option<&XmlElement> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    pub fn path(&self, path: &[&str]) -> Option<&XmlElement> {
+        path.iter().try_fold(self, |el, name| el.child(name))
+    }
+
+    pub fn child_text(&self, name: &str) -> Option<&str> {
+        self.child(name)
+            .map(|c| c.text.trim())
+            .filter(|t| !t.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let xml = r#"<?xml version="1.0"?>
+<project xmlns="http://maven.apache.org/POM/4.0.0">
+  <!-- <artifactId>commented</artifactId> -->
+  <artifactId>tool &amp; lib</artifactId>
+  <description><![CDATA[a <b>tool</b>]]></description>
+  <modules><module>a</module><module>b</module><module/></modules>
+</project>"#;
+        let project = XmlElement::parse(xml).unwrap();
+        assert_eq!(project.name, "project");
+        assert_eq!(project.child_text("artifactId"), Some("tool & lib"));
+        assert_eq!(project.child_text("description"), Some("a <b>tool</b>"));
+        let modules = project.child("modules").unwrap();
+        assert_eq!(
+            modules
+                .children("module")
+                .filter_map(|m| Some(m.text.as_str()).filter(|t| !t.is_empty()))
+                .collect::<Vec<_>>(),
+            ["a", "b"]
+        );
+        assert!(project.path(&["modules", "module"]).is_some());
+        assert!(XmlElement::parse("<project>").is_err());
+    }
+
+    #[test]
+    fn test_parse_attributes() {
+        let xml = r#"<Project Sdk="Microsoft.NET.Sdk">
+  <ItemGroup>
+    <ProjectReference Include="..\Lib\Lib.csproj" />
+    <PackageReference Include="A &amp; B" Version="1.0" />
+  </ItemGroup>
+</Project>"#;
+        let project = XmlElement::parse(xml).unwrap();
+        assert_eq!(project.attribute("Sdk"), Some("Microsoft.NET.Sdk"));
+        let items = project.child("ItemGroup").unwrap();
+        assert_eq!(
+            items
+                .child("ProjectReference")
+                .unwrap()
+                .attribute("Include"),
+            Some("..\\Lib\\Lib.csproj")
+        );
+        let package = items.child("PackageReference").unwrap();
+        assert_eq!(package.attribute("Include"), Some("A & B"));
+        assert_eq!(package.attribute("Version"), Some("1.0"));
+        assert_eq!(package.attribute("Missing"), None);
+    }
+}